@@ -77,6 +77,32 @@ struct PendingHunk {
     new_status: DiffHunkSecondaryStatus,
 }
 
+/// The lines selected to be staged or unstaged, rather than whole hunks.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineSelection {
+    /// The rows of the buffer, selecting the lines it adds.
+    pub buffer_rows: Vec<Range<u32>>,
+    /// The rows of the HEAD text, selecting the lines the buffer deletes.
+    pub head_rows: Vec<Range<u32>>,
+}
+
+impl LineSelection {
+    fn contains_buffer_row(&self, row: u32) -> bool {
+        self.buffer_rows.iter().any(|rows| rows.contains(&row))
+    }
+
+    fn contains_head_row(&self, row: u32) -> bool {
+        self.head_rows.iter().any(|rows| rows.contains(&row))
+    }
+}
+
+/// The rows replaced by a hunk of a line diff between two texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LineHunk {
+    old_rows: Range<u32>,
+    new_rows: Range<u32>,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct DiffHunkSummary {
     buffer_range: Range<Anchor>,
@@ -186,51 +212,13 @@ impl BufferDiffSnapshot {
 
 impl BufferDiffInner {
     /// Returns the new index text and new pending hunks.
-    fn stage_or_unstage_hunks_impl(
-        &mut self,
-        unstaged_diff: &Self,
+    /// Returns the pending hunks with the hunks being staged or unstaged merged into them.
+    fn merge_pending_hunks(
+        &self,
         stage: bool,
         hunks: &[DiffHunk],
         buffer: &text::BufferSnapshot,
-        file_exists: bool,
-    ) -> Option<Rope> {
-        let head_text = self
-            .base_text_exists
-            .then(|| self.base_text.as_rope().clone());
-        let index_text = unstaged_diff
-            .base_text_exists
-            .then(|| unstaged_diff.base_text.as_rope().clone());
-
-        // If the file doesn't exist in either HEAD or the index, then the
-        // entire file must be either created or deleted in the index.
-        let (index_text, head_text) = match (index_text, head_text) {
-            (Some(index_text), Some(head_text)) if file_exists || !stage => (index_text, head_text),
-            (index_text, head_text) => {
-                let (new_index_text, new_status) = if stage {
-                    log::debug!("stage all");
-                    (
-                        file_exists.then(|| buffer.as_rope().clone()),
-                        DiffHunkSecondaryStatus::SecondaryHunkRemovalPending,
-                    )
-                } else {
-                    log::debug!("unstage all");
-                    (
-                        head_text,
-                        DiffHunkSecondaryStatus::SecondaryHunkAdditionPending,
-                    )
-                };
-
-                let hunk = PendingHunk {
-                    buffer_range: Anchor::MIN..Anchor::MAX,
-                    diff_base_byte_range: 0..index_text.map_or(0, |rope| rope.len()),
-                    buffer_version: buffer.version().clone(),
-                    new_status,
-                };
-                self.pending_hunks = SumTree::from_item(hunk, buffer);
-                return new_index_text;
-            }
-        };
-
+    ) -> SumTree<PendingHunk> {
         let mut pending_hunks = SumTree::new(buffer);
         let mut old_pending_hunks = self.pending_hunks.cursor::<DiffHunkSummary>(buffer);
 
@@ -279,6 +267,55 @@ impl BufferDiffInner {
         }
         // append the remainder
         pending_hunks.append(old_pending_hunks.suffix(buffer), buffer);
+        pending_hunks
+    }
+
+    fn stage_or_unstage_hunks_impl(
+        &mut self,
+        unstaged_diff: &Self,
+        stage: bool,
+        hunks: &[DiffHunk],
+        buffer: &text::BufferSnapshot,
+        file_exists: bool,
+    ) -> Option<Rope> {
+        let head_text = self
+            .base_text_exists
+            .then(|| self.base_text.as_rope().clone());
+        let index_text = unstaged_diff
+            .base_text_exists
+            .then(|| unstaged_diff.base_text.as_rope().clone());
+
+        // If the file doesn't exist in either HEAD or the index, then the
+        // entire file must be either created or deleted in the index.
+        let (index_text, head_text) = match (index_text, head_text) {
+            (Some(index_text), Some(head_text)) if file_exists || !stage => (index_text, head_text),
+            (index_text, head_text) => {
+                let (new_index_text, new_status) = if stage {
+                    log::debug!("stage all");
+                    (
+                        file_exists.then(|| buffer.as_rope().clone()),
+                        DiffHunkSecondaryStatus::SecondaryHunkRemovalPending,
+                    )
+                } else {
+                    log::debug!("unstage all");
+                    (
+                        head_text,
+                        DiffHunkSecondaryStatus::SecondaryHunkAdditionPending,
+                    )
+                };
+
+                let hunk = PendingHunk {
+                    buffer_range: Anchor::MIN..Anchor::MAX,
+                    diff_base_byte_range: 0..index_text.map_or(0, |rope| rope.len()),
+                    buffer_version: buffer.version().clone(),
+                    new_status,
+                };
+                self.pending_hunks = SumTree::from_item(hunk, buffer);
+                return new_index_text;
+            }
+        };
+
+        let pending_hunks = self.merge_pending_hunks(stage, hunks, buffer);
 
         let mut unstaged_hunk_cursor = unstaged_diff.hunks.cursor::<DiffHunkSummary>(buffer);
         unstaged_hunk_cursor.next(buffer);
@@ -364,7 +401,6 @@ impl BufferDiffInner {
             edits.push((index_byte_range, replacement_text));
         }
         drop(pending_hunks_iter);
-        drop(old_pending_hunks);
         self.pending_hunks = pending_hunks;
 
         #[cfg(debug_assertions)] // invariants: non-overlapping and sorted
@@ -741,6 +777,144 @@ fn process_patch_hunk(
     }
 }
 
+fn line_hunks(old_text: &str, new_text: &str) -> Vec<LineHunk> {
+    let mut options = GitOptions::default();
+    options.context_lines(0);
+    let Some(patch) = GitPatch::from_buffers(
+        old_text.as_bytes(),
+        None,
+        new_text.as_bytes(),
+        None,
+        Some(&mut options),
+    )
+    .log_err() else {
+        return Vec::new();
+    };
+
+    // Hunks without old or new lines start after the line they're numbered with.
+    let rows = |start: u32, len: u32| {
+        let start = if len == 0 { start } else { start - 1 };
+        start..start + len
+    };
+    (0..patch.num_hunks())
+        .filter_map(|hunk_index| {
+            let (hunk, _) = patch.hunk(hunk_index).log_err()?;
+            Some(LineHunk {
+                old_rows: rows(hunk.old_start(), hunk.old_lines()),
+                new_rows: rows(hunk.new_start(), hunk.new_lines()),
+            })
+        })
+        .collect()
+}
+
+/// Maps a row of the old text of a line diff to the row of the new text it's kept as, or to the
+/// row of the new text the hunk replacing it starts at.
+fn map_row(hunks: &[LineHunk], row: u32) -> Result<u32, u32> {
+    let mut delta = 0i64;
+    for hunk in hunks {
+        if row < hunk.old_rows.start {
+            break;
+        }
+        if row < hunk.old_rows.end {
+            return Err(hunk.new_rows.start);
+        }
+        delta += hunk.new_rows.len() as i64 - hunk.old_rows.len() as i64;
+    }
+    Ok((row as i64 + delta) as u32)
+}
+
+/// Applies the selected parts of the changes from `old_text` to `new_text`: the old lines are kept
+/// unless their removal is selected, and the new lines are only inserted if their addition is.
+fn apply_line_hunks(
+    old_text: &str,
+    new_text: &str,
+    hunks: &[LineHunk],
+    mut is_removal_selected: impl FnMut(&LineHunk, u32) -> bool,
+    mut is_addition_selected: impl FnMut(&LineHunk, u32) -> bool,
+) -> String {
+    let line_ending = if old_text.contains("\r\n") || new_text.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let old_lines = old_text.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new_text.split_inclusive('\n').collect::<Vec<_>>();
+
+    let mut result = String::with_capacity(old_text.len());
+    // A line missing its newline at the end of a text may be followed by other lines.
+    let mut push_line = |line: &str| {
+        if !result.is_empty() && !result.ends_with('\n') {
+            result.push_str(line_ending);
+        }
+        result.push_str(line);
+    };
+    let mut old_row = 0;
+    for hunk in hunks {
+        for line in &old_lines[old_row..hunk.old_rows.start as usize] {
+            push_line(line);
+        }
+        for row in hunk.old_rows.clone() {
+            if !is_removal_selected(hunk, row) {
+                push_line(old_lines[row as usize]);
+            }
+        }
+        for row in hunk.new_rows.clone() {
+            if is_addition_selected(hunk, row) {
+                push_line(new_lines[row as usize]);
+            }
+        }
+        old_row = hunk.old_rows.end as usize;
+    }
+    for line in &old_lines[old_row..] {
+        push_line(line);
+    }
+    result
+}
+
+/// Returns the index text with the changed lines in the selection staged or unstaged.
+///
+/// The lines the buffer adds are selected by their buffer row, and the ones it deletes by their
+/// HEAD row, which is how the editor displays them. Staged lines that are changed again in the
+/// buffer, and unstaged ones missing from HEAD, aren't displayed, so they're selected with the
+/// buffer row their hunk is displayed at.
+fn stage_or_unstage_lines_impl(
+    stage: bool,
+    selection: &LineSelection,
+    head_text: &str,
+    index_text: &str,
+    buffer_text: &str,
+) -> String {
+    let max_buffer_row = buffer_text.matches('\n').count() as u32;
+    let is_buffer_position_selected =
+        |row: u32| selection.contains_buffer_row(row.min(max_buffer_row));
+    let unstaged_hunks = line_hunks(index_text, buffer_text);
+    if stage {
+        let index_to_head_hunks = line_hunks(index_text, head_text);
+        apply_line_hunks(
+            index_text,
+            buffer_text,
+            &unstaged_hunks,
+            |hunk, index_row| match map_row(&index_to_head_hunks, index_row) {
+                Ok(head_row) => selection.contains_head_row(head_row),
+                Err(_) => is_buffer_position_selected(hunk.new_rows.start),
+            },
+            |_, buffer_row| selection.contains_buffer_row(buffer_row),
+        )
+    } else {
+        let staged_hunks = line_hunks(index_text, head_text);
+        apply_line_hunks(
+            index_text,
+            head_text,
+            &staged_hunks,
+            |_, index_row| match map_row(&unstaged_hunks, index_row) {
+                Ok(buffer_row) => selection.contains_buffer_row(buffer_row),
+                Err(buffer_row) => is_buffer_position_selected(buffer_row),
+            },
+            |_, head_row| selection.contains_head_row(head_row),
+        )
+    }
+}
+
 impl std::fmt::Debug for BufferDiff {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BufferChangeSet")
@@ -757,6 +931,9 @@ pub enum BufferDiffEvent {
     },
     LanguageChanged,
     HunksStagedOrUnstaged(Option<Rope>),
+    /// Some lines of the hunks were staged or unstaged. The new index text has `\n` line endings,
+    /// which may differ from the ones stored in the index.
+    LinesStagedOrUnstaged(Option<Rope>),
 }
 
 impl EventEmitter<BufferDiffEvent> for BufferDiff {}
@@ -888,6 +1065,75 @@ impl BufferDiff {
         new_index_text
     }
 
+    /// Stages or unstages the changed lines in the selection rather than whole hunks, returning
+    /// the new index text.
+    pub fn stage_or_unstage_lines(
+        &mut self,
+        stage: bool,
+        selection: &LineSelection,
+        buffer: &text::BufferSnapshot,
+        file_exists: bool,
+        cx: &mut Context<Self>,
+    ) -> Option<Rope> {
+        let hunks = self
+            .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, buffer, cx)
+            .collect::<Vec<_>>();
+        // The deletion of a file can only be staged as a whole.
+        if stage && !file_exists {
+            return self.stage_or_unstage_hunks(stage, &hunks, buffer, file_exists, cx);
+        }
+
+        let unstaged_diff = &self.secondary_diff.as_ref()?.read(cx).inner;
+        let index_text = unstaged_diff
+            .base_text_exists
+            .then(|| unstaged_diff.base_text.text())
+            .unwrap_or_default();
+        let head_text = self
+            .inner
+            .base_text_exists
+            .then(|| self.inner.base_text.text())
+            .unwrap_or_default();
+        let new_index_text =
+            stage_or_unstage_lines_impl(stage, selection, &head_text, &index_text, &buffer.text());
+        // A file missing from HEAD is removed from the index once none of its lines are staged.
+        let new_index_text = (self.inner.base_text_exists || !new_index_text.is_empty())
+            .then(|| Rope::from(new_index_text.as_str()));
+
+        // Hunks with all of their lines selected are displayed as staged or unstaged right away,
+        // as when staging whole hunks.
+        let rows = |range: Range<Point>| {
+            if range.end.column == 0 {
+                range.start.row..range.end.row
+            } else {
+                range.start.row..range.end.row + 1
+            }
+        };
+        let base_text = &self.inner.base_text;
+        let selected_hunks = hunks
+            .into_iter()
+            .filter(|hunk| {
+                let head_range = base_text.offset_to_point(hunk.diff_base_byte_range.start)
+                    ..base_text.offset_to_point(hunk.diff_base_byte_range.end);
+                rows(hunk.range.clone()).all(|row| selection.contains_buffer_row(row))
+                    && rows(head_range).all(|row| selection.contains_head_row(row))
+            })
+            .collect::<Vec<_>>();
+        self.inner.pending_hunks = self
+            .inner
+            .merge_pending_hunks(stage, &selected_hunks, buffer);
+
+        cx.emit(BufferDiffEvent::LinesStagedOrUnstaged(
+            new_index_text.clone(),
+        ));
+        if let Some((first, last)) = selected_hunks.first().zip(selected_hunks.last()) {
+            let changed_range = first.buffer_range.start..last.buffer_range.end;
+            cx.emit(BufferDiffEvent::DiffChanged {
+                changed_range: Some(changed_range),
+            });
+        }
+        new_index_text
+    }
+
     pub fn range_to_hunk_range(
         &self,
         range: Range<Anchor>,
@@ -1104,6 +1350,30 @@ impl BufferDiff {
         rx
     }
 
+    /// Recomputes the diff of a buffer against its current base text, for diffs whose base text
+    /// isn't kept up to date by the git store.
+    pub fn recalculate_diff(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let base_text = self.base_text_string().map(Arc::new);
+        let snapshot = BufferDiff::build_with_base_buffer(
+            buffer.clone(),
+            base_text,
+            self.inner.base_text.clone(),
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            let snapshot = snapshot.await;
+            this.update(cx, |this, cx| {
+                let changed_range = this.set_state(snapshot, &buffer);
+                cx.emit(BufferDiffEvent::DiffChanged { changed_range });
+            })
+            .log_err();
+        })
+    }
+
     pub fn base_text_string(&self) -> Option<String> {
         self.inner
             .base_text_exists
@@ -1844,6 +2114,267 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_stage_and_unstage_lines(cx: &mut TestAppContext) {
+        let head_text = "
+            one
+            two
+            three
+        "
+        .unindent();
+        let index_text = head_text.clone();
+        let buffer_text = "
+            one
+            TWO
+            three
+            four
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text.clone());
+        let unstaged = BufferDiff::build_sync(buffer.clone(), index_text, cx);
+        let uncommitted = BufferDiff::build_sync(buffer.clone(), head_text.clone(), cx);
+        let unstaged_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&buffer, cx);
+            diff.set_state(unstaged, &buffer);
+            diff
+        });
+        let uncommitted_diff = cx.new(|cx| {
+            let mut diff = BufferDiff::new(&buffer, cx);
+            diff.set_state(uncommitted, &buffer);
+            diff.set_secondary_diff(unstaged_diff.clone());
+            diff
+        });
+
+        // Only the addition of the modified line is staged, keeping the line it replaces.
+        let selection = LineSelection {
+            buffer_rows: vec![1..2],
+            head_rows: Vec::new(),
+        };
+        let new_index_text = uncommitted_diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(true, &selection, &buffer, true, cx)
+                .unwrap()
+                .to_string()
+        });
+        assert_eq!(
+            new_index_text,
+            "
+            one
+            two
+            TWO
+            three
+            "
+            .unindent()
+        );
+        let secondary_statuses = |cx: &mut TestAppContext| {
+            uncommitted_diff.update(cx, |diff, cx| {
+                diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &buffer, cx)
+                    .map(|hunk| hunk.secondary_status)
+                    .collect::<Vec<_>>()
+            })
+        };
+        // Hunks only partially staged keep their status until the index is reloaded.
+        assert_eq!(
+            secondary_statuses(cx),
+            [
+                DiffHunkSecondaryStatus::HasSecondaryHunk,
+                DiffHunkSecondaryStatus::HasSecondaryHunk
+            ]
+        );
+
+        // Hunks with all of their lines staged are displayed as staged right away.
+        let selection = LineSelection {
+            buffer_rows: vec![3..4],
+            head_rows: Vec::new(),
+        };
+        uncommitted_diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(true, &selection, &buffer, true, cx)
+        });
+        assert_eq!(
+            secondary_statuses(cx),
+            [
+                DiffHunkSecondaryStatus::HasSecondaryHunk,
+                DiffHunkSecondaryStatus::SecondaryHunkRemovalPending
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stage_and_unstage_lines_impl() {
+        let buffer_rows = |rows: &[Range<u32>]| LineSelection {
+            buffer_rows: rows.to_vec(),
+            head_rows: Vec::new(),
+        };
+        let head_rows = |rows: &[Range<u32>]| LineSelection {
+            buffer_rows: Vec::new(),
+            head_rows: rows.to_vec(),
+        };
+
+        // Modified lines are staged by their deletion and their addition separately.
+        let head_text = "one\ntwo\nthree\n";
+        let buffer_text = "one\nTWO\nthree\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &buffer_rows(&[1..2]),
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            "one\ntwo\nTWO\nthree\n"
+        );
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &head_rows(&[1..2]),
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            "one\nthree\n"
+        );
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &LineSelection {
+                    buffer_rows: vec![1..2],
+                    head_rows: vec![1..2],
+                },
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            buffer_text
+        );
+
+        // Deleted lines are selected by their HEAD row.
+        let head_text = "one\ntwo\nthree\nfour\n";
+        let buffer_text = "one\nfour\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &head_rows(&[2..3]),
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            "one\ntwo\nfour\n"
+        );
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &buffer_rows(&[0..2]),
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            head_text
+        );
+
+        // Staged additions are unstaged by their buffer row, and staged deletions by their HEAD
+        // row.
+        let head_text = "one\ntwo\nfour\n";
+        let index_text = "one\nthree\nfour\nfive\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                false,
+                &buffer_rows(&[3..4]),
+                head_text,
+                index_text,
+                index_text
+            ),
+            "one\nthree\nfour\n"
+        );
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                false,
+                &head_rows(&[1..2]),
+                head_text,
+                index_text,
+                index_text
+            ),
+            "one\nthree\ntwo\nfour\nfive\n"
+        );
+        // A staged addition changed again in the buffer is selected with the row of its hunk.
+        let buffer_text = "one\nTHREE\nfour\nfive\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                false,
+                &buffer_rows(&[1..2]),
+                head_text,
+                index_text,
+                buffer_text
+            ),
+            "one\nfour\nfive\n"
+        );
+
+        // Line endings are preserved.
+        let head_text = "one\r\ntwo\r\n";
+        let buffer_text = "one\r\ntwo\r\nthree\r\nfour\r\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &buffer_rows(&[3..4]),
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            "one\r\ntwo\r\nfour\r\n"
+        );
+        let head_text = "one\r\nTWO\r\nthree\r\n";
+        let index_text = "one\r\ntwo\r\nthree\r\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                false,
+                &head_rows(&[1..2]),
+                head_text,
+                index_text,
+                index_text
+            ),
+            "one\r\ntwo\r\nTWO\r\nthree\r\n"
+        );
+
+        // A line missing its newline is kept on its own line when lines are added after it.
+        let head_text = "one\ntwo";
+        let buffer_text = "one\ntwo\nthree";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &buffer_rows(&[2..3]),
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            "one\ntwo\nthree"
+        );
+        let head_text = "one\r\ntwo";
+        let buffer_text = "one\r\ntwo\r\nthree\r\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &buffer_rows(&[2..3]),
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            "one\r\ntwo\r\nthree\r\n"
+        );
+        let buffer_text = "one\ntwo\nthree\n";
+        assert_eq!(
+            stage_or_unstage_lines_impl(
+                true,
+                &LineSelection {
+                    buffer_rows: vec![1..2],
+                    head_rows: vec![1..2],
+                },
+                head_text,
+                head_text,
+                buffer_text
+            ),
+            "one\ntwo\n"
+        );
+    }
+
     #[gpui::test]
     async fn test_buffer_diff_compare(cx: &mut TestAppContext) {
         let base_text = "
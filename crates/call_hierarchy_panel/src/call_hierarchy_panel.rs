@@ -0,0 +1,989 @@
+mod call_hierarchy_panel_settings;
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Context as _;
+use call_hierarchy_panel_settings::{CallHierarchyPanelDockPosition, CallHierarchyPanelSettings};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, scroll::Autoscroll};
+use gpui::{
+    Action, App, AsyncWindowContext, ClickEvent, Context, ElementId, Entity, EventEmitter,
+    FocusHandle, Focusable, InteractiveElement, IntoElement, KeyContext, ListSizingBehavior,
+    MouseButton, ParentElement, Pixels, Render, SharedString, StatefulInteractiveElement as _,
+    Styled, Subscription, Task, UniformListScrollHandle, WeakEntity, Window, actions, div, px,
+    uniform_list,
+};
+use language::{Buffer, OffsetRangeExt as _};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{CallHierarchyItem, Fs, Location, Project, TypeHierarchyItem};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use util::ResultExt;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    ui::{
+        ActiveTheme, ButtonCommon, Clickable, Color, FluentBuilder, Icon, IconButton, IconName,
+        IconSize, Label, LabelCommon, LabelSize, ListItem, Toggleable, Tooltip, h_flex, v_flex,
+    },
+};
+
+actions!(
+    call_hierarchy_panel,
+    [
+        CollapseSelectedEntry,
+        ExpandSelectedEntry,
+        ShowIncomingCalls,
+        ShowOutgoingCalls,
+        ShowSubtypes,
+        ShowSupertypes,
+        ToggleDirection,
+        ToggleFocus,
+    ]
+);
+
+const CALL_HIERARCHY_PANEL_KEY: &str = "CallHierarchyPanel";
+
+/// Highlights the call sites of the selected call hierarchy entry.
+enum CallSiteHighlights {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum HierarchyItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        match self {
+            Self::Call(item) => item.kind,
+            Self::Type(item) => item.kind,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Call(item) => item.detail.as_deref(),
+            Self::Type(item) => item.detail.as_deref(),
+        }
+    }
+
+    fn location(&self) -> &Location {
+        match self {
+            Self::Call(item) => &item.location,
+            Self::Type(item) => &item.location,
+        }
+    }
+
+    fn selection_range(&self) -> &Range<language::Anchor> {
+        match self {
+            Self::Call(item) => &item.selection_range,
+            Self::Type(item) => &item.selection_range,
+        }
+    }
+}
+
+enum Children {
+    NotLoaded,
+    Loading(Task<()>),
+    Loaded(Vec<usize>),
+}
+
+struct Node {
+    item: HierarchyItem,
+    /// The ranges of the calls between this entry and its parent.
+    /// Empty for the root entries.
+    call_sites: Vec<Location>,
+    depth: usize,
+    expanded: bool,
+    children: Children,
+}
+
+pub struct CallHierarchyPanel {
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    active: bool,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    pending_serialization: Task<Option<()>>,
+    kind: HierarchyKind,
+    nodes: Vec<Node>,
+    roots: Vec<usize>,
+    visible_entries: Vec<usize>,
+    selected_entry: Option<usize>,
+    prepare_task: Option<Task<anyhow::Result<()>>>,
+    highlighted_editor: Option<WeakEntity<Editor>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Debug)]
+pub enum Event {
+    Focus,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedCallHierarchyPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut App) {
+    CallHierarchyPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut App) {
+    init_settings(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<CallHierarchyPanel>(window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::IncomingCalls, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::OutgoingCalls, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSupertypes, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Supertypes, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, window, cx| {
+            show_hierarchy(workspace, HierarchyKind::Subtypes, window, cx);
+        });
+    })
+    .detach();
+}
+
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    kind: HierarchyKind,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    let Some(panel) = workspace.focus_panel::<CallHierarchyPanel>(window, cx) else {
+        return;
+    };
+    panel.update(cx, |panel, cx| {
+        panel.show_hierarchy(kind, buffer, position, window, cx)
+    });
+}
+
+impl CallHierarchyPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        let serialized_panel = cx
+            .background_spawn(async move { KEY_VALUE_STORE.read_kvp(CALL_HIERARCHY_PANEL_KEY) })
+            .await
+            .context("loading call hierarchy panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedCallHierarchyPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let panel = Self::new(workspace, window, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = cx.entity().downgrade();
+        let fs = workspace.app_state().fs.clone();
+        cx.new(|cx| {
+            let focus_handle = cx.focus_handle();
+            let focus_subscription = cx.on_focus(&focus_handle, window, |_, _, cx| {
+                cx.emit(Event::Focus);
+            });
+
+            let mut panel_settings = *CallHierarchyPanelSettings::get_global(cx);
+            let settings_subscription =
+                cx.observe_global_in::<SettingsStore>(window, move |_, _, cx| {
+                    let new_settings = CallHierarchyPanelSettings::get_global(cx);
+                    if &panel_settings != new_settings {
+                        panel_settings = *new_settings;
+                        cx.notify();
+                    }
+                });
+
+            Self {
+                fs,
+                width: None,
+                project,
+                workspace: workspace_handle,
+                active: false,
+                focus_handle,
+                scroll_handle: UniformListScrollHandle::new(),
+                pending_serialization: Task::ready(None),
+                kind: HierarchyKind::IncomingCalls,
+                nodes: Vec::new(),
+                roots: Vec::new(),
+                visible_entries: Vec::new(),
+                selected_entry: None,
+                prepare_task: None,
+                highlighted_editor: None,
+                _subscriptions: vec![focus_subscription, settings_subscription],
+            }
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        CALL_HIERARCHY_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedCallHierarchyPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self, _: &Window, _: &Context<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("CallHierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    /// Replaces the panel contents with the hierarchy of the symbol at the given position.
+    fn show_hierarchy(
+        &mut self,
+        kind: HierarchyKind,
+        buffer: Entity<Buffer>,
+        position: language::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let project = self.project.clone();
+        self.kind = kind;
+        self.clear_entries(cx);
+        self.prepare_task = Some(cx.spawn_in(window, async move |panel, cx| {
+            let items = match kind {
+                HierarchyKind::IncomingCalls | HierarchyKind::OutgoingCalls => project
+                    .update(cx, |project, cx| {
+                        project.prepare_call_hierarchy(&buffer, position, cx)
+                    })?
+                    .await
+                    .log_err()
+                    .into_iter()
+                    .flatten()
+                    .map(HierarchyItem::Call)
+                    .collect(),
+                HierarchyKind::Supertypes | HierarchyKind::Subtypes => project
+                    .update(cx, |project, cx| {
+                        project.prepare_type_hierarchy(&buffer, position, cx)
+                    })?
+                    .await
+                    .log_err()
+                    .into_iter()
+                    .flatten()
+                    .map(HierarchyItem::Type)
+                    .collect(),
+            };
+            panel
+                .update_in(cx, |panel, window, cx| {
+                    panel.prepare_task = None;
+                    panel.set_roots(items, window, cx);
+                })?;
+            anyhow::Ok(())
+        }));
+        cx.notify();
+    }
+
+    fn clear_entries(&mut self, cx: &mut Context<Self>) {
+        self.nodes.clear();
+        self.roots.clear();
+        self.visible_entries.clear();
+        self.selected_entry = None;
+        self.clear_call_site_highlights(cx);
+    }
+
+    fn set_roots(
+        &mut self,
+        items: Vec<HierarchyItem>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.clear_entries(cx);
+        for item in items {
+            let ix = self.push_node(item, Vec::new(), 0);
+            self.roots.push(ix);
+        }
+        for root in self.roots.clone() {
+            self.expand_entry(root, window, cx);
+        }
+        self.selected_entry = self.roots.first().copied();
+        self.update_visible_entries(cx);
+    }
+
+    fn push_node(&mut self, item: HierarchyItem, call_sites: Vec<Location>, depth: usize) -> usize {
+        self.nodes.push(Node {
+            item,
+            call_sites,
+            depth,
+            expanded: false,
+            children: Children::NotLoaded,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut Context<Self>) {
+        fn visit(nodes: &[Node], ix: usize, visible_entries: &mut Vec<usize>) {
+            visible_entries.push(ix);
+            let node = &nodes[ix];
+            if let (true, Children::Loaded(children)) = (node.expanded, &node.children) {
+                for &child in children {
+                    visit(nodes, child, visible_entries);
+                }
+            }
+        }
+
+        self.visible_entries.clear();
+        for &root in &self.roots {
+            visit(&self.nodes, root, &mut self.visible_entries);
+        }
+        cx.notify();
+    }
+
+    fn expand_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.nodes.get_mut(ix) else {
+            return;
+        };
+        node.expanded = true;
+        if !matches!(node.children, Children::NotLoaded) {
+            self.update_visible_entries(cx);
+            return;
+        }
+
+        let children: Task<anyhow::Result<Vec<(HierarchyItem, Vec<Location>)>>> =
+            match (self.kind, &node.item) {
+                (HierarchyKind::IncomingCalls, HierarchyItem::Call(item)) => {
+                    let calls = self
+                        .project
+                        .update(cx, |project, cx| project.incoming_calls(item, cx));
+                    cx.background_spawn(async move {
+                        Ok(calls
+                            .await?
+                            .into_iter()
+                            .map(|call| (HierarchyItem::Call(call.item), call.call_sites))
+                            .collect())
+                    })
+                }
+                (HierarchyKind::OutgoingCalls, HierarchyItem::Call(item)) => {
+                    let calls = self
+                        .project
+                        .update(cx, |project, cx| project.outgoing_calls(item, cx));
+                    cx.background_spawn(async move {
+                        Ok(calls
+                            .await?
+                            .into_iter()
+                            .map(|call| (HierarchyItem::Call(call.item), call.call_sites))
+                            .collect())
+                    })
+                }
+                (HierarchyKind::Supertypes, HierarchyItem::Type(item)) => {
+                    let supertypes = self
+                        .project
+                        .update(cx, |project, cx| project.supertypes(item, cx));
+                    cx.background_spawn(async move {
+                        Ok(supertypes
+                            .await?
+                            .into_iter()
+                            .map(|item| (HierarchyItem::Type(item), Vec::new()))
+                            .collect())
+                    })
+                }
+                (HierarchyKind::Subtypes, HierarchyItem::Type(item)) => {
+                    let subtypes = self
+                        .project
+                        .update(cx, |project, cx| project.subtypes(item, cx));
+                    cx.background_spawn(async move {
+                        Ok(subtypes
+                            .await?
+                            .into_iter()
+                            .map(|item| (HierarchyItem::Type(item), Vec::new()))
+                            .collect())
+                    })
+                }
+                _ => Task::ready(Ok(Vec::new())),
+            };
+        node.children = Children::Loading(cx.spawn_in(window, async move |panel, cx| {
+            let children = children.await.log_err().unwrap_or_default();
+            panel
+                .update(cx, |panel, cx| panel.set_children(ix, children, cx))
+                .ok();
+        }));
+    }
+
+    fn set_children(
+        &mut self,
+        ix: usize,
+        children: Vec<(HierarchyItem, Vec<Location>)>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(depth) = self.nodes.get(ix).map(|node| node.depth + 1) else {
+            return;
+        };
+        let children = children
+            .into_iter()
+            .map(|(item, call_sites)| self.push_node(item, call_sites, depth))
+            .collect();
+        self.nodes[ix].children = Children::Loaded(children);
+        self.update_visible_entries(cx);
+    }
+
+    fn collapse_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if let Some(node) = self.nodes.get_mut(ix) {
+            node.expanded = false;
+            self.update_visible_entries(cx);
+        }
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_entry {
+            self.expand_entry(ix, window, cx);
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.selected_entry else {
+            return;
+        };
+        if self.nodes[ix].expanded {
+            self.collapse_entry(ix, cx);
+        } else if let Some(parent) = self.parent_of(ix) {
+            self.selected_entry = Some(parent);
+            self.autoscroll_to_selection();
+            cx.notify();
+        }
+    }
+
+    fn parent_of(&self, ix: usize) -> Option<usize> {
+        self.nodes.iter().position(|node| match &node.children {
+            Children::Loaded(children) => children.contains(&ix),
+            _ => false,
+        })
+    }
+
+    fn toggle_direction(
+        &mut self,
+        _: &ToggleDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.kind = self.kind.opposite();
+        let items = self
+            .roots
+            .iter()
+            .map(|&root| self.nodes[root].item.clone())
+            .collect();
+        self.set_roots(items, window, cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        let next = match self.selected_visible_index() {
+            Some(visible_ix) => self.visible_entries.get(visible_ix + 1).copied(),
+            None => self.visible_entries.first().copied(),
+        };
+        if let Some(ix) = next {
+            self.select_entry(ix, window, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        let previous = match self.selected_visible_index() {
+            Some(visible_ix) => visible_ix
+                .checked_sub(1)
+                .and_then(|visible_ix| self.visible_entries.get(visible_ix))
+                .copied(),
+            None => self.visible_entries.last().copied(),
+        };
+        if let Some(ix) = previous {
+            self.select_entry(ix, window, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.visible_entries.first().copied() {
+            self.select_entry(ix, window, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.visible_entries.last().copied() {
+            self.select_entry(ix, window, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.navigate_to_entry(ix, window, cx);
+        }
+    }
+
+    fn selected_visible_index(&self) -> Option<usize> {
+        let selected_entry = self.selected_entry?;
+        self.visible_entries
+            .iter()
+            .position(|&ix| ix == selected_entry)
+    }
+
+    fn autoscroll_to_selection(&mut self) {
+        if let Some(visible_ix) = self.selected_visible_index() {
+            self.scroll_handle
+                .scroll_to_item(visible_ix, gpui::ScrollStrategy::Center);
+        }
+    }
+
+    /// Selects the entry and previews its call sites, keeping the focus in the panel.
+    fn select_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_entry = Some(ix);
+        self.autoscroll_to_selection();
+        let node = &self.nodes[ix];
+        if let Some(first_call_site) = node.call_sites.first() {
+            let buffer = first_call_site.buffer.clone();
+            let ranges = node
+                .call_sites
+                .iter()
+                .filter(|call_site| call_site.buffer == buffer)
+                .map(|call_site| call_site.range.clone())
+                .collect::<Vec<_>>();
+            self.open_ranges(buffer, ranges, false, window, cx);
+        } else {
+            let location = node.item.location().clone();
+            let selection_range = node.item.selection_range().clone();
+            self.open_ranges(location.buffer, vec![selection_range], false, window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Opens the definition of the entry's symbol and focuses its editor.
+    fn navigate_to_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_entry = Some(ix);
+        let item = &self.nodes[ix].item;
+        let buffer = item.location().buffer.clone();
+        let selection_range = item.selection_range().clone();
+        self.clear_call_site_highlights(cx);
+        self.open_ranges(buffer, vec![selection_range], true, window, cx);
+        cx.notify();
+    }
+
+    fn open_ranges(
+        &mut self,
+        buffer: Entity<Buffer>,
+        ranges: Vec<Range<language::Anchor>>,
+        focus: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let point_ranges = ranges
+            .iter()
+            .map(|range| range.to_point(&buffer_snapshot))
+            .collect::<Vec<_>>();
+        let Some(first_range) = point_ranges.first().cloned() else {
+            return;
+        };
+
+        let editor = workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            workspace.open_project_item::<Editor>(pane, buffer, true, focus, window, cx)
+        });
+        self.clear_call_site_highlights(cx);
+        editor.update(cx, |editor, cx| {
+            if focus {
+                editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                    selections.select_ranges([first_range.start..first_range.start])
+                });
+            } else {
+                let multi_buffer = editor.buffer().read(cx).snapshot(cx);
+                let highlight_ranges = point_ranges
+                    .iter()
+                    .map(|range| {
+                        multi_buffer.anchor_after(range.start)
+                            ..multi_buffer.anchor_before(range.end)
+                    })
+                    .collect::<Vec<_>>();
+                editor.highlight_background::<CallSiteHighlights>(
+                    &highlight_ranges,
+                    |colors| colors.editor_document_highlight_read_background,
+                    cx,
+                );
+                editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                    selections.select_ranges([first_range.start..first_range.start])
+                });
+            }
+        });
+        if !focus {
+            self.highlighted_editor = Some(editor.downgrade());
+        }
+    }
+
+    fn clear_call_site_highlights(&mut self, cx: &mut Context<Self>) {
+        if let Some(editor) = self
+            .highlighted_editor
+            .take()
+            .and_then(|editor| editor.upgrade())
+        {
+            editor.update(cx, |editor, cx| {
+                editor.clear_background_highlights::<CallSiteHighlights>(cx);
+            });
+        }
+    }
+
+    fn render_entry(&self, ix: usize, window: &Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = CallHierarchyPanelSettings::get_global(cx);
+        let node = &self.nodes[ix];
+        let is_selected = self.selected_entry == Some(ix);
+        let is_loading = matches!(node.children, Children::Loading(_));
+        let is_empty = matches!(&node.children, Children::Loaded(children) if children.is_empty());
+        let file_name = node
+            .item
+            .location()
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| SharedString::from(file.file_name(cx).to_string_lossy().into_owned()));
+        let call_count = node.call_sites.len();
+
+        div()
+            .id(ElementId::Integer(ix))
+            .on_click(cx.listener(move |panel, event: &ClickEvent, window, cx| {
+                if event.down.button == MouseButton::Right || event.down.first_mouse {
+                    return;
+                }
+                if event.down.click_count > 1 {
+                    panel.navigate_to_entry(ix, window, cx);
+                } else {
+                    panel.select_entry(ix, window, cx);
+                }
+            }))
+            .cursor_pointer()
+            .child(
+                ListItem::new(ix)
+                    .indent_level(node.depth)
+                    .indent_step_size(px(settings.indent_size))
+                    .toggle_state(is_selected)
+                    .toggle((!is_empty).then_some(node.expanded))
+                    .on_toggle(cx.listener(move |panel, _, window, cx| {
+                        if panel.nodes[ix].expanded {
+                            panel.collapse_entry(ix, cx);
+                        } else {
+                            panel.expand_entry(ix, window, cx);
+                        }
+                    }))
+                    .child(
+                        h_flex()
+                            .h_6()
+                            .ml_1()
+                            .gap_1()
+                            .child(
+                                Icon::new(symbol_kind_icon(node.item.kind()))
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(Label::new(node.item.name().to_string()).single_line())
+                            .when_some(node.item.detail(), |this, detail| {
+                                this.child(
+                                    Label::new(detail.to_string())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                        .single_line(),
+                                )
+                            })
+                            .when_some(file_name, |this, file_name| {
+                                this.child(
+                                    Label::new(file_name)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                        .single_line(),
+                                )
+                            })
+                            .when(call_count > 1, |this| {
+                                this.child(
+                                    Label::new(format!("({call_count})"))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .when(is_loading, |this| {
+                                this.child(
+                                    Label::new("Loading…")
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    ),
+            )
+            .border_1()
+            .border_r_2()
+            .rounded_none()
+            .hover(|style| {
+                if is_selected {
+                    style
+                } else {
+                    let hover_color = cx.theme().colors().ghost_element_hover;
+                    style.bg(hover_color).border_color(hover_color)
+                }
+            })
+            .when(
+                is_selected && self.focus_handle.contains_focused(window, cx),
+                |div| div.border_color(Color::Selected.color(cx)),
+            )
+    }
+}
+
+fn symbol_kind_icon(kind: lsp::SymbolKind) -> IconName {
+    match kind {
+        lsp::SymbolKind::FUNCTION
+        | lsp::SymbolKind::METHOD
+        | lsp::SymbolKind::CONSTRUCTOR
+        | lsp::SymbolKind::OPERATOR => IconName::Code,
+        lsp::SymbolKind::CLASS
+        | lsp::SymbolKind::INTERFACE
+        | lsp::SymbolKind::STRUCT
+        | lsp::SymbolKind::ENUM
+        | lsp::SymbolKind::TYPE_PARAMETER => IconName::Hash,
+        lsp::SymbolKind::FILE | lsp::SymbolKind::MODULE | lsp::SymbolKind::NAMESPACE => {
+            IconName::FileCode
+        }
+        _ => IconName::ListTree,
+    }
+}
+
+impl Panel for CallHierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Call Hierarchy Panel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        match CallHierarchyPanelSettings::get_global(cx).dock {
+            CallHierarchyPanelDockPosition::Left => DockPosition::Left,
+            CallHierarchyPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<CallHierarchyPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => {
+                        CallHierarchyPanelDockPosition::Left
+                    }
+                    DockPosition::Right => CallHierarchyPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| CallHierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        CallHierarchyPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::ArrowRightLeft)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Call Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _window: &Window, _: &App) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if self.active != active {
+            self.active = active;
+            if !active {
+                self.clear_call_site_highlights(cx);
+            }
+            self.serialize(cx);
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        6
+    }
+}
+
+impl Focusable for CallHierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<Event> for CallHierarchyPanel {}
+
+impl EventEmitter<PanelEvent> for CallHierarchyPanel {}
+
+impl Render for CallHierarchyPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = h_flex()
+            .px_2()
+            .py_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(self.kind.label()))
+            .child(
+                IconButton::new("toggle-hierarchy-direction", IconName::ArrowRightLeft)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title(
+                        format!("Show {}", self.kind.opposite().label()),
+                        &ToggleDirection,
+                    ))
+                    .on_click(cx.listener(|panel, _, window, cx| {
+                        panel.toggle_direction(&ToggleDirection, window, cx)
+                    })),
+            );
+
+        let contents = if self.visible_entries.is_empty() {
+            let message = if self.prepare_task.is_some() {
+                "Loading hierarchy…"
+            } else {
+                "No hierarchy available"
+            };
+            v_flex()
+                .flex_1()
+                .size_full()
+                .justify_center()
+                .child(h_flex().justify_center().child(Label::new(message)))
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.entity().clone(),
+                "call-hierarchy-entries",
+                self.visible_entries.len(),
+                |panel, range, window, cx| {
+                    panel.visible_entries[range]
+                        .to_vec()
+                        .into_iter()
+                        .map(|ix| panel.render_entry(ix, window, cx).into_any_element())
+                        .collect()
+                },
+            )
+            .with_sizing_behavior(ListSizingBehavior::Infer)
+            .track_scroll(self.scroll_handle.clone())
+            .size_full()
+            .into_any_element()
+        };
+
+        v_flex()
+            .id("call-hierarchy-panel")
+            .size_full()
+            .overflow_hidden()
+            .key_context(self.dispatch_context(window, cx))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .track_focus(&self.focus_handle)
+            .child(header)
+            .child(contents)
+    }
+}
@@ -62,6 +62,7 @@ pub enum DebugPanelItemEvent {
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ThreadItem {
+    Breakpoints,
     Console,
     LoadedSource,
     Modules,
@@ -161,6 +161,7 @@ impl Render for InertState {
                             initialize_args: None,
                             args: Default::default(),
                             locator: None,
+                            pre_launch_task: None,
                         },
                     });
                 } else {
@@ -323,6 +324,7 @@ impl InertState {
             initialize_args: None,
             args: Default::default(),
             locator: None,
+            pre_launch_task: None,
             tcp_connection: Some(TCPHost::default()),
         };
 
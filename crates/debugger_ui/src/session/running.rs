@@ -1,3 +1,4 @@
+mod breakpoint_list;
 mod console;
 mod loaded_source_list;
 mod module_list;
@@ -5,6 +6,7 @@ pub mod stack_frame_list;
 pub mod variable_list;
 
 use super::{DebugPanelItemEvent, ThreadItem};
+use breakpoint_list::BreakpointList;
 use console::Console;
 use dap::{Capabilities, Thread, client::SessionId, debugger_settings::DebuggerSettings};
 use gpui::{AppContext, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity};
@@ -39,6 +41,7 @@ pub struct RunningState {
     _subscriptions: Vec<Subscription>,
     stack_frame_list: Entity<stack_frame_list::StackFrameList>,
     loaded_source_list: Entity<loaded_source_list::LoadedSourceList>,
+    breakpoint_list: Entity<breakpoint_list::BreakpointList>,
 }
 
 impl Render for RunningState {
@@ -339,6 +342,11 @@ impl Render for RunningState {
                                     ))
                                 },
                             )
+                            .child(self.render_entry_button(
+                                &SharedString::from("Breakpoints"),
+                                ThreadItem::Breakpoints,
+                                cx,
+                            ))
                             .child(self.render_entry_button(
                                 &SharedString::from("Console"),
                                 ThreadItem::Console,
@@ -354,6 +362,9 @@ impl Render for RunningState {
                     .when(*active_thread_item == ThreadItem::LoadedSource, |this| {
                         this.size_full().child(self.loaded_source_list.clone())
                     })
+                    .when(*active_thread_item == ThreadItem::Breakpoints, |this| {
+                        this.size_full().child(self.breakpoint_list.clone())
+                    })
                     .when(*active_thread_item == ThreadItem::Console, |this| {
                         this.child(self.console.clone())
                     }),
@@ -375,13 +386,23 @@ impl RunningState {
             StackFrameList::new(workspace.clone(), session.clone(), weak_state, window, cx)
         });
 
-        let variable_list =
-            cx.new(|cx| VariableList::new(session.clone(), stack_frame_list.clone(), window, cx));
+        let variable_list = cx.new(|cx| {
+            VariableList::new(
+                session.clone(),
+                stack_frame_list.clone(),
+                workspace.clone(),
+                window,
+                cx,
+            )
+        });
 
         let module_list = cx.new(|cx| ModuleList::new(session.clone(), workspace.clone(), cx));
 
         let loaded_source_list = cx.new(|cx| LoadedSourceList::new(session.clone(), cx));
 
+        let breakpoint_list =
+            cx.new(|cx| BreakpointList::new(session.clone(), workspace.clone(), window, cx));
+
         let console = cx.new(|cx| {
             Console::new(
                 session.clone(),
@@ -429,6 +450,7 @@ impl RunningState {
             _remote_id: None,
             stack_frame_list,
             loaded_source_list,
+            breakpoint_list,
             session_id,
             show_console_indicator: false,
             active_thread_item: ThreadItem::Variables,
@@ -476,6 +498,11 @@ impl RunningState {
         &self.variable_list
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn breakpoint_list(&self) -> &Entity<BreakpointList> {
+        &self.breakpoint_list
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn are_breakpoints_ignored(&self, cx: &App) -> bool {
         self.session.read(cx).ignore_breakpoints()
@@ -0,0 +1,552 @@
+use std::sync::Arc;
+
+use editor::Editor;
+use gpui::{AnyElement, Entity, FocusHandle, Focusable, Subscription, WeakEntity};
+use project::debugger::{
+    breakpoint_store::{
+        BreakpointState, BreakpointStore, DataBreakpoint, ExceptionBreakpoint, FunctionBreakpoint,
+        NonSourceBreakpoints,
+    },
+    session::Session,
+};
+use ui::{Checkbox, Tooltip, prelude::*};
+use workspace::Workspace;
+
+/// What the condition editor is editing the condition of.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ConditionTarget {
+    ExceptionFilter(Arc<str>),
+    Function(Arc<str>),
+}
+
+/// Lists the exception filters of the debug adapter, and the function and data breakpoints of the project.
+pub struct BreakpointList {
+    session: Entity<Session>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    function_editor: Entity<Editor>,
+    condition_editor: Option<(ConditionTarget, Entity<Editor>)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BreakpointList {
+    pub fn new(
+        session: Entity<Session>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let function_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Add function breakpoint", cx);
+            editor
+        });
+
+        let mut _subscriptions = vec![cx.observe(&session, |_, _, cx| cx.notify())];
+        if let Some(workspace) = workspace.upgrade() {
+            let breakpoint_store = workspace.read(cx).project().read(cx).breakpoint_store();
+            _subscriptions.push(cx.observe(&breakpoint_store, |_, _, cx| cx.notify()));
+        }
+
+        Self {
+            session,
+            workspace,
+            focus_handle,
+            function_editor,
+            condition_editor: None,
+            _subscriptions,
+        }
+    }
+
+    fn breakpoint_store(&self, cx: &App) -> Option<Entity<BreakpointStore>> {
+        self.workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().read(cx).breakpoint_store())
+    }
+
+    fn non_source_breakpoints(&self, cx: &App) -> NonSourceBreakpoints {
+        self.breakpoint_store(cx)
+            .map(|store| store.read(cx).non_source_breakpoints().clone())
+            .unwrap_or_default()
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(breakpoint_store) = self.breakpoint_store(cx) else {
+            return;
+        };
+
+        if let Some((target, editor)) = self.condition_editor.take() {
+            let condition = editor.read(cx).text(cx);
+            let condition = (!condition.trim().is_empty()).then(|| Arc::from(condition.trim()));
+            let breakpoints = breakpoint_store.read(cx).non_source_breakpoints().clone();
+            breakpoint_store.update(cx, |store, cx| match target {
+                ConditionTarget::ExceptionFilter(filter_id) => {
+                    let state = breakpoints
+                        .exception_breakpoints
+                        .iter()
+                        .find(|breakpoint| breakpoint.filter_id == filter_id)
+                        .map_or(BreakpointState::Enabled, |breakpoint| breakpoint.state);
+                    store.set_exception_breakpoint(
+                        ExceptionBreakpoint {
+                            filter_id,
+                            condition,
+                            state,
+                        },
+                        cx,
+                    );
+                }
+                ConditionTarget::Function(name) => {
+                    if let Some(breakpoint) = breakpoints
+                        .function_breakpoints
+                        .into_iter()
+                        .find(|breakpoint| breakpoint.name == name)
+                    {
+                        store.set_function_breakpoint(
+                            FunctionBreakpoint {
+                                condition,
+                                ..breakpoint
+                            },
+                            cx,
+                        );
+                    }
+                }
+            });
+            self.focus_handle.focus(window);
+            cx.notify();
+            return;
+        }
+
+        let name = self.function_editor.read(cx).text(cx);
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        breakpoint_store.update(cx, |store, cx| {
+            store.set_function_breakpoint(FunctionBreakpoint::new(name), cx);
+        });
+        self.function_editor.update(cx, |editor, cx| {
+            editor.set_text("", window, cx);
+        });
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.condition_editor.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+
+    fn edit_condition(
+        &mut self,
+        target: ConditionTarget,
+        condition: Option<&str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Condition", cx);
+            if let Some(condition) = condition {
+                editor.set_text(condition, window, cx);
+            }
+            editor
+        });
+        editor.focus_handle(cx).focus(window);
+        self.condition_editor = Some((target, editor));
+        cx.notify();
+    }
+
+    fn render_section_header(&self, label: &'static str) -> AnyElement {
+        h_flex()
+            .w_full()
+            .px_1()
+            .pt_2()
+            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+            .into_any_element()
+    }
+
+    fn render_condition_editor(&self, target: &ConditionTarget, cx: &App) -> Option<AnyElement> {
+        let (editing, editor) = self.condition_editor.as_ref()?;
+        (editing == target).then(|| {
+            div()
+                .w_full()
+                .pl_6()
+                .py_0p5()
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(editor.clone())
+                .into_any_element()
+        })
+    }
+
+    fn render_exception_filters(
+        &self,
+        breakpoints: &NonSourceBreakpoints,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let capabilities = self.session.read(cx).capabilities().clone();
+        let Some(filters) = capabilities.exception_breakpoint_filters else {
+            return Vec::new();
+        };
+        let supports_conditions = capabilities
+            .supports_exception_filter_options
+            .unwrap_or_default();
+
+        let mut elements = Vec::new();
+        for (ix, filter) in filters.into_iter().enumerate() {
+            let breakpoint = breakpoints
+                .exception_breakpoints
+                .iter()
+                .find(|breakpoint| breakpoint.filter_id.as_ref() == filter.filter)
+                .cloned();
+            let enabled = breakpoint
+                .as_ref()
+                .map_or(filter.default.unwrap_or_default(), |breakpoint| {
+                    breakpoint.is_enabled()
+                });
+            let condition = breakpoint
+                .as_ref()
+                .and_then(|breakpoint| breakpoint.condition.clone());
+            let filter_id: Arc<str> = filter.filter.as_str().into();
+            let target = ConditionTarget::ExceptionFilter(filter_id.clone());
+
+            elements.push(
+                h_flex()
+                    .id(("exception-breakpoint", ix))
+                    .w_full()
+                    .gap_1()
+                    .px_1()
+                    .group("")
+                    .hover(|s| s.bg(cx.theme().colors().element_hover))
+                    .child(
+                        Checkbox::new(("exception-breakpoint-checkbox", ix), enabled.into())
+                            .on_click({
+                                let filter_id = filter_id.clone();
+                                let condition = condition.clone();
+                                cx.listener(move |this, state: &ToggleState, _, cx| {
+                                    let Some(store) = this.breakpoint_store(cx) else {
+                                        return;
+                                    };
+                                    store.update(cx, |store, cx| {
+                                        store.set_exception_breakpoint(
+                                            ExceptionBreakpoint {
+                                                filter_id: filter_id.clone(),
+                                                condition: condition.clone(),
+                                                state: if state.selected() {
+                                                    BreakpointState::Enabled
+                                                } else {
+                                                    BreakpointState::Disabled
+                                                },
+                                            },
+                                            cx,
+                                        );
+                                    });
+                                })
+                            }),
+                    )
+                    .child(Label::new(filter.label.clone()).size(LabelSize::Small))
+                    .when_some(condition.clone(), |this, condition| {
+                        this.child(
+                            Label::new(format!("if {condition}"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when_some(filter.description.clone(), |this, description| {
+                        this.tooltip(Tooltip::text(description))
+                    })
+                    .when(
+                        supports_conditions && filter.supports_condition.unwrap_or_default(),
+                        |this| {
+                            let tooltip = filter
+                                .condition_description
+                                .clone()
+                                .unwrap_or_else(|| "Edit Condition".to_owned());
+                            this.child(div().flex_1()).child(
+                                IconButton::new(
+                                    ("exception-breakpoint-condition", ix),
+                                    IconName::Pencil,
+                                )
+                                .icon_size(IconSize::XSmall)
+                                .visible_on_hover("")
+                                .tooltip(Tooltip::text(tooltip))
+                                .on_click(cx.listener(
+                                    move |this, _, window, cx| {
+                                        this.edit_condition(
+                                            ConditionTarget::ExceptionFilter(filter_id.clone()),
+                                            condition.as_deref(),
+                                            window,
+                                            cx,
+                                        );
+                                    },
+                                )),
+                            )
+                        },
+                    )
+                    .into_any_element(),
+            );
+            elements.extend(self.render_condition_editor(&target, cx));
+        }
+        elements
+    }
+
+    fn render_function_breakpoint(
+        &self,
+        ix: usize,
+        breakpoint: FunctionBreakpoint,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let target = ConditionTarget::Function(breakpoint.name.clone());
+        let details = match (&breakpoint.condition, &breakpoint.hit_condition) {
+            (Some(condition), Some(hit_condition)) => {
+                Some(format!("if {condition}, hit {hit_condition}"))
+            }
+            (Some(condition), None) => Some(format!("if {condition}")),
+            (None, Some(hit_condition)) => Some(format!("hit {hit_condition}")),
+            (None, None) => None,
+        };
+
+        let mut elements = vec![
+            h_flex()
+                .id(("function-breakpoint", ix))
+                .w_full()
+                .gap_1()
+                .px_1()
+                .group("")
+                .hover(|s| s.bg(cx.theme().colors().element_hover))
+                .child(
+                    Checkbox::new(
+                        ("function-breakpoint-checkbox", ix),
+                        breakpoint.is_enabled().into(),
+                    )
+                    .on_click({
+                        let breakpoint = breakpoint.clone();
+                        cx.listener(move |this, state: &ToggleState, _, cx| {
+                            let Some(store) = this.breakpoint_store(cx) else {
+                                return;
+                            };
+                            store.update(cx, |store, cx| {
+                                store.set_function_breakpoint(
+                                    FunctionBreakpoint {
+                                        state: if state.selected() {
+                                            BreakpointState::Enabled
+                                        } else {
+                                            BreakpointState::Disabled
+                                        },
+                                        ..breakpoint.clone()
+                                    },
+                                    cx,
+                                );
+                            });
+                        })
+                    }),
+                )
+                .child(Label::new(breakpoint.name.to_string()).size(LabelSize::Small))
+                .when_some(details, |this, details| {
+                    this.child(
+                        Label::new(details)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
+                .child(div().flex_1())
+                .child(
+                    IconButton::new(("function-breakpoint-condition", ix), IconName::Pencil)
+                        .icon_size(IconSize::XSmall)
+                        .visible_on_hover("")
+                        .tooltip(Tooltip::text("Edit Condition"))
+                        .on_click({
+                            let breakpoint = breakpoint.clone();
+                            cx.listener(move |this, _, window, cx| {
+                                this.edit_condition(
+                                    ConditionTarget::Function(breakpoint.name.clone()),
+                                    breakpoint.condition.as_deref(),
+                                    window,
+                                    cx,
+                                );
+                            })
+                        }),
+                )
+                .child(
+                    IconButton::new(("function-breakpoint-remove", ix), IconName::Close)
+                        .icon_size(IconSize::XSmall)
+                        .visible_on_hover("")
+                        .tooltip(Tooltip::text("Remove Breakpoint"))
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            let Some(store) = this.breakpoint_store(cx) else {
+                                return;
+                            };
+                            store.update(cx, |store, cx| {
+                                store.remove_function_breakpoint(&breakpoint.name, cx);
+                            });
+                        })),
+                )
+                .into_any_element(),
+        ];
+        elements.extend(self.render_condition_editor(&target, cx));
+        elements
+    }
+
+    fn render_data_breakpoint(
+        &self,
+        ix: usize,
+        breakpoint: DataBreakpoint,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        h_flex()
+            .id(("data-breakpoint", ix))
+            .w_full()
+            .gap_1()
+            .px_1()
+            .group("")
+            .hover(|s| s.bg(cx.theme().colors().element_hover))
+            .child(
+                Checkbox::new(
+                    ("data-breakpoint-checkbox", ix),
+                    breakpoint.is_enabled().into(),
+                )
+                .on_click({
+                    let breakpoint = breakpoint.clone();
+                    cx.listener(move |this, state: &ToggleState, _, cx| {
+                        let Some(store) = this.breakpoint_store(cx) else {
+                            return;
+                        };
+                        store.update(cx, |store, cx| {
+                            store.set_data_breakpoint(
+                                DataBreakpoint {
+                                    state: if state.selected() {
+                                        BreakpointState::Enabled
+                                    } else {
+                                        BreakpointState::Disabled
+                                    },
+                                    ..breakpoint.clone()
+                                },
+                                cx,
+                            );
+                        });
+                    })
+                }),
+            )
+            .child(Label::new(breakpoint.description.to_string()).size(LabelSize::Small))
+            .when_some(breakpoint.access_type, |this, access_type| {
+                this.child(
+                    Label::new(access_type.label())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .child(div().flex_1())
+            .child(
+                IconButton::new(("data-breakpoint-remove", ix), IconName::Close)
+                    .icon_size(IconSize::XSmall)
+                    .visible_on_hover("")
+                    .tooltip(Tooltip::text("Remove Breakpoint"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        let Some(store) = this.breakpoint_store(cx) else {
+                            return;
+                        };
+                        store.update(cx, |store, cx| {
+                            store.remove_data_breakpoint(&breakpoint.data_id, cx);
+                        });
+                    })),
+            )
+            .into_any_element()
+    }
+}
+
+impl Focusable for BreakpointList {
+    fn focus_handle(&self, _: &gpui::App) -> gpui::FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for BreakpointList {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let breakpoints = self.non_source_breakpoints(cx);
+        let capabilities = self.session.read(cx).capabilities().clone();
+
+        let exception_filters = self.render_exception_filters(&breakpoints, cx);
+        let has_exception_filters = !exception_filters.is_empty();
+        let supports_function_breakpoints = capabilities
+            .supports_function_breakpoints
+            .unwrap_or_default();
+        let supports_data_breakpoints = capabilities.supports_data_breakpoints.unwrap_or_default();
+
+        v_flex()
+            .id("breakpoint-list")
+            .key_context("BreakpointList")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .p_1()
+            .overflow_y_scroll()
+            .when(has_exception_filters, |this| {
+                this.child(self.render_section_header("Exception Breakpoints"))
+                    .children(exception_filters)
+            })
+            .when(supports_function_breakpoints, |this| {
+                this.child(self.render_section_header("Function Breakpoints"))
+                    .children(
+                        breakpoints
+                            .function_breakpoints
+                            .iter()
+                            .cloned()
+                            .enumerate()
+                            .flat_map(|(ix, breakpoint)| {
+                                self.render_function_breakpoint(ix, breakpoint, cx)
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .child(
+                        div()
+                            .w_full()
+                            .px_1()
+                            .py_0p5()
+                            .border_b_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(self.function_editor.clone()),
+                    )
+            })
+            .when(supports_data_breakpoints, |this| {
+                this.child(self.render_section_header("Data Breakpoints"))
+                    .children(
+                        breakpoints
+                            .data_breakpoints
+                            .iter()
+                            .cloned()
+                            .enumerate()
+                            .map(|(ix, breakpoint)| {
+                                self.render_data_breakpoint(ix, breakpoint, cx)
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .when(breakpoints.data_breakpoints.is_empty(), |this| {
+                        this.child(
+                            h_flex().px_1().child(
+                                Label::new("Add data breakpoints from the variable list")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                        )
+                    })
+            })
+            .when(
+                !has_exception_filters
+                    && !supports_function_breakpoints
+                    && !supports_data_breakpoints,
+                |this| {
+                    this.child(
+                        h_flex().px_1().child(
+                            Label::new(
+                                "The debug adapter does not support exception, function or data breakpoints",
+                            )
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        ),
+                    )
+                },
+            )
+    }
+}
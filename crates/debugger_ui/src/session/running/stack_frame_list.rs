@@ -9,6 +9,7 @@ use gpui::{
 };
 
 use language::PointUtf16;
+use project::debugger::breakpoint_store::ActiveStackFrame;
 use project::debugger::session::{Session, SessionEvent, StackFrame};
 use project::{ProjectItem, ProjectPath};
 use ui::{Tooltip, prelude::*};
@@ -230,6 +231,7 @@ impl StackFrameList {
         };
 
         let row = (stack_frame.line.saturating_sub(1)) as u32;
+        let stack_frame_id = stack_frame.id;
 
         let Some(abs_path) = self.abs_path_from_stack_frame(&stack_frame) else {
             return Task::ready(Err(anyhow!("Project path not found")));
@@ -288,7 +290,12 @@ impl StackFrameList {
 
                     breakpoint_store.update(cx, |store, cx| {
                         store.set_active_position(
-                            (this.session.read(cx).session_id(), abs_path, position),
+                            ActiveStackFrame {
+                                session_id: this.session.read(cx).session_id(),
+                                stack_frame_id,
+                                path: abs_path,
+                                position,
+                            },
                             cx,
                         );
                     })
@@ -4,13 +4,17 @@ use editor::Editor;
 use gpui::{
     AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Entity, FocusHandle, Focusable,
     Hsla, MouseButton, MouseDownEvent, Point, Stateful, Subscription, TextStyleRefinement,
-    UniformListScrollHandle, actions, anchored, deferred, uniform_list,
+    UniformListScrollHandle, WeakEntity, actions, anchored, deferred, uniform_list,
 };
 use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
-use project::debugger::session::{Session, SessionEvent};
+use project::debugger::{
+    breakpoint_store::{BreakpointState, DataBreakpoint, DataBreakpointAccessType},
+    session::{Session, SessionEvent},
+};
 use std::{collections::HashMap, ops::Range, sync::Arc};
 use ui::{ContextMenu, ListItem, Scrollbar, ScrollbarState, prelude::*};
 use util::{debug_panic, maybe};
+use workspace::Workspace;
 
 actions!(variable_list, [ExpandSelectedEntry, CollapseSelectedEntry]);
 
@@ -135,6 +139,7 @@ pub struct VariableList {
     list_handle: UniformListScrollHandle,
     scrollbar_state: ScrollbarState,
     session: Entity<Session>,
+    workspace: WeakEntity<Workspace>,
     selection: Option<EntryPath>,
     open_context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     focus_handle: FocusHandle,
@@ -147,6 +152,7 @@ impl VariableList {
     pub fn new(
         session: Entity<Session>,
         stack_frame_list: Entity<StackFrameList>,
+        workspace: WeakEntity<Workspace>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -174,6 +180,7 @@ impl VariableList {
             scrollbar_state: ScrollbarState::new(list_state.clone()),
             list_handle: list_state,
             session,
+            workspace,
             focus_handle,
             _subscriptions,
             selected_stack_frame_id: None,
@@ -500,8 +507,35 @@ impl VariableList {
         let variable_value = dap_var.value.clone();
         let variable_name = dap_var.name.clone();
         let this = cx.entity().clone();
+        // Only the host can ask the adapter for data ids, so guests cannot add data breakpoints.
+        let session = self.session.read(cx);
+        let supports_data_breakpoints = session.is_local()
+            && session
+                .capabilities()
+                .supports_data_breakpoints
+                .unwrap_or_default();
 
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+            let menu = if supports_data_breakpoints {
+                [
+                    DataBreakpointAccessType::Write,
+                    DataBreakpointAccessType::Read,
+                    DataBreakpointAccessType::ReadWrite,
+                ]
+                .into_iter()
+                .fold(menu, |menu, access_type| {
+                    let this = this.clone();
+                    let variable = variable.clone();
+                    menu.entry(access_type.label(), None, move |_, cx| {
+                        this.update(cx, |variable_list, cx| {
+                            variable_list.add_data_breakpoint(&variable, access_type, cx);
+                        });
+                    })
+                })
+                .separator()
+            } else {
+                menu
+            };
             menu.entry("Copy name", None, move |_, cx| {
                 cx.write_to_clipboard(ClipboardItem::new_string(variable_name.clone()))
             })
@@ -539,6 +573,75 @@ impl VariableList {
         self.open_context_menu = Some((context_menu, position, subscription));
     }
 
+    /// Asks the adapter for the data id of the variable, and adds a data breakpoint on it.
+    fn add_data_breakpoint(
+        &mut self,
+        entry: &ListEntry,
+        access_type: DataBreakpointAccessType,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(variable) = entry.as_variable() else {
+            return;
+        };
+        let Some(state) = self.entry_states.get(&entry.path) else {
+            return;
+        };
+        let Some(breakpoint_store) = self
+            .workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().read(cx).breakpoint_store())
+        else {
+            return;
+        };
+
+        let name = variable.name.clone();
+        let session_id = self.session.read(cx).session_id();
+        let info = self.session.read(cx).data_breakpoint_info(
+            state.parent_reference,
+            name.clone(),
+            self.selected_stack_frame_id,
+            cx,
+        );
+        cx.spawn(async move |_, cx| {
+            let info = info.await?;
+            let Some(data_id) = info.data_id else {
+                anyhow::bail!(
+                    "Cannot add a data breakpoint on `{name}`: {}",
+                    info.description
+                );
+            };
+            if let Some(access_types) = &info.access_types {
+                if !access_types.iter().any(|supported| {
+                    DataBreakpointAccessType::from(supported.clone()) == access_type
+                }) {
+                    anyhow::bail!(
+                        "The debug adapter does not support `{}` on `{name}`",
+                        access_type.label()
+                    );
+                }
+            }
+            breakpoint_store.update(cx, |store, cx| {
+                store.set_data_breakpoint(
+                    DataBreakpoint {
+                        data_id: data_id.into(),
+                        session_id: if info.can_persist == Some(true) {
+                            None
+                        } else {
+                            Some(session_id)
+                        },
+                        description: info.description.into(),
+                        access_type: Some(access_type),
+                        condition: None,
+                        hit_condition: None,
+                        state: BreakpointState::Enabled,
+                    },
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     #[track_caller]
     #[cfg(any(test, feature = "test-support"))]
     pub fn assert_visual_entries(&self, expected: Vec<&str>) {
@@ -90,6 +90,7 @@ async fn test_show_attach_modal_and_select_process(
                         initialize_args: None,
                         tcp_connection: Some(TCPHost::default()),
                         locator: None,
+                        pre_launch_task: None,
                         args: Default::default(),
                     },
                     vec![
@@ -4,8 +4,8 @@ use dap::{
     StartDebuggingRequestArgumentsRequest,
     client::SessionId,
     requests::{
-        Continue, Disconnect, Launch, Next, RunInTerminal, SetBreakpoints, StackTrace,
-        StartDebugging, StepBack, StepIn, StepOut, Threads,
+        Continue, Disconnect, Launch, Next, RunInTerminal, SetBreakpoints, SetExceptionBreakpoints,
+        StackTrace, StartDebugging, StepBack, StepIn, StepOut, Threads,
     },
 };
 use editor::{
@@ -15,7 +15,10 @@ use editor::{
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use project::{
     FakeFs, Project,
-    debugger::session::{ThreadId, ThreadStatus},
+    debugger::{
+        breakpoint_store::{BreakpointState, ExceptionBreakpoint},
+        session::{ThreadId, ThreadStatus},
+    },
 };
 use serde_json::json;
 use std::{
@@ -1503,3 +1506,108 @@ async fn test_debug_session_is_shutdown_when_attach_and_launch_request_fails(
         );
     });
 }
+
+#[gpui::test]
+async fn test_send_exception_breakpoints_with_adapter_filters(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let task = project.update(cx, |project, cx| {
+        project.fake_debug_session(
+            dap::DebugRequestType::Launch(LaunchConfig::default()),
+            Some(dap::Capabilities {
+                exception_breakpoint_filters: Some(vec![
+                    dap::ExceptionBreakpointsFilter {
+                        filter: "uncaught".into(),
+                        label: "Uncaught Exceptions".into(),
+                        default: Some(true),
+                        ..Default::default()
+                    },
+                    dap::ExceptionBreakpointsFilter {
+                        filter: "raised".into(),
+                        label: "Raised Exceptions".into(),
+                        supports_condition: Some(true),
+                        ..Default::default()
+                    },
+                ]),
+                supports_exception_filter_options: Some(true),
+                ..Default::default()
+            }),
+            false,
+            cx,
+        )
+    });
+
+    let session = task.await.unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    let called_set_exception_breakpoints = Arc::new(AtomicBool::new(false));
+
+    client
+        .on_request::<SetExceptionBreakpoints, _>({
+            let called_set_exception_breakpoints = called_set_exception_breakpoints.clone();
+            move |_, args| {
+                assert_eq!(
+                    vec!["uncaught".to_string()],
+                    args.filters,
+                    "Filters the user did not configure use the adapter's default"
+                );
+                assert_eq!(
+                    Some(vec![dap::ExceptionFilterOptions {
+                        filter_id: "raised".into(),
+                        condition: Some("ValueError".into()),
+                        mode: None,
+                    }]),
+                    args.filter_options,
+                );
+
+                called_set_exception_breakpoints.store(true, Ordering::SeqCst);
+
+                Ok(dap::SetExceptionBreakpointsResponse { breakpoints: None })
+            }
+        })
+        .await;
+
+    project.update(cx, |project, cx| {
+        project.breakpoint_store().update(cx, |store, cx| {
+            store.set_exception_breakpoint(
+                ExceptionBreakpoint {
+                    filter_id: "raised".into(),
+                    condition: Some("ValueError".into()),
+                    state: BreakpointState::Enabled,
+                },
+                cx,
+            );
+        })
+    });
+    cx.run_until_parked();
+
+    assert!(
+        called_set_exception_breakpoints.load(std::sync::atomic::Ordering::SeqCst),
+        "SetExceptionBreakpoints request must be called when an exception filter changes"
+    );
+
+    let shutdown_session = project.update(cx, |project, cx| {
+        project.dap_store().update(cx, |dap_store, cx| {
+            dap_store.shutdown_session(session.read(cx).session_id(), cx)
+        })
+    });
+
+    shutdown_session.await.unwrap();
+}
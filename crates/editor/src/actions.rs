@@ -312,6 +312,8 @@ actions!(
         GoToPreviousHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToNextConflict,
+        GoToPreviousConflict,
         GoToPreviousDiagnostic,
         GoToTypeDefinition,
         GoToTypeDefinitionSplit,
@@ -326,6 +328,7 @@ actions!(
         KillRingYank,
         LineDown,
         LineUp,
+        MarkConflictsResolved,
         MoveDown,
         MoveLeft,
         MoveLineDown,
@@ -365,9 +368,13 @@ actions!(
         PageUp,
         Paste,
         PreviousEditPrediction,
+        PreviewRename,
         Redo,
         RedoSelection,
         Rename,
+        ResolveConflictWithBoth,
+        ResolveConflictWithOurs,
+        ResolveConflictWithTheirs,
         RestartLanguageServer,
         RevealInFileManager,
         ReverseLines,
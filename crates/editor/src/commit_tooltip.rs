@@ -1,6 +1,7 @@
 use futures::Future;
 use git::PullRequest;
 use git::blame::BlameEntry;
+use git::repository::{CommitSignature, SignatureStatus};
 use gpui::{
     App, Asset, ClipboardItem, Element, Entity, MouseButton, ParentElement, Render, ScrollHandle,
     StatefulInteractiveElement,
@@ -11,7 +12,7 @@ use std::hash::Hash;
 use theme::ThemeSettings;
 use time::{OffsetDateTime, UtcOffset};
 use time_format::format_local_timestamp;
-use ui::{Avatar, Divider, IconButtonShape, prelude::*, tooltip_container};
+use ui::{Avatar, Divider, IconButtonShape, Tooltip, prelude::*, tooltip_container};
 use url::Url;
 
 use crate::git::blame::GitRemote;
@@ -24,6 +25,7 @@ pub struct CommitDetails {
     pub author_email: SharedString,
     pub commit_time: OffsetDateTime,
     pub message: Option<ParsedCommitMessage>,
+    pub signature: Option<CommitSignature>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -140,6 +142,7 @@ impl CommitTooltip {
                     .into(),
                 author_email: blame.author_mail.clone().unwrap_or("".to_string()).into(),
                 message: details,
+                signature: None,
             },
             window,
             cx,
@@ -206,6 +209,8 @@ impl Render for CommitTooltip {
             .as_ref()
             .and_then(|details| details.pull_request.clone());
 
+        let signature = self.commit.signature.as_ref().map(render_signature);
+
         let ui_font_size = ThemeSettings::get_global(cx).ui_font_size(cx);
         let message_max_height = window.line_height() * 12 + (ui_font_size / 0.4);
 
@@ -255,6 +260,7 @@ impl Render for CommitTooltip {
                                 .child(
                                     h_flex()
                                         .gap_1p5()
+                                        .children(signature)
                                         .when_some(pull_request, |this, pr| {
                                             this.child(
                                                 Button::new(
@@ -323,6 +329,29 @@ impl Render for CommitTooltip {
     }
 }
 
+fn render_signature(signature: &CommitSignature) -> AnyElement {
+    let (label, icon, color) = match signature.status {
+        SignatureStatus::Good => ("Signed", IconName::Check, Color::Success),
+        SignatureStatus::Untrusted => ("Signed", IconName::Check, Color::Muted),
+        SignatureStatus::Expired => ("Expired Signature", IconName::Warning, Color::Warning),
+        SignatureStatus::Revoked => ("Revoked Signature", IconName::Warning, Color::Warning),
+        SignatureStatus::Bad => ("Bad Signature", IconName::XCircle, Color::Error),
+        SignatureStatus::Unverified => ("Unverified Signature", IconName::Warning, Color::Muted),
+    };
+    let tooltip = if signature.signer.is_empty() {
+        SharedString::from(label)
+    } else {
+        format!("{label} by {}", signature.signer).into()
+    };
+    h_flex()
+        .id("commit-signature")
+        .gap_1()
+        .child(Icon::new(icon).size(IconSize::Small).color(color))
+        .child(Label::new(label).size(LabelSize::Small).color(color))
+        .tooltip(Tooltip::text(tooltip))
+        .into_any_element()
+}
+
 fn blame_entry_timestamp(blame_entry: &BlameEntry, format: time_format::TimestampFormat) -> String {
     match blame_entry.author_offset_date_time() {
         Ok(timestamp) => {
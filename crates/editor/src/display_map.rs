@@ -45,8 +45,8 @@ use inlay_map::{InlayMap, InlaySnapshot};
 pub use inlay_map::{InlayOffset, InlayPoint};
 pub use invisibles::{is_invisible, replacement};
 use language::{
-    ChunkRenderer, OffsetUtf16, Point, Subscription as BufferSubscription,
-    language_settings::language_settings,
+    BufferSnapshot, ChunkRenderer, HighlightId, OffsetUtf16, Point,
+    Subscription as BufferSubscription, language_settings::language_settings,
 };
 use lsp::DiagnosticSeverity;
 use multi_buffer::{
@@ -82,6 +82,19 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type SemanticTokenHighlights = TreeMap<BufferId, Arc<[(Range<Anchor>, HighlightId)]>>;
+type FoldRangesByBuffer = TreeMap<BufferId, FoldRanges>;
+
+/// Where the foldable ranges of a buffer come from. Buffers without fold ranges are folded
+/// based on their indentation.
+#[derive(Clone, Debug)]
+pub enum FoldRanges {
+    /// The syntax nodes captured by the folds query of the buffer's language.
+    Syntax,
+    /// Line ranges reported by a language server, sorted by their start and anchored at the
+    /// start of their first and last lines.
+    LanguageServer(Arc<[Range<text::Anchor>]>),
+}
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -105,6 +118,10 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Syntax highlights reported by language servers, per buffer.
+    semantic_token_highlights: SemanticTokenHighlights,
+    /// The source of foldable ranges, per buffer.
+    fold_ranges: FoldRangesByBuffer,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -148,6 +165,8 @@ impl DisplayMap {
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_token_highlights: Default::default(),
+            fold_ranges: Default::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -175,6 +194,8 @@ impl DisplayMap {
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
+            fold_ranges: self.fold_ranges.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -491,6 +512,35 @@ impl DisplayMap {
         cleared
     }
 
+    /// Replaces the semantic token highlights of the given buffer.
+    ///
+    /// The ranges must be sorted and must not overlap.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        buffer_id: BufferId,
+        highlights: Arc<[(Range<Anchor>, HighlightId)]>,
+    ) {
+        if highlights.is_empty() {
+            self.semantic_token_highlights.remove(&buffer_id);
+        } else {
+            self.semantic_token_highlights.insert(buffer_id, highlights);
+        }
+    }
+
+    pub(crate) fn clear_semantic_token_highlights(&mut self, buffer_id: BufferId) {
+        self.semantic_token_highlights.remove(&buffer_id);
+    }
+
+    /// Replaces the source of the foldable ranges of the given buffer.
+    pub(crate) fn set_fold_ranges(&mut self, buffer_id: BufferId, fold_ranges: FoldRanges) {
+        self.fold_ranges.insert(buffer_id, fold_ranges);
+    }
+
+    /// Makes the given buffer fold based on its indentation.
+    pub(crate) fn clear_fold_ranges(&mut self, buffer_id: BufferId) {
+        self.fold_ranges.remove(&buffer_id);
+    }
+
     pub fn set_font(&self, font: Font, font_size: Pixels, cx: &mut Context<Self>) -> bool {
         self.wrap_map
             .update(cx, |map, cx| map.set_font_with_size(font, font_size, cx))
@@ -553,6 +603,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
     pub styles: HighlightStyles,
 }
 
@@ -687,6 +738,8 @@ pub struct DisplaySnapshot {
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
+    fold_ranges: FoldRangesByBuffer,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -853,6 +906,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 styles: highlight_styles,
             },
         )
@@ -1168,6 +1222,75 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Returns whether a foldable range starts at the given row, according to the fold ranges
+    /// of its buffer or, when those aren't available, to its indentation.
+    pub fn starts_fold(&self, buffer_row: MultiBufferRow) -> bool {
+        match self.fold_range_for_buffer_row(buffer_row) {
+            Some(fold_range) => fold_range.is_some(),
+            None => self.starts_indent(buffer_row),
+        }
+    }
+
+    /// Returns the range to fold for the given row according to the fold ranges of its buffer,
+    /// or `None` if the buffer is folded based on its indentation.
+    fn fold_range_for_buffer_row(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<Option<Range<Point>>> {
+        let line_start = MultiBufferPoint::new(buffer_row.0, 0);
+        let line_end =
+            MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
+        let mut excerpt = self
+            .buffer_snapshot
+            .excerpt_containing(line_start..line_end)?;
+        let fold_ranges = self.fold_ranges.get(&excerpt.buffer_id())?;
+        let buffer = excerpt.buffer();
+        let buffer_line = excerpt.map_range_to_buffer(
+            line_start.to_offset(&self.buffer_snapshot)..line_end.to_offset(&self.buffer_snapshot),
+        );
+        let row = buffer.offset_to_point(buffer_line.start).row;
+
+        let end_row = match fold_ranges {
+            FoldRanges::Syntax => {
+                let has_folds_query = buffer
+                    .language()
+                    .and_then(|language| language.grammar())
+                    .is_some_and(|grammar| grammar.fold_config.is_some());
+                if !has_folds_query {
+                    return None;
+                }
+                buffer
+                    .syntax_fold_ranges(buffer_line.clone())
+                    .filter(|range| buffer.offset_to_point(range.start).row == row)
+                    .map(|range| syntax_fold_end_row(buffer, range.end))
+                    .max()
+            }
+            FoldRanges::LanguageServer(ranges) => {
+                let start_ix = ranges.partition_point(|range| {
+                    buffer.summary_for_anchor::<Point>(&range.start).row < row
+                });
+                ranges[start_ix..]
+                    .iter()
+                    .take_while(|range| buffer.summary_for_anchor::<Point>(&range.start).row == row)
+                    .map(|range| buffer.summary_for_anchor::<Point>(&range.end).row)
+                    .max()
+            }
+        };
+        let Some(end_row) = end_row.filter(|end_row| *end_row > row) else {
+            return Some(None);
+        };
+
+        let end = buffer.point_to_offset(Point::new(end_row, buffer.line_len(end_row)));
+        let buffer_range = buffer_line.end..end;
+        if !excerpt.contains_buffer_range(buffer_range.clone()) {
+            return Some(None);
+        }
+        let range = excerpt.map_range_from_buffer(buffer_range);
+        Some(Some(
+            range.start.to_point(&self.buffer_snapshot)..range.end.to_point(&self.buffer_snapshot),
+        ))
+    }
+
     pub fn crease_for_buffer_row(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
         let start = MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
         if let Some(crease) = self
@@ -1204,6 +1327,15 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
+        } else if let Some(fold_range) = self.fold_range_for_buffer_row(buffer_row) {
+            let range = fold_range.filter(|_| !self.is_line_folded(buffer_row))?;
+            Some(Crease::Inline {
+                range,
+                placeholder: self.fold_placeholder.clone(),
+                render_toggle: None,
+                render_trailer: None,
+                metadata: None,
+            })
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
@@ -1278,6 +1410,24 @@ impl DisplaySnapshot {
     }
 }
 
+/// Returns the last row to fold for a syntax node ending at the given offset. A last line that
+/// only closes the node, like `}` or `)`, is left visible, as in indentation based folds.
+fn syntax_fold_end_row(buffer: &BufferSnapshot, end: usize) -> u32 {
+    let end = buffer.offset_to_point(end);
+    if end.column == 0 {
+        return end.row.saturating_sub(1);
+    }
+    let last_line = buffer
+        .text_for_range(Point::new(end.row, 0)..end)
+        .collect::<String>();
+    let last_line = last_line.trim();
+    if !last_line.is_empty() && !last_line.chars().any(char::is_alphanumeric) {
+        end.row.saturating_sub(1)
+    } else {
+        end.row
+    }
+}
+
 #[derive(Copy, Clone, Default, Eq, Ord, PartialOrd, PartialEq)]
 pub struct DisplayPoint(BlockPoint);
 
@@ -1,6 +1,6 @@
 use collections::BTreeMap;
 use gpui::HighlightStyle;
-use language::Chunk;
+use language::{Chunk, HighlightId};
 use multi_buffer::{Anchor, MultiBufferChunks, MultiBufferSnapshot, ToOffset as _};
 use std::{
     any::TypeId,
@@ -11,16 +11,24 @@ use std::{
     vec,
 };
 use sum_tree::TreeMap;
+use text::BufferId;
+
+type SemanticTokenHighlights = TreeMap<BufferId, Arc<[(Range<Anchor>, HighlightId)]>>;
 
 pub struct CustomHighlightsChunks<'a> {
     buffer_chunks: MultiBufferChunks<'a>,
     buffer_chunk: Option<Chunk<'a>>,
     offset: usize,
+    language_aware: bool,
     multibuffer_snapshot: &'a MultiBufferSnapshot,
 
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
     active_highlights: BTreeMap<TypeId, HighlightStyle>,
     text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+
+    semantic_token_endpoints: Peekable<vec::IntoIter<SemanticTokenEndpoint>>,
+    active_semantic_token: Option<HighlightId>,
+    semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -31,11 +39,19 @@ struct HighlightEndpoint {
     style: HighlightStyle,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct SemanticTokenEndpoint {
+    offset: usize,
+    is_start: bool,
+    highlight_id: HighlightId,
+}
+
 impl<'a> CustomHighlightsChunks<'a> {
     pub fn new(
         range: Range<usize>,
         language_aware: bool,
         text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+        semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
         multibuffer_snapshot: &'a MultiBufferSnapshot,
     ) -> Self {
         Self {
@@ -50,6 +66,16 @@ impl<'a> CustomHighlightsChunks<'a> {
                 multibuffer_snapshot,
             ),
             active_highlights: Default::default(),
+            semantic_token_highlights,
+            semantic_token_endpoints: create_semantic_token_endpoints(
+                &range,
+                language_aware
+                    .then_some(semantic_token_highlights)
+                    .flatten(),
+                multibuffer_snapshot,
+            ),
+            active_semantic_token: None,
+            language_aware,
             multibuffer_snapshot,
         }
     }
@@ -57,10 +83,18 @@ impl<'a> CustomHighlightsChunks<'a> {
     pub fn seek(&mut self, new_range: Range<usize>) {
         self.highlight_endpoints =
             create_highlight_endpoints(&new_range, self.text_highlights, self.multibuffer_snapshot);
+        self.semantic_token_endpoints = create_semantic_token_endpoints(
+            &new_range,
+            self.language_aware
+                .then_some(self.semantic_token_highlights)
+                .flatten(),
+            self.multibuffer_snapshot,
+        );
         self.offset = new_range.start;
         self.buffer_chunks.seek(new_range);
         self.buffer_chunk.take();
-        self.active_highlights.clear()
+        self.active_highlights.clear();
+        self.active_semantic_token.take();
     }
 }
 
@@ -112,6 +146,51 @@ fn create_highlight_endpoints(
     highlight_endpoints.into_iter().peekable()
 }
 
+fn create_semantic_token_endpoints(
+    range: &Range<usize>,
+    semantic_token_highlights: Option<&SemanticTokenHighlights>,
+    buffer: &MultiBufferSnapshot,
+) -> iter::Peekable<vec::IntoIter<SemanticTokenEndpoint>> {
+    let mut endpoints = Vec::new();
+    if let Some(semantic_token_highlights) = semantic_token_highlights {
+        let start = buffer.anchor_after(range.start);
+        let end = buffer.anchor_after(range.end);
+        for (_, tokens) in semantic_token_highlights.iter() {
+            let start_ix = match tokens.binary_search_by(|(probe, _)| {
+                let cmp = probe.end.cmp(&start, &buffer);
+                if cmp.is_gt() {
+                    cmp::Ordering::Greater
+                } else {
+                    cmp::Ordering::Less
+                }
+            }) {
+                Ok(i) | Err(i) => i,
+            };
+
+            for (range, highlight_id) in &tokens[start_ix..] {
+                if range.start.cmp(&end, &buffer).is_ge() {
+                    break;
+                }
+
+                endpoints.push(SemanticTokenEndpoint {
+                    offset: range.start.to_offset(&buffer),
+                    is_start: true,
+                    highlight_id: *highlight_id,
+                });
+                endpoints.push(SemanticTokenEndpoint {
+                    offset: range.end.to_offset(&buffer),
+                    is_start: false,
+                    highlight_id: *highlight_id,
+                });
+            }
+        }
+        // Tokens don't overlap, so when one token ends where the next one starts,
+        // the end must be processed first.
+        endpoints.sort_by_key(|endpoint| (endpoint.offset, endpoint.is_start));
+    }
+    endpoints.into_iter().peekable()
+}
+
 impl<'a> Iterator for CustomHighlightsChunks<'a> {
     type Item = Chunk<'a>;
 
@@ -130,6 +209,19 @@ impl<'a> Iterator for CustomHighlightsChunks<'a> {
                 break;
             }
         }
+        while let Some(endpoint) = self.semantic_token_endpoints.peek().copied() {
+            if endpoint.offset <= self.offset {
+                if endpoint.is_start {
+                    self.active_semantic_token = Some(endpoint.highlight_id);
+                } else if self.active_semantic_token == Some(endpoint.highlight_id) {
+                    self.active_semantic_token = None;
+                }
+                self.semantic_token_endpoints.next();
+            } else {
+                next_highlight_endpoint = next_highlight_endpoint.min(endpoint.offset);
+                break;
+            }
+        }
 
         let chunk = self
             .buffer_chunk
@@ -148,6 +240,9 @@ impl<'a> Iterator for CustomHighlightsChunks<'a> {
             text: prefix,
             ..chunk.clone()
         };
+        if let Some(highlight_id) = self.active_semantic_token {
+            prefix.syntax_highlight_id = Some(highlight_id);
+        }
         if !self.active_highlights.is_empty() {
             let mut highlight_style = HighlightStyle::default();
             for active_highlight in self.active_highlights.values() {
@@ -0,0 +1,330 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use gpui::{
+    App, Context, Entity, HighlightStyle, Hsla, InteractiveElement as _, IntoElement as _,
+    ParentElement as _, Pixels, Point, Rgba, StatefulInteractiveElement as _, Styled as _,
+    WeakEntity, Window, div, rgb,
+};
+use language::{Bias, Buffer};
+use project::{ColorPresentation, DocumentColor};
+use text::BufferId;
+use theme::ActiveTheme as _;
+use ui::{ContextMenu, h_flex, px, v_flex};
+use util::{ResultExt as _, post_inc};
+
+use crate::{
+    Editor, EditorMode, EditorSnapshot, InlayId,
+    display_map::{Inlay, InlayOffset},
+    element::PointForPosition,
+    hover_links::InlayHighlight,
+    mouse_context_menu::MouseContextMenu,
+};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
+const SWATCH: &str = "■";
+
+/// The colors offered by the color picker, in addition to the notations of the current color.
+const PALETTE: [u32; 16] = [
+    0x000000, 0x808080, 0xc0c0c0, 0xffffff, 0x800000, 0xff0000, 0x808000, 0xffff00, 0x008000,
+    0x00ff00, 0x008080, 0x00ffff, 0x000080, 0x0000ff, 0x800080, 0xff00ff,
+];
+
+enum DocumentColorSwatch {}
+
+/// Requests the colors of the given buffer, or of all buffers of the editor, and shows a swatch
+/// before each of them once the language server responds.
+pub(super) fn refresh_document_colors(
+    editor: &mut Editor,
+    for_buffer: Option<BufferId>,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(provider) = editor.semantics_provider.clone() else {
+        return;
+    };
+
+    let multi_buffer = editor.buffer.read(cx);
+    let buffers = match for_buffer {
+        Some(buffer_id) => multi_buffer.buffer(buffer_id).into_iter().collect(),
+        None => multi_buffer.all_buffers().into_iter().collect::<Vec<_>>(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let provider = provider.clone();
+        let task = cx.spawn(async move |editor, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            let Some(colors_task) = cx
+                .update(|cx| provider.document_colors(&buffer, cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let colors = colors_task.await.log_err().unwrap_or_default();
+            editor
+                .update(cx, |editor, cx| {
+                    if colors.is_empty() {
+                        if editor.document_colors.remove(&buffer_id).is_none() {
+                            return;
+                        }
+                    } else {
+                        editor.document_colors.insert(buffer_id, colors.into());
+                    }
+                    update_color_swatches(editor, cx);
+                })
+                .ok();
+        });
+        editor.document_colors_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Replaces the swatches shown in the editor with the ones of the cached document colors.
+pub(super) fn update_color_swatches(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let multi_buffer = editor.buffer.read(cx);
+    let snapshot = multi_buffer.snapshot(cx);
+    let mut swatches = HashMap::default();
+    let mut to_insert = Vec::new();
+    let mut highlights = Vec::new();
+    for (buffer_id, colors) in &editor.document_colors {
+        for (excerpt_id, _) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+            for color in colors.iter() {
+                let Some(position) = snapshot.anchor_in_excerpt(excerpt_id, color.range.start)
+                else {
+                    continue;
+                };
+                let inlay = Inlay::color_swatch(
+                    post_inc(&mut editor.next_inlay_id),
+                    position,
+                    format!("{SWATCH} "),
+                );
+                highlights.push((
+                    InlayHighlight {
+                        inlay: inlay.id,
+                        inlay_position: position,
+                        range: 0..SWATCH.len(),
+                    },
+                    color_to_hsla(color.color),
+                ));
+                swatches.insert(inlay.id, (*buffer_id, color.clone()));
+                to_insert.push(inlay);
+            }
+        }
+    }
+
+    let to_remove = std::mem::replace(&mut editor.color_swatches, swatches)
+        .into_keys()
+        .collect::<Vec<_>>();
+    editor.clear_highlights::<DocumentColorSwatch>(cx);
+    editor.splice_inlays(&to_remove, to_insert, cx);
+    for (highlight, color) in highlights {
+        editor.highlight_inlays::<DocumentColorSwatch>(
+            vec![highlight],
+            HighlightStyle {
+                color: Some(color),
+                ..HighlightStyle::default()
+            },
+            cx,
+        );
+    }
+}
+
+fn color_to_hsla(color: lsp::Color) -> Hsla {
+    Rgba {
+        r: color.red,
+        g: color.green,
+        b: color.blue,
+        a: color.alpha,
+    }
+    .into()
+}
+
+impl Editor {
+    /// Returns the color swatch under the given position, if any.
+    pub(crate) fn color_swatch_at(
+        &self,
+        point_for_position: &PointForPosition,
+        snapshot: &EditorSnapshot,
+        cx: &App,
+    ) -> Option<Inlay> {
+        if self.color_swatches.is_empty() || point_for_position.column_overshoot_after_line_end != 0
+        {
+            return None;
+        }
+
+        let hovered_offset =
+            snapshot.display_point_to_inlay_offset(point_for_position.exact_unclipped, Bias::Left);
+        self.display_map
+            .read(cx)
+            .current_inlays()
+            .filter(|inlay| self.color_swatches.contains_key(&inlay.id))
+            .find(|inlay| {
+                let start = snapshot.anchor_to_inlay_offset(inlay.position);
+                let end = InlayOffset(start.0 + inlay.text.len());
+                (start..end).contains(&hovered_offset)
+            })
+            .cloned()
+    }
+
+    /// Shows a color picker for the color of the given swatch, which offers other colors and
+    /// other notations of the current color as provided by the language server.
+    pub(crate) fn deploy_color_picker(
+        &mut self,
+        swatch: Inlay,
+        clicked_point: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((buffer_id, color)) = self.color_swatches.get(&swatch.id).cloned() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        let Some(provider) = self.semantics_provider.clone() else {
+            return;
+        };
+        let Some(presentations) = provider.color_presentations(&buffer, &color, color.color, cx)
+        else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let presentations = presentations.await.log_err().unwrap_or_default();
+            editor.update_in(cx, |editor, window, cx| {
+                let weak_editor = cx.weak_entity();
+                let palette_editor = weak_editor.clone();
+                let palette_buffer = buffer.clone();
+                let palette_color = color.clone();
+                let context_menu = ContextMenu::build(window, cx, move |menu, _, _| {
+                    let menu = menu.custom_row(move |_, cx| {
+                        let border = cx.theme().colors().border;
+                        v_flex()
+                            .gap_1()
+                            .children(PALETTE.chunks(8).enumerate().map(|(row, colors)| {
+                                h_flex().gap_1().children(colors.iter().enumerate().map(
+                                    |(column, hex)| {
+                                        let editor = palette_editor.clone();
+                                        let buffer = palette_buffer.clone();
+                                        let color = palette_color.clone();
+                                        let new_color = lsp::Color {
+                                            alpha: color.color.alpha,
+                                            ..palette_color_to_lsp(*hex)
+                                        };
+                                        div()
+                                            .id(row * 8 + column)
+                                            .size(px(16.))
+                                            .rounded_sm()
+                                            .border_1()
+                                            .border_color(border)
+                                            .bg(rgb(*hex))
+                                            .cursor_pointer()
+                                            .on_click(move |_, _, cx| {
+                                                apply_color(
+                                                    editor.clone(),
+                                                    buffer.clone(),
+                                                    color.clone(),
+                                                    new_color,
+                                                    cx,
+                                                );
+                                            })
+                                    },
+                                ))
+                            }))
+                            .into_any_element()
+                    });
+                    if presentations.is_empty() {
+                        return menu;
+                    }
+                    presentations.into_iter().fold(
+                        menu.separator().header("Notations"),
+                        |menu, presentation| {
+                            let editor = weak_editor.clone();
+                            let buffer = buffer.clone();
+                            let color = color.clone();
+                            menu.entry(presentation.label.clone(), None, move |_, cx| {
+                                editor
+                                    .update(cx, |_, cx| {
+                                        apply_presentation(&buffer, &color, &presentation, cx)
+                                    })
+                                    .ok();
+                            })
+                        },
+                    )
+                });
+                editor.mouse_context_menu = MouseContextMenu::pinned_to_editor(
+                    editor,
+                    swatch.position,
+                    clicked_point,
+                    context_menu,
+                    window,
+                    cx,
+                );
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+fn palette_color_to_lsp(hex: u32) -> lsp::Color {
+    let color = rgb(hex);
+    lsp::Color {
+        red: color.r,
+        green: color.g,
+        blue: color.b,
+        alpha: color.a,
+    }
+}
+
+/// Replaces the given color with `new_color`, written the way the language server presents it
+/// first.
+fn apply_color(
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    color: DocumentColor,
+    new_color: lsp::Color,
+    cx: &mut App,
+) {
+    editor
+        .update(cx, |editor, cx| {
+            editor.mouse_context_menu.take();
+            let presentations = editor
+                .semantics_provider
+                .as_ref()?
+                .color_presentations(&buffer, &color, new_color, cx)?;
+            cx.spawn(async move |editor, cx| {
+                let presentations = presentations.await?;
+                if let Some(presentation) = presentations.first() {
+                    editor.update(cx, |_, cx| {
+                        apply_presentation(&buffer, &color, presentation, cx)
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+            Some(())
+        })
+        .ok();
+}
+
+fn apply_presentation(
+    buffer: &Entity<Buffer>,
+    color: &DocumentColor,
+    presentation: &ColorPresentation,
+    cx: &mut Context<Editor>,
+) {
+    let edit = presentation
+        .text_edit
+        .clone()
+        .unwrap_or_else(|| (color.range.clone(), presentation.label.clone()));
+    let edits = Some(edit)
+        .into_iter()
+        .chain(presentation.additional_text_edits.iter().cloned())
+        .collect::<Vec<_>>();
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(edits, None, cx);
+    });
+}
@@ -0,0 +1,135 @@
+use std::{ops::Range, time::Duration};
+
+use gpui::Context;
+use language::{BufferSnapshot, OffsetRangeExt as _, ToOffset as _};
+use multi_buffer::Anchor;
+use project::DocumentLink;
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{
+    DisplayPoint, Editor, EditorMode,
+    display_map::{DisplaySnapshot, ToDisplayPoint as _},
+};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Requests the document links of the given buffer, or of all buffers of the editor,
+/// and caches them once the language server responds.
+pub(super) fn refresh_document_links(
+    editor: &mut Editor,
+    for_buffer: Option<BufferId>,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(provider) = editor.semantics_provider.clone() else {
+        return;
+    };
+
+    let multi_buffer = editor.buffer.read(cx);
+    let buffers = match for_buffer {
+        Some(buffer_id) => multi_buffer.buffer(buffer_id).into_iter().collect(),
+        None => multi_buffer.all_buffers().into_iter().collect::<Vec<_>>(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let provider = provider.clone();
+        let task = cx.spawn(async move |editor, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            let Some(links_task) = cx
+                .update(|cx| provider.document_links(&buffer, cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let links = links_task.await.log_err().unwrap_or_default();
+            editor
+                .update(cx, |editor, cx| {
+                    if links.is_empty() {
+                        editor.document_links.remove(&buffer_id);
+                    } else {
+                        editor.document_links.insert(buffer_id, links.into());
+                    }
+                    cx.notify();
+                })
+                .ok();
+        });
+        editor.document_links_tasks.insert(buffer_id, task);
+    }
+}
+
+impl Editor {
+    /// Returns the links of the given buffer that intersect the given range. The links reported
+    /// by the language server take precedence over the ones found by the `links.scm` query.
+    fn document_links_in_range(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Vec<DocumentLink> {
+        if let Some(links) = self.document_links.get(&buffer.remote_id()) {
+            return links
+                .iter()
+                .filter(|link| {
+                    link.range.start.to_offset(buffer) <= range.end
+                        && link.range.end.to_offset(buffer) >= range.start
+                })
+                .cloned()
+                .collect();
+        }
+
+        buffer
+            .syntax_links(range)
+            .map(|range| DocumentLink {
+                target: Some(buffer.text_for_range(range.clone()).collect()),
+                tooltip: None,
+                range: buffer.anchor_after(range.start)..buffer.anchor_before(range.end),
+                lsp_link: None,
+            })
+            .collect()
+    }
+
+    pub(crate) fn document_link_at(
+        &self,
+        buffer: &BufferSnapshot,
+        position: text::Anchor,
+    ) -> Option<DocumentLink> {
+        let offset = position.to_offset(buffer);
+        self.document_links_in_range(buffer, offset..offset)
+            .into_iter()
+            .min_by_key(|link| link.range.to_offset(buffer).len())
+    }
+
+    /// Returns the display ranges of the document links to underline, which are only shown
+    /// while the modifier to follow links is held.
+    pub(crate) fn document_link_ranges(
+        &self,
+        search_range: Range<Anchor>,
+        display_snapshot: &DisplaySnapshot,
+    ) -> Vec<Range<DisplayPoint>> {
+        if !self.show_document_links {
+            return Vec::new();
+        }
+
+        let multi_buffer = &display_snapshot.buffer_snapshot;
+        let mut ranges = Vec::new();
+        for (buffer, range, excerpt_id) in multi_buffer.range_to_buffer_ranges(search_range) {
+            for link in self.document_links_in_range(buffer, range) {
+                let Some(start) = multi_buffer.anchor_in_excerpt(excerpt_id, link.range.start)
+                else {
+                    continue;
+                };
+                let Some(end) = multi_buffer.anchor_in_excerpt(excerpt_id, link.range.end) else {
+                    continue;
+                };
+                ranges.push(
+                    start.to_display_point(display_snapshot)
+                        ..end.to_display_point(display_snapshot),
+                );
+            }
+        }
+        ranges
+    }
+}
@@ -18,15 +18,19 @@ mod clangd_ext;
 mod code_context_menus;
 pub mod commit_tooltip;
 pub mod display_map;
+mod document_colors;
+mod document_links;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
 mod indent_guides;
 mod inlay_hint_cache;
+mod inline_values;
 pub mod items;
 mod jsx_tag_auto_close;
 mod linked_editing_ranges;
@@ -35,9 +39,12 @@ mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod proposed_changes_editor;
+mod rename_preview;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
 
 #[cfg(test)]
@@ -53,7 +60,7 @@ pub use actions::{AcceptEditPrediction, OpenExcerpts, OpenExcerptsSplit};
 use aho_corasick::AhoCorasick;
 use anyhow::{Context as _, Result, anyhow};
 use blink_manager::BlinkManager;
-use buffer_diff::DiffHunkStatus;
+use buffer_diff::{DiffHunkStatus, LineSelection};
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
 use collections::{BTreeMap, HashMap, HashSet, VecDeque};
@@ -86,10 +93,10 @@ use git::blame::GitBlame;
 use gpui::{
     Action, Animation, AnimationExt, AnyElement, App, AppContext, AsyncWindowContext,
     AvailableSpace, Background, Bounds, ClickEvent, ClipboardEntry, ClipboardItem, Context,
-    DispatchPhase, Edges, Entity, EntityInputHandler, EventEmitter, FocusHandle, FocusOutEvent,
-    Focusable, FontId, FontWeight, Global, HighlightStyle, Hsla, KeyContext, Modifiers,
-    MouseButton, MouseDownEvent, PaintQuad, ParentElement, Pixels, Render, SharedString, Size,
-    Stateful, Styled, StyledText, Subscription, Task, TextStyle, TextStyleRefinement,
+    DispatchPhase, Edges, Entity, EntityId, EntityInputHandler, EventEmitter, FocusHandle,
+    FocusOutEvent, Focusable, FontId, FontWeight, Global, HighlightStyle, Hsla, KeyContext,
+    Modifiers, MouseButton, MouseDownEvent, PaintQuad, ParentElement, Pixels, Render, SharedString,
+    Size, Stateful, Styled, StyledText, Subscription, Task, TextStyle, TextStyleRefinement,
     UTF16Selection, UnderlineStyle, UniformListScrollHandle, WeakEntity, WeakFocusHandle, Window,
     div, impl_actions, point, prelude::*, pulsating_between, px, relative, size,
 };
@@ -127,6 +134,7 @@ use project::{
 pub use proposed_changes_editor::{
     ProposedChangeLocation, ProposedChangesEditor, ProposedChangesEditorToolbar,
 };
+pub use rename_preview::RenamePreview;
 use smallvec::smallvec;
 use std::{cell::OnceCell, iter::Peekable};
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
@@ -149,10 +157,12 @@ use multi_buffer::{
 };
 use parking_lot::Mutex;
 use project::{
-    CodeAction, Completion, CompletionIntent, CompletionSource, DocumentHighlight, InlayHint,
+    BufferSemanticTokens, CodeAction, ColorPresentation, Completion, CompletionIntent,
+    CompletionSource, DocumentColor, DocumentHighlight, DocumentLink, InlayHint, InlineValue,
     Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectTransaction,
-    TaskSourceKind,
+    RenameEdit, TaskSourceKind,
     debugger::breakpoint_store::Breakpoint,
+    git_store::GitEvent,
     lsp_store::{CompletionDocumentation, FormatTrigger, LspFormatTarget, OpenLspBufferHandle},
     project_settings::{GitGutterSetting, ProjectSettings},
 };
@@ -247,6 +257,8 @@ const COLUMNAR_SELECTION_MODIFIERS: Modifiers = Modifiers {
 pub enum InlayId {
     InlineCompletion(usize),
     Hint(usize),
+    DebuggerValue(usize),
+    Color(usize),
 }
 
 impl InlayId {
@@ -254,6 +266,8 @@ impl InlayId {
         match self {
             Self::InlineCompletion(id) => *id,
             Self::Hint(id) => *id,
+            Self::DebuggerValue(id) => *id,
+            Self::Color(id) => *id,
         }
     }
 }
@@ -669,6 +683,7 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_syntax_node_history: SelectSyntaxNodeHistory,
+    pending_selection_ranges: Option<selection_ranges::PendingSelectionRanges>,
     ime_transaction: Option<TransactionId>,
     active_diagnostics: Option<ActiveDiagnosticGroup>,
     show_inline_diagnostics: bool,
@@ -720,6 +735,19 @@ pub struct Editor {
     selection_highlight_task: Option<Task<()>>,
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
+    semantic_tokens_tasks: HashMap<BufferId, Task<()>>,
+    fold_ranges_tasks: HashMap<BufferId, Task<()>>,
+    document_links: HashMap<BufferId, Arc<[DocumentLink]>>,
+    document_links_tasks: HashMap<BufferId, Task<()>>,
+    show_document_links: bool,
+    document_colors: HashMap<BufferId, Arc<[DocumentColor]>>,
+    document_colors_tasks: HashMap<BufferId, Task<()>>,
+    color_swatches: HashMap<InlayId, (BufferId, DocumentColor)>,
+    conflicts: HashMap<BufferId, git::conflicts::BufferConflicts>,
+    conflicts_tasks: HashMap<BufferId, Task<()>>,
+    inline_values: Vec<InlayId>,
+    inline_values_task: Task<()>,
+    inline_values_session: Option<(EntityId, Subscription)>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
     pending_rename: Option<RenameState>,
     searchable: bool,
@@ -1073,6 +1101,8 @@ type SelectSyntaxNodeHistoryState = (
 #[derive(Default)]
 struct SelectSyntaxNodeHistory {
     stack: Vec<SelectSyntaxNodeHistoryState>,
+    // the ranges reported by the language server for each selection, from innermost to outermost
+    lsp_ranges: HashMap<usize, Arc<[Range<Anchor>]>>,
     // disable temporarily to allow changing selections without losing the stack
     pub disable_clearing: bool,
 }
@@ -1081,6 +1111,7 @@ impl SelectSyntaxNodeHistory {
     pub fn try_clear(&mut self) {
         if !self.disable_clearing {
             self.stack.clear();
+            self.lsp_ranges.clear();
         }
     }
 
@@ -1334,6 +1365,9 @@ impl Editor {
                             editor
                                 .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
                         }
+                        project::Event::RefreshSemanticTokens => {
+                            semantic_tokens::refresh_semantic_tokens(editor, None, cx);
+                        }
                         project::Event::SnippetEdit(id, snippet_edits) => {
                             if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                                 let focus_handle = editor.focus_handle(cx);
@@ -1379,6 +1413,17 @@ impl Editor {
                     |editor, _, event, window, cx| match event {
                         BreakpointStoreEvent::ActiveDebugLineChanged => {
                             editor.go_to_active_debug_line(window, cx);
+                            inline_values::refresh_inline_values(editor, cx);
+                        }
+                        _ => {}
+                    },
+                ));
+                project_subscriptions.push(cx.subscribe_in(
+                    &project.read(cx).git_store().clone(),
+                    window,
+                    |editor, _, event, _, cx| match event {
+                        GitEvent::FileSystemUpdated | GitEvent::GitStateUpdated => {
+                            git::conflicts::refresh_conflicts(editor, None, cx);
                         }
                         _ => {}
                     },
@@ -1442,6 +1487,7 @@ impl Editor {
             autoclose_regions: Default::default(),
             snippet_stack: Default::default(),
             select_syntax_node_history: SelectSyntaxNodeHistory::default(),
+            pending_selection_ranges: None,
             ime_transaction: Default::default(),
             active_diagnostics: None,
             show_inline_diagnostics: ProjectSettings::get_global(cx).diagnostics.inline.enabled,
@@ -1490,6 +1536,19 @@ impl Editor {
             selection_highlight_task: Default::default(),
             document_highlights_task: Default::default(),
             linked_editing_range_task: Default::default(),
+            semantic_tokens_tasks: Default::default(),
+            fold_ranges_tasks: Default::default(),
+            document_links: Default::default(),
+            document_links_tasks: Default::default(),
+            inline_values: Vec::new(),
+            inline_values_task: Task::ready(()),
+            inline_values_session: None,
+            show_document_links: false,
+            document_colors: Default::default(),
+            document_colors_tasks: Default::default(),
+            conflicts: Default::default(),
+            conflicts_tasks: Default::default(),
+            color_swatches: Default::default(),
             pending_rename: Default::default(),
             searchable: true,
             cursor_shape: EditorSettings::get_global(cx)
@@ -1627,6 +1686,11 @@ impl Editor {
             }
 
             this.go_to_active_debug_line(window, cx);
+            semantic_tokens::refresh_semantic_tokens(&mut this, None, cx);
+            folding_ranges::refresh_fold_ranges(&mut this, None, cx);
+            document_links::refresh_document_links(&mut this, None, cx);
+            document_colors::refresh_document_colors(&mut this, None, cx);
+            git::conflicts::refresh_conflicts(&mut this, None, cx);
 
             if let Some(buffer) = buffer.read(cx).as_singleton() {
                 if let Some(project) = this.project.as_ref() {
@@ -12203,6 +12267,9 @@ impl Editor {
         if old_selections.is_empty() {
             return;
         }
+        if selection_ranges::request_selection_ranges(self, &old_selections, window, cx) {
+            return;
+        }
 
         self.hide_mouse_cursor(&HideMouseCursorOrigin::MovementAction);
 
@@ -12214,6 +12281,33 @@ impl Editor {
             .iter()
             .map(|selection| {
                 let old_range = selection.start..selection.end;
+                let lsp_range = self
+                    .select_syntax_node_history
+                    .lsp_ranges
+                    .get(&selection.id)
+                    .and_then(|ranges| {
+                        ranges
+                            .iter()
+                            .map(|range| {
+                                range.start.to_offset(&buffer)..range.end.to_offset(&buffer)
+                            })
+                            .find(|range| {
+                                range.start <= old_range.start
+                                    && range.end >= old_range.end
+                                    && *range != old_range
+                            })
+                    });
+                if let Some(new_range) = lsp_range {
+                    selected_larger_node = true;
+                    return Selection {
+                        id: selection.id,
+                        start: new_range.start,
+                        end: new_range.end,
+                        goal: SelectionGoal::None,
+                        reversed: selection.reversed,
+                    };
+                }
+
                 let mut new_range = old_range.clone();
                 let mut new_node = None;
                 while let Some((node, containing_range)) = buffer.syntax_ancestor(new_range.clone())
@@ -13794,22 +13888,8 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Task<Result<()>>> {
-        let rename = self.take_rename(false, window, cx)?;
+        let (buffer, start, old_name, new_name) = self.take_confirmed_rename(window, cx)?;
         let workspace = self.workspace()?.downgrade();
-        let (buffer, start) = self
-            .buffer
-            .read(cx)
-            .text_anchor_for_position(rename.range.start, cx)?;
-        let (end_buffer, _) = self
-            .buffer
-            .read(cx)
-            .text_anchor_for_position(rename.range.end, cx)?;
-        if buffer != end_buffer {
-            return None;
-        }
-
-        let old_name = rename.old_name;
-        let new_name = rename.editor.read(cx).text(cx);
 
         let rename = self.semantics_provider.as_ref()?.perform_rename(
             &buffer,
@@ -13836,6 +13916,80 @@ impl Editor {
         }))
     }
 
+    /// Confirms the pending rename like [`Editor::confirm_rename`], but opens a preview of its
+    /// edits instead of applying them right away.
+    pub fn preview_rename(
+        &mut self,
+        _: &PreviewRename,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let (buffer, start, old_name, new_name) = self.take_confirmed_rename(window, cx)?;
+        let workspace = self.workspace()?.downgrade();
+        let project = self.project.clone()?;
+        let provider = self.semantics_provider.clone()?;
+        let edits = provider.rename_edits(&buffer, start, new_name.clone(), cx)?;
+
+        Some(cx.spawn_in(window, async move |editor, cx| {
+            let title = format!("Rename: {} → {}", old_name, new_name);
+            let Some(edits) = edits.await? else {
+                // Renames which create, rename or delete files can't be previewed.
+                let project_transaction = cx
+                    .update(|_, cx| provider.perform_rename(&buffer, start, new_name, cx))?
+                    .context("no language server to perform the rename")?
+                    .await?;
+                return Self::open_project_transaction(
+                    &editor,
+                    workspace,
+                    project_transaction,
+                    title,
+                    cx,
+                )
+                .await;
+            };
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let preview = cx.new(|cx| {
+                    RenamePreview::new(
+                        title,
+                        edits,
+                        editor.clone(),
+                        workspace.weak_handle(),
+                        project,
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(preview), None, true, window, cx);
+            })?;
+            Ok(())
+        }))
+    }
+
+    /// Ends the pending rename, returning the position of the renamed symbol along with its
+    /// old and new name.
+    fn take_confirmed_rename(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<(Entity<Buffer>, text::Anchor, Arc<str>, String)> {
+        let rename = self.take_rename(false, window, cx)?;
+        let (buffer, start) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(rename.range.start, cx)?;
+        let (end_buffer, _) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(rename.range.end, cx)?;
+        if buffer != end_buffer {
+            return None;
+        }
+
+        let new_name = rename.editor.read(cx).text(cx);
+        Some((buffer, start, rename.old_name, new_name))
+    }
+
     fn take_rename(
         &mut self,
         moving_cursor: bool,
@@ -15063,6 +15217,129 @@ impl Editor {
         self.do_stage_or_unstage_and_next(false, window, cx);
     }
 
+    pub fn stage_selected_lines(
+        &mut self,
+        _: &::git::StageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stage_or_unstage_selected_lines(true, cx);
+    }
+
+    pub fn unstage_selected_lines(
+        &mut self,
+        _: &::git::UnstageSelectedLines,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stage_or_unstage_selected_lines(false, cx);
+    }
+
+    /// Stages or unstages the changed lines within the selections, rather than the whole hunks
+    /// they intersect.
+    pub fn stage_or_unstage_selected_lines(&mut self, stage: bool, cx: &mut Context<Self>) {
+        let ranges = self.selections.disjoint_anchor_ranges().collect::<Vec<_>>();
+        let task = self.save_buffers_for_ranges_if_needed(&ranges, cx);
+        cx.spawn(async move |this, cx| {
+            task.await?;
+            this.update(cx, |this, cx| {
+                for (buffer_id, selection) in this.line_selections(&ranges, cx) {
+                    this.do_stage_or_unstage_lines(stage, buffer_id, &selection, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn line_selections(
+        &self,
+        ranges: &[Range<Anchor>],
+        cx: &App,
+    ) -> HashMap<BufferId, LineSelection> {
+        fn rows(start: Point, end: Point) -> Range<u32> {
+            if end.column == 0 && end.row > start.row {
+                start.row..end.row
+            } else {
+                start.row..end.row + 1
+            }
+        }
+
+        let multibuffer = self.buffer.read(cx);
+        let snapshot = multibuffer.snapshot(cx);
+        let mut selections = HashMap::<BufferId, LineSelection>::default();
+        for range in ranges {
+            for (ix, (buffer, buffer_range, excerpt_id, deleted_hunk_anchor)) in snapshot
+                .range_to_buffer_ranges_with_deleted_hunks(range.clone())
+                .enumerate()
+            {
+                // Regions starting where the selection ends are yielded too.
+                if ix > 0 && buffer_range.is_empty() {
+                    continue;
+                }
+                let Some(buffer_id) = snapshot
+                    .buffer_for_excerpt(excerpt_id)
+                    .map(|buffer| buffer.remote_id())
+                else {
+                    continue;
+                };
+                let start = buffer.offset_to_point(buffer_range.start);
+                let end = buffer.offset_to_point(buffer_range.end);
+                let selection = selections.entry(buffer_id).or_default();
+                // The deleted lines displayed in the editor come from the HEAD text.
+                if deleted_hunk_anchor.is_some() {
+                    selection.head_rows.push(rows(start, end));
+                } else {
+                    selection.buffer_rows.push(rows(start, end));
+                }
+            }
+        }
+
+        // The deletions of collapsed hunks can't be selected, so they're staged along with any
+        // of the hunk's lines.
+        for hunk in self.diff_hunks_in_ranges(ranges, &snapshot) {
+            if hunk.diff_base_byte_range.is_empty() {
+                continue;
+            }
+            let hunk_range = snapshot.anchor_before(Point::new(hunk.row_range.start.0, 0))
+                ..snapshot.anchor_before(Point::new(hunk.row_range.end.0, 0));
+            if multibuffer.has_expanded_diff_hunks_in_ranges(&[hunk_range], cx) {
+                continue;
+            }
+            let Some(diff) = snapshot.diff_for_buffer_id(hunk.buffer_id) else {
+                continue;
+            };
+            let base_text = diff.base_text();
+            let start = base_text.offset_to_point(hunk.diff_base_byte_range.start);
+            let end = base_text.offset_to_point(hunk.diff_base_byte_range.end);
+            selections
+                .entry(hunk.buffer_id)
+                .or_default()
+                .head_rows
+                .push(rows(start, end));
+        }
+        selections
+    }
+
+    fn do_stage_or_unstage_lines(
+        &self,
+        stage: bool,
+        buffer_id: BufferId,
+        selection: &LineSelection,
+        cx: &mut App,
+    ) -> Option<()> {
+        let project = self.project.as_ref()?;
+        let buffer = project.read(cx).buffer_for_id(buffer_id, cx)?;
+        let diff = self.buffer.read(cx).diff_for(buffer_id)?;
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let file_exists = buffer_snapshot
+            .file()
+            .is_some_and(|file| file.disk_state().exists());
+        diff.update(cx, |diff, cx| {
+            diff.stage_or_unstage_lines(stage, selection, &buffer_snapshot, file_exists, cx)
+        });
+        None
+    }
+
     pub fn stage_or_unstage_diff_hunks(
         &mut self,
         stage: bool,
@@ -15768,8 +16045,10 @@ impl Editor {
         let _ = maybe!({
             let breakpoint_store = self.breakpoint_store.as_ref()?;
 
-            let Some((_, _, active_position)) =
-                breakpoint_store.read(cx).active_position().cloned()
+            let Some(active_position) = breakpoint_store
+                .read(cx)
+                .active_position()
+                .map(|frame| frame.position)
             else {
                 self.clear_row_highlights::<DebugCurrentRowHighlight>();
                 return None;
@@ -16770,6 +17049,11 @@ impl Editor {
                 }
                 if let Some(buffer) = buffer_edited {
                     let buffer_id = buffer.read(cx).remote_id();
+                    semantic_tokens::refresh_semantic_tokens(self, Some(buffer_id), cx);
+                    folding_ranges::refresh_fold_ranges(self, Some(buffer_id), cx);
+                    document_links::refresh_document_links(self, Some(buffer_id), cx);
+                    document_colors::refresh_document_colors(self, Some(buffer_id), cx);
+                    git::conflicts::refresh_conflicts(self, Some(buffer_id), cx);
                     if !self.registered_buffers.contains_key(&buffer_id) {
                         if let Some(project) = self.project.as_ref() {
                             project.update(cx, |project, cx| {
@@ -16845,12 +17129,54 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                semantic_tokens::refresh_semantic_tokens(self, Some(buffer_id), cx);
+                folding_ranges::refresh_fold_ranges(self, Some(buffer_id), cx);
+                document_links::refresh_document_links(self, Some(buffer_id), cx);
+                document_colors::refresh_document_colors(self, Some(buffer_id), cx);
+                git::conflicts::refresh_conflicts(self, Some(buffer_id), cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 let buffer = self.buffer.read(cx);
                 self.registered_buffers
                     .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                let removed_buffer_ids = self
+                    .semantic_tokens_tasks
+                    .keys()
+                    .filter(|buffer_id| buffer.buffer(**buffer_id).is_none())
+                    .copied()
+                    .collect::<Vec<_>>();
+                self.display_map.update(cx, |display_map, _| {
+                    for buffer_id in &removed_buffer_ids {
+                        display_map.clear_semantic_token_highlights(*buffer_id);
+                    }
+                });
+                for buffer_id in removed_buffer_ids {
+                    self.semantic_tokens_tasks.remove(&buffer_id);
+                }
+                let buffer = self.buffer.read(cx);
+                self.fold_ranges_tasks
+                    .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                self.document_links
+                    .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                self.document_links_tasks
+                    .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                self.document_colors_tasks
+                    .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                let document_colors_count = self.document_colors.len();
+                self.document_colors
+                    .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                if self.document_colors.len() != document_colors_count {
+                    document_colors::update_color_swatches(self, cx);
+                }
+                self.conflicts_tasks
+                    .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                let conflicts_count = self.conflicts.len();
+                self.conflicts
+                    .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
+                if self.conflicts.len() != conflicts_count {
+                    git::conflicts::update_conflict_highlights(self, cx);
+                }
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
@@ -16880,6 +17206,11 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, window, cx);
+                semantic_tokens::refresh_semantic_tokens(self, Some(*buffer_id), cx);
+                folding_ranges::refresh_fold_ranges(self, Some(*buffer_id), cx);
+                document_links::refresh_document_links(self, Some(*buffer_id), cx);
+                document_colors::refresh_document_colors(self, Some(*buffer_id), cx);
+                git::conflicts::refresh_conflicts(self, Some(*buffer_id), cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
@@ -16921,6 +17252,8 @@ impl Editor {
             )),
             cx,
         );
+        semantic_tokens::refresh_semantic_tokens(self, None, cx);
+        folding_ranges::refresh_fold_ranges(self, None, cx);
 
         let old_cursor_shape = self.cursor_shape;
 
@@ -18228,6 +18561,69 @@ pub trait SemanticsProvider {
         new_name: String,
         cx: &mut App,
     ) -> Option<Task<Result<ProjectTransaction>>>;
+
+    fn rename_edits(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        new_name: String,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<Vec<RenameEdit>>>>>;
+
+    fn semantic_tokens(
+        &self,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<BufferSemanticTokens>>>>;
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Range<text::Anchor>>>>>;
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        link: &DocumentLink,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<String>>>>;
+
+    fn inline_values(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        stopped_location: Range<text::Anchor>,
+        frame_id: u64,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<InlineValue>>>>;
+
+    fn document_colors(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentColor>>>>;
+
+    fn color_presentations(
+        &self,
+        buffer: &Entity<Buffer>,
+        color: &DocumentColor,
+        new_color: lsp::Color,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<ColorPresentation>>>>;
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>>;
 }
 
 pub trait CompletionProvider {
@@ -18695,6 +19091,97 @@ impl SemanticsProvider for Entity<Project> {
             project.perform_rename(buffer.clone(), position, new_name, cx)
         }))
     }
+
+    fn rename_edits(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        new_name: String,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<Vec<RenameEdit>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.rename_edits(buffer.clone(), position, new_name, cx)
+        }))
+    }
+
+    fn semantic_tokens(
+        &self,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<BufferSemanticTokens>>>> {
+        Some(self.update(cx, |project, cx| project.semantic_tokens(buffer, cx)))
+    }
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Range<text::Anchor>>>>> {
+        Some(self.update(cx, |project, cx| project.folding_ranges(buffer, cx)))
+    }
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        link: &DocumentLink,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<String>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(buffer, link, cx)
+        }))
+    }
+
+    fn inline_values(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        stopped_location: Range<text::Anchor>,
+        frame_id: u64,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<InlineValue>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.inline_values(buffer, range, stopped_location, frame_id, cx)
+        }))
+    }
+
+    fn document_colors(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentColor>>>> {
+        Some(self.update(cx, |project, cx| project.document_colors(buffer, cx)))
+    }
+
+    fn color_presentations(
+        &self,
+        buffer: &Entity<Buffer>,
+        color: &DocumentColor,
+        new_color: lsp::Color,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<ColorPresentation>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.color_presentations(buffer, color, new_color, cx)
+        }))
+    }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.selection_ranges(buffer, positions, cx)
+        }))
+    }
 }
 
 fn inlay_hint_settings(
@@ -19008,7 +19495,7 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.starts_fold(buffer_row);
 
         if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
             Some(
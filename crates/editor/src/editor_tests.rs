@@ -22,7 +22,7 @@ use language::{
     FakeLspAdapter, LanguageConfig, LanguageConfigOverride, LanguageMatcher, LanguageName,
     Override, Point,
     language_settings::{
-        AllLanguageSettings, AllLanguageSettingsContent, CompletionSettings,
+        AllLanguageSettings, AllLanguageSettingsContent, CompletionSettings, FoldSource,
         LanguageSettingsContent, PrettierSettings,
     },
 };
@@ -1129,6 +1129,66 @@ fn test_fold_action_multiple_line_breaks(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_fold_syntax_ranges(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_fold_query("(function_item) @fold")
+        .unwrap(),
+    );
+
+    let text = "
+        fn sum(
+            a: u32,
+            b: u32,
+        ) -> u32 {
+            a + b
+        }
+    "
+    .unindent();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.fold_at(
+            &FoldAt {
+                buffer_row: MultiBufferRow(0),
+            },
+            window,
+            cx,
+        );
+        assert_eq!(editor.display_text(cx), "fn sum(⋯\n}\n");
+        editor.unfold_lines(&UnfoldLines, window, cx);
+    });
+
+    update_test_language_settings(cx, |settings| {
+        settings.defaults.fold_source = Some(FoldSource::Indentation);
+    });
+    editor.update_in(cx, |editor, window, cx| {
+        editor.fold_at(
+            &FoldAt {
+                buffer_row: MultiBufferRow(0),
+            },
+            window,
+            cx,
+        );
+        assert_eq!(
+            editor.display_text(cx),
+            "fn sum(⋯\n) -> u32 {\n    a + b\n}\n"
+        );
+    });
+}
+
 #[gpui::test]
 fn test_fold_at_level(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -343,6 +343,12 @@ impl EditorElement {
         register_action(editor, window, Editor::go_to_prev_diagnostic);
         register_action(editor, window, Editor::go_to_next_hunk);
         register_action(editor, window, Editor::go_to_prev_hunk);
+        register_action(editor, window, Editor::go_to_next_conflict);
+        register_action(editor, window, Editor::go_to_previous_conflict);
+        register_action(editor, window, Editor::resolve_conflict_with_ours);
+        register_action(editor, window, Editor::resolve_conflict_with_theirs);
+        register_action(editor, window, Editor::resolve_conflict_with_both);
+        register_action(editor, window, Editor::mark_conflicts_resolved);
         register_action(editor, window, |editor, action, window, cx| {
             editor
                 .go_to_definition(action, window, cx)
@@ -430,6 +436,8 @@ impl EditorElement {
         register_action(editor, window, Editor::toggle_staged_selected_diff_hunks);
         register_action(editor, window, Editor::stage_and_next);
         register_action(editor, window, Editor::unstage_and_next);
+        register_action(editor, window, Editor::stage_selected_lines);
+        register_action(editor, window, Editor::unstage_selected_lines);
         register_action(editor, window, Editor::expand_all_diff_hunks);
 
         register_action(editor, window, |editor, action, window, cx| {
@@ -490,6 +498,13 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(editor, window, |editor, action, window, cx| {
+            if let Some(task) = editor.preview_rename(action, window, cx) {
+                task.detach_and_notify_err(window, cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(editor, window, |editor, action, window, cx| {
             if let Some(task) = editor.find_all_references(action, window, cx) {
                 task.detach_and_log_err(cx);
@@ -638,6 +653,16 @@ impl EditorElement {
         }
 
         let point_for_position = position_map.point_for_position(event.position);
+        if click_count == 1 && !modifiers.modified() {
+            if let Some(swatch) =
+                editor.color_swatch_at(&point_for_position, &position_map.snapshot, cx)
+            {
+                editor.deploy_color_picker(swatch, event.position, window, cx);
+                cx.stop_propagation();
+                return;
+            }
+        }
+
         let position = point_for_position.previous_valid;
         if modifiers == COLUMNAR_SELECTION_MODIFIERS {
             editor.select(
@@ -4713,6 +4738,7 @@ impl EditorElement {
                 let invisible_display_ranges = self.paint_highlights(layout, window);
                 self.paint_lines(&invisible_display_ranges, layout, window, cx);
                 self.paint_redactions(layout, window);
+                self.paint_document_links(layout, window, cx);
                 self.paint_cursors(layout, window, cx);
                 self.paint_inline_diagnostics(layout, window, cx);
                 self.paint_inline_blame(layout, window, cx);
@@ -4836,6 +4862,54 @@ impl EditorElement {
         });
     }
 
+    fn paint_document_links(&mut self, layout: &EditorLayout, window: &mut Window, cx: &mut App) {
+        if layout.document_link_ranges.is_empty() {
+            return;
+        }
+
+        let start_row = layout.visible_display_row_range.start;
+        let end_row = layout.visible_display_row_range.end;
+        let line_height = layout.position_map.line_height;
+        let scroll_position = layout.position_map.scroll_pixel_position;
+        let thickness = px(1.);
+        let color = cx.theme().colors().link_text_hover;
+
+        window.paint_layer(layout.position_map.text_hitbox.bounds, |window| {
+            for range in layout.document_link_ranges.iter() {
+                let row_range = cmp::max(range.start.row(), start_row)
+                    ..cmp::min(range.end.row().next_row(), end_row);
+                for row in row_range.iter_rows() {
+                    let line_layout =
+                        &layout.position_map.line_layouts[row.minus(start_row) as usize];
+                    let start_x = if row == range.start.row() {
+                        line_layout.x_for_index(range.start.column() as usize)
+                    } else {
+                        Pixels::ZERO
+                    };
+                    let end_x = if row == range.end.row() {
+                        line_layout.x_for_index(range.end.column() as usize)
+                    } else {
+                        line_layout.width
+                    };
+                    if end_x <= start_x {
+                        continue;
+                    }
+
+                    let origin = point(
+                        layout.content_origin.x + start_x - scroll_position.x,
+                        layout.content_origin.y + (row.as_f32() + 1.) * line_height
+                            - thickness * 2.
+                            - scroll_position.y,
+                    );
+                    window.paint_quad(fill(
+                        Bounds::new(origin, size(end_x - start_x, thickness)),
+                        color,
+                    ));
+                }
+            }
+        });
+    }
+
     fn paint_cursors(&mut self, layout: &mut EditorLayout, window: &mut Window, cx: &mut App) {
         for cursor in &mut layout.visible_cursors {
             cursor.paint(layout.content_origin, window, cx);
@@ -6811,6 +6885,11 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let document_link_ranges = self
+                        .editor
+                        .read(cx)
+                        .document_link_ranges(start_anchor..end_anchor, &snapshot.display_snapshot);
+
                     let (local_selections, selected_buffer_ids): (
                         Vec<Selection<Point>>,
                         Vec<BufferId>,
@@ -7537,6 +7616,7 @@ impl Element for EditorElement {
                         highlighted_ranges,
                         highlighted_gutter_ranges,
                         redacted_ranges,
+                        document_link_ranges,
                         line_elements,
                         line_numbers,
                         blamed_display_rows,
@@ -7719,6 +7799,7 @@ pub struct EditorLayout {
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     highlighted_gutter_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     redacted_ranges: Vec<Range<DisplayPoint>>,
+    document_link_ranges: Vec<Range<DisplayPoint>>,
     cursors: Vec<(DisplayPoint, Hsla)>,
     visible_cursors: Vec<CursorLayout>,
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use gpui::Context;
+use language::language_settings::{FoldSource, language_settings};
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{Editor, EditorMode, display_map::FoldRanges};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Determines where the foldable ranges of the given buffer, or of all buffers of the editor,
+/// come from, requesting them from the language server when the `fold_source` setting says so.
+pub(super) fn refresh_fold_ranges(
+    editor: &mut Editor,
+    for_buffer: Option<BufferId>,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let multi_buffer = editor.buffer.read(cx);
+    let buffers = match for_buffer {
+        Some(buffer_id) => multi_buffer.buffer(buffer_id).into_iter().collect(),
+        None => multi_buffer.all_buffers().into_iter().collect::<Vec<_>>(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let fold_source = {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).fold_source
+        };
+        let provider = match fold_source {
+            FoldSource::Indentation => {
+                editor.fold_ranges_tasks.remove(&buffer_id);
+                editor.display_map.update(cx, |display_map, _| {
+                    display_map.clear_fold_ranges(buffer_id)
+                });
+                continue;
+            }
+            FoldSource::TreeSitter => None,
+            FoldSource::LanguageServer => editor.semantics_provider.clone(),
+        };
+        let Some(provider) = provider else {
+            editor.fold_ranges_tasks.remove(&buffer_id);
+            editor.display_map.update(cx, |display_map, _| {
+                display_map.set_fold_ranges(buffer_id, FoldRanges::Syntax)
+            });
+            continue;
+        };
+
+        let task = cx.spawn(async move |editor, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            let Some(ranges_task) = cx
+                .update(|cx| provider.folding_ranges(&buffer, cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let ranges = ranges_task.await.log_err().unwrap_or_default();
+            editor
+                .update(cx, |editor, cx| {
+                    // Fall back to the syntax when the language servers report no ranges.
+                    let fold_ranges = if ranges.is_empty() {
+                        FoldRanges::Syntax
+                    } else {
+                        FoldRanges::LanguageServer(ranges.into())
+                    };
+                    editor.display_map.update(cx, |display_map, _| {
+                        display_map.set_fold_ranges(buffer_id, fold_ranges)
+                    });
+                    cx.notify();
+                })
+                .ok();
+        });
+        editor.fold_ranges_tasks.insert(buffer_id, task);
+    }
+}
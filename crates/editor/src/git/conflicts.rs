@@ -0,0 +1,678 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use collections::HashSet;
+use gpui::{App, AppContext as _, Context, Entity, WeakEntity, Window};
+use language::{Buffer, Point};
+use multi_buffer::ToPoint as _;
+use project::git_store::conflicts::{ConflictRegion, ConflictResolution, conflict_regions};
+use text::{BufferId, OffsetRangeExt as _};
+use ui::{Tooltip, prelude::*};
+
+use crate::{
+    Direction, Editor, EditorMode, GoToNextConflict, GoToPreviousConflict, MarkConflictsResolved,
+    ResolveConflictWithBoth, ResolveConflictWithOurs, ResolveConflictWithTheirs,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    scroll::Autoscroll,
+};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+enum ConflictOurs {}
+enum ConflictTheirs {}
+enum ConflictBase {}
+
+/// The merge conflicts found in a buffer of the editor, along with the blocks offering to
+/// resolve them. Buffers that are still conflicted in the repository, but have no conflict
+/// markers left, have no regions and a block offering to mark them as resolved instead.
+pub(crate) struct BufferConflicts {
+    regions: Arc<[ConflictRegion]>,
+    blocks: HashSet<CustomBlockId>,
+}
+
+/// Looks for merge conflict markers in the given buffer, or in all buffers of the editor, that
+/// the repository reports as conflicted.
+pub(crate) fn refresh_conflicts(
+    editor: &mut Editor,
+    for_buffer: Option<BufferId>,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let multi_buffer = editor.buffer.read(cx);
+    let buffers = match for_buffer {
+        Some(buffer_id) => multi_buffer.buffer(buffer_id).into_iter().collect(),
+        None => multi_buffer.all_buffers().into_iter().collect::<Vec<_>>(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let conflicted = project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+            .is_some_and(|(repository, repo_path)| repository.read(cx).has_conflict(&repo_path));
+        if !conflicted {
+            if editor.conflicts.contains_key(&buffer_id) {
+                set_buffer_conflicts(editor, buffer_id, None, cx);
+            }
+            editor.conflicts_tasks.remove(&buffer_id);
+            continue;
+        }
+
+        let task = cx.spawn(async move |editor, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            let Ok(snapshot) = buffer.read_with(cx, |buffer, _| buffer.text_snapshot()) else {
+                return;
+            };
+            let regions = cx
+                .background_spawn(async move { conflict_regions(&snapshot) })
+                .await;
+            editor
+                .update(cx, |editor, cx| {
+                    set_buffer_conflicts(editor, buffer_id, Some(regions), cx);
+                })
+                .ok();
+        });
+        editor.conflicts_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Replaces the conflicts shown for the buffer, `None` if the buffer is not conflicted anymore.
+fn set_buffer_conflicts(
+    editor: &mut Editor,
+    buffer_id: BufferId,
+    regions: Option<Vec<ConflictRegion>>,
+    cx: &mut Context<Editor>,
+) {
+    if let Some(conflicts) = editor.conflicts.remove(&buffer_id) {
+        editor.remove_blocks(conflicts.blocks, None, cx);
+    }
+    if let Some(regions) = regions {
+        let blocks = if regions.is_empty() {
+            insert_resolved_blocks(editor, buffer_id, cx)
+        } else {
+            insert_conflict_blocks(editor, buffer_id, &regions, cx)
+        };
+        editor.conflicts.insert(
+            buffer_id,
+            BufferConflicts {
+                regions: regions.into(),
+                blocks,
+            },
+        );
+    }
+    update_conflict_highlights(editor, cx);
+}
+
+fn insert_conflict_blocks(
+    editor: &mut Editor,
+    buffer_id: BufferId,
+    regions: &[ConflictRegion],
+    cx: &mut Context<Editor>,
+) -> HashSet<CustomBlockId> {
+    let multi_buffer = editor.buffer.read(cx);
+    let Some(buffer) = multi_buffer.buffer(buffer_id) else {
+        return HashSet::default();
+    };
+    let snapshot = multi_buffer.snapshot(cx);
+    let excerpts = multi_buffer.excerpts_for_buffer(buffer_id, cx);
+    let mut blocks = Vec::new();
+    for region in regions {
+        for (excerpt_id, _) in &excerpts {
+            let Some(position) = snapshot.anchor_in_excerpt(*excerpt_id, region.range.start) else {
+                continue;
+            };
+            let editor = cx.weak_entity();
+            let buffer = buffer.clone();
+            let region = region.clone();
+            blocks.push(BlockProperties {
+                placement: BlockPlacement::Above(position),
+                height: 1,
+                style: BlockStyle::Sticky,
+                render: Arc::new(move |cx| {
+                    render_conflict_actions(editor.clone(), buffer.clone(), region.clone(), cx)
+                }),
+                priority: 0,
+            });
+
+            let sides = [
+                (
+                    Some(&region.ours),
+                    side_header("Ours", &region.ours_label),
+                    ConflictSide::Ours,
+                ),
+                (region.base.as_ref(), "Base".into(), ConflictSide::Base),
+                (
+                    Some(&region.theirs),
+                    side_header("Theirs", &region.theirs_label),
+                    ConflictSide::Theirs,
+                ),
+            ];
+            for (range, header, side) in sides {
+                let Some(position) =
+                    range.and_then(|range| snapshot.anchor_in_excerpt(*excerpt_id, range.start))
+                else {
+                    continue;
+                };
+                blocks.push(BlockProperties {
+                    placement: BlockPlacement::Above(position),
+                    height: 1,
+                    style: BlockStyle::Sticky,
+                    render: Arc::new(move |cx| render_conflict_side(header.clone(), side, cx)),
+                    priority: 0,
+                });
+            }
+        }
+    }
+    editor.insert_blocks(blocks, None, cx).into_iter().collect()
+}
+
+/// Inserts a block at the start of the buffer, offering to mark its file as resolved.
+fn insert_resolved_blocks(
+    editor: &mut Editor,
+    buffer_id: BufferId,
+    cx: &mut Context<Editor>,
+) -> HashSet<CustomBlockId> {
+    let multi_buffer = editor.buffer.read(cx);
+    let Some(buffer) = multi_buffer.buffer(buffer_id) else {
+        return HashSet::default();
+    };
+    let snapshot = multi_buffer.snapshot(cx);
+    let blocks = multi_buffer
+        .excerpts_for_buffer(buffer_id, cx)
+        .into_iter()
+        .filter_map(|(excerpt_id, range)| {
+            let position = snapshot.anchor_in_excerpt(excerpt_id, range.context.start)?;
+            let editor = cx.weak_entity();
+            let buffer = buffer.clone();
+            Some(BlockProperties {
+                placement: BlockPlacement::Above(position),
+                height: 1,
+                style: BlockStyle::Sticky,
+                render: Arc::new(move |cx| {
+                    render_mark_resolved(editor.clone(), buffer.clone(), cx)
+                }),
+                priority: 0,
+            })
+        })
+        .collect::<Vec<_>>();
+    editor.insert_blocks(blocks, None, cx).into_iter().collect()
+}
+
+#[derive(Clone, Copy)]
+enum ConflictSide {
+    Ours,
+    Theirs,
+    Base,
+}
+
+fn side_header(side: &str, label: &str) -> SharedString {
+    if label.is_empty() {
+        side.to_string().into()
+    } else {
+        format!("{side} ({label})").into()
+    }
+}
+
+fn render_conflict_side(
+    header: SharedString,
+    side: ConflictSide,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let colors = cx.theme().colors();
+    let color = match side {
+        ConflictSide::Ours => colors.version_control_added,
+        ConflictSide::Theirs => colors.version_control_modified,
+        ConflictSide::Base => colors.version_control_conflict,
+    };
+    h_flex()
+        .id(SharedString::from(format!(
+            "conflict-side-{:?}",
+            cx.block_id
+        )))
+        .w_full()
+        .pl(cx.gutter_dimensions.full_width())
+        .bg(color.opacity(0.24))
+        .child(
+            Label::new(header)
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+        .into_any_element()
+}
+
+fn render_mark_resolved(
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    h_flex()
+        .id(SharedString::from(format!(
+            "conflict-resolved-{:?}",
+            cx.block_id
+        )))
+        .block_mouse_down()
+        .pl(cx.gutter_dimensions.full_width())
+        .gap_2()
+        .child(
+            Label::new("No merge conflict markers left")
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+        .child(
+            Button::new("mark-resolved", "Mark as Resolved")
+                .label_size(LabelSize::Small)
+                .tooltip(Tooltip::text(
+                    "Stage the file to mark its conflicts as resolved",
+                ))
+                .on_click(move |_, _, cx| {
+                    editor
+                        .update(cx, |editor, cx| editor.mark_resolved(&buffer, cx))
+                        .ok();
+                }),
+        )
+        .into_any_element()
+}
+
+fn render_conflict_actions(
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    region: ConflictRegion,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let button = |id: &'static str, label: SharedString, resolution: ConflictResolution| {
+        let editor = editor.clone();
+        let buffer = buffer.clone();
+        let region = region.clone();
+        Button::new(id, label)
+            .label_size(LabelSize::Small)
+            .on_click(move |_, _, cx| {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.resolve_conflict(&buffer, &region, resolution, cx)
+                    })
+                    .ok();
+            })
+    };
+    let side_label = |side: &str, label: &str| side_header(&format!("Accept {side}"), label);
+
+    h_flex()
+        .id(SharedString::from(format!("conflict-{:?}", cx.block_id)))
+        .block_mouse_down()
+        .pl(cx.gutter_dimensions.full_width())
+        .gap_1()
+        .child(
+            button(
+                "accept-ours",
+                side_label("Ours", &region.ours_label),
+                ConflictResolution::Ours,
+            )
+            .tooltip(Tooltip::text("Keep the current changes")),
+        )
+        .child(
+            button(
+                "accept-theirs",
+                side_label("Theirs", &region.theirs_label),
+                ConflictResolution::Theirs,
+            )
+            .tooltip(Tooltip::text("Keep the incoming changes")),
+        )
+        .child(button(
+            "accept-both",
+            "Accept Both".into(),
+            ConflictResolution::Both,
+        ))
+        .into_any_element()
+}
+
+pub(crate) fn update_conflict_highlights(editor: &mut Editor, cx: &mut Context<Editor>) {
+    editor.clear_row_highlights::<ConflictOurs>();
+    editor.clear_row_highlights::<ConflictTheirs>();
+    editor.clear_row_highlights::<ConflictBase>();
+
+    let multi_buffer = editor.buffer.read(cx);
+    let snapshot = multi_buffer.snapshot(cx);
+    let (mut ours, mut theirs, mut base) = (Vec::new(), Vec::new(), Vec::new());
+    for (buffer_id, conflicts) in &editor.conflicts {
+        for (excerpt_id, _) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+            let range_in_excerpt = |range: &Range<text::Anchor>| {
+                let start = snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                Some(start..end)
+            };
+            for region in conflicts.regions.iter() {
+                ours.extend(range_in_excerpt(&region.ours));
+                theirs.extend(range_in_excerpt(&region.theirs));
+                base.extend(region.base.as_ref().and_then(range_in_excerpt));
+            }
+        }
+    }
+
+    let colors = cx.theme().colors();
+    let ours_color = colors.version_control_added.opacity(0.12);
+    let theirs_color = colors.version_control_modified.opacity(0.12);
+    let base_color = colors.version_control_conflict.opacity(0.12);
+    for range in ours {
+        editor.highlight_rows::<ConflictOurs>(range, ours_color, false, cx);
+    }
+    for range in theirs {
+        editor.highlight_rows::<ConflictTheirs>(range, theirs_color, false, cx);
+    }
+    for range in base {
+        editor.highlight_rows::<ConflictBase>(range, base_color, false, cx);
+    }
+    cx.notify();
+}
+
+impl Editor {
+    /// Replaces the given conflict region with the text of the chosen sides.
+    pub(crate) fn resolve_conflict(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        region: &ConflictRegion,
+        resolution: ConflictResolution,
+        cx: &mut Context<Self>,
+    ) {
+        buffer.update(cx, |buffer, cx| {
+            let snapshot = buffer.text_snapshot();
+            // The region was already resolved if its anchors collapsed.
+            if region.range.to_offset(&snapshot).is_empty() {
+                return;
+            }
+            let text = region.resolved_text(resolution, &snapshot);
+            buffer.edit([(region.range.clone(), text)], None, cx);
+        });
+    }
+
+    /// Stages the buffer's file, marking its merge conflicts as resolved, if it has no conflict
+    /// markers left.
+    fn mark_resolved(&self, buffer: &Entity<Buffer>, cx: &mut App) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        if self
+            .conflicts
+            .get(&buffer_id)
+            .is_none_or(|conflicts| !conflicts.regions.is_empty())
+        {
+            return;
+        }
+        let Some((repository, repo_path)) = project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+        else {
+            return;
+        };
+        repository
+            .update(cx, |repository, cx| {
+                repository.stage_entries(vec![repo_path], cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Marks the conflicts of the buffers in the editor that have no conflict markers left as
+    /// resolved.
+    pub fn mark_conflicts_resolved(
+        &mut self,
+        _: &MarkConflictsResolved,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for buffer in self.buffer.read(cx).all_buffers() {
+            self.mark_resolved(&buffer, cx);
+        }
+    }
+
+    /// Returns the conflict region that contains the newest selection.
+    fn conflict_at_cursor(&self, cx: &App) -> Option<(Entity<Buffer>, ConflictRegion)> {
+        let cursor = self.selections.newest_anchor().head();
+        let (buffer, position) = self.buffer.read(cx).text_anchor_for_position(cursor, cx)?;
+        let snapshot = buffer.read(cx).text_snapshot();
+        let region = self
+            .conflicts
+            .get(&snapshot.remote_id())?
+            .regions
+            .iter()
+            .find(|region| {
+                region.range.start.cmp(&position, &snapshot).is_le()
+                    && region.range.end.cmp(&position, &snapshot).is_ge()
+            })?
+            .clone();
+        Some((buffer, region))
+    }
+
+    fn resolve_conflict_at_cursor(
+        &mut self,
+        resolution: ConflictResolution,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some((buffer, region)) = self.conflict_at_cursor(cx) {
+            self.resolve_conflict(&buffer, &region, resolution, cx);
+        }
+    }
+
+    pub fn resolve_conflict_with_ours(
+        &mut self,
+        _: &ResolveConflictWithOurs,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Ours, cx);
+    }
+
+    pub fn resolve_conflict_with_theirs(
+        &mut self,
+        _: &ResolveConflictWithTheirs,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Theirs, cx);
+    }
+
+    pub fn resolve_conflict_with_both(
+        &mut self,
+        _: &ResolveConflictWithBoth,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Both, cx);
+    }
+
+    pub fn go_to_next_conflict(
+        &mut self,
+        _: &GoToNextConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_conflict(Direction::Next, window, cx);
+    }
+
+    pub fn go_to_previous_conflict(
+        &mut self,
+        _: &GoToPreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_conflict(Direction::Prev, window, cx);
+    }
+
+    /// Moves the cursor to the start of the next or previous conflict, wrapping around the
+    /// editor's contents.
+    fn go_to_conflict(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut starts = Vec::new();
+        for (buffer_id, conflicts) in &self.conflicts {
+            for (excerpt_id, _) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+                starts.extend(conflicts.regions.iter().filter_map(|region| {
+                    let start = snapshot.anchor_in_excerpt(excerpt_id, region.range.start)?;
+                    Some(start.to_point(&snapshot))
+                }));
+            }
+        }
+        starts.sort();
+        starts.dedup();
+
+        let cursor_row = self.selections.newest::<Point>(cx).head().row;
+        let destination = match direction {
+            Direction::Next => starts
+                .iter()
+                .find(|start| start.row > cursor_row)
+                .or_else(|| starts.first()),
+            Direction::Prev => starts
+                .iter()
+                .rev()
+                .find(|start| start.row < cursor_row)
+                .or_else(|| starts.last()),
+        };
+        if let Some(&destination) = destination {
+            self.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+                s.select_ranges([destination..destination]);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use git::status::{UnmergedStatus, UnmergedStatusCode};
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use unindent::Unindent as _;
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            client::init_settings(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_resolving_conflicts(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let conflicted_text = "
+            one
+            <<<<<<< HEAD
+            two
+            =======
+            TWO
+            >>>>>>> feature
+            three
+        "
+        .unindent();
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/repo",
+            json!({
+                ".git": {},
+                "file.txt": conflicted_text,
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/repo/.git");
+        fs.set_index_for_repo(dot_git, &[("file.txt".into(), "one\nthree\n".into())]);
+        fs.set_unmerged_paths_for_repo(
+            dot_git,
+            &[(
+                "file.txt".into(),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                },
+            )],
+        );
+        let index_state = |fs: &FakeFs| {
+            fs.with_git_state(dot_git, false, |state| {
+                (
+                    state.index_contents.get(Path::new("file.txt")).cloned(),
+                    state.unmerged_paths.contains_key(Path::new("file.txt")),
+                )
+            })
+            .unwrap()
+        };
+
+        let project = Project::test(fs.clone(), ["/repo".as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/repo/file.txt", cx)
+            })
+            .await
+            .unwrap();
+        let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+        let editor = cx.add_window(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        cx.executor().advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+
+        editor
+            .update(cx, |editor, window, cx| {
+                assert_eq!(editor.conflicts[&buffer_id].regions.len(), 1);
+                editor.change_selections(None, window, cx, |s| {
+                    s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+                });
+                editor.resolve_conflict_with_theirs(&ResolveConflictWithTheirs, window, cx);
+            })
+            .unwrap();
+        cx.executor().advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "one\nTWO\nthree\n"
+        );
+        editor
+            .update(cx, |editor, _, _| {
+                assert!(editor.conflicts[&buffer_id].regions.is_empty());
+            })
+            .unwrap();
+        assert_eq!(
+            index_state(&fs),
+            (Some("one\nthree\n".to_string()), true),
+            "Resolving the last conflict should not stage the file on its own"
+        );
+
+        editor
+            .update(cx, |editor, window, cx| {
+                editor.mark_conflicts_resolved(&MarkConflictsResolved, window, cx);
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        assert_eq!(
+            index_state(&fs),
+            (Some("one\nTWO\nthree\n".to_string()), false)
+        );
+        editor
+            .update(cx, |editor, _, _| {
+                assert!(!editor.conflicts.contains_key(&buffer_id));
+            })
+            .unwrap();
+    }
+}
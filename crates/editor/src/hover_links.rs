@@ -1,12 +1,13 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToTypeDefinition, GotoDefinitionKind, InlayId, Navigated, PointForPosition, SelectPhase,
+    SemanticsProvider,
     editor_settings::{GoToDefinitionFallback, MultiCursorModifier},
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
 };
-use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Task, Window, px};
-use language::{Bias, ToOffset};
+use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Task, WeakEntity, Window, px};
+use language::{Bias, LanguageScope, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
@@ -14,7 +15,7 @@ use project::{
     ResolveState, ResolvedPath,
 };
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, rc::Rc};
 use theme::ActiveTheme as _;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
@@ -125,6 +126,10 @@ impl Editor {
             MultiCursorModifier::Alt => modifiers.secondary(),
             MultiCursorModifier::CmdOrCtrl => modifiers.alt,
         };
+        if self.show_document_links != hovered_link_modifier {
+            self.show_document_links = hovered_link_modifier;
+            cx.notify();
+        }
         if !hovered_link_modifier || self.has_pending_selection() {
             self.hide_hovered_link(cx);
             return;
@@ -538,7 +543,25 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    if let Some((link_range, link)) = find_document_link(
+                        &this,
+                        &buffer,
+                        buffer_position,
+                        project.clone(),
+                        provider.clone(),
+                        cx,
+                    )
+                    .await
+                    {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.update(cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -789,7 +812,20 @@ pub(crate) async fn find_file(
     let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot()).ok()?;
     let scope = snapshot.language_scope_at(position);
     let (range, candidate_file_path) = surrounding_filename(snapshot, position)?;
+    let existing_path =
+        resolve_file_path(&candidate_file_path, scope, &project, buffer, cx).await?;
+    Some((range, existing_path))
+}
 
+/// Resolves a path relative to the given buffer to an existing file, trying the path suffixes
+/// of the buffer's language when the path has no extension.
+async fn resolve_file_path(
+    candidate_file_path: &str,
+    scope: Option<LanguageScope>,
+    project: &Entity<Project>,
+    buffer: &Entity<language::Buffer>,
+    cx: &mut AsyncWindowContext,
+) -> Option<ResolvedPath> {
     async fn check_path(
         candidate_file_path: &str,
         project: &Entity<Project>,
@@ -805,8 +841,8 @@ pub(crate) async fn find_file(
             .filter(|s| s.is_file())
     }
 
-    if let Some(existing_path) = check_path(&candidate_file_path, &project, buffer, cx).await {
-        return Some((range, existing_path));
+    if let Some(existing_path) = check_path(candidate_file_path, project, buffer, cx).await {
+        return Some(existing_path);
     }
 
     if let Some(scope) = scope {
@@ -816,9 +852,9 @@ pub(crate) async fn find_file(
             }
 
             let suffixed_candidate = format!("{candidate_file_path}.{suffix}");
-            if let Some(existing_path) = check_path(&suffixed_candidate, &project, buffer, cx).await
+            if let Some(existing_path) = check_path(&suffixed_candidate, project, buffer, cx).await
             {
-                return Some((range, existing_path));
+                return Some(existing_path);
             }
         }
     }
@@ -826,6 +862,57 @@ pub(crate) async fn find_file(
     None
 }
 
+/// Finds the document link at the given position and resolves its target to a URL or to an
+/// existing file.
+pub(crate) async fn find_document_link(
+    editor: &WeakEntity<Editor>,
+    buffer: &Entity<language::Buffer>,
+    position: text::Anchor,
+    project: Option<Entity<Project>>,
+    provider: Option<Rc<dyn SemanticsProvider>>,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot()).ok()?;
+    let link = editor
+        .update(cx, |editor, _| editor.document_link_at(&snapshot, position))
+        .ok()??;
+    let target = match &link.target {
+        Some(target) => target.clone(),
+        None => {
+            let provider = provider?;
+            let resolve_task = cx
+                .update(|_, cx| provider.resolve_document_link(buffer, &link, cx))
+                .ok()??;
+            resolve_task.await.log_err()??
+        }
+    };
+
+    if target.starts_with("file://") {
+        let path = lsp::Url::parse(&target).ok()?.to_file_path().ok()?;
+        let resolved_path = project?
+            .update(cx, |project, cx| {
+                project.resolve_abs_file_path(&path.to_string_lossy(), cx)
+            })
+            .ok()?
+            .await?;
+        return Some((link.range, HoverLink::File(resolved_path)));
+    }
+
+    let mut finder = LinkFinder::new();
+    finder.kinds(&[LinkKind::Url]);
+    if finder
+        .links(&target)
+        .next()
+        .is_some_and(|url| url.start() == 0 && url.end() == target.len())
+    {
+        return Some((link.range, HoverLink::Url(target)));
+    }
+
+    let scope = snapshot.language_scope_at(position);
+    let resolved_path = resolve_file_path(&target, scope, &project?, buffer, cx).await?;
+    Some((link.range, HoverLink::File(resolved_path)))
+}
+
 fn surrounding_filename(
     snapshot: language::BufferSnapshot,
     position: text::Anchor,
@@ -931,6 +1018,7 @@ mod tests {
     use indoc::indoc;
     use language::language_settings::InlayHintSettings;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
+    use std::time::Duration;
     use util::{assert_set_eq, path};
     use workspace::item::Item;
 
@@ -1456,6 +1544,64 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let link_range = cx.lsp_range(indoc! {"
+            const DOCS: &str = \"«docs»\";
+        "});
+        let mut link_requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: None,
+                    tooltip: None,
+                    data: Some(serde_json::json!({ "id": 1 })),
+                }]))
+            },
+        );
+        cx.set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+            move |link, _, _| async move {
+                assert_eq!(link.data, Some(serde_json::json!({ "id": 1 })));
+                Ok(lsp::DocumentLink {
+                    target: Some(lsp::Url::parse("https://zed.dev/docs").unwrap()),
+                    ..link
+                })
+            },
+        );
+
+        cx.set_state(indoc! {"
+            const DOCS: &str = \"docs\";ˇ
+        "});
+        cx.executor().advance_clock(Duration::from_millis(200));
+        link_requests.next().await;
+        cx.run_until_parked();
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            const DOCS: &str = \"doˇcs\";
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            const DOCS: &str = \"«docsˇ»\";
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://zed.dev/docs".into()));
+    }
+
     #[gpui::test]
     async fn test_urls_at_beginning_of_buffer(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
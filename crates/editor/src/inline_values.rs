@@ -0,0 +1,270 @@
+use std::{ops::Range, time::Duration};
+
+use collections::HashMap;
+use futures::future;
+use gpui::{AppContext as _, Context, Entity, Task};
+use language::{Buffer, BufferSnapshot, Point, ToOffset as _, ToPoint as _};
+use project::{
+    InlineValue, InlineValueKind,
+    debugger::{
+        breakpoint_store::ActiveStackFrame,
+        session::{Session, SessionEvent},
+    },
+};
+use util::{ResultExt as _, post_inc};
+
+use crate::{Editor, EditorMode, InlayId, display_map::Inlay};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+const MAX_VALUE_LEN: usize = 64;
+
+/// Where the value shown for a part of the stopped stack frame comes from.
+enum ValueSource {
+    Text(String),
+    Variable { name: String, case_sensitive: bool },
+    Expression(String),
+}
+
+/// Recomputes the values shown at the end of the lines of the stack frame the debugger is stopped
+/// in. Values are requested from the language server with `textDocument/inlineValue`, falling
+/// back to the identifiers found in the syntax tree, and resolved through the debug session.
+pub(super) fn refresh_inline_values(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let Some((frame, buffer, session)) = active_stack_frame(editor, cx) else {
+        editor.inline_values_session = None;
+        editor.inline_values_task = Task::ready(());
+        clear_inline_values(editor, cx);
+        return;
+    };
+
+    if editor
+        .inline_values_session
+        .as_ref()
+        .is_none_or(|(session_id, _)| *session_id != session.entity_id())
+    {
+        let subscription = cx.subscribe(&session, |editor, _, event, cx| match event {
+            SessionEvent::Variables | SessionEvent::StackTrace => refresh_inline_values(editor, cx),
+            _ => {}
+        });
+        editor.inline_values_session = Some((session.entity_id(), subscription));
+    }
+
+    let provider = editor.semantics_provider.clone();
+    editor.inline_values_task = cx.spawn(async move |editor, cx| {
+        cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+
+        let Ok((snapshot, scope, stopped_location)) = buffer.update(cx, |buffer, _| {
+            let snapshot = buffer.snapshot();
+            let (scope, stopped_location) = frame_ranges(&snapshot, frame.position);
+            (snapshot, scope, stopped_location)
+        }) else {
+            return;
+        };
+
+        let lsp_values = match cx
+            .update(|cx| {
+                provider.as_ref()?.inline_values(
+                    &buffer,
+                    snapshot.anchor_before(scope.start)..snapshot.anchor_after(scope.end),
+                    snapshot.anchor_before(stopped_location.start)
+                        ..snapshot.anchor_after(stopped_location.end),
+                    frame.stack_frame_id,
+                    cx,
+                )
+            })
+            .ok()
+            .flatten()
+        {
+            Some(task) => task.await.log_err().unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        let stopped_row = stopped_location.start.row;
+        let sources = if lsp_values.is_empty() {
+            identifiers_in_scope(&snapshot, scope.clone())
+        } else {
+            lsp_values
+                .into_iter()
+                .map(|value| value_source(&snapshot, value))
+                .collect()
+        };
+        let sources = sources
+            .into_iter()
+            .filter(|(row, _)| *row <= stopped_row)
+            .collect::<Vec<_>>();
+
+        let Ok(values) = session.update(cx, |session, cx| {
+            sources
+                .into_iter()
+                .map(|(row, source)| match source {
+                    ValueSource::Text(text) => Task::ready(Some((row, text))),
+                    ValueSource::Variable {
+                        name,
+                        case_sensitive,
+                    } => Task::ready(
+                        session
+                            .variable_value(frame.stack_frame_id, &name, case_sensitive, cx)
+                            .map(|value| (row, format_value(&name, &value))),
+                    ),
+                    ValueSource::Expression(expression) => {
+                        let evaluation =
+                            session.evaluate_silently(expression.clone(), frame.stack_frame_id, cx);
+                        cx.background_spawn(async move {
+                            let value = evaluation.await?;
+                            Some((row, format_value(&expression, &value)))
+                        })
+                    }
+                })
+                .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        let values = future::join_all(values).await;
+
+        let mut labels_by_row = HashMap::<u32, Vec<String>>::default();
+        for (row, label) in values.into_iter().flatten() {
+            let labels = labels_by_row.entry(row).or_default();
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+
+        editor
+            .update(cx, |editor, cx| {
+                let multi_buffer = editor.buffer.read(cx);
+                let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+                let excerpts = multi_buffer.excerpts_for_buffer(snapshot.remote_id(), cx);
+                let mut to_insert = Vec::new();
+                for (row, labels) in labels_by_row {
+                    let position = snapshot.anchor_after(Point::new(row, snapshot.line_len(row)));
+                    for (excerpt_id, _) in &excerpts {
+                        if let Some(position) =
+                            multi_buffer_snapshot.anchor_in_excerpt(*excerpt_id, position)
+                        {
+                            to_insert.push(Inlay::debugger_value(
+                                post_inc(&mut editor.next_inlay_id),
+                                position,
+                                format!("  {}", labels.join(", ")),
+                            ));
+                        }
+                    }
+                }
+                let to_remove = std::mem::replace(
+                    &mut editor.inline_values,
+                    to_insert.iter().map(|inlay| inlay.id).collect(),
+                );
+                editor.splice_inlays(&to_remove, to_insert, cx);
+            })
+            .ok();
+    });
+}
+
+fn active_stack_frame(
+    editor: &Editor,
+    cx: &mut Context<Editor>,
+) -> Option<(ActiveStackFrame, Entity<Buffer>, Entity<Session>)> {
+    if editor.mode != EditorMode::Full {
+        return None;
+    }
+    let project = editor.project.as_ref()?;
+    let frame = editor
+        .breakpoint_store
+        .as_ref()?
+        .read(cx)
+        .active_position()?
+        .clone();
+    let buffer = editor.buffer.read(cx).buffer(frame.position.buffer_id?)?;
+    let session = project
+        .read(cx)
+        .dap_store()
+        .read(cx)
+        .session_by_id(frame.session_id)?;
+    Some((frame, buffer, session))
+}
+
+fn clear_inline_values(editor: &mut Editor, cx: &mut Context<Editor>) {
+    if !editor.inline_values.is_empty() {
+        let to_remove: Vec<InlayId> = std::mem::take(&mut editor.inline_values);
+        editor.splice_inlays(&to_remove, Vec::new(), cx);
+    }
+}
+
+/// Returns the range to show values for, spanning from the start of the innermost outline item
+/// around the stopped position to the end of the stopped line, along with the stopped line.
+fn frame_ranges(snapshot: &BufferSnapshot, position: text::Anchor) -> (Range<Point>, Range<Point>) {
+    let stopped_row = position.to_point(snapshot).row;
+    let stopped_location =
+        Point::new(stopped_row, 0)..Point::new(stopped_row, snapshot.line_len(stopped_row));
+    let scope_start = snapshot
+        .outline_items_containing(position..position, false, None)
+        .and_then(|items| items.into_iter().max_by_key(|item| item.depth))
+        .map_or(Point::zero(), |item| item.range.start.to_point(snapshot));
+    (scope_start..stopped_location.end, stopped_location)
+}
+
+fn value_source(snapshot: &BufferSnapshot, value: InlineValue) -> (u32, ValueSource) {
+    let row = value.range.end.to_point(snapshot).row;
+    let text = || {
+        snapshot
+            .text_for_range(value.range.clone())
+            .collect::<String>()
+    };
+    let source = match value.kind {
+        InlineValueKind::Text(text) => ValueSource::Text(text),
+        InlineValueKind::VariableLookup {
+            variable_name,
+            case_sensitive,
+        } => ValueSource::Variable {
+            name: variable_name.unwrap_or_else(text),
+            case_sensitive,
+        },
+        InlineValueKind::EvaluatableExpression { expression } => {
+            ValueSource::Expression(expression.unwrap_or_else(text))
+        }
+    };
+    (row, source)
+}
+
+/// Finds the identifiers within the given range of the buffer's syntax tree, to look them up
+/// as variables when the language server doesn't provide inline values.
+fn identifiers_in_scope(snapshot: &BufferSnapshot, range: Range<Point>) -> Vec<(u32, ValueSource)> {
+    let range = range.start.to_offset(snapshot)..range.end.to_offset(snapshot);
+    let Some(layer) = snapshot.syntax_layer_at(range.start) else {
+        return Vec::new();
+    };
+
+    let mut identifiers = Vec::new();
+    let mut cursor = layer.node().walk();
+    loop {
+        let node = cursor.node();
+        let descend = node.start_byte() < range.end && node.end_byte() > range.start;
+        if descend && node.kind() == "identifier" && node.child_count() == 0 {
+            identifiers.push((
+                snapshot.offset_to_point(node.start_byte()).row,
+                ValueSource::Variable {
+                    name: snapshot
+                        .text_for_range(node.start_byte()..node.end_byte())
+                        .collect(),
+                    case_sensitive: true,
+                },
+            ));
+        }
+        if descend && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return identifiers;
+            }
+        }
+    }
+}
+
+fn format_value(name: &str, value: &str) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.chars().count() > MAX_VALUE_LEN {
+        let truncated = value.chars().take(MAX_VALUE_LEN).collect::<String>();
+        format!("{name} = {truncated}…")
+    } else {
+        format!("{name} = {value}")
+    }
+}
@@ -219,6 +219,22 @@ pub fn deploy_context_menu(
                     } else {
                         builder.disabled_action(COPY_PERMALINK_LABEL, Box::new(CopyPermalinkToLine))
                     }
+                })
+                .when(has_git_repo, |builder| {
+                    let label = if has_selections {
+                        "View Selection History"
+                    } else {
+                        "View File History"
+                    };
+                    builder.action(label, Box::new(git::FileHistory))
+                })
+                .when(has_git_repo && has_selections, |builder| {
+                    builder
+                        .action("Stage Selected Lines", Box::new(git::StageSelectedLines))
+                        .action(
+                            "Unstage Selected Lines",
+                            Box::new(git::UnstageSelectedLines),
+                        )
                 });
             match focus {
                 Some(focus) => builder.context(focus),
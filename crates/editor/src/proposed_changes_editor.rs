@@ -513,4 +513,82 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
     ) -> Option<Task<gpui::Result<project::ProjectTransaction>>> {
         None
     }
+
+    fn rename_edits(
+        &self,
+        _: &Entity<Buffer>,
+        _: text::Anchor,
+        _: String,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Option<Vec<project::RenameEdit>>>>> {
+        None
+    }
+
+    fn semantic_tokens(
+        &self,
+        _: Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Option<project::BufferSemanticTokens>>>> {
+        None
+    }
+
+    fn folding_ranges(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Vec<Range<text::Anchor>>>>> {
+        None
+    }
+
+    fn document_links(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Vec<project::DocumentLink>>>> {
+        None
+    }
+
+    fn resolve_document_link(
+        &self,
+        _: &Entity<Buffer>,
+        _: &project::DocumentLink,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Option<String>>>> {
+        None
+    }
+    fn inline_values(
+        &self,
+        _: &Entity<Buffer>,
+        _: Range<text::Anchor>,
+        _: Range<text::Anchor>,
+        _: u64,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Vec<project::InlineValue>>>> {
+        None
+    }
+    fn document_colors(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Vec<project::DocumentColor>>>> {
+        None
+    }
+
+    fn color_presentations(
+        &self,
+        _: &Entity<Buffer>,
+        _: &project::DocumentColor,
+        _: lsp::Color,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Vec<project::ColorPresentation>>>> {
+        None
+    }
+    fn selection_ranges(
+        &self,
+        _: &Entity<Buffer>,
+        _: Vec<text::Anchor>,
+        _: &mut App,
+    ) -> Option<Task<gpui::Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        None
+    }
 }
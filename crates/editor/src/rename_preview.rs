@@ -0,0 +1,507 @@
+use std::{any::TypeId, ops::Range, sync::Arc};
+
+use gpui::{Empty, Entity, EventEmitter, FocusHandle, Focusable, Render, WeakEntity};
+use language::{Buffer, Capability, OffsetRangeExt as _};
+use multi_buffer::{Anchor, MultiBuffer};
+use project::{ChangeAnnotation, Project, ProjectTransaction, RenameEdit};
+use ui::{Checkbox, prelude::*};
+use workspace::{Item, Workspace, item::ItemEvent, searchable::SearchableItemHandle};
+
+use crate::{
+    DEFAULT_MULTIBUFFER_CONTEXT, Editor, EditorEvent,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle},
+};
+
+/// A preview of the edits of a rename, grouped by file, in which edits and whole files can be
+/// excluded before the rename is applied.
+pub struct RenamePreview {
+    editor: Entity<Editor>,
+    source_editor: WeakEntity<Editor>,
+    workspace: WeakEntity<Workspace>,
+    title: SharedString,
+    files: Vec<PreviewFile>,
+}
+
+struct PreviewFile {
+    buffer: Entity<Buffer>,
+    edits: Vec<PreviewEdit>,
+}
+
+struct PreviewEdit {
+    buffer_range: Range<text::Anchor>,
+    /// The range of the edit in the preview's multibuffer.
+    range: Range<Anchor>,
+    new_text: String,
+    annotation: Option<ChangeAnnotation>,
+    included: bool,
+}
+
+impl RenamePreview {
+    pub fn new(
+        title: impl Into<SharedString>,
+        edits: Vec<RenameEdit>,
+        source_editor: WeakEntity<Editor>,
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut edits_by_buffer = Vec::<(Entity<Buffer>, Vec<RenameEdit>)>::new();
+        for edit in edits {
+            match edits_by_buffer
+                .iter_mut()
+                .find(|(buffer, _)| *buffer == edit.location.buffer)
+            {
+                Some((_, edits)) => edits.push(edit),
+                None => edits_by_buffer.push((edit.location.buffer.clone(), vec![edit])),
+            }
+        }
+        edits_by_buffer.sort_by_cached_key(|(buffer, _)| {
+            buffer.read(cx).file().map(|file| file.full_path(cx))
+        });
+
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
+        let files = multibuffer.update(cx, |multibuffer, cx| {
+            edits_by_buffer
+                .into_iter()
+                .map(|(buffer, mut edits)| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    edits.sort_by(|a, b| {
+                        a.location
+                            .range
+                            .start
+                            .cmp(&b.location.range.start, &snapshot)
+                    });
+                    let ranges = multibuffer.push_excerpts_with_context_lines(
+                        buffer.clone(),
+                        edits
+                            .iter()
+                            .map(|edit| edit.location.range.to_point(&snapshot))
+                            .collect(),
+                        DEFAULT_MULTIBUFFER_CONTEXT,
+                        cx,
+                    );
+                    let edits = edits
+                        .into_iter()
+                        .zip(ranges)
+                        .map(|(edit, range)| {
+                            // Edits which need confirmation are only applied if opted into.
+                            let included = edit
+                                .annotation
+                                .as_ref()
+                                .is_none_or(|annotation| !annotation.needs_confirmation);
+                            PreviewEdit {
+                                buffer_range: edit.location.range,
+                                range,
+                                new_text: edit.new_text,
+                                annotation: edit.annotation,
+                                included,
+                            }
+                        })
+                        .collect();
+                    PreviewFile { buffer, edits }
+                })
+                .collect()
+        });
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+            editor.set_completion_provider(None);
+            editor.clear_code_action_providers();
+            editor
+        });
+        let this = Self {
+            editor,
+            source_editor,
+            workspace,
+            title: title.into(),
+            files,
+        };
+        this.insert_blocks(cx);
+        this.update_highlights(cx);
+        this
+    }
+
+    fn insert_blocks(&self, cx: &mut Context<Self>) {
+        let preview = cx.weak_entity();
+        let multibuffer = self.editor.read(cx).buffer().read(cx);
+        let snapshot = multibuffer.snapshot(cx);
+        let mut blocks = Vec::new();
+        for (file_ix, file) in self.files.iter().enumerate() {
+            let buffer_id = file.buffer.read(cx).remote_id();
+            if let Some(start) = multibuffer
+                .excerpts_for_buffer(buffer_id, cx)
+                .first()
+                .and_then(|(excerpt_id, range)| {
+                    snapshot.anchor_in_excerpt(*excerpt_id, range.context.start)
+                })
+            {
+                let preview = preview.clone();
+                blocks.push(BlockProperties {
+                    placement: BlockPlacement::Above(start),
+                    height: 1,
+                    style: BlockStyle::Sticky,
+                    render: Arc::new(move |cx| render_file_toggle(preview.clone(), file_ix, cx)),
+                    priority: 0,
+                });
+            }
+
+            for (edit_ix, edit) in file.edits.iter().enumerate() {
+                let preview = preview.clone();
+                blocks.push(BlockProperties {
+                    placement: BlockPlacement::Below(edit.range.start),
+                    height: 1,
+                    style: BlockStyle::Flex,
+                    render: Arc::new(move |cx| {
+                        render_edit_toggle(preview.clone(), file_ix, edit_ix, cx)
+                    }),
+                    priority: 0,
+                });
+            }
+        }
+
+        self.editor.update(cx, |editor, cx| {
+            editor.insert_blocks(blocks, None, cx);
+        });
+    }
+
+    fn update_highlights(&self, cx: &mut Context<Self>) {
+        let ranges = self
+            .included_edits()
+            .map(|edit| edit.range.clone())
+            .collect::<Vec<_>>();
+        self.editor.update(cx, |editor, cx| {
+            editor.highlight_background::<Self>(
+                &ranges,
+                |colors| colors.editor_document_highlight_write_background,
+                cx,
+            );
+            cx.notify();
+        });
+    }
+
+    fn included_edits(&self) -> impl Iterator<Item = &PreviewEdit> {
+        self.files
+            .iter()
+            .flat_map(|file| file.edits.iter().filter(|edit| edit.included))
+    }
+
+    fn toggle_edit(&mut self, file_ix: usize, edit_ix: usize, cx: &mut Context<Self>) {
+        if let Some(edit) = self
+            .files
+            .get_mut(file_ix)
+            .and_then(|file| file.edits.get_mut(edit_ix))
+        {
+            edit.included = !edit.included;
+            self.update_highlights(cx);
+            cx.notify();
+        }
+    }
+
+    fn toggle_file(&mut self, file_ix: usize, cx: &mut Context<Self>) {
+        if let Some(file) = self.files.get_mut(file_ix) {
+            let included = !file.edits.iter().all(|edit| edit.included);
+            for edit in &mut file.edits {
+                edit.included = included;
+            }
+            self.update_highlights(cx);
+            cx.notify();
+        }
+    }
+
+    /// Applies the included edits and closes the preview, showing the result like a rename
+    /// that wasn't previewed.
+    fn apply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut project_transaction = ProjectTransaction::default();
+        for file in &self.files {
+            let edits = file
+                .edits
+                .iter()
+                .filter(|edit| edit.included)
+                .map(|edit| (edit.buffer_range.clone(), edit.new_text.clone()))
+                .collect::<Vec<_>>();
+            if edits.is_empty() {
+                continue;
+            }
+            let transaction = file.buffer.update(cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.start_transaction();
+                buffer.edit(edits, None, cx);
+                buffer.end_transaction(cx)?;
+                buffer.finalize_last_transaction().cloned()
+            });
+            if let Some(transaction) = transaction {
+                project_transaction
+                    .0
+                    .insert(file.buffer.clone(), transaction);
+            }
+        }
+        cx.emit(EditorEvent::Closed);
+
+        let source_editor = self.source_editor.clone();
+        let workspace = self.workspace.clone();
+        let title = self.title.to_string();
+        cx.spawn_in(window, async move |_, cx| {
+            Editor::open_project_transaction(
+                &source_editor,
+                workspace,
+                project_transaction,
+                title,
+                cx,
+            )
+            .await?;
+            source_editor.update(cx, |editor, cx| {
+                editor.refresh_document_highlights(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+fn render_file_toggle(
+    preview: WeakEntity<RenamePreview>,
+    file_ix: usize,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let Some((any_included, all_included, edit_count)) = preview.upgrade().and_then(|preview| {
+        let file = preview.read(cx.app).files.get(file_ix)?;
+        Some((
+            file.edits.iter().any(|edit| edit.included),
+            file.edits.iter().all(|edit| edit.included),
+            file.edits.len(),
+        ))
+    }) else {
+        return Empty.into_any_element();
+    };
+
+    h_flex()
+        .id(("rename-preview-file", file_ix))
+        .block_mouse_down()
+        .pl(cx.gutter_dimensions.full_width())
+        .gap_2()
+        .child(
+            Checkbox::new(
+                ("rename-preview-file-toggle", file_ix),
+                ToggleState::from_any_and_all(any_included, all_included),
+            )
+            .on_click(move |_, _, cx| {
+                preview
+                    .update(cx, |preview, cx| preview.toggle_file(file_ix, cx))
+                    .ok();
+            }),
+        )
+        .child(
+            Label::new(if edit_count == 1 {
+                "1 edit in this file".to_string()
+            } else {
+                format!("{edit_count} edits in this file")
+            })
+            .size(LabelSize::Small)
+            .color(Color::Muted),
+        )
+        .into_any_element()
+}
+
+fn render_edit_toggle(
+    preview: WeakEntity<RenamePreview>,
+    file_ix: usize,
+    edit_ix: usize,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let Some((included, new_text, annotation)) = preview.upgrade().and_then(|preview| {
+        let edit = preview
+            .read(cx.app)
+            .files
+            .get(file_ix)?
+            .edits
+            .get(edit_ix)?;
+        Some((
+            edit.included,
+            edit.new_text.clone(),
+            edit.annotation.clone(),
+        ))
+    }) else {
+        return Empty.into_any_element();
+    };
+
+    h_flex()
+        .id(SharedString::from(format!(
+            "rename-preview-edit-{file_ix}-{edit_ix}"
+        )))
+        .block_mouse_down()
+        .pl(cx.anchor_x)
+        .gap_2()
+        .child(
+            Checkbox::new(
+                SharedString::from(format!("rename-preview-edit-toggle-{file_ix}-{edit_ix}")),
+                included.into(),
+            )
+            .on_click(move |_, _, cx| {
+                preview
+                    .update(cx, |preview, cx| preview.toggle_edit(file_ix, edit_ix, cx))
+                    .ok();
+            }),
+        )
+        .child(
+            Label::new(format!("→ {new_text}"))
+                .buffer_font(cx.app)
+                .color(if included {
+                    Color::Created
+                } else {
+                    Color::Disabled
+                }),
+        )
+        .when_some(annotation, |this, annotation| {
+            let label = if annotation.needs_confirmation {
+                format!("{} (needs confirmation)", annotation.label)
+            } else {
+                annotation.label
+            };
+            this.child(Label::new(label).size(LabelSize::Small).color(
+                if annotation.needs_confirmation {
+                    Color::Warning
+                } else {
+                    Color::Muted
+                },
+            ))
+            .when_some(annotation.description, |this, description| {
+                this.child(
+                    Label::new(description)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+        })
+        .into_any_element()
+}
+
+impl Render for RenamePreview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let edit_count = self
+            .files
+            .iter()
+            .map(|file| file.edits.len())
+            .sum::<usize>();
+        let included_count = self.included_edits().count();
+
+        v_flex()
+            .size_full()
+            .key_context("RenamePreview")
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Label::new(format!(
+                            "{included_count} of {edit_count} edits in {} files selected",
+                            self.files.len()
+                        ))
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("discard-rename", "Discard").on_click(
+                                    cx.listener(|_, _, _, cx| cx.emit(EditorEvent::Closed)),
+                                ),
+                            )
+                            .child(
+                                Button::new("apply-rename", "Apply")
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(included_count == 0)
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.apply(window, cx)),
+                                    ),
+                            ),
+                    ),
+            )
+            .child(self.editor.clone())
+    }
+}
+
+impl Focusable for RenamePreview {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<EditorEvent> for RenamePreview {}
+
+impl Item for RenamePreview {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, mut f: impl FnMut(ItemEvent)) {
+        if let EditorEvent::Closed = event {
+            f(ItemEvent::CloseItem);
+        }
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Pencil))
+    }
+
+    fn tab_content_text(&self, _window: &Window, _cx: &App) -> Option<SharedString> {
+        Some(self.title.clone())
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::added_to_workspace(editor, workspace, window, cx)
+        });
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn std::any::Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, window, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: workspace::ItemNavHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, window, cx)
+        });
+    }
+}
@@ -0,0 +1,146 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::HashMap;
+use futures::future;
+use gpui::{Context, Task, Window};
+use language::language_settings::SelectionRangeSource;
+use multi_buffer::{Anchor, ExcerptId};
+use text::{BufferId, Selection};
+use util::ResultExt as _;
+
+use crate::{Editor, SelectLargerSyntaxNode};
+
+/// A request for the selection ranges of the language server, along with the number of times
+/// the selections have to be expanded once it completes.
+pub(super) struct PendingSelectionRanges {
+    selections: Vec<(usize, Range<usize>)>,
+    expansions: usize,
+    _task: Task<()>,
+}
+
+/// Requests the selection ranges of the language server for the given selections, when their
+/// language prefers them over the syntax tree and they aren't known yet. Returns whether
+/// expanding the selections has to wait for the response.
+pub(super) fn request_selection_ranges(
+    editor: &mut Editor,
+    selections: &[Selection<usize>],
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) -> bool {
+    if let Some(pending) = &mut editor.pending_selection_ranges {
+        pending.expansions += 1;
+        return true;
+    }
+    let Some(provider) = editor.semantics_provider.clone() else {
+        return false;
+    };
+
+    let multi_buffer = editor.buffer.read(cx);
+    let snapshot = multi_buffer.snapshot(cx);
+    let mut positions_by_buffer = HashMap::<BufferId, Vec<_>>::default();
+    for selection in selections {
+        if editor
+            .select_syntax_node_history
+            .lsp_ranges
+            .contains_key(&selection.id)
+        {
+            continue;
+        }
+        let source = multi_buffer
+            .language_settings_at(selection.start, cx)
+            .selection_range_source;
+        if source != SelectionRangeSource::LanguageServer {
+            continue;
+        }
+        let anchor = snapshot.anchor_after(selection.start);
+        let Some(buffer_id) = anchor.buffer_id else {
+            continue;
+        };
+        positions_by_buffer.entry(buffer_id).or_default().push((
+            selection.id,
+            anchor.excerpt_id,
+            anchor.text_anchor,
+        ));
+    }
+    if positions_by_buffer.is_empty() {
+        return false;
+    }
+
+    let requests = positions_by_buffer
+        .into_iter()
+        .filter_map(|(buffer_id, positions)| {
+            let buffer = editor.buffer.read(cx).buffer(buffer_id)?;
+            let task = provider.selection_ranges(
+                &buffer,
+                positions.iter().map(|(_, _, position)| *position).collect(),
+                cx,
+            )?;
+            Some(async move { (positions, task.await.log_err().unwrap_or_default()) })
+        })
+        .collect::<Vec<_>>();
+    if requests.is_empty() {
+        return false;
+    }
+
+    let task = cx.spawn_in(window, async move |editor, cx| {
+        let responses = future::join_all(requests).await;
+        editor
+            .update_in(cx, |editor, window, cx| {
+                let Some(pending) = editor.pending_selection_ranges.take() else {
+                    return;
+                };
+                let current_selections = editor
+                    .selections
+                    .all::<usize>(cx)
+                    .into_iter()
+                    .map(|selection| (selection.id, selection.range()))
+                    .collect::<Vec<_>>();
+                if current_selections != pending.selections {
+                    return;
+                }
+
+                let snapshot = editor.buffer.read(cx).snapshot(cx);
+                for (positions, selection_ranges) in responses {
+                    for (ix, (selection_id, excerpt_id, _)) in positions.into_iter().enumerate() {
+                        let ranges = selection_ranges
+                            .get(ix)
+                            .map(|ranges| {
+                                ranges_in_excerpt(&snapshot, excerpt_id, ranges.iter().cloned())
+                            })
+                            .unwrap_or_default();
+                        editor
+                            .select_syntax_node_history
+                            .lsp_ranges
+                            .insert(selection_id, ranges);
+                    }
+                }
+                for _ in 0..pending.expansions {
+                    editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+                }
+            })
+            .ok();
+    });
+    editor.pending_selection_ranges = Some(PendingSelectionRanges {
+        selections: selections
+            .iter()
+            .map(|selection| (selection.id, selection.range()))
+            .collect(),
+        expansions: 1,
+        _task: task,
+    });
+    true
+}
+
+fn ranges_in_excerpt(
+    snapshot: &multi_buffer::MultiBufferSnapshot,
+    excerpt_id: ExcerptId,
+    ranges: impl Iterator<Item = Range<text::Anchor>>,
+) -> Arc<[Range<Anchor>]> {
+    ranges
+        .filter_map(|range| {
+            let start = snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+            let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+            Some(start..end)
+        })
+        .collect()
+}
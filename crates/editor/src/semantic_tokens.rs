@@ -0,0 +1,235 @@
+use std::{ops::Range, time::Duration};
+
+use collections::HashMap;
+use gpui::Context;
+use language::{HighlightId, HighlightMap, language_settings::language_settings};
+use multi_buffer::Anchor;
+use project::BufferSemanticTokens;
+use text::BufferId;
+use theme::{ActiveTheme as _, SyntaxTheme};
+use util::ResultExt as _;
+
+use crate::{Editor, EditorMode};
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Requests semantic tokens for the given buffer, or for all buffers of the editor,
+/// and replaces the corresponding highlights once the language server responds.
+pub(super) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    for_buffer: Option<BufferId>,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(provider) = editor.semantics_provider.clone() else {
+        return;
+    };
+
+    let multi_buffer = editor.buffer.read(cx);
+    let buffers = match for_buffer {
+        Some(buffer_id) => multi_buffer.buffer(buffer_id).into_iter().collect(),
+        None => multi_buffer.all_buffers().into_iter().collect::<Vec<_>>(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let enabled = {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+                .semantic_tokens
+                .enabled
+        };
+        if !enabled {
+            editor.semantic_tokens_tasks.remove(&buffer_id);
+            editor.display_map.update(cx, |display_map, _| {
+                display_map.clear_semantic_token_highlights(buffer_id)
+            });
+            continue;
+        }
+
+        let task = cx.spawn(async move |editor, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            let Some(tokens_task) = cx
+                .update(|cx| provider.semantic_tokens(buffer.clone(), cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let tokens = tokens_task.await.log_err().flatten();
+            editor
+                .update(cx, |editor, cx| {
+                    let highlights = tokens
+                        .map(|tokens| semantic_token_highlights(editor, buffer_id, &tokens, cx))
+                        .unwrap_or_default();
+                    editor.display_map.update(cx, |display_map, _| {
+                        display_map.set_semantic_token_highlights(buffer_id, highlights.into())
+                    });
+                    cx.notify();
+                })
+                .ok();
+        });
+        editor.semantic_tokens_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Converts the tokens of a buffer into syntax highlights for every excerpt of that buffer.
+fn semantic_token_highlights(
+    editor: &Editor,
+    buffer_id: BufferId,
+    tokens: &BufferSemanticTokens,
+    cx: &Context<Editor>,
+) -> Vec<(Range<Anchor>, HighlightId)> {
+    let Some(buffer) = editor.buffer.read(cx).buffer(buffer_id) else {
+        return Vec::new();
+    };
+    let buffer = buffer.read(cx);
+    let token_styles = language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+        .semantic_tokens
+        .token_styles
+        .clone();
+    let buffer_snapshot = buffer.snapshot();
+    let syntax_theme = cx.theme().syntax().clone();
+    let multi_buffer = editor.buffer.read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+
+    let mut highlight_ids = HashMap::default();
+    let mut highlights = Vec::new();
+    for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(buffer_id, cx) {
+        let context = excerpt_range.context;
+        let start_ix = tokens.tokens.partition_point(|token| {
+            token
+                .range
+                .end
+                .cmp(&context.start, &buffer_snapshot)
+                .is_le()
+        });
+        for token in &tokens.tokens[start_ix..] {
+            if token
+                .range
+                .start
+                .cmp(&context.end, &buffer_snapshot)
+                .is_ge()
+            {
+                break;
+            }
+            let highlight_id = *highlight_ids
+                .entry((token.token_type, token.token_modifiers))
+                .or_insert_with(|| {
+                    let capture_name = tokens.token_type(token).and_then(|token_type| {
+                        capture_name(token_type, tokens.token_modifiers(token), &token_styles)
+                    });
+                    capture_name.map_or(HighlightId::default(), |capture_name| {
+                        resolve_highlight_id(&capture_name, &syntax_theme)
+                    })
+                });
+            // Leave tokens unknown to the theme to the syntax highlighting.
+            if highlight_id.is_default() {
+                continue;
+            }
+            let start = multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, token.range.start);
+            let end = multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, token.range.end);
+            if let Some((start, end)) = start.zip(end) {
+                highlights.push((start..end, highlight_id));
+            }
+        }
+    }
+    highlights
+}
+
+fn resolve_highlight_id(capture_name: &str, theme: &SyntaxTheme) -> HighlightId {
+    HighlightMap::new(&[capture_name], theme).get(0)
+}
+
+/// Returns the syntax capture name to highlight the given token with.
+///
+/// The user-configured styles take precedence, looked up by `type.modifier` first and
+/// then by `type`. Otherwise, the token type is mapped to the capture name used by the
+/// tree-sitter highlight queries, followed by the token modifiers, so that themes can
+/// style e.g. `variable.mutable` differently from `variable`.
+fn capture_name<'a>(
+    token_type: &str,
+    modifiers: impl Iterator<Item = &'a str>,
+    token_styles: &HashMap<String, String>,
+) -> Option<String> {
+    let modifiers = modifiers.collect::<Vec<_>>();
+    for modifier in &modifiers {
+        if let Some(style) = token_styles.get(&format!("{token_type}.{modifier}")) {
+            return Some(style.clone());
+        }
+    }
+    if let Some(style) = token_styles.get(token_type) {
+        return Some(style.clone());
+    }
+
+    let mut capture_name = default_capture_name(token_type)?.to_string();
+    for modifier in modifiers {
+        capture_name.push('.');
+        capture_name.push_str(modifier);
+    }
+    Some(capture_name)
+}
+
+fn default_capture_name(token_type: &str) -> Option<&'static str> {
+    Some(match token_type {
+        "namespace" => "namespace",
+        "type" | "class" | "interface" | "struct" => "type",
+        "typeParameter" => "type.parameter",
+        "enum" => "enum",
+        "enumMember" => "variant",
+        "parameter" => "variable.parameter",
+        "variable" => "variable",
+        "property" => "property",
+        "event" => "property",
+        "function" => "function",
+        "method" => "function.method",
+        "macro" => "function.macro",
+        "keyword" => "keyword",
+        "modifier" => "keyword.modifier",
+        "comment" => "comment",
+        "string" => "string",
+        "number" => "number",
+        "regexp" => "string.regex",
+        "operator" => "operator",
+        "decorator" => "attribute",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_token_capture_names() {
+        let mut token_styles = HashMap::default();
+        assert_eq!(
+            capture_name("method", ["static"].into_iter(), &token_styles).as_deref(),
+            Some("function.method.static")
+        );
+        assert_eq!(
+            capture_name("unknownType", [].into_iter(), &token_styles),
+            None
+        );
+
+        token_styles.insert("variable".to_string(), "constant".to_string());
+        token_styles.insert(
+            "variable.mutable".to_string(),
+            "variable.special".to_string(),
+        );
+        assert_eq!(
+            capture_name("variable", [].into_iter(), &token_styles).as_deref(),
+            Some("constant")
+        );
+        assert_eq!(
+            capture_name(
+                "variable",
+                ["declaration", "mutable"].into_iter(),
+                &token_styles
+            )
+            .as_deref(),
+            Some("variable.special")
+        );
+    }
+}
@@ -1,12 +1,14 @@
-use crate::FakeFs;
-use anyhow::{Context as _, Result, anyhow};
+use crate::{FakeFs, Fs, RemoveOptions};
+use anyhow::{Context as _, Result, anyhow, bail};
 use collections::{HashMap, HashSet};
 use futures::future::{self, BoxFuture};
 use git::{
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDetails, GitRepository, GitRepositoryCheckpoint,
-        PushOptions, Remote, RepoPath, ResetMode,
+        AskPassDelegate, Branch, CommitDetails, CommitFileDiff, CommitOptions, DiffRevisions,
+        GitRepository, GitRepositoryCheckpoint, GitWorktree, LogEntry, LogOptions, PushOptions,
+        RebaseCommand, RebaseStatus, RebaseStep, Remote, RepoPath, ResetMode, SequencerOperation,
+        StashEntry, StashOptions, Submodule, Tag,
     },
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
@@ -33,6 +35,15 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    pub stash_entries: Vec<StashEntry>,
+    /// The HEAD and the stashed text of the paths saved by each stash entry, in the order of
+    /// `stash_entries`.
+    pub stash_changes: Vec<HashMap<RepoPath, (Option<String>, Option<String>)>>,
+    /// The worktrees linked to the repository, besides the main one it is in.
+    pub linked_worktrees: Vec<GitWorktree>,
+    /// The tags, the most recent one first.
+    pub tags: Vec<Tag>,
+    pub remotes: Vec<String>,
     pub simulated_index_write_error_message: Option<String>,
 }
 
@@ -47,6 +58,11 @@ impl FakeGitRepositoryState {
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            stash_entries: Default::default(),
+            stash_changes: Default::default(),
+            linked_worktrees: Default::default(),
+            tags: Default::default(),
+            remotes: Default::default(),
             simulated_index_write_error_message: Default::default(),
         }
     }
@@ -76,6 +92,35 @@ impl FakeGitRepository {
         }
         .boxed()
     }
+
+    fn load_worktree_text(&self, path: &RepoPath) -> Option<String> {
+        let workdir_path = self.dot_git_path.parent().unwrap();
+        let content = self.fs.read_file_sync(workdir_path.join(path)).ok()?;
+        String::from_utf8(content).ok()
+    }
+
+    /// Writes the given texts to the working copy, removing the files without one.
+    async fn write_worktree_texts(&self, texts: Vec<(RepoPath, Option<String>)>) -> Result<()> {
+        let workdir_path = self.dot_git_path.parent().unwrap();
+        for (path, text) in texts {
+            let abs_path = workdir_path.join(&path);
+            match text {
+                Some(text) => self.fs.insert_file(&abs_path, text.into_bytes()).await,
+                None => {
+                    self.fs
+                        .remove_file(
+                            &abs_path,
+                            RemoveOptions {
+                                ignore_if_not_exists: true,
+                                ..RemoveOptions::default()
+                            },
+                        )
+                        .await?
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl GitRepository for FakeGitRepository {
@@ -342,10 +387,31 @@ impl GitRepository for FakeGitRepository {
 
     fn stage_paths(
         &self,
-        _paths: Vec<RepoPath>,
+        paths: Vec<RepoPath>,
         _env: HashMap<String, String>,
     ) -> BoxFuture<Result<()>> {
-        unimplemented!()
+        let workdir_path = self.dot_git_path.parent().unwrap();
+        let contents = paths
+            .into_iter()
+            .map(|path| {
+                let content = self
+                    .fs
+                    .read_file_sync(workdir_path.join(&path))
+                    .ok()
+                    .and_then(|content| String::from_utf8(content).ok());
+                (path, content)
+            })
+            .collect::<Vec<_>>();
+        self.with_state_async(true, move |state| {
+            for (path, content) in contents {
+                state.unmerged_paths.remove(&path);
+                match content {
+                    Some(content) => state.index_contents.insert(path, content),
+                    None => state.index_contents.remove(&path),
+                };
+            }
+            Ok(())
+        })
     }
 
     fn unstage_paths(
@@ -360,11 +426,21 @@ impl GitRepository for FakeGitRepository {
         &self,
         _message: gpui::SharedString,
         _name_and_email: Option<(gpui::SharedString, gpui::SharedString)>,
+        _options: CommitOptions,
+        _askpass: AskPassDelegate,
         _env: HashMap<String, String>,
+        _cx: AsyncApp,
     ) -> BoxFuture<Result<()>> {
         unimplemented!()
     }
 
+    fn commit_message_template(
+        &self,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<Option<String>>> {
+        future::ready(Ok(None)).boxed()
+    }
+
     fn push(
         &self,
         _branch: String,
@@ -398,7 +474,15 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn get_remotes(&self, _branch: Option<String>) -> BoxFuture<Result<Vec<Remote>>> {
-        unimplemented!()
+        self.with_state_async(false, |state| {
+            Ok(state
+                .remotes
+                .iter()
+                .map(|name| Remote {
+                    name: name.clone().into(),
+                })
+                .collect())
+        })
     }
 
     fn check_for_pushed_commit(&self) -> BoxFuture<Result<Vec<gpui::SharedString>>> {
@@ -436,4 +520,518 @@ impl GitRepository for FakeGitRepository {
     ) -> BoxFuture<Result<String>> {
         unimplemented!()
     }
+
+    fn stash_entries(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        self.with_state_async(false, |state| Ok(state.stash_entries.clone()))
+    }
+
+    fn stash_push(
+        &self,
+        options: StashOptions,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        async move {
+            let status = self.status_blocking(&[RepoPath::default()])?;
+            let worktree_texts = status
+                .entries
+                .iter()
+                .filter(|(_, status)| options.include_untracked || !status.is_untracked())
+                .map(|(path, _)| (path.clone(), self.load_worktree_text(path)))
+                .collect::<Vec<_>>();
+            let restored_texts = self
+                .with_state_async(true, move |state| {
+                    let mut changes = HashMap::default();
+                    let mut restored_texts = Vec::new();
+                    for (path, worktree_text) in worktree_texts {
+                        let head_text = state.head_contents.get(&path).cloned();
+                        let index_text = state.index_contents.get(&path).cloned();
+                        let stashed_text = if options.staged {
+                            if index_text == head_text {
+                                continue;
+                            }
+                            if worktree_text == index_text {
+                                restored_texts.push((path.clone(), head_text.clone()));
+                            }
+                            index_text
+                        } else {
+                            restored_texts.push((path.clone(), head_text.clone()));
+                            worktree_text
+                        };
+                        match &head_text {
+                            Some(head_text) => {
+                                state.index_contents.insert(path.clone(), head_text.clone())
+                            }
+                            None => state.index_contents.remove(&path),
+                        };
+                        changes.insert(path, (head_text, stashed_text));
+                    }
+                    if changes.is_empty() {
+                        bail!("no local changes to save");
+                    }
+
+                    let branch = state
+                        .current_branch_name
+                        .as_deref()
+                        .unwrap_or("(no branch)");
+                    let message = match options.message {
+                        Some(message) => format!("On {branch}: {message}"),
+                        None => format!("WIP on {branch}"),
+                    };
+                    state.stash_entries.insert(
+                        0,
+                        StashEntry {
+                            index: 0,
+                            sha: format!("stash-{}", state.stash_entries.len()).into(),
+                            message: message.into(),
+                            commit_timestamp: 0,
+                        },
+                    );
+                    for (index, entry) in state.stash_entries.iter_mut().enumerate() {
+                        entry.index = index;
+                    }
+                    state.stash_changes.insert(0, changes);
+                    Ok(restored_texts)
+                })
+                .await?;
+            self.write_worktree_texts(restored_texts).await
+        }
+        .boxed()
+    }
+
+    fn stash_diff(&self, index: usize) -> BoxFuture<Result<Vec<CommitFileDiff>>> {
+        self.with_state_async(false, move |state| {
+            let changes = state
+                .stash_changes
+                .get(index)
+                .with_context(|| format!("no stash entry at index {index}"))?;
+            let mut diffs = changes
+                .iter()
+                .map(|(path, (head_text, stashed_text))| CommitFileDiff {
+                    path: path.clone(),
+                    old_text: head_text.clone(),
+                    new_text: stashed_text.clone(),
+                })
+                .collect::<Vec<_>>();
+            diffs.sort_by(|a, b| a.path.cmp(&b.path));
+            Ok(diffs)
+        })
+    }
+
+    fn stash_apply(&self, index: usize, _env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        async move {
+            let changes = self
+                .with_state_async(false, move |state| {
+                    state
+                        .stash_changes
+                        .get(index)
+                        .cloned()
+                        .with_context(|| format!("no stash entry at index {index}"))
+                })
+                .await?;
+            let mut texts = Vec::new();
+            for (path, (head_text, stashed_text)) in changes {
+                if self.load_worktree_text(&path) != head_text {
+                    bail!("your local changes to {path} would be overwritten by the stash");
+                }
+                texts.push((path, stashed_text));
+            }
+            self.write_worktree_texts(texts).await
+        }
+        .boxed()
+    }
+
+    fn stash_pop(&self, index: usize, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        async move {
+            self.stash_apply(index, env.clone()).await?;
+            self.stash_drop(index, env).await
+        }
+        .boxed()
+    }
+
+    fn stash_drop(&self, index: usize, _env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if index >= state.stash_entries.len() {
+                return Err(anyhow!("no stash entry at index {index}"));
+            }
+            state.stash_entries.remove(index);
+            if index < state.stash_changes.len() {
+                state.stash_changes.remove(index);
+            }
+            for (index, entry) in state.stash_entries.iter_mut().enumerate() {
+                entry.index = index;
+            }
+            Ok(())
+        })
+    }
+
+    fn log(&self, _options: LogOptions) -> BoxFuture<Result<Vec<LogEntry>>> {
+        // Only the contents of HEAD are recorded, not the commits leading to it.
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn commit_diff(&self, commit: String) -> BoxFuture<Result<Vec<CommitFileDiff>>> {
+        future::ready(Err(anyhow!("commit {commit} not found"))).boxed()
+    }
+
+    fn revision_diff(&self, revisions: DiffRevisions) -> BoxFuture<Result<Vec<CommitFileDiff>>> {
+        async move {
+            // Only HEAD can be compared, with the working copy.
+            if revisions.base != "HEAD" || revisions.target.is_some() {
+                bail!(
+                    "revision {} not found",
+                    revisions.target.unwrap_or(revisions.base)
+                );
+            }
+            let status = self.status_blocking(&[RepoPath::default()])?;
+            let worktree_texts = status
+                .entries
+                .iter()
+                .filter(|(_, status)| !status.is_untracked())
+                .map(|(path, _)| (path.clone(), self.load_worktree_text(path)))
+                .collect::<Vec<_>>();
+            self.with_state_async(false, move |state| {
+                Ok(worktree_texts
+                    .into_iter()
+                    .map(|(path, worktree_text)| CommitFileDiff {
+                        old_text: state.head_contents.get(&path).cloned(),
+                        new_text: worktree_text,
+                        path,
+                    })
+                    .filter(|diff| diff.old_text != diff.new_text)
+                    .collect())
+            })
+            .await
+        }
+        .boxed()
+    }
+
+    fn rebase_commits(&self, _upstream: String) -> BoxFuture<Result<Vec<LogEntry>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn rebase(
+        &self,
+        _upstream: String,
+        steps: Vec<RebaseStep>,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        // There are no commits to rebase, as only the contents of HEAD are recorded.
+        let result = match steps.first() {
+            Some(step) => Err(anyhow!("commit {} not found", step.sha)),
+            None => Ok(()),
+        };
+        future::ready(result).boxed()
+    }
+
+    fn rebase_command(
+        &self,
+        _command: RebaseCommand,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        future::ready(Err(anyhow!("no rebase in progress"))).boxed()
+    }
+
+    fn rebase_status(&self) -> BoxFuture<Result<Option<RebaseStatus>>> {
+        future::ready(Ok(None)).boxed()
+    }
+
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>> {
+        let workdir_path = self.dot_git_path.parent().unwrap().to_path_buf();
+        self.with_state_async(false, move |state| {
+            let main_worktree = GitWorktree {
+                path: workdir_path,
+                sha: None,
+                branch: state.current_branch_name.clone().map(Into::into),
+                is_main: true,
+                is_locked: false,
+                is_prunable: false,
+            };
+            Ok(std::iter::once(main_worktree)
+                .chain(state.linked_worktrees.iter().cloned())
+                .collect())
+        })
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        async move {
+            if self.fs.is_dir(&path).await {
+                bail!("{path:?} already exists");
+            }
+            self.with_state_async(true, {
+                let path = path.clone();
+                move |state| {
+                    if create_branch {
+                        if !state.branches.insert(branch.clone()) {
+                            bail!("a branch named {branch} already exists");
+                        }
+                    } else if !state.branches.contains(&branch) {
+                        bail!("invalid reference: {branch}");
+                    } else if state.current_branch_name.as_ref() == Some(&branch)
+                        || state.linked_worktrees.iter().any(|worktree| {
+                            worktree.branch.as_ref().is_some_and(|name| *name == branch)
+                        })
+                    {
+                        bail!("{branch} is already checked out");
+                    }
+                    state.linked_worktrees.push(GitWorktree {
+                        path,
+                        sha: None,
+                        branch: Some(branch.into()),
+                        is_main: false,
+                        is_locked: false,
+                        is_prunable: false,
+                    });
+                    Ok(())
+                }
+            })
+            .await?;
+            self.fs.create_dir(&path).await
+        }
+        .boxed()
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        _force: bool,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        async move {
+            self.with_state_async(true, {
+                let path = path.clone();
+                move |state| {
+                    let ix = state
+                        .linked_worktrees
+                        .iter()
+                        .position(|worktree| worktree.path == path)
+                        .with_context(|| format!("{path:?} is not a working tree"))?;
+                    state.linked_worktrees.remove(ix);
+                    Ok(())
+                }
+            })
+            .await?;
+            self.fs
+                .remove_dir(
+                    &path,
+                    RemoveOptions {
+                        recursive: true,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await
+        }
+        .boxed()
+    }
+
+    fn prune_worktrees(&self, _env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        async move {
+            let worktrees = self.with_state(|state| state.linked_worktrees.clone());
+            let mut missing_paths = Vec::new();
+            for worktree in worktrees {
+                if !self.fs.is_dir(&worktree.path).await {
+                    missing_paths.push(worktree.path);
+                }
+            }
+            self.with_state_async(true, move |state| {
+                state
+                    .linked_worktrees
+                    .retain(|worktree| !missing_paths.contains(&worktree.path));
+                Ok(())
+            })
+            .await
+        }
+        .boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>> {
+        self.with_state_async(false, |state| Ok(state.tags.clone()))
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.iter().any(|tag| tag.name == name) {
+                bail!("tag '{name}' already exists");
+            }
+            let subject = message
+                .as_deref()
+                .and_then(|message| message.lines().next())
+                .unwrap_or_default();
+            state.tags.insert(
+                0,
+                Tag {
+                    name: name.into(),
+                    sha: target.unwrap_or_else(|| "HEAD".into()).into(),
+                    subject: subject.to_owned().into(),
+                    timestamp: 0,
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn delete_tag(&self, name: String, _env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ix = state
+                .tags
+                .iter()
+                .position(|tag| tag.name == name)
+                .with_context(|| format!("tag '{name}' not found"))?;
+            state.tags.remove(ix);
+            Ok(())
+        })
+    }
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        _askpass: AskPassDelegate,
+        _env: HashMap<String, String>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<git::repository::RemoteCommandOutput>> {
+        self.with_state_async(false, move |state| {
+            if !state.remotes.contains(&remote_name) {
+                bail!("'{remote_name}' does not appear to be a git repository");
+            }
+            if !state.tags.iter().any(|tag| tag.name == name) {
+                bail!("src refspec {name} does not match any");
+            }
+            Ok(git::repository::RemoteCommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn add_remote(
+        &self,
+        name: String,
+        _url: String,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.remotes.contains(&name) {
+                bail!("remote {name} already exists");
+            }
+            state.remotes.push(name);
+            Ok(())
+        })
+    }
+
+    fn rename_remote(
+        &self,
+        name: String,
+        new_name: String,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.remotes.contains(&new_name) {
+                bail!("remote {new_name} already exists");
+            }
+            let remote = state
+                .remotes
+                .iter_mut()
+                .find(|remote| **remote == name)
+                .with_context(|| format!("no such remote: '{name}'"))?;
+            *remote = new_name;
+            Ok(())
+        })
+    }
+
+    fn remove_remote(&self, name: String, _env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ix = state
+                .remotes
+                .iter()
+                .position(|remote| *remote == name)
+                .with_context(|| format!("no such remote: '{name}'"))?;
+            state.remotes.remove(ix);
+            Ok(())
+        })
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        // There are no commits to apply, as only the contents of HEAD are recorded.
+        let result = match commits.first() {
+            Some(commit) => Err(anyhow!("bad revision '{commit}'")),
+            None => Ok(()),
+        };
+        future::ready(result).boxed()
+    }
+
+    fn revert(&self, commits: Vec<String>, _env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        let result = match commits.first() {
+            Some(commit) => Err(anyhow!("bad revision '{commit}'")),
+            None => Ok(()),
+        };
+        future::ready(result).boxed()
+    }
+
+    fn sequencer_command(
+        &self,
+        _operation: SequencerOperation,
+        _command: RebaseCommand,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        future::ready(Err(anyhow!("no cherry-pick or revert in progress"))).boxed()
+    }
+
+    fn sequencer_status(&self) -> BoxFuture<Result<Option<SequencerOperation>>> {
+        future::ready(Ok(None)).boxed()
+    }
+
+    fn submodules(&self, _env: HashMap<String, String>) -> BoxFuture<Result<Vec<Submodule>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        _init: bool,
+        _askpass: AskPassDelegate,
+        _env: HashMap<String, String>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<git::repository::RemoteCommandOutput>> {
+        // The fake repository has no submodules.
+        let result = match paths.first() {
+            Some(path) => Err(anyhow!("no submodule at {path}")),
+            None => Ok(git::repository::RemoteCommandOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+            }),
+        };
+        future::ready(result).boxed()
+    }
+
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        _env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let result = match paths.first() {
+            Some(path) => Err(anyhow!("no submodule at {path}")),
+            None => Ok(()),
+        };
+        future::ready(result).boxed()
+    }
+
+    fn submodule_diff(&self, path: RepoPath) -> BoxFuture<Result<CommitFileDiff>> {
+        future::ready(Err(anyhow!("no submodule at {path}"))).boxed()
+    }
 }
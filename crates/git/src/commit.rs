@@ -37,3 +37,71 @@ pub async fn get_messages(working_directory: &Path, shas: &[Oid]) -> Result<Hash
         )
         .collect::<HashMap<Oid, String>>())
 }
+
+/// Returns the trailers ending `message`, e.g. `("Co-authored-by", "Name <email>")`, which
+/// are the `Key: value` lines of its last paragraph, when it's made only of those.
+pub fn parse_trailers(message: &str) -> Vec<(&str, &str)> {
+    // A message made of a single paragraph has no body for trailers to follow.
+    let Some((_, last_paragraph)) = message.trim_end().rsplit_once("\n\n") else {
+        return Vec::new();
+    };
+    last_paragraph
+        .lines()
+        .map(|line| {
+            let (key, value) = line.split_once(": ")?;
+            let is_key = !key.is_empty()
+                && key
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || char == '-');
+            is_key.then(|| (key, value.trim()))
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+/// Appends `trailers` to `message`, continuing the trailer block it ends with if it has one.
+pub fn append_trailers(message: &mut String, trailers: &[(&str, &str)]) {
+    if trailers.is_empty() {
+        return;
+    }
+    let ends_with_trailers = !parse_trailers(message).is_empty();
+    message.truncate(message.trim_end().len());
+    if !message.is_empty() {
+        message.push_str(if ends_with_trailers { "\n" } else { "\n\n" });
+    }
+    for (key, value) in trailers {
+        message.push_str(key);
+        message.push_str(": ");
+        message.push_str(value);
+        message.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailers() {
+        assert_eq!(parse_trailers("Fix: the bug"), []);
+        assert_eq!(parse_trailers("Subject\n\nSome body.\n"), []);
+        assert_eq!(
+            parse_trailers(
+                "Subject\n\nBody\n\nSigned-off-by: A <a@b.c>\nCo-authored-by: B <b@c.d>\n"
+            ),
+            [
+                ("Signed-off-by", "A <a@b.c>"),
+                ("Co-authored-by", "B <b@c.d>")
+            ]
+        );
+
+        let mut message = "Subject\n".to_string();
+        append_trailers(&mut message, &[("Co-authored-by", "B <b@c.d>")]);
+        assert_eq!(message, "Subject\n\nCo-authored-by: B <b@c.d>\n");
+        append_trailers(&mut message, &[("Co-authored-by", "C <c@d.e>")]);
+        assert_eq!(
+            message,
+            "Subject\n\nCo-authored-by: B <b@c.d>\nCo-authored-by: C <c@d.e>\n"
+        );
+    }
+}
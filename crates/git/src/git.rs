@@ -36,6 +36,9 @@ actions!(
         ToggleStaged,
         StageAndNext,
         UnstageAndNext,
+        // per-line
+        StageSelectedLines,
+        UnstageSelectedLines,
         // per-file
         StageFile,
         UnstageFile,
@@ -45,6 +48,36 @@ actions!(
         RestoreTrackedFiles,
         TrashUntrackedFiles,
         Uncommit,
+        StashAll,
+        StashStaged,
+        StashPop,
+        ViewStash,
+        ViewHistory,
+        FileHistory,
+        InteractiveRebase,
+        RebaseContinue,
+        RebaseSkip,
+        RebaseAbort,
+        CompareBranches,
+        ViewWorktrees,
+        PruneWorktrees,
+        CreateTag,
+        DeleteTag,
+        PushTag,
+        AddRemote,
+        RenameRemote,
+        RemoveRemote,
+        CherryPickCommit,
+        CherryPickContinue,
+        CherryPickSkip,
+        CherryPickAbort,
+        RevertCommit,
+        RevertContinue,
+        RevertSkip,
+        RevertAbort,
+        InitSubmodules,
+        UpdateSubmodules,
+        SyncSubmodules,
         Push,
         ForcePush,
         Pull,
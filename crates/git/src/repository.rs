@@ -13,6 +13,7 @@ use serde::Deserialize;
 use std::borrow::{Borrow, Cow};
 use std::ffi::{OsStr, OsString};
 use std::future;
+use std::ops::Range;
 use std::path::Component;
 use std::process::{ExitStatus, Stdio};
 use std::sync::LazyLock;
@@ -131,6 +132,52 @@ pub struct CommitDetails {
     pub commit_timestamp: i64,
     pub committer_email: SharedString,
     pub committer_name: SharedString,
+    /// The signature of the commit, if it's signed.
+    pub signature: Option<CommitSignature>,
+}
+
+/// The signature of a commit, as verified by git.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    /// The name, email or key of the signer, when git could tell it.
+    pub signer: SharedString,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// A good signature from a trusted key.
+    Good,
+    /// A good signature from a key of unknown validity.
+    Untrusted,
+    /// A good signature that has expired, or was made by a key that has.
+    Expired,
+    /// A good signature made by a revoked key.
+    Revoked,
+    /// A signature that doesn't match the commit.
+    Bad,
+    /// A signature git couldn't check, e.g. because the key isn't known.
+    Unverified,
+}
+
+impl SignatureStatus {
+    /// Parses the `%G?` placeholder of `git log` for a commit known to be signed.
+    fn from_placeholder(placeholder: &str) -> Self {
+        match placeholder {
+            "G" => SignatureStatus::Good,
+            "U" => SignatureStatus::Untrusted,
+            "X" | "Y" => SignatureStatus::Expired,
+            "R" => SignatureStatus::Revoked,
+            "B" => SignatureStatus::Bad,
+            _ => SignatureStatus::Unverified,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommitOptions {
+    /// Adds a `Signed-off-by` trailer for the committer.
+    pub signoff: bool,
 }
 
 impl CommitDetails {
@@ -144,6 +191,195 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points to.
+    pub sha: SharedString,
+    /// The subject of the tag's message for an annotated tag, or of the commit's otherwise.
+    pub subject: SharedString,
+    /// This is a unix timestamp
+    pub timestamp: i64,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of the entry in the stash, the most recent one being at 0.
+    pub index: usize,
+    pub sha: SharedString,
+    pub message: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+}
+
+impl StashEntry {
+    /// Returns the name git uses to refer to the entry, e.g. `stash@{0}`.
+    pub fn name(&self) -> String {
+        stash_ref(self.index)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StashOptions {
+    pub message: Option<String>,
+    pub include_untracked: bool,
+    /// Only stash the changes that are staged.
+    pub staged: bool,
+}
+
+/// A submodule of the repository, as listed by `git submodule status`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Submodule {
+    pub path: RepoPath,
+    /// The commit checked out in the submodule, or the one the repository records for it when
+    /// it isn't initialized.
+    pub sha: SharedString,
+    pub status: SubmoduleStatus,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SubmoduleStatus {
+    /// The submodule is declared in `.gitmodules`, but hasn't been cloned.
+    Uninitialized,
+    /// The submodule has the commit the repository records for it checked out.
+    UpToDate,
+    /// The submodule has another commit checked out than the one the repository records.
+    Modified,
+    /// The repository has merge conflicts on the commit recorded for the submodule.
+    Conflicted,
+}
+
+/// A working tree of the repository, as listed by `git worktree list`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    /// The commit checked out in the worktree, which is `None` for a bare repository.
+    pub sha: Option<SharedString>,
+    /// The branch checked out in the worktree, or `None` if its `HEAD` is detached.
+    pub branch: Option<SharedString>,
+    /// Whether this is the worktree the repository was created in, rather than a linked one.
+    pub is_main: bool,
+    pub is_locked: bool,
+    /// Whether the worktree's directory is missing, so that pruning would remove it.
+    pub is_prunable: bool,
+}
+
+/// The contents of a file before and after the changes of a commit or a stash entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitFileDiff {
+    pub path: RepoPath,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+}
+
+/// Filters and pagination of the commits returned by [`GitRepository::log`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// The number of commits to skip, from the most recent one.
+    pub skip: usize,
+    pub limit: usize,
+    /// Only returns the commits changing this path, following renames.
+    pub path: Option<RepoPath>,
+    /// Only returns the commits changing these lines of [`Self::path`], zero-based and exclusive.
+    pub line_range: Option<Range<u32>>,
+    pub author: Option<String>,
+    pub message: Option<String>,
+    /// This is a unix timestamp
+    pub since: Option<i64>,
+    /// This is a unix timestamp
+    pub until: Option<i64>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    /// The parents of the commit, the first one being the commit it was made on top of.
+    pub parent_shas: Vec<SharedString>,
+    pub subject: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    /// The branches and tags pointing at the commit, e.g. `HEAD -> main` or `tag: v1.0`.
+    pub ref_names: Vec<SharedString>,
+}
+
+/// What to do with a commit during an interactive rebase.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    Pick,
+    /// Picks the commit and replaces its message.
+    Reword,
+    /// Melds the commit into the previous one, keeping both messages.
+    Squash,
+    /// Melds the commit into the previous one, discarding its message.
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [Self; 5] = [
+        Self::Pick,
+        Self::Reword,
+        Self::Squash,
+        Self::Fixup,
+        Self::Drop,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pick => "Pick",
+            Self::Reword => "Reword",
+            Self::Squash => "Squash",
+            Self::Fixup => "Fixup",
+            Self::Drop => "Drop",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseStep {
+    pub sha: SharedString,
+    pub action: RebaseAction,
+    /// The new message of the commit, when it's reworded.
+    pub message: Option<String>,
+}
+
+/// A command resuming or ending a rebase, or a [`SequencerOperation`], that stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RebaseCommand {
+    Continue,
+    Skip,
+    Abort,
+}
+
+/// An operation applying commits one at a time, which stops at the first one that conflicts.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum SequencerOperation {
+    CherryPick,
+    Revert,
+}
+
+impl SequencerOperation {
+    fn command(&self) -> &'static str {
+        match self {
+            SequencerOperation::CherryPick => "cherry-pick",
+            SequencerOperation::Revert => "revert",
+        }
+    }
+}
+
+/// The progress of a rebase that stopped, e.g. to resolve conflicts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseStatus {
+    /// The branch being rebased, or `None` when rebasing a detached `HEAD`.
+    pub branch: Option<SharedString>,
+    pub onto: SharedString,
+    /// The step being applied, starting at 1.
+    pub step: usize,
+    pub total_steps: usize,
+}
+
 pub enum ResetMode {
     // reset the branch pointer, leave index and worktree unchanged
     // (this will make it look like things that were committed are now
@@ -237,13 +473,27 @@ pub trait GitRepository: Send + Sync {
         env: HashMap<String, String>,
     ) -> BoxFuture<Result<()>>;
 
+    /// Commits the index, signing the commit when `commit.gpgsign` is set. The passphrase of
+    /// an SSH signing key is asked through `askpass`, while GPG keys are unlocked by gpg-agent.
     fn commit(
         &self,
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
+        options: CommitOptions,
+        askpass: AskPassDelegate,
         env: HashMap<String, String>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
     ) -> BoxFuture<Result<()>>;
 
+    /// Returns the message new commits start from: the `commit.template`, as edited by the
+    /// `prepare-commit-msg` hook.
+    fn commit_message_template(
+        &self,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<Option<String>>>;
+
     fn push(
         &self,
         branch_name: String,
@@ -306,11 +556,200 @@ pub trait GitRepository: Send + Sync {
         base_checkpoint: GitRepositoryCheckpoint,
         target_checkpoint: GitRepositoryCheckpoint,
     ) -> BoxFuture<Result<String>>;
+
+    /// Returns the entries of the stash, the most recent one first.
+    fn stash_entries(&self) -> BoxFuture<Result<Vec<StashEntry>>>;
+
+    fn stash_push(
+        &self,
+        options: StashOptions,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the files changed by the stash entry at the given index, including the untracked
+    /// files it stashed.
+    fn stash_diff(&self, index: usize) -> BoxFuture<Result<Vec<CommitFileDiff>>>;
+
+    fn stash_apply(&self, index: usize, env: HashMap<String, String>) -> BoxFuture<Result<()>>;
+
+    fn stash_pop(&self, index: usize, env: HashMap<String, String>) -> BoxFuture<Result<()>>;
+
+    fn stash_drop(&self, index: usize, env: HashMap<String, String>) -> BoxFuture<Result<()>>;
+
+    /// Returns the commits reachable from `HEAD`, children before their parents.
+    fn log(&self, options: LogOptions) -> BoxFuture<Result<Vec<LogEntry>>>;
+
+    /// Returns the files changed by the commit, compared to its first parent.
+    fn commit_diff(&self, commit: String) -> BoxFuture<Result<Vec<CommitFileDiff>>>;
+
+    /// Returns the contents of the files changed between two revisions, or between a revision
+    /// and the worktree.
+    fn revision_diff(&self, revisions: DiffRevisions) -> BoxFuture<Result<Vec<CommitFileDiff>>>;
+
+    /// Returns the commits a rebase onto `upstream` would apply, oldest first.
+    fn rebase_commits(&self, upstream: String) -> BoxFuture<Result<Vec<LogEntry>>>;
+
+    /// Runs an interactive rebase onto `upstream`, applying the steps in order. Commits of
+    /// [`Self::rebase_commits`] without a step are dropped.
+    fn rebase(
+        &self,
+        upstream: String,
+        steps: Vec<RebaseStep>,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the progress of the rebase in progress, if any.
+    fn rebase_status(&self) -> BoxFuture<Result<Option<RebaseStatus>>>;
+
+    /// Returns the worktrees of the repository, the main one first.
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>>;
+
+    /// Creates a linked worktree at `path` with `branch` checked out, creating the branch from
+    /// `HEAD` first if `create_branch` is set.
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Removes the linked worktree at `path`, even if it has uncommitted changes when `force`
+    /// is set.
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Forgets the linked worktrees whose directory was deleted.
+    fn prune_worktrees(&self, env: HashMap<String, String>) -> BoxFuture<Result<()>>;
+
+    /// Returns the tags of the repository, the most recent one first.
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>>;
+
+    /// Creates a tag pointing at `target`, or at `HEAD` if it's `None`. The tag is annotated
+    /// when it has a message.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn delete_tag(&self, name: String, env: HashMap<String, String>) -> BoxFuture<Result<()>>;
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: HashMap<String, String>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
+    fn add_remote(
+        &self,
+        name: String,
+        url: String,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn rename_remote(
+        &self,
+        name: String,
+        new_name: String,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn remove_remote(&self, name: String, env: HashMap<String, String>) -> BoxFuture<Result<()>>;
+
+    /// Applies the changes of the commits on top of `HEAD`, in order, committing each of them.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Reverts the changes of the commits, in order, committing each revert.
+    fn revert(&self, commits: Vec<String>, env: HashMap<String, String>) -> BoxFuture<Result<()>>;
+
+    fn sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        command: RebaseCommand,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the cherry-pick or revert in progress, if any.
+    fn sequencer_status(&self) -> BoxFuture<Result<Option<SequencerOperation>>>;
+
+    /// Returns the submodules declared in the repository's `.gitmodules`.
+    fn submodules(&self, env: HashMap<String, String>) -> BoxFuture<Result<Vec<Submodule>>>;
+
+    /// Checks out the commits the repository records for the submodules at `paths`, or for all
+    /// of them if `paths` is empty, and for their own submodules. Uninitialized submodules are
+    /// cloned when `init` is set, and skipped otherwise.
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        askpass: AskPassDelegate,
+        env: HashMap<String, String>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
+    /// Copies the URLs of the submodules at `paths`, or of all of them if `paths` is empty,
+    /// from `.gitmodules` to their configuration.
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the commit the submodule at `path` has checked out, compared with the one `HEAD`
+    /// records for it, in the form git shows them in diffs.
+    fn submodule_diff(&self, path: RepoPath) -> BoxFuture<Result<CommitFileDiff>>;
 }
 
 pub enum DiffType {
     HeadToIndex,
     HeadToWorktree,
+    Revisions(DiffRevisions),
+}
+
+/// Two revisions to compare, or a revision to compare the worktree with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffRevisions {
+    pub base: String,
+    /// The revision compared with the base, or `None` to compare the worktree.
+    pub target: Option<String>,
+    /// Whether to compare with the merge base of the base and the target instead, to only
+    /// include the changes made on the target since it diverged from the base.
+    pub merge_base: bool,
+}
+
+impl DiffRevisions {
+    fn diff_args(&self) -> Vec<String> {
+        match (&self.target, self.merge_base) {
+            (Some(target), true) => vec![format!("{}...{target}", self.base)],
+            (Some(target), false) => vec![self.base.clone(), target.clone()],
+            (None, true) => vec!["--merge-base".into(), self.base.clone()],
+            (None, false) => vec![self.base.clone()],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
@@ -381,25 +820,44 @@ impl GitRepository for RealGitRepository {
 
     fn show(&self, commit: String) -> BoxFuture<Result<CommitDetails>> {
         let repo = self.repository.clone();
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let repo = repo.lock();
-                let Ok(commit) = repo.revparse_single(&commit)?.into_commit() else {
-                    anyhow::bail!("{} is not a commit", commit);
-                };
-                let details = CommitDetails {
-                    sha: commit.id().to_string().into(),
-                    message: String::from_utf8_lossy(commit.message_raw_bytes())
-                        .to_string()
-                        .into(),
-                    commit_timestamp: commit.time().seconds(),
-                    committer_email: String::from_utf8_lossy(commit.committer().email_bytes())
-                        .to_string()
-                        .into(),
-                    committer_name: String::from_utf8_lossy(commit.committer().name_bytes())
-                        .to_string()
-                        .into(),
+                let (mut details, is_signed) = {
+                    let repo = repo.lock();
+                    let Ok(commit) = repo.revparse_single(&commit)?.into_commit() else {
+                        anyhow::bail!("{} is not a commit", commit);
+                    };
+                    let details = CommitDetails {
+                        sha: commit.id().to_string().into(),
+                        message: String::from_utf8_lossy(commit.message_raw_bytes())
+                            .to_string()
+                            .into(),
+                        commit_timestamp: commit.time().seconds(),
+                        committer_email: String::from_utf8_lossy(commit.committer().email_bytes())
+                            .to_string()
+                            .into(),
+                        committer_name: String::from_utf8_lossy(commit.committer().name_bytes())
+                            .to_string()
+                            .into(),
+                        signature: None,
+                    };
+                    (details, repo.extract_signature(&commit.id(), None).is_ok())
                 };
+                // Verifying the signature needs gpg or ssh-keygen, so it's left to git.
+                if is_signed {
+                    let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                    let output = git
+                        .run(&["show", "--no-patch", "--format=%G?%x00%GS", &details.sha])
+                        .await?;
+                    let (status, signer) = output.split_once('\0').unwrap_or((&output, ""));
+                    details.signature = Some(CommitSignature {
+                        status: SignatureStatus::from_placeholder(status),
+                        signer: signer.to_string().into(),
+                    });
+                }
                 Ok(details)
             })
             .boxed()
@@ -602,14 +1060,16 @@ impl GitRepository for RealGitRepository {
                 true
             })
             .ok();
-        if let Some(oid) = self
-            .repository
-            .lock()
-            .find_reference("CHERRY_PICK_HEAD")
-            .ok()
-            .and_then(|reference| reference.target())
-        {
-            shas.push(oid.to_string())
+        for head in ["CHERRY_PICK_HEAD", "REVERT_HEAD"] {
+            if let Some(oid) = self
+                .repository
+                .lock()
+                .find_reference(head)
+                .ok()
+                .and_then(|reference| reference.target())
+            {
+                shas.push(oid.to_string())
+            }
         }
         shas
     }
@@ -747,8 +1207,9 @@ impl GitRepository for RealGitRepository {
         self.executor
             .spawn(async move {
                 let args = match diff {
-                    DiffType::HeadToIndex => Some("--staged"),
-                    DiffType::HeadToWorktree => None,
+                    DiffType::HeadToIndex => vec!["--staged".to_string()],
+                    DiffType::HeadToWorktree => Vec::new(),
+                    DiffType::Revisions(revisions) => revisions.diff_args(),
                 };
 
                 let output = new_smol_command(&git_binary_path)
@@ -834,31 +1295,122 @@ impl GitRepository for RealGitRepository {
         &self,
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
+        options: CommitOptions,
+        askpass: AskPassDelegate,
         env: HashMap<String, String>,
+        cx: AsyncApp,
     ) -> BoxFuture<Result<()>> {
         let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = cx.background_executor().clone();
+        async move {
+            let working_directory = working_directory?;
+            let git = GitBinary::new(git_binary_path, working_directory.clone(), executor.clone())
+                .envs(env.clone());
+            // gpg-agent asks for the passphrases of GPG keys with its own pinentry, which
+            // mustn't be raced by the askpass timeout.
+            let signs_with_ssh = git
+                .run(&["config", "--type=bool", "--get", "commit.gpgsign"])
+                .await
+                .is_ok_and(|gpgsign| gpgsign == "true")
+                && git
+                    .run(&["config", "--get", "gpg.format"])
+                    .await
+                    .is_ok_and(|format| format == "ssh");
+
+            let mut cmd = new_smol_command("git");
+            cmd.current_dir(&working_directory)
+                .envs(&env)
+                .args(["commit", "--quiet", "-m"])
+                .arg(&message.to_string())
+                .arg("--cleanup=strip");
+
+            if options.signoff {
+                cmd.arg("--signoff");
+            }
+
+            if let Some((name, email)) = name_and_email {
+                cmd.arg("--author").arg(&format!("{name} <{email}>"));
+            }
+
+            if signs_with_ssh {
+                run_git_command(env, askpass, cmd, &executor)
+                    .await
+                    .map_err(|error| anyhow!("Failed to commit:\n{error}"))?;
+                return Ok(());
+            }
+
+            let output = cmd.output().await?;
+
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "Failed to commit:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn commit_message_template(
+        &self,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<Option<String>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        let executor = self.executor.clone();
         self.executor
             .spawn(async move {
-                let mut cmd = new_smol_command("git");
-                cmd.current_dir(&working_directory?)
-                    .envs(env)
-                    .args(["commit", "--quiet", "-m"])
-                    .arg(&message.to_string())
-                    .arg("--cleanup=strip");
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory.clone(), executor)
+                    .envs(env.clone());
 
-                if let Some((name, email)) = name_and_email {
-                    cmd.arg("--author").arg(&format!("{name} <{email}>"));
+                let template = match git
+                    .run(&["config", "--path", "--get", "commit.template"])
+                    .await
+                {
+                    Ok(path) if !path.is_empty() => Some(
+                        smol::fs::read_to_string(working_directory.join(&path))
+                            .await
+                            .with_context(|| format!("reading commit template {path}"))?,
+                    ),
+                    _ => None,
+                };
+
+                // The hook path accounts for `core.hooksPath`.
+                let hook_path = working_directory.join(
+                    git.run(&["rev-parse", "--git-path", "hooks/prepare-commit-msg"])
+                        .await?,
+                );
+                if !is_executable(&hook_path).await {
+                    return Ok(template.filter(|template| !template.trim().is_empty()));
                 }
 
-                let output = cmd.output().await?;
+                let message_path = git_dir.join(format!("ZED_COMMIT_MSG-{}", Uuid::new_v4()));
+                smol::fs::write(&message_path, template.as_deref().unwrap_or_default()).await?;
+                let _remove_message = util::defer({
+                    let message_path = message_path.clone();
+                    move || {
+                        std::fs::remove_file(message_path).log_err();
+                    }
+                });
+                let output = new_smol_command(&hook_path)
+                    .current_dir(&working_directory)
+                    .envs(&env)
+                    .arg(&message_path)
+                    .args(template.is_some().then_some("template"))
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "prepare-commit-msg hook failed:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
 
-                if !output.status.success() {
-                    return Err(anyhow!(
-                        "Failed to commit:\n{}",
-                        String::from_utf8_lossy(&output.stderr)
-                    ));
-                }
-                Ok(())
+                let message = smol::fs::read_to_string(&message_path).await?;
+                Ok(Some(message).filter(|message| !message.trim().is_empty()))
             })
             .boxed()
     }
@@ -1213,676 +1765,2530 @@ impl GitRepository for RealGitRepository {
             })
             .boxed()
     }
-}
 
-fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
-    let mut args = vec![
-        OsString::from("--no-optional-locks"),
-        OsString::from("status"),
-        OsString::from("--porcelain=v1"),
-        OsString::from("--untracked-files=all"),
-        OsString::from("--no-renames"),
-        OsString::from("-z"),
-    ];
-    args.extend(path_prefixes.iter().map(|path_prefix| {
-        if path_prefix.0.as_ref() == Path::new("") {
-            Path::new(".").into()
-        } else {
-            path_prefix.as_os_str().into()
-        }
-    }));
-    args
-}
-
-struct GitBinary {
-    git_binary_path: PathBuf,
-    working_directory: PathBuf,
-    executor: BackgroundExecutor,
-    index_file_path: Option<PathBuf>,
-    envs: HashMap<String, String>,
-}
+    fn stash_entries(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let output = git
+                    .run(&["stash", "list", "--format=%H%x00%ct%x00%gs"])
+                    .await?;
+                parse_stash_list(&output)
+            })
+            .boxed()
+    }
 
-impl GitBinary {
-    fn new(
-        git_binary_path: PathBuf,
-        working_directory: PathBuf,
-        executor: BackgroundExecutor,
-    ) -> Self {
-        Self {
-            git_binary_path,
-            working_directory,
-            executor,
-            index_file_path: None,
-            envs: HashMap::default(),
+    fn stash_push(
+        &self,
+        options: StashOptions,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args = vec!["stash".to_string(), "push".to_string()];
+        if options.include_untracked {
+            args.push("--include-untracked".to_string());
+        }
+        if options.staged {
+            args.push("--staged".to_string());
+        }
+        if let Some(message) = options.message {
+            args.push("--message".to_string());
+            args.push(message);
         }
+        self.run_git_command(args, env, "Failed to stash changes")
     }
 
-    fn envs(mut self, envs: HashMap<String, String>) -> Self {
-        self.envs = envs;
-        self
+    fn stash_diff(&self, index: usize) -> BoxFuture<Result<Vec<CommitFileDiff>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let stash = stash_ref(index);
+                let mut files = git
+                    .file_diffs(Some(format!("{stash}^1").as_str()), &stash)
+                    .await?;
+
+                // Untracked files are stored in a third parent, when the entry has any.
+                let untracked = format!("{stash}^3");
+                if git.revision_exists(&untracked).await {
+                    files.extend(git.file_diffs(None, &untracked).await?);
+                }
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+                Ok(files)
+            })
+            .boxed()
     }
 
-    pub async fn with_temp_index<R>(
-        &mut self,
-        f: impl AsyncFnOnce(&Self) -> Result<R>,
-    ) -> Result<R> {
-        let index_file_path = self.path_for_index_id(Uuid::new_v4());
+    fn stash_apply(&self, index: usize, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["stash".into(), "apply".into(), stash_ref(index)],
+            env,
+            "Failed to apply stash",
+        )
+    }
 
-        let delete_temp_index = util::defer({
-            let index_file_path = index_file_path.clone();
-            let executor = self.executor.clone();
-            move || {
-                executor
-                    .spawn(async move {
-                        smol::fs::remove_file(index_file_path).await.log_err();
-                    })
-                    .detach();
-            }
-        });
+    fn stash_pop(&self, index: usize, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["stash".into(), "pop".into(), stash_ref(index)],
+            env,
+            "Failed to pop stash",
+        )
+    }
 
-        self.index_file_path = Some(index_file_path.clone());
-        let result = f(self).await;
-        self.index_file_path = None;
-        let result = result?;
+    fn stash_drop(&self, index: usize, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["stash".into(), "drop".into(), stash_ref(index)],
+            env,
+            "Failed to drop stash",
+        )
+    }
 
-        smol::fs::remove_file(index_file_path).await.ok();
-        delete_temp_index.abort();
+    fn log(&self, options: LogOptions) -> BoxFuture<Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let mut args = vec![
+                    "log".to_string(),
+                    "--date-order".to_string(),
+                    format!("--format={LOG_FORMAT}"),
+                    format!("--skip={}", options.skip),
+                    format!("--max-count={}", options.limit),
+                ];
+                if options.author.is_some() || options.message.is_some() {
+                    args.push("--regexp-ignore-case".to_string());
+                    args.push("--fixed-strings".to_string());
+                }
+                if let Some(author) = options.author {
+                    args.push(format!("--author={author}"));
+                }
+                if let Some(message) = options.message {
+                    args.push(format!("--grep={message}"));
+                }
+                if let Some(since) = options.since {
+                    args.push(format!("--since=@{since}"));
+                }
+                if let Some(until) = options.until {
+                    args.push(format!("--until=@{until}"));
+                }
+                match (options.path, options.line_range) {
+                    (Some(path), Some(line_range)) => {
+                        args.push("--no-patch".to_string());
+                        args.push(format!(
+                            "-L{},{}:{}",
+                            line_range.start + 1,
+                            line_range.end.max(line_range.start + 1),
+                            path.to_string_lossy()
+                        ));
+                    }
+                    (Some(path), None) => {
+                        args.push("--follow".to_string());
+                        args.push("--".to_string());
+                        args.push(path.to_string_lossy().to_string());
+                    }
+                    (None, _) => {}
+                }
+                let output = git.run(&args).await?;
+                parse_log(&output)
+            })
+            .boxed()
+    }
 
-        Ok(result)
+    fn commit_diff(&self, commit: String) -> BoxFuture<Result<Vec<CommitFileDiff>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let parent = format!("{commit}^1");
+                let base = git
+                    .revision_exists(&parent)
+                    .await
+                    .then_some(parent.as_str());
+                let mut files = git.file_diffs(base, &commit).await?;
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+                Ok(files)
+            })
+            .boxed()
     }
 
-    fn path_for_index_id(&self, id: Uuid) -> PathBuf {
-        self.working_directory
-            .join(".git")
-            .join(format!("index-{}.tmp", id))
+    fn revision_diff(&self, revisions: DiffRevisions) -> BoxFuture<Result<Vec<CommitFileDiff>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let base = if revisions.merge_base {
+                    let target = revisions.target.as_deref().unwrap_or("HEAD");
+                    git.run(&["merge-base", &revisions.base, target])
+                        .await
+                        .with_context(|| {
+                            format!("no common ancestor of {} and {target}", revisions.base)
+                        })?
+                } else {
+                    revisions.base
+                };
+                let mut files = match revisions.target {
+                    Some(target) => git.file_diffs(Some(&base), &target).await?,
+                    None => git.worktree_file_diffs(&base).await?,
+                };
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+                Ok(files)
+            })
+            .boxed()
     }
 
-    pub async fn run<S>(&self, args: impl IntoIterator<Item = S>) -> Result<String>
-    where
-        S: AsRef<OsStr>,
-    {
-        let mut stdout = self.run_raw(args).await?;
-        if stdout.chars().last() == Some('\n') {
-            stdout.pop();
-        }
-        Ok(stdout)
+    fn rebase_commits(&self, upstream: String) -> BoxFuture<Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let output = git
+                    .run(&[
+                        "log",
+                        "--reverse",
+                        "--topo-order",
+                        "--no-merges",
+                        &format!("--format={LOG_FORMAT}"),
+                        &format!("{upstream}..HEAD"),
+                    ])
+                    .await?;
+                parse_log(&output)
+            })
+            .boxed()
     }
 
-    /// Returns the result of the command without trimming the trailing newline.
-    pub async fn run_raw<S>(&self, args: impl IntoIterator<Item = S>) -> Result<String>
-    where
-        S: AsRef<OsStr>,
-    {
-        let mut command = self.build_command(args);
-        let output = command.output().await?;
-        if output.status.success() {
-            Ok(String::from_utf8(output.stdout)?)
-        } else {
-            Err(anyhow!(GitBinaryCommandError {
-                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                status: output.status,
-            }))
+    fn rebase(
+        &self,
+        upstream: String,
+        steps: Vec<RebaseStep>,
+        mut env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        // The todo list is written by `cp` and the reworded messages are applied by `exec` lines,
+        // both run by git through a POSIX shell.
+        if cfg!(windows) {
+            return async { Err(anyhow!("Interactive rebases are not supported on Windows")) }
+                .boxed();
         }
+        // The todo list and the messages of the reworded commits are written to the git
+        // directory, as they must outlive the rebase being stopped to resolve conflicts.
+        let rebase_dir = self.path().join(REBASE_DIR);
+        let todo_path = rebase_dir.join("git-rebase-todo");
+        let todo = rebase_todo(&steps, &rebase_dir);
+        env.insert(
+            "GIT_SEQUENCE_EDITOR".into(),
+            format!("cp {}", shell_quote(&todo_path)),
+        );
+        env.insert("GIT_EDITOR".into(), "true".into());
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let (todo, messages) = todo?;
+                smol::fs::remove_dir_all(&rebase_dir).await.ok();
+                smol::fs::create_dir_all(&rebase_dir).await?;
+                for (path, message) in messages {
+                    smol::fs::write(path, message).await?;
+                }
+                smol::fs::write(&todo_path, todo).await?;
+
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env)
+                    .args(["rebase", "--interactive", &upstream])
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to rebase:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
     }
 
-    fn build_command<S>(&self, args: impl IntoIterator<Item = S>) -> smol::process::Command
-    where
-        S: AsRef<OsStr>,
-    {
-        let mut command = new_smol_command(&self.git_binary_path);
-        command.current_dir(&self.working_directory);
-        command.args(args);
-        if let Some(index_file_path) = self.index_file_path.as_ref() {
-            command.env("GIT_INDEX_FILE", index_file_path);
-        }
-        command.envs(&self.envs);
-        command
+    fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        mut env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let (arg, error_message) = match command {
+            RebaseCommand::Continue => ("--continue", "Failed to continue rebase"),
+            RebaseCommand::Skip => ("--skip", "Failed to skip commit"),
+            RebaseCommand::Abort => ("--abort", "Failed to abort rebase"),
+        };
+        // Keeps the messages of the commits being continued, instead of opening an editor.
+        env.insert("GIT_EDITOR".into(), "true".into());
+        self.run_git_command(vec!["rebase".into(), arg.into()], env, error_message)
     }
-}
 
-#[derive(Error, Debug)]
-#[error("Git command failed: {stdout}")]
-struct GitBinaryCommandError {
-    stdout: String,
-    status: ExitStatus,
-}
+    fn rebase_status(&self) -> BoxFuture<Result<Option<RebaseStatus>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                // Rebases using the merge backend store their state in `rebase-merge`, while
+                // the ones using the apply backend use `rebase-apply`.
+                for (state_dir, step_file, total_steps_file) in [
+                    ("rebase-merge", "msgnum", "end"),
+                    ("rebase-apply", "next", "last"),
+                ] {
+                    let state_dir = git_dir.join(state_dir);
+                    if !smol::fs::metadata(&state_dir)
+                        .await
+                        .is_ok_and(|metadata| metadata.is_dir())
+                    {
+                        continue;
+                    }
+                    let read = async |name: &str| {
+                        smol::fs::read_to_string(state_dir.join(name))
+                            .await
+                            .ok()
+                            .map(|contents| contents.trim().to_string())
+                    };
+                    let branch = read("head-name").await.and_then(|head_name| {
+                        head_name
+                            .strip_prefix("refs/heads/")
+                            .map(|branch| SharedString::from(branch.to_string()))
+                    });
+                    let parse_number = |contents: Option<String>| {
+                        contents
+                            .and_then(|contents| contents.parse().ok())
+                            .unwrap_or_default()
+                    };
+                    return Ok(Some(RebaseStatus {
+                        branch,
+                        onto: read("onto").await.unwrap_or_default().into(),
+                        step: parse_number(read(step_file).await),
+                        total_steps: parse_number(read(total_steps_file).await),
+                    }));
+                }
+                Ok(None)
+            })
+            .boxed()
+    }
 
-async fn run_git_command(
-    env: HashMap<String, String>,
-    ask_pass: AskPassDelegate,
-    mut command: smol::process::Command,
-    executor: &BackgroundExecutor,
-) -> Result<RemoteCommandOutput> {
-    if env.contains_key("GIT_ASKPASS") {
-        let git_process = command.spawn()?;
-        let output = git_process.output().await?;
-        if !output.status.success() {
-            Err(anyhow!("{}", String::from_utf8_lossy(&output.stderr)))
-        } else {
-            Ok(RemoteCommandOutput {
-                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let output = git.run(&["worktree", "list", "--porcelain"]).await?;
+                parse_worktree_list(&output)
             })
+            .boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let path = path.to_string_lossy().to_string();
+        let args = if create_branch {
+            vec!["worktree".into(), "add".into(), "-b".into(), branch, path]
+        } else {
+            vec!["worktree".into(), "add".into(), path, branch]
+        };
+        self.run_git_command(args, env, "Failed to create worktree")
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args = vec!["worktree".to_string(), "remove".to_string()];
+        if force {
+            args.push("--force".to_string());
         }
-    } else {
-        let ask_pass = AskPassSession::new(executor, ask_pass).await?;
-        command
-            .env("GIT_ASKPASS", ask_pass.script_path())
-            .env("SSH_ASKPASS", ask_pass.script_path())
-            .env("SSH_ASKPASS_REQUIRE", "force");
-        let git_process = command.spawn()?;
+        args.push(path.to_string_lossy().to_string());
+        self.run_git_command(args, env, "Failed to remove worktree")
+    }
 
-        run_askpass_command(ask_pass, git_process).await
+    fn prune_worktrees(&self, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["worktree".into(), "prune".into()],
+            env,
+            "Failed to prune worktrees",
+        )
     }
-}
 
-async fn run_askpass_command(
-    mut ask_pass: AskPassSession,
-    git_process: smol::process::Child,
-) -> std::result::Result<RemoteCommandOutput, anyhow::Error> {
-    select_biased! {
-        result = ask_pass.run().fuse() => {
-            match result {
-                AskPassResult::CancelledByUser => {
-                    Err(anyhow!(REMOTE_CANCELLED_BY_USER))?
-                }
-                AskPassResult::Timedout => {
-                    Err(anyhow!("Connecting to host timed out"))?
-                }
-            }
-        }
-        output = git_process.output().fuse() => {
-            let output = output?;
-            if !output.status.success() {
-                Err(anyhow!(
-                    "{}",
-                    String::from_utf8_lossy(&output.stderr)
-                ))
-            } else {
-                Ok(RemoteCommandOutput {
-                    stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                    stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-                })
-            }
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                // The commit of an annotated tag is given by `*objectname`, while `objectname`
+                // is the tag object itself.
+                let output = git
+                    .run(&[
+                        "for-each-ref",
+                        "--sort=-creatordate",
+                        "--format=%(refname:strip=2)%00%(objectname)%00%(*objectname)%00%(contents:subject)%00%(creatordate:unix)",
+                        "refs/tags",
+                    ])
+                    .await?;
+                parse_tag_list(&output)
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args = vec!["tag".to_string()];
+        if let Some(message) = message {
+            args.push("--annotate".to_string());
+            args.push("--message".to_string());
+            args.push(message);
         }
+        args.push(name);
+        args.extend(target);
+        self.run_git_command(args, env, "Failed to create tag")
     }
-}
 
-pub static WORK_DIRECTORY_REPO_PATH: LazyLock<RepoPath> =
-    LazyLock::new(|| RepoPath(Path::new("").into()));
+    fn delete_tag(&self, name: String, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["tag".into(), "--delete".into(), name],
+            env,
+            "Failed to delete tag",
+        )
+    }
 
-#[derive(Clone, Debug, Ord, Hash, PartialOrd, Eq, PartialEq)]
-pub struct RepoPath(pub Arc<Path>);
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: HashMap<String, String>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        async move {
+            let working_directory = working_directory?;
+            let mut command = new_smol_command("git");
+            command
+                .envs(&env)
+                .env("GIT_HTTP_USER_AGENT", "Zed")
+                .current_dir(&working_directory)
+                .args(["push"])
+                .arg(remote_name)
+                .arg(format!("refs/tags/{name}"))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
 
-impl RepoPath {
-    pub fn new(path: PathBuf) -> Self {
-        debug_assert!(path.is_relative(), "Repo paths must be relative");
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
 
-        RepoPath(path.into())
+    fn add_remote(
+        &self,
+        name: String,
+        url: String,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["remote".into(), "add".into(), name, url],
+            env,
+            "Failed to add remote",
+        )
     }
 
-    pub fn from_str(path: &str) -> Self {
-        let path = Path::new(path);
-        debug_assert!(path.is_relative(), "Repo paths must be relative");
+    fn rename_remote(
+        &self,
+        name: String,
+        new_name: String,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["remote".into(), "rename".into(), name, new_name],
+            env,
+            "Failed to rename remote",
+        )
+    }
 
-        RepoPath(path.into())
+    fn remove_remote(&self, name: String, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        self.run_git_command(
+            vec!["remote".into(), "remove".into(), name],
+            env,
+            "Failed to remove remote",
+        )
     }
 
-    pub fn to_unix_style(&self) -> Cow<'_, OsStr> {
-        #[cfg(target_os = "windows")]
-        {
-            use std::ffi::OsString;
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args = vec!["cherry-pick".to_string()];
+        args.extend(commits);
+        self.run_git_command(args, env, "Failed to cherry-pick")
+    }
 
-            let path = self.0.as_os_str().to_string_lossy().replace("\\", "/");
-            Cow::Owned(OsString::from(path))
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            Cow::Borrowed(self.0.as_os_str())
-        }
+    fn revert(&self, commits: Vec<String>, env: HashMap<String, String>) -> BoxFuture<Result<()>> {
+        let mut args = vec!["revert".to_string(), "--no-edit".to_string()];
+        args.extend(commits);
+        self.run_git_command(args, env, "Failed to revert")
     }
-}
 
-impl std::fmt::Display for RepoPath {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.to_string_lossy().fmt(f)
+    fn sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        command: RebaseCommand,
+        mut env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let (arg, error_message) = match (operation, command) {
+            (SequencerOperation::CherryPick, RebaseCommand::Continue) => {
+                ("--continue", "Failed to continue cherry-pick")
+            }
+            (SequencerOperation::Revert, RebaseCommand::Continue) => {
+                ("--continue", "Failed to continue revert")
+            }
+            (_, RebaseCommand::Skip) => ("--skip", "Failed to skip commit"),
+            (SequencerOperation::CherryPick, RebaseCommand::Abort) => {
+                ("--abort", "Failed to abort cherry-pick")
+            }
+            (SequencerOperation::Revert, RebaseCommand::Abort) => {
+                ("--abort", "Failed to abort revert")
+            }
+        };
+        // Keeps the messages of the commits being continued, instead of opening an editor.
+        env.insert("GIT_EDITOR".into(), "true".into());
+        self.run_git_command(
+            vec![operation.command().into(), arg.into()],
+            env,
+            error_message,
+        )
     }
-}
 
-impl From<&Path> for RepoPath {
-    fn from(value: &Path) -> Self {
-        RepoPath::new(value.into())
+    fn sequencer_status(&self) -> BoxFuture<Result<Option<SequencerOperation>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let exists =
+                    async |name: &str| smol::fs::metadata(git_dir.join(name)).await.is_ok();
+                if exists("CHERRY_PICK_HEAD").await {
+                    return Ok(Some(SequencerOperation::CherryPick));
+                }
+                if exists("REVERT_HEAD").await {
+                    return Ok(Some(SequencerOperation::Revert));
+                }
+                // Once a conflict has been committed, only the list of the commits left to
+                // apply remains until the operation is continued.
+                let Ok(todo) = smol::fs::read_to_string(git_dir.join("sequencer/todo")).await
+                else {
+                    return Ok(None);
+                };
+                let first_step = todo
+                    .lines()
+                    .find(|line| !line.trim().is_empty() && !line.starts_with('#'));
+                Ok(first_step.map(|step| {
+                    if step.starts_with("revert") {
+                        SequencerOperation::Revert
+                    } else {
+                        SequencerOperation::CherryPick
+                    }
+                }))
+            })
+            .boxed()
     }
-}
 
-impl From<Arc<Path>> for RepoPath {
-    fn from(value: Arc<Path>) -> Self {
-        RepoPath(value)
+    fn submodules(&self, env: HashMap<String, String>) -> BoxFuture<Result<Vec<Submodule>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor).envs(env);
+                let output = git.run(&["submodule", "status"]).await?;
+                Ok(parse_submodule_status(&output))
+            })
+            .boxed()
     }
-}
 
-impl From<PathBuf> for RepoPath {
-    fn from(value: PathBuf) -> Self {
-        RepoPath::new(value)
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        ask_pass: AskPassDelegate,
+        env: HashMap<String, String>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        async move {
+            let mut command = new_smol_command("git");
+            command
+                .envs(&env)
+                .env("GIT_HTTP_USER_AGENT", "Zed")
+                .current_dir(&working_directory?)
+                .args(["submodule", "update", "--recursive"])
+                .args(init.then_some("--init"))
+                .arg("--")
+                .args(paths.iter().map(|path| path.as_ref()))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
     }
-}
 
-impl From<&str> for RepoPath {
-    fn from(value: &str) -> Self {
-        Self::from_str(value)
+    fn sync_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        env: HashMap<String, String>,
+    ) -> BoxFuture<Result<()>> {
+        let mut args = vec!["submodule".into(), "sync".into(), "--recursive".into()];
+        args.push("--".into());
+        args.extend(paths.iter().map(|path| path.to_string_lossy().into_owned()));
+        self.run_git_command(args, env, "Failed to sync submodules")
     }
-}
 
-impl Default for RepoPath {
-    fn default() -> Self {
-        RepoPath(Path::new("").into())
+    fn submodule_diff(&self, path: RepoPath) -> BoxFuture<Result<CommitFileDiff>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor);
+                let path_str = path.to_string_lossy();
+                Ok(CommitFileDiff {
+                    old_text: git.revision_text("HEAD", &path_str).await,
+                    new_text: git.worktree_text(&path_str).await,
+                    path,
+                })
+            })
+            .boxed()
     }
 }
 
-impl AsRef<Path> for RepoPath {
-    fn as_ref(&self) -> &Path {
-        self.0.as_ref()
+impl RealGitRepository {
+    fn run_git_command(
+        &self,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        error_message: &'static str,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env)
+                    .args(args)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "{error_message}:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
     }
 }
 
-impl std::ops::Deref for RepoPath {
-    type Target = Path;
+/// The text git shows for a submodule in diffs, which is the commit checked out in it.
+fn submodule_text(sha: &str, is_dirty: bool) -> String {
+    let dirty = if is_dirty { "-dirty" } else { "" };
+    format!("Subproject commit {sha}{dirty}\n")
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
+/// Parses the output of `git submodule status`, whose lines are a status character followed
+/// by the commit and the path of a submodule, e.g. `+abc123 vendor/lib (v1.0)`.
+fn parse_submodule_status(output: &str) -> Vec<Submodule> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut chars = line.chars();
+            let status = match chars.next()? {
+                '-' => SubmoduleStatus::Uninitialized,
+                '+' => SubmoduleStatus::Modified,
+                'U' => SubmoduleStatus::Conflicted,
+                _ => SubmoduleStatus::UpToDate,
+            };
+            let (sha, rest) = chars.as_str().split_once(' ')?;
+            // The path is followed by the description of the commit, if it has one.
+            let path = match rest.rsplit_once(" (") {
+                Some((path, description)) if description.ends_with(')') => path,
+                _ => rest,
+            };
+            Some(Submodule {
+                path: RepoPath::from_str(path),
+                sha: sha.to_string().into(),
+                status,
+            })
+        })
+        .collect()
 }
 
-impl Borrow<Path> for RepoPath {
-    fn borrow(&self) -> &Path {
-        self.0.as_ref()
+/// Each commit starts with a record separator, so that the patches `git log -L` prints after
+/// the header lines can be skipped.
+const LOG_FORMAT: &str = "%x1e%H%x00%P%x00%an%x00%ae%x00%ct%x00%s%x00%D";
+
+/// The directory of the git directory holding the files of an interactive rebase started from
+/// the editor.
+const REBASE_DIR: &str = "zed-rebase";
+
+/// Returns the todo list of an interactive rebase applying the steps, and the messages of the
+/// reworded commits to write to the directory, from which the todo list amends the commits.
+fn rebase_todo(
+    steps: &[RebaseStep],
+    message_dir: &Path,
+) -> Result<(String, Vec<(PathBuf, String)>)> {
+    let mut todo = String::new();
+    let mut messages = Vec::new();
+    let mut has_previous_commit = false;
+    for step in steps {
+        let command = match step.action {
+            RebaseAction::Pick | RebaseAction::Reword => "pick",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        };
+        if matches!(step.action, RebaseAction::Squash | RebaseAction::Fixup) && !has_previous_commit
+        {
+            return Err(anyhow!(
+                "Cannot {command} {} without a previous commit",
+                step.sha
+            ));
+        }
+        has_previous_commit |= step.action != RebaseAction::Drop;
+        todo.push_str(&format!("{command} {}\n", step.sha));
+
+        if let (RebaseAction::Reword, Some(message)) = (step.action, &step.message) {
+            let message_path = message_dir.join(format!("message-{}", messages.len()));
+            todo.push_str(&format!(
+                "exec git commit --amend --allow-empty --no-verify --quiet --file={}\n",
+                shell_quote(&message_path)
+            ));
+            messages.push((message_path, message.clone()));
+        }
     }
+    Ok((todo, messages))
 }
 
-#[derive(Debug)]
-pub struct RepoPathDescendants<'a>(pub &'a Path);
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
 
-impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
-    fn cmp_cursor(&self, key: &RepoPath) -> Ordering {
-        if key.starts_with(self.0) {
-            Ordering::Greater
+fn stash_ref(index: usize) -> String {
+    format!("stash@{{{index}}}")
+}
+
+fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
+    let mut args = vec![
+        OsString::from("--no-optional-locks"),
+        OsString::from("status"),
+        OsString::from("--porcelain=v1"),
+        OsString::from("--untracked-files=all"),
+        OsString::from("--no-renames"),
+        OsString::from("-z"),
+    ];
+    args.extend(path_prefixes.iter().map(|path_prefix| {
+        if path_prefix.0.as_ref() == Path::new("") {
+            Path::new(".").into()
         } else {
-            self.0.cmp(key)
+            path_prefix.as_os_str().into()
         }
-    }
+    }));
+    args
 }
 
-fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
-    let mut branches = Vec::new();
-    for line in input.split('\n') {
-        if line.is_empty() {
-            continue;
-        }
-        let mut fields = line.split('\x00');
-        let is_current_branch = fields.next().context("no HEAD")? == "*";
-        let head_sha: SharedString = fields.next().context("no objectname")?.to_string().into();
-        let parent_sha: SharedString = fields.next().context("no parent")?.to_string().into();
-        let ref_name: SharedString = fields
-            .next()
-            .context("no refname")?
-            .strip_prefix("refs/heads/")
-            .context("unexpected format for refname")?
-            .to_string()
-            .into();
-        let upstream_name = fields.next().context("no upstream")?.to_string();
-        let upstream_tracking = parse_upstream_track(fields.next().context("no upstream:track")?)?;
-        let commiterdate = fields.next().context("no committerdate")?.parse::<i64>()?;
-        let subject: SharedString = fields
-            .next()
-            .context("no contents:subject")?
-            .to_string()
-            .into();
+struct GitBinary {
+    git_binary_path: PathBuf,
+    working_directory: PathBuf,
+    executor: BackgroundExecutor,
+    index_file_path: Option<PathBuf>,
+    envs: HashMap<String, String>,
+}
 
-        branches.push(Branch {
-            is_head: is_current_branch,
-            name: ref_name,
-            most_recent_commit: Some(CommitSummary {
-                sha: head_sha,
-                subject,
-                commit_timestamp: commiterdate,
-                has_parent: !parent_sha.is_empty(),
-            }),
-            upstream: if upstream_name.is_empty() {
-                None
-            } else {
-                Some(Upstream {
-                    ref_name: upstream_name.into(),
-                    tracking: upstream_tracking,
-                })
-            },
-        })
+impl GitBinary {
+    fn new(
+        git_binary_path: PathBuf,
+        working_directory: PathBuf,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        Self {
+            git_binary_path,
+            working_directory,
+            executor,
+            index_file_path: None,
+            envs: HashMap::default(),
+        }
     }
 
-    Ok(branches)
-}
+    fn envs(mut self, envs: HashMap<String, String>) -> Self {
+        self.envs = envs;
+        self
+    }
 
-fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
-    if upstream_track == "" {
-        return Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
-            ahead: 0,
-            behind: 0,
-        }));
+    pub async fn with_temp_index<R>(
+        &mut self,
+        f: impl AsyncFnOnce(&Self) -> Result<R>,
+    ) -> Result<R> {
+        let index_file_path = self.path_for_index_id(Uuid::new_v4());
+
+        let delete_temp_index = util::defer({
+            let index_file_path = index_file_path.clone();
+            let executor = self.executor.clone();
+            move || {
+                executor
+                    .spawn(async move {
+                        smol::fs::remove_file(index_file_path).await.log_err();
+                    })
+                    .detach();
+            }
+        });
+
+        self.index_file_path = Some(index_file_path.clone());
+        let result = f(self).await;
+        self.index_file_path = None;
+        let result = result?;
+
+        smol::fs::remove_file(index_file_path).await.ok();
+        delete_temp_index.abort();
+
+        Ok(result)
     }
 
-    let upstream_track = upstream_track
-        .strip_prefix("[")
-        .ok_or_else(|| anyhow!("missing ["))?;
-    let upstream_track = upstream_track
-        .strip_suffix("]")
-        .ok_or_else(|| anyhow!("missing ["))?;
-    let mut ahead: u32 = 0;
-    let mut behind: u32 = 0;
-    for component in upstream_track.split(", ") {
-        if component == "gone" {
-            return Ok(UpstreamTracking::Gone);
+    fn path_for_index_id(&self, id: Uuid) -> PathBuf {
+        self.working_directory
+            .join(".git")
+            .join(format!("index-{}.tmp", id))
+    }
+
+    pub async fn run<S>(&self, args: impl IntoIterator<Item = S>) -> Result<String>
+    where
+        S: AsRef<OsStr>,
+    {
+        let mut stdout = self.run_raw(args).await?;
+        if stdout.chars().last() == Some('\n') {
+            stdout.pop();
         }
-        if let Some(ahead_num) = component.strip_prefix("ahead ") {
-            ahead = ahead_num.parse::<u32>()?;
+        Ok(stdout)
+    }
+
+    async fn revision_exists(&self, revision: &str) -> bool {
+        self.run(&["rev-parse", "--verify", "--quiet", revision])
+            .await
+            .is_ok()
+    }
+
+    /// Returns the contents of the files changed between the two revisions. When there is no
+    /// base revision, all files of the target are considered added.
+    async fn file_diffs(&self, base: Option<&str>, target: &str) -> Result<Vec<CommitFileDiff>> {
+        let show = async |revision: &str, path: &str| self.revision_text(revision, path).await;
+        let paths = if let Some(base) = base {
+            self.run(&["diff", "--name-only", "-z", base, target])
+                .await?
+        } else {
+            self.run(&["ls-tree", "-r", "--name-only", "-z", target])
+                .await?
+        };
+
+        let mut files = Vec::new();
+        for path in paths.split('\0').filter(|path| !path.is_empty()) {
+            let old_text = if let Some(base) = base {
+                show(base, path).await
+            } else {
+                None
+            };
+            files.push(CommitFileDiff {
+                path: RepoPath::from_str(path),
+                old_text,
+                new_text: show(target, path).await,
+            });
         }
-        if let Some(behind_num) = component.strip_prefix("behind ") {
-            behind = behind_num.parse::<u32>()?;
+        Ok(files)
+    }
+
+    /// Returns the contents of the tracked files changed in the worktree since the base revision.
+    async fn worktree_file_diffs(&self, base: &str) -> Result<Vec<CommitFileDiff>> {
+        let paths = self.run(&["diff", "--name-only", "-z", base]).await?;
+        let mut files = Vec::new();
+        for path in paths.split('\0').filter(|path| !path.is_empty()) {
+            files.push(CommitFileDiff {
+                path: RepoPath::from_str(path),
+                old_text: self.revision_text(base, path).await,
+                new_text: self.worktree_text(path).await,
+            });
         }
+        Ok(files)
     }
-    Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
-        ahead,
-        behind,
-    }))
-}
 
-fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
-    match relative_file_path.components().next() {
-        None => anyhow::bail!("repo path should not be empty"),
-        Some(Component::Prefix(_)) => anyhow::bail!(
-            "repo path `{}` should be relative, not a windows prefix",
-            relative_file_path.to_string_lossy()
-        ),
-        Some(Component::RootDir) => {
-            anyhow::bail!(
-                "repo path `{}` should be relative",
-                relative_file_path.to_string_lossy()
-            )
+    /// Returns the contents of the file at `path` in the revision. Submodules, whose commits
+    /// `git show` can't load, are shown as the commit recorded for them, like `git diff` does.
+    async fn revision_text(&self, revision: &str, path: &str) -> Option<String> {
+        if let Ok(text) = self.run_raw(&["show", &format!("{revision}:{path}")]).await {
+            return Some(text);
         }
-        Some(Component::CurDir) => {
-            anyhow::bail!(
-                "repo path `{}` should not start with `.`",
-                relative_file_path.to_string_lossy()
-            )
+        let entry = self.run(&["ls-tree", revision, "--", path]).await.ok()?;
+        let sha = entry.strip_prefix("160000 commit ")?.split('\t').next()?;
+        Some(submodule_text(sha, false))
+    }
+
+    /// Returns the contents of the file at `path` in the worktree, or the commit checked out in
+    /// it if it's a submodule.
+    async fn worktree_text(&self, path: &str) -> Option<String> {
+        let abs_path = self.working_directory.join(path);
+        if !smol::fs::metadata(&abs_path).await.ok()?.is_dir() {
+            return smol::fs::read_to_string(abs_path).await.ok();
         }
-        Some(Component::ParentDir) => {
-            anyhow::bail!(
-                "repo path `{}` should not start with `..`",
-                relative_file_path.to_string_lossy()
-            )
+        // Submodules are the directories git tracks, which have their own `.git`.
+        smol::fs::metadata(abs_path.join(".git")).await.ok()?;
+        let sha = self.run(&["-C", path, "rev-parse", "HEAD"]).await.ok()?;
+        let is_dirty = self
+            .run(&["-C", path, "status", "--porcelain", "--untracked-files=no"])
+            .await
+            .is_ok_and(|status| !status.is_empty());
+        Some(submodule_text(&sha, is_dirty))
+    }
+
+    /// Returns the result of the command without trimming the trailing newline.
+    pub async fn run_raw<S>(&self, args: impl IntoIterator<Item = S>) -> Result<String>
+    where
+        S: AsRef<OsStr>,
+    {
+        let mut command = self.build_command(args);
+        let output = command.output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8(output.stdout)?)
+        } else {
+            Err(anyhow!(GitBinaryCommandError {
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                status: output.status,
+            }))
         }
-        _ => Ok(()),
+    }
+
+    fn build_command<S>(&self, args: impl IntoIterator<Item = S>) -> smol::process::Command
+    where
+        S: AsRef<OsStr>,
+    {
+        let mut command = new_smol_command(&self.git_binary_path);
+        command.current_dir(&self.working_directory);
+        command.args(args);
+        if let Some(index_file_path) = self.index_file_path.as_ref() {
+            command.env("GIT_INDEX_FILE", index_file_path);
+        }
+        command.envs(&self.envs);
+        command
     }
 }
 
-fn checkpoint_author_envs() -> HashMap<String, String> {
-    HashMap::from_iter([
-        ("GIT_AUTHOR_NAME".to_string(), "Zed".to_string()),
-        ("GIT_AUTHOR_EMAIL".to_string(), "hi@zed.dev".to_string()),
-        ("GIT_COMMITTER_NAME".to_string(), "Zed".to_string()),
-        ("GIT_COMMITTER_EMAIL".to_string(), "hi@zed.dev".to_string()),
-    ])
+#[derive(Error, Debug)]
+#[error("Git command failed: {stdout}")]
+struct GitBinaryCommandError {
+    stdout: String,
+    status: ExitStatus,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::status::FileStatus;
-    use gpui::TestAppContext;
+async fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = smol::fs::metadata(path).await else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+async fn run_git_command(
+    env: HashMap<String, String>,
+    ask_pass: AskPassDelegate,
+    mut command: smol::process::Command,
+    executor: &BackgroundExecutor,
+) -> Result<RemoteCommandOutput> {
+    if env.contains_key("GIT_ASKPASS") {
+        let git_process = command.spawn()?;
+        let output = git_process.output().await?;
+        if !output.status.success() {
+            Err(anyhow!("{}", String::from_utf8_lossy(&output.stderr)))
+        } else {
+            Ok(RemoteCommandOutput {
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            })
+        }
+    } else {
+        let ask_pass = AskPassSession::new(executor, ask_pass).await?;
+        command
+            .env("GIT_ASKPASS", ask_pass.script_path())
+            .env("SSH_ASKPASS", ask_pass.script_path())
+            .env("SSH_ASKPASS_REQUIRE", "force");
+        let git_process = command.spawn()?;
+
+        run_askpass_command(ask_pass, git_process).await
+    }
+}
+
+async fn run_askpass_command(
+    mut ask_pass: AskPassSession,
+    git_process: smol::process::Child,
+) -> std::result::Result<RemoteCommandOutput, anyhow::Error> {
+    select_biased! {
+        result = ask_pass.run().fuse() => {
+            match result {
+                AskPassResult::CancelledByUser => {
+                    Err(anyhow!(REMOTE_CANCELLED_BY_USER))?
+                }
+                AskPassResult::Timedout => {
+                    Err(anyhow!("Connecting to host timed out"))?
+                }
+            }
+        }
+        output = git_process.output().fuse() => {
+            let output = output?;
+            if !output.status.success() {
+                Err(anyhow!(
+                    "{}",
+                    String::from_utf8_lossy(&output.stderr)
+                ))
+            } else {
+                Ok(RemoteCommandOutput {
+                    stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                    stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                })
+            }
+        }
+    }
+}
+
+pub static WORK_DIRECTORY_REPO_PATH: LazyLock<RepoPath> =
+    LazyLock::new(|| RepoPath(Path::new("").into()));
+
+#[derive(Clone, Debug, Ord, Hash, PartialOrd, Eq, PartialEq)]
+pub struct RepoPath(pub Arc<Path>);
+
+impl RepoPath {
+    pub fn new(path: PathBuf) -> Self {
+        debug_assert!(path.is_relative(), "Repo paths must be relative");
+
+        RepoPath(path.into())
+    }
+
+    pub fn from_str(path: &str) -> Self {
+        let path = Path::new(path);
+        debug_assert!(path.is_relative(), "Repo paths must be relative");
+
+        RepoPath(path.into())
+    }
+
+    pub fn to_unix_style(&self) -> Cow<'_, OsStr> {
+        #[cfg(target_os = "windows")]
+        {
+            use std::ffi::OsString;
+
+            let path = self.0.as_os_str().to_string_lossy().replace("\\", "/");
+            Cow::Owned(OsString::from(path))
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            Cow::Borrowed(self.0.as_os_str())
+        }
+    }
+}
+
+impl std::fmt::Display for RepoPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.to_string_lossy().fmt(f)
+    }
+}
+
+impl From<&Path> for RepoPath {
+    fn from(value: &Path) -> Self {
+        RepoPath::new(value.into())
+    }
+}
+
+impl From<Arc<Path>> for RepoPath {
+    fn from(value: Arc<Path>) -> Self {
+        RepoPath(value)
+    }
+}
+
+impl From<PathBuf> for RepoPath {
+    fn from(value: PathBuf) -> Self {
+        RepoPath::new(value)
+    }
+}
+
+impl From<&str> for RepoPath {
+    fn from(value: &str) -> Self {
+        Self::from_str(value)
+    }
+}
+
+impl Default for RepoPath {
+    fn default() -> Self {
+        RepoPath(Path::new("").into())
+    }
+}
+
+impl AsRef<Path> for RepoPath {
+    fn as_ref(&self) -> &Path {
+        self.0.as_ref()
+    }
+}
+
+impl std::ops::Deref for RepoPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Borrow<Path> for RepoPath {
+    fn borrow(&self) -> &Path {
+        self.0.as_ref()
+    }
+}
+
+#[derive(Debug)]
+pub struct RepoPathDescendants<'a>(pub &'a Path);
+
+impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
+    fn cmp_cursor(&self, key: &RepoPath) -> Ordering {
+        if key.starts_with(self.0) {
+            Ordering::Greater
+        } else {
+            self.0.cmp(key)
+        }
+    }
+}
+
+fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
+    let mut branches = Vec::new();
+    for line in input.split('\n') {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\x00');
+        let is_current_branch = fields.next().context("no HEAD")? == "*";
+        let head_sha: SharedString = fields.next().context("no objectname")?.to_string().into();
+        let parent_sha: SharedString = fields.next().context("no parent")?.to_string().into();
+        let ref_name: SharedString = fields
+            .next()
+            .context("no refname")?
+            .strip_prefix("refs/heads/")
+            .context("unexpected format for refname")?
+            .to_string()
+            .into();
+        let upstream_name = fields.next().context("no upstream")?.to_string();
+        let upstream_tracking = parse_upstream_track(fields.next().context("no upstream:track")?)?;
+        let commiterdate = fields.next().context("no committerdate")?.parse::<i64>()?;
+        let subject: SharedString = fields
+            .next()
+            .context("no contents:subject")?
+            .to_string()
+            .into();
+
+        branches.push(Branch {
+            is_head: is_current_branch,
+            name: ref_name,
+            most_recent_commit: Some(CommitSummary {
+                sha: head_sha,
+                subject,
+                commit_timestamp: commiterdate,
+                has_parent: !parent_sha.is_empty(),
+            }),
+            upstream: if upstream_name.is_empty() {
+                None
+            } else {
+                Some(Upstream {
+                    ref_name: upstream_name.into(),
+                    tracking: upstream_tracking,
+                })
+            },
+        })
+    }
+
+    Ok(branches)
+}
+
+fn parse_stash_list(input: &str) -> Result<Vec<StashEntry>> {
+    let mut entries = Vec::new();
+    for (index, line) in input
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .enumerate()
+    {
+        let mut fields = line.split('\x00');
+        let sha: SharedString = fields.next().context("no sha")?.to_string().into();
+        let commit_timestamp = fields.next().context("no timestamp")?.parse::<i64>()?;
+        let message: SharedString = fields.next().context("no message")?.to_string().into();
+        entries.push(StashEntry {
+            index,
+            sha,
+            message,
+            commit_timestamp,
+        });
+    }
+    Ok(entries)
+}
+
+fn parse_tag_list(input: &str) -> Result<Vec<Tag>> {
+    let mut tags = Vec::new();
+    for line in input.split('\n').filter(|line| !line.is_empty()) {
+        let mut fields = line.split('\x00');
+        let name: SharedString = fields.next().context("no name")?.to_string().into();
+        let object_sha = fields.next().context("no sha")?;
+        let commit_sha = fields.next().context("no commit sha")?;
+        let subject: SharedString = fields.next().context("no subject")?.to_string().into();
+        let timestamp = fields.next().context("no timestamp")?.parse::<i64>()?;
+        let sha = if commit_sha.is_empty() {
+            object_sha
+        } else {
+            commit_sha
+        };
+        tags.push(Tag {
+            name,
+            sha: sha.to_string().into(),
+            subject,
+            timestamp,
+        });
+    }
+    Ok(tags)
+}
+
+fn parse_worktree_list(input: &str) -> Result<Vec<GitWorktree>> {
+    let mut worktrees = Vec::new();
+    for record in input
+        .split("\n\n")
+        .filter(|record| !record.trim().is_empty())
+    {
+        let mut lines = record.lines();
+        let path = lines
+            .next()
+            .and_then(|line| line.strip_prefix("worktree "))
+            .context("no worktree path")?;
+        let mut worktree = GitWorktree {
+            path: PathBuf::from(path),
+            sha: None,
+            branch: None,
+            is_main: worktrees.is_empty(),
+            is_locked: false,
+            is_prunable: false,
+        };
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "HEAD" => worktree.sha = Some(value.to_string().into()),
+                "branch" => {
+                    let branch = value.strip_prefix("refs/heads/").unwrap_or(value);
+                    worktree.branch = Some(branch.to_string().into());
+                }
+                "locked" => worktree.is_locked = true,
+                "prunable" => worktree.is_prunable = true,
+                _ => {}
+            }
+        }
+        worktrees.push(worktree);
+    }
+    Ok(worktrees)
+}
+
+fn parse_log(input: &str) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for record in input
+        .split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+    {
+        let header = record.lines().next().unwrap_or_default();
+        let mut fields = header.split('\x00');
+        let sha: SharedString = fields.next().context("no sha")?.to_string().into();
+        let parent_shas = fields
+            .next()
+            .context("no parents")?
+            .split(' ')
+            .filter(|parent| !parent.is_empty())
+            .map(|parent| SharedString::from(parent.to_string()))
+            .collect();
+        let author_name: SharedString = fields.next().context("no author name")?.to_string().into();
+        let author_email: SharedString =
+            fields.next().context("no author email")?.to_string().into();
+        let commit_timestamp = fields.next().context("no timestamp")?.parse::<i64>()?;
+        let subject: SharedString = fields.next().context("no subject")?.to_string().into();
+        let ref_names = fields
+            .next()
+            .unwrap_or_default()
+            .split(", ")
+            .filter(|ref_name| !ref_name.is_empty())
+            .map(|ref_name| SharedString::from(ref_name.to_string()))
+            .collect();
+        entries.push(LogEntry {
+            sha,
+            parent_shas,
+            subject,
+            author_name,
+            author_email,
+            commit_timestamp,
+            ref_names,
+        });
+    }
+    Ok(entries)
+}
+
+fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
+    if upstream_track == "" {
+        return Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
+            ahead: 0,
+            behind: 0,
+        }));
+    }
+
+    let upstream_track = upstream_track
+        .strip_prefix("[")
+        .ok_or_else(|| anyhow!("missing ["))?;
+    let upstream_track = upstream_track
+        .strip_suffix("]")
+        .ok_or_else(|| anyhow!("missing ["))?;
+    let mut ahead: u32 = 0;
+    let mut behind: u32 = 0;
+    for component in upstream_track.split(", ") {
+        if component == "gone" {
+            return Ok(UpstreamTracking::Gone);
+        }
+        if let Some(ahead_num) = component.strip_prefix("ahead ") {
+            ahead = ahead_num.parse::<u32>()?;
+        }
+        if let Some(behind_num) = component.strip_prefix("behind ") {
+            behind = behind_num.parse::<u32>()?;
+        }
+    }
+    Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
+        ahead,
+        behind,
+    }))
+}
+
+fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
+    match relative_file_path.components().next() {
+        None => anyhow::bail!("repo path should not be empty"),
+        Some(Component::Prefix(_)) => anyhow::bail!(
+            "repo path `{}` should be relative, not a windows prefix",
+            relative_file_path.to_string_lossy()
+        ),
+        Some(Component::RootDir) => {
+            anyhow::bail!(
+                "repo path `{}` should be relative",
+                relative_file_path.to_string_lossy()
+            )
+        }
+        Some(Component::CurDir) => {
+            anyhow::bail!(
+                "repo path `{}` should not start with `.`",
+                relative_file_path.to_string_lossy()
+            )
+        }
+        Some(Component::ParentDir) => {
+            anyhow::bail!(
+                "repo path `{}` should not start with `..`",
+                relative_file_path.to_string_lossy()
+            )
+        }
+        _ => Ok(()),
+    }
+}
+
+fn checkpoint_author_envs() -> HashMap<String, String> {
+    HashMap::from_iter([
+        ("GIT_AUTHOR_NAME".to_string(), "Zed".to_string()),
+        ("GIT_AUTHOR_EMAIL".to_string(), "hi@zed.dev".to_string()),
+        ("GIT_COMMITTER_NAME".to_string(), "Zed".to_string()),
+        ("GIT_COMMITTER_EMAIL".to_string(), "hi@zed.dev".to_string()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::FileStatus;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_checkpoint_basic(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "initial").await.unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass_delegate(cx),
+            checkpoint_author_envs(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_path, "modified before checkpoint")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new_file_before_checkpoint"), "1")
+            .await
+            .unwrap();
+        let sha_before_checkpoint = repo.head_sha().unwrap();
+        let checkpoint = repo.checkpoint().await.unwrap();
+
+        // Ensure the user can't see any branches after creating a checkpoint.
+        assert_eq!(repo.branches().await.unwrap().len(), 1);
+
+        smol::fs::write(&file_path, "modified after checkpoint")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
+            .await
+            .unwrap();
+        repo.commit(
+            "Commit after checkpoint".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass_delegate(cx),
+            checkpoint_author_envs(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::remove_file(repo_dir.path().join("new_file_before_checkpoint"))
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new_file_after_checkpoint"), "2")
+            .await
+            .unwrap();
+
+        // Ensure checkpoint stays alive even after a Git GC.
+        repo.gc().await.unwrap();
+        repo.restore_checkpoint(checkpoint.clone()).await.unwrap();
+
+        assert_eq!(repo.head_sha().unwrap(), sha_before_checkpoint);
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "modified before checkpoint"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("new_file_before_checkpoint"))
+                .await
+                .unwrap(),
+            "1"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("new_file_after_checkpoint"))
+                .await
+                .ok(),
+            None
+        );
+
+        // Garbage collecting after deleting a checkpoint makes it unreachable.
+        repo.delete_checkpoint(checkpoint.clone()).await.unwrap();
+        repo.gc().await.unwrap();
+        repo.restore_checkpoint(checkpoint.clone())
+            .await
+            .unwrap_err();
+    }
+
+    #[gpui::test]
+    async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+
+        smol::fs::write(repo_dir.path().join("foo"), "foo")
+            .await
+            .unwrap();
+        let checkpoint_sha = repo.checkpoint().await.unwrap();
+
+        // Ensure the user can't see any branches after creating a checkpoint.
+        assert_eq!(repo.branches().await.unwrap().len(), 1);
+
+        smol::fs::write(repo_dir.path().join("foo"), "bar")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("baz"), "qux")
+            .await
+            .unwrap();
+        repo.restore_checkpoint(checkpoint_sha).await.unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("foo"))
+                .await
+                .unwrap(),
+            "foo"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("baz"))
+                .await
+                .ok(),
+            None
+        );
+    }
+
+    #[gpui::test]
+    async fn test_undoing_commit_via_checkpoint(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "initial").await.unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass_delegate(cx),
+            checkpoint_author_envs(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        let initial_commit_sha = repo.head_sha().unwrap();
+
+        smol::fs::write(repo_dir.path().join("new_file1"), "content1")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new_file2"), "content2")
+            .await
+            .unwrap();
+
+        let checkpoint = repo.checkpoint().await.unwrap();
+
+        repo.stage_paths(
+            vec![
+                RepoPath::from_str("new_file1"),
+                RepoPath::from_str("new_file2"),
+            ],
+            HashMap::default(),
+        )
+        .await
+        .unwrap();
+        repo.commit(
+            "Commit new files".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass_delegate(cx),
+            checkpoint_author_envs(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        repo.restore_checkpoint(checkpoint).await.unwrap();
+        assert_eq!(repo.head_sha().unwrap(), initial_commit_sha);
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("new_file1"))
+                .await
+                .unwrap(),
+            "content1"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("new_file2"))
+                .await
+                .unwrap(),
+            "content2"
+        );
+        assert_eq!(
+            repo.status_blocking(&[]).unwrap().entries.as_ref(),
+            &[
+                (RepoPath::from_str("new_file1"), FileStatus::Untracked),
+                (RepoPath::from_str("new_file2"), FileStatus::Untracked)
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_compare_checkpoints(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+
+        smol::fs::write(repo_dir.path().join("file1"), "content1")
+            .await
+            .unwrap();
+        let checkpoint1 = repo.checkpoint().await.unwrap();
+
+        smol::fs::write(repo_dir.path().join("file2"), "content2")
+            .await
+            .unwrap();
+        let checkpoint2 = repo.checkpoint().await.unwrap();
+
+        assert!(
+            !repo
+                .compare_checkpoints(checkpoint1, checkpoint2.clone())
+                .await
+                .unwrap()
+        );
+
+        let checkpoint3 = repo.checkpoint().await.unwrap();
+        assert!(
+            repo.compare_checkpoints(checkpoint2, checkpoint3)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_branches_parsing() {
+        // suppress "help: octal escapes are not supported, `\0` is always null"
+        #[allow(clippy::octal_escapes)]
+        let input = "*\0060964da10574cd9bf06463a53bf6e0769c5c45e\0\0refs/heads/zed-patches\0refs/remotes/origin/zed-patches\0\01733187470\0generated protobuf\n";
+        assert_eq!(
+            parse_branch_input(&input).unwrap(),
+            vec![Branch {
+                is_head: true,
+                name: "zed-patches".into(),
+                upstream: Some(Upstream {
+                    ref_name: "refs/remotes/origin/zed-patches".into(),
+                    tracking: UpstreamTracking::Tracked(UpstreamTrackingStatus {
+                        ahead: 0,
+                        behind: 0
+                    })
+                }),
+                most_recent_commit: Some(CommitSummary {
+                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                    subject: "generated protobuf".into(),
+                    commit_timestamp: 1733187470,
+                    has_parent: false,
+                })
+            }]
+        )
+    }
+
+    #[test]
+    fn test_stash_list_parsing() {
+        let input = "3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8\x001733187470\x00On main: wip\n\
+                     a1b2c3d4e5f60718293a4b5c6d7e8f9011223344\x001733180000\x00WIP on main: 060964d generated protobuf\n";
+        assert_eq!(
+            parse_stash_list(input).unwrap(),
+            vec![
+                StashEntry {
+                    index: 0,
+                    sha: "3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8".into(),
+                    message: "On main: wip".into(),
+                    commit_timestamp: 1733187470,
+                },
+                StashEntry {
+                    index: 1,
+                    sha: "a1b2c3d4e5f60718293a4b5c6d7e8f9011223344".into(),
+                    message: "WIP on main: 060964d generated protobuf".into(),
+                    commit_timestamp: 1733180000,
+                },
+            ]
+        );
+        assert_eq!(parse_stash_list("").unwrap(), Vec::new());
+    }
+
+    #[gpui::test]
+    async fn test_stash(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "initial").await.unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass_delegate(cx),
+            checkpoint_author_envs(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_path, "modified").await.unwrap();
+        smol::fs::write(repo_dir.path().join("untracked"), "new")
+            .await
+            .unwrap();
+        repo.stash_push(
+            StashOptions {
+                message: Some("my changes".into()),
+                include_untracked: true,
+                staged: false,
+            },
+            checkpoint_author_envs(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "initial"
+        );
+        assert!(!repo_dir.path().join("untracked").exists());
+
+        let entries = repo.stash_entries().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].index, 0);
+        assert!(entries[0].message.ends_with("my changes"));
+
+        assert_eq!(
+            repo.stash_diff(0).await.unwrap(),
+            vec![
+                CommitFileDiff {
+                    path: RepoPath::from_str("file"),
+                    old_text: Some("initial".into()),
+                    new_text: Some("modified".into()),
+                },
+                CommitFileDiff {
+                    path: RepoPath::from_str("untracked"),
+                    old_text: None,
+                    new_text: Some("new".into()),
+                },
+            ]
+        );
+
+        repo.stash_apply(0, checkpoint_author_envs()).await.unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "modified"
+        );
+        assert_eq!(repo.stash_entries().await.unwrap().len(), 1);
+
+        repo.stash_drop(0, checkpoint_author_envs()).await.unwrap();
+        assert!(repo.stash_entries().await.unwrap().is_empty());
+        repo.stash_pop(0, checkpoint_author_envs())
+            .await
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_worktree_list_parsing() {
+        let input = "worktree /home/user/zed\n\
+                     HEAD 3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8\n\
+                     branch refs/heads/main\n\
+                     \n\
+                     worktree /home/user/zed-review\n\
+                     HEAD a1b2c3d4e5f60718293a4b5c6d7e8f9011223344\n\
+                     detached\n\
+                     locked reviewing\n\
+                     \n\
+                     worktree /tmp/gone\n\
+                     HEAD a1b2c3d4e5f60718293a4b5c6d7e8f9011223344\n\
+                     branch refs/heads/feature/gone\n\
+                     prunable gitdir file points to non-existent location\n\
+                     \n";
+        assert_eq!(
+            parse_worktree_list(input).unwrap(),
+            vec![
+                GitWorktree {
+                    path: PathBuf::from("/home/user/zed"),
+                    sha: Some("3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8".into()),
+                    branch: Some("main".into()),
+                    is_main: true,
+                    is_locked: false,
+                    is_prunable: false,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/home/user/zed-review"),
+                    sha: Some("a1b2c3d4e5f60718293a4b5c6d7e8f9011223344".into()),
+                    branch: None,
+                    is_main: false,
+                    is_locked: true,
+                    is_prunable: false,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/tmp/gone"),
+                    sha: Some("a1b2c3d4e5f60718293a4b5c6d7e8f9011223344".into()),
+                    branch: Some("feature/gone".into()),
+                    is_main: false,
+                    is_locked: false,
+                    is_prunable: true,
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_worktrees(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        smol::fs::write(repo_dir.path().join("file"), "initial")
+            .await
+            .unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass_delegate(cx),
+            checkpoint_author_envs(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        let worktrees_dir = tempfile::tempdir().unwrap();
+        let feature_path = worktrees_dir.path().join("feature");
+        repo.create_worktree(
+            feature_path.clone(),
+            "feature".into(),
+            true,
+            checkpoint_author_envs(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(feature_path.join("file"))
+                .await
+                .unwrap(),
+            "initial"
+        );
+
+        let worktrees = repo.worktrees().await.unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[1].branch, Some("feature".into()));
+        assert_eq!(
+            worktrees[1].path.canonicalize().unwrap(),
+            feature_path.canonicalize().unwrap()
+        );
+
+        // A branch can only be checked out in a single worktree.
+        repo.create_worktree(
+            worktrees_dir.path().join("again"),
+            "feature".into(),
+            false,
+            checkpoint_author_envs(),
+        )
+        .await
+        .unwrap_err();
+
+        repo.remove_worktree(feature_path.clone(), false, checkpoint_author_envs())
+            .await
+            .unwrap();
+        assert!(!feature_path.exists());
+        assert_eq!(repo.worktrees().await.unwrap().len(), 1);
+
+        repo.create_worktree(
+            feature_path.clone(),
+            "feature".into(),
+            false,
+            checkpoint_author_envs(),
+        )
+        .await
+        .unwrap();
+        smol::fs::remove_dir_all(&feature_path).await.unwrap();
+        assert!(repo.worktrees().await.unwrap()[1].is_prunable);
+        repo.prune_worktrees(checkpoint_author_envs())
+            .await
+            .unwrap();
+        assert_eq!(repo.worktrees().await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_tag_list_parsing() {
+        let input = "v1.0\x003b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8\x00a1b2c3d4e5f60718293a4b5c6d7e8f9011223344\x00First release\x001733187470\n\
+                     nightly\x000123456789abcdef0123456789abcdef01234567\x00\x00Fix crash\x001733180000\n";
+        assert_eq!(
+            parse_tag_list(input).unwrap(),
+            vec![
+                Tag {
+                    name: "v1.0".into(),
+                    sha: "a1b2c3d4e5f60718293a4b5c6d7e8f9011223344".into(),
+                    subject: "First release".into(),
+                    timestamp: 1733187470,
+                },
+                Tag {
+                    name: "nightly".into(),
+                    sha: "0123456789abcdef0123456789abcdef01234567".into(),
+                    subject: "Fix crash".into(),
+                    timestamp: 1733180000,
+                },
+            ]
+        );
+        assert_eq!(parse_tag_list("").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_submodule_status_parsing() {
+        let input = " 3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8 vendor/lib (v1.0)\n\
+                     +a1b2c3d4e5f60718293a4b5c6d7e8f9011223344 vendor/other lib (heads/main)\n\
+                     -0123456789abcdef0123456789abcdef01234567 docs\n\
+                     U0000000000000000000000000000000000000000 themes\n";
+        assert_eq!(
+            parse_submodule_status(input),
+            vec![
+                Submodule {
+                    path: "vendor/lib".into(),
+                    sha: "3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8".into(),
+                    status: SubmoduleStatus::UpToDate,
+                },
+                Submodule {
+                    path: "vendor/other lib".into(),
+                    sha: "a1b2c3d4e5f60718293a4b5c6d7e8f9011223344".into(),
+                    status: SubmoduleStatus::Modified,
+                },
+                Submodule {
+                    path: "docs".into(),
+                    sha: "0123456789abcdef0123456789abcdef01234567".into(),
+                    status: SubmoduleStatus::Uninitialized,
+                },
+                Submodule {
+                    path: "themes".into(),
+                    sha: "0000000000000000000000000000000000000000".into(),
+                    status: SubmoduleStatus::Conflicted,
+                },
+            ]
+        );
+        assert_eq!(parse_submodule_status(""), Vec::new());
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_and_revert(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "one\n").await.unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let commit = async |message: &str| {
+            repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
+                .await
+                .unwrap();
+            repo.commit(
+                message.to_string().into(),
+                None,
+                CommitOptions::default(),
+                test_askpass_delegate(cx),
+                checkpoint_author_envs(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+            repo.head_sha().unwrap()
+        };
+        let base = commit("Initial commit").await;
+        smol::fs::write(&file_path, "two\n").await.unwrap();
+        let second = commit("Second commit").await;
+
+        repo.create_tag("v1".into(), None, None, checkpoint_author_envs())
+            .await
+            .unwrap();
+        repo.create_tag(
+            "v0".into(),
+            Some(base.clone()),
+            Some("First version".into()),
+            checkpoint_author_envs(),
+        )
+        .await
+        .unwrap();
+        let mut tags = repo.tags().await.unwrap();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            tags.iter()
+                .map(|tag| (tag.name.as_ref(), tag.sha.as_ref(), tag.subject.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                ("v0", base.as_str(), "First version"),
+                ("v1", second.as_str(), "Second commit"),
+            ]
+        );
+        repo.delete_tag("v0".into(), checkpoint_author_envs())
+            .await
+            .unwrap();
+        assert_eq!(repo.tags().await.unwrap().len(), 1);
+
+        repo.revert(vec![second.clone()], checkpoint_author_envs())
+            .await
+            .unwrap();
+        assert_eq!(smol::fs::read_to_string(&file_path).await.unwrap(), "one\n");
+
+        // Picking the second commit again conflicts, as the file was changed since.
+        smol::fs::write(&file_path, "three\n").await.unwrap();
+        commit("Third commit").await;
+        repo.cherry_pick(vec![second.clone()], checkpoint_author_envs())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            repo.sequencer_status().await.unwrap(),
+            Some(SequencerOperation::CherryPick)
+        );
+        assert_eq!(repo.merge_head_shas(), vec![second.clone()]);
+
+        repo.sequencer_command(
+            SequencerOperation::CherryPick,
+            RebaseCommand::Abort,
+            checkpoint_author_envs(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(repo.sequencer_status().await.unwrap(), None);
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "three\n"
+        );
+    }
 
     #[gpui::test]
-    async fn test_checkpoint_basic(cx: &mut TestAppContext) {
+    async fn test_remotes(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
 
         let repo_dir = tempfile::tempdir().unwrap();
-
         git2::Repository::init(repo_dir.path()).unwrap();
-        let file_path = repo_dir.path().join("file");
-        smol::fs::write(&file_path, "initial").await.unwrap();
-
         let repo =
             RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
-        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
-            .await
-            .unwrap();
-        repo.commit("Initial commit".into(), None, checkpoint_author_envs())
+        let remote_names = async || {
+            let mut names = repo
+                .get_remotes(None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|remote| remote.name.to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        repo.add_remote(
+            "origin".into(),
+            "https://example.com/origin.git".into(),
+            HashMap::default(),
+        )
+        .await
+        .unwrap();
+        repo.add_remote(
+            "fork".into(),
+            "https://example.com/fork.git".into(),
+            HashMap::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(remote_names().await, ["fork", "origin"]);
+
+        repo.rename_remote("fork".into(), "upstream".into(), HashMap::default())
             .await
             .unwrap();
+        assert_eq!(remote_names().await, ["origin", "upstream"]);
 
-        smol::fs::write(&file_path, "modified before checkpoint")
+        repo.remove_remote("origin".into(), HashMap::default())
             .await
             .unwrap();
-        smol::fs::write(repo_dir.path().join("new_file_before_checkpoint"), "1")
+        assert_eq!(remote_names().await, ["upstream"]);
+        repo.remove_remote("origin".into(), HashMap::default())
             .await
-            .unwrap();
-        let sha_before_checkpoint = repo.head_sha().unwrap();
-        let checkpoint = repo.checkpoint().await.unwrap();
+            .unwrap_err();
+    }
 
-        // Ensure the user can't see any branches after creating a checkpoint.
-        assert_eq!(repo.branches().await.unwrap().len(), 1);
+    #[test]
+    fn test_log_parsing() {
+        let input = "\x1e3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8\x00a1b2c3d4e5f60718293a4b5c6d7e8f9011223344 0123456789abcdef0123456789abcdef01234567\x00Alice\x00alice@example.com\x001733187470\x00Merge branch 'feature'\x00HEAD -> main, tag: v1.0\n\
+                     \x1ea1b2c3d4e5f60718293a4b5c6d7e8f9011223344\x00\x00Bob\x00bob@example.com\x001733180000\x00Initial commit\x00\n\
+                     \n\
+                     diff --git a/file b/file\n";
+        assert_eq!(
+            parse_log(input).unwrap(),
+            vec![
+                LogEntry {
+                    sha: "3b6f0b0c5ac6f0d2e4b1e0e0f6a4a3c2d1e0f9a8".into(),
+                    parent_shas: vec![
+                        "a1b2c3d4e5f60718293a4b5c6d7e8f9011223344".into(),
+                        "0123456789abcdef0123456789abcdef01234567".into(),
+                    ],
+                    subject: "Merge branch 'feature'".into(),
+                    author_name: "Alice".into(),
+                    author_email: "alice@example.com".into(),
+                    commit_timestamp: 1733187470,
+                    ref_names: vec!["HEAD -> main".into(), "tag: v1.0".into()],
+                },
+                LogEntry {
+                    sha: "a1b2c3d4e5f60718293a4b5c6d7e8f9011223344".into(),
+                    parent_shas: Vec::new(),
+                    subject: "Initial commit".into(),
+                    author_name: "Bob".into(),
+                    author_email: "bob@example.com".into(),
+                    commit_timestamp: 1733180000,
+                    ref_names: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(parse_log("").unwrap(), Vec::new());
+    }
 
-        smol::fs::write(&file_path, "modified after checkpoint")
+    #[gpui::test]
+    async fn test_log(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let commit = async |path: &str, text: &str, message: &str| {
+            smol::fs::write(repo_dir.path().join(path), text)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(path)], HashMap::default())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                test_askpass_delegate(cx),
+                checkpoint_author_envs(),
+                cx.to_async(),
+            )
             .await
             .unwrap();
-        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
+        };
+        commit("a", "one\ntwo\n", "Add a").await;
+        commit("b", "three\n", "Add b").await;
+        commit("a", "one\n2\n", "Change a").await;
+
+        let log = repo
+            .log(LogOptions {
+                limit: 10,
+                ..Default::default()
+            })
             .await
             .unwrap();
-        repo.commit(
-            "Commit after checkpoint".into(),
-            None,
-            checkpoint_author_envs(),
-        )
-        .await
-        .unwrap();
-
-        smol::fs::remove_file(repo_dir.path().join("new_file_before_checkpoint"))
+        assert_eq!(
+            log.iter().map(|entry| &entry.subject).collect::<Vec<_>>(),
+            ["Change a", "Add b", "Add a"]
+        );
+        assert_eq!(log[0].parent_shas, vec![log[1].sha.clone()]);
+        assert!(log[2].parent_shas.is_empty());
+
+        let page = repo
+            .log(LogOptions {
+                skip: 1,
+                limit: 1,
+                ..Default::default()
+            })
             .await
             .unwrap();
-        smol::fs::write(repo_dir.path().join("new_file_after_checkpoint"), "2")
+        assert_eq!(page[0].subject, "Add b");
+
+        let file_history = repo
+            .log(LogOptions {
+                limit: 10,
+                path: Some(RepoPath::from_str("a")),
+                ..Default::default()
+            })
             .await
             .unwrap();
+        assert_eq!(
+            file_history
+                .iter()
+                .map(|entry| &entry.subject)
+                .collect::<Vec<_>>(),
+            ["Change a", "Add a"]
+        );
 
-        // Ensure checkpoint stays alive even after a Git GC.
-        repo.gc().await.unwrap();
-        repo.restore_checkpoint(checkpoint.clone()).await.unwrap();
-
-        assert_eq!(repo.head_sha().unwrap(), sha_before_checkpoint);
+        let line_history = repo
+            .log(LogOptions {
+                limit: 10,
+                path: Some(RepoPath::from_str("a")),
+                line_range: Some(0..1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
         assert_eq!(
-            smol::fs::read_to_string(&file_path).await.unwrap(),
-            "modified before checkpoint"
+            line_history
+                .iter()
+                .map(|entry| &entry.subject)
+                .collect::<Vec<_>>(),
+            ["Add a"]
         );
+
+        let filtered = repo
+            .log(LogOptions {
+                limit: 10,
+                message: Some("ADD".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(filtered.len(), 2);
+
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("new_file_before_checkpoint"))
-                .await
-                .unwrap(),
-            "1"
+            repo.commit_diff(log[0].sha.to_string()).await.unwrap(),
+            vec![CommitFileDiff {
+                path: RepoPath::from_str("a"),
+                old_text: Some("one\ntwo\n".into()),
+                new_text: Some("one\n2\n".into()),
+            }]
         );
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("new_file_after_checkpoint"))
-                .await
-                .ok(),
-            None
+            repo.commit_diff(log[2].sha.to_string()).await.unwrap(),
+            vec![CommitFileDiff {
+                path: RepoPath::from_str("a"),
+                old_text: None,
+                new_text: Some("one\ntwo\n".into()),
+            }]
         );
-
-        // Garbage collecting after deleting a checkpoint makes it unreachable.
-        repo.delete_checkpoint(checkpoint.clone()).await.unwrap();
-        repo.gc().await.unwrap();
-        repo.restore_checkpoint(checkpoint.clone())
-            .await
-            .unwrap_err();
     }
 
     #[gpui::test]
-    async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
+    async fn test_revision_diff(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
 
         let repo_dir = tempfile::tempdir().unwrap();
         git2::Repository::init(repo_dir.path()).unwrap();
         let repo =
             RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
-
-        smol::fs::write(repo_dir.path().join("foo"), "foo")
+        let commit = async |path: &str, text: &str, message: &str| {
+            smol::fs::write(repo_dir.path().join(path), text)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(path)], HashMap::default())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                test_askpass_delegate(cx),
+                checkpoint_author_envs(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+            repo.head_sha().unwrap()
+        };
+        let base = commit("a", "one\n", "Add a").await;
+        commit("b", "two\n", "Add b").await;
+        commit("a", "one\n1\n", "Change a").await;
+        smol::fs::write(repo_dir.path().join("b"), "2\n")
             .await
             .unwrap();
-        let checkpoint_sha = repo.checkpoint().await.unwrap();
 
-        // Ensure the user can't see any branches after creating a checkpoint.
-        assert_eq!(repo.branches().await.unwrap().len(), 1);
+        let files = repo
+            .revision_diff(DiffRevisions {
+                base: base.clone(),
+                target: Some("HEAD".into()),
+                merge_base: true,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            files,
+            [
+                CommitFileDiff {
+                    path: RepoPath::from_str("a"),
+                    old_text: Some("one\n".into()),
+                    new_text: Some("one\n1\n".into()),
+                },
+                CommitFileDiff {
+                    path: RepoPath::from_str("b"),
+                    old_text: None,
+                    new_text: Some("two\n".into()),
+                },
+            ]
+        );
 
-        smol::fs::write(repo_dir.path().join("foo"), "bar")
+        let files = repo
+            .revision_diff(DiffRevisions {
+                base: "HEAD".into(),
+                target: None,
+                merge_base: false,
+            })
             .await
             .unwrap();
-        smol::fs::write(repo_dir.path().join("baz"), "qux")
+        assert_eq!(
+            files,
+            [CommitFileDiff {
+                path: RepoPath::from_str("b"),
+                old_text: Some("two\n".into()),
+                new_text: Some("2\n".into()),
+            }]
+        );
+
+        let diff = repo
+            .diff(DiffType::Revisions(DiffRevisions {
+                base,
+                target: None,
+                merge_base: true,
+            }))
             .await
             .unwrap();
-        repo.restore_checkpoint(checkpoint_sha).await.unwrap();
+        assert!(diff.contains("+1\n"));
+        assert!(diff.contains("+2\n"));
+    }
+
+    #[test]
+    fn test_rebase_todo() {
+        let step = |sha: &str, action, message: Option<&str>| RebaseStep {
+            sha: sha.to_string().into(),
+            action,
+            message: message.map(ToString::to_string),
+        };
+        let (todo, messages) = rebase_todo(
+            &[
+                step("a", RebaseAction::Drop, None),
+                step("b", RebaseAction::Reword, Some("New message")),
+                step("c", RebaseAction::Fixup, None),
+            ],
+            Path::new("/repo/.git/zed-rebase"),
+        )
+        .unwrap();
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("foo"))
-                .await
-                .unwrap(),
-            "foo"
+            todo,
+            "drop a\n\
+             pick b\n\
+             exec git commit --amend --allow-empty --no-verify --quiet --file='/repo/.git/zed-rebase/message-0'\n\
+             fixup c\n"
         );
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("baz"))
-                .await
-                .ok(),
-            None
+            messages,
+            [(
+                PathBuf::from("/repo/.git/zed-rebase/message-0"),
+                "New message".to_string()
+            )]
         );
+
+        rebase_todo(
+            &[
+                step("a", RebaseAction::Drop, None),
+                step("b", RebaseAction::Squash, None),
+            ],
+            Path::new("/repo/.git/zed-rebase"),
+        )
+        .unwrap_err();
     }
 
     #[gpui::test]
-    async fn test_undoing_commit_via_checkpoint(cx: &mut TestAppContext) {
+    async fn test_rebase(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
 
         let repo_dir = tempfile::tempdir().unwrap();
-
         git2::Repository::init(repo_dir.path()).unwrap();
-        let file_path = repo_dir.path().join("file");
-        smol::fs::write(&file_path, "initial").await.unwrap();
-
         let repo =
             RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
-        repo.stage_paths(vec![RepoPath::from_str("file")], HashMap::default())
-            .await
-            .unwrap();
-        repo.commit("Initial commit".into(), None, checkpoint_author_envs())
-            .await
-            .unwrap();
-
-        let initial_commit_sha = repo.head_sha().unwrap();
-
-        smol::fs::write(repo_dir.path().join("new_file1"), "content1")
-            .await
-            .unwrap();
-        smol::fs::write(repo_dir.path().join("new_file2"), "content2")
+        let commit = async |path: &str, text: &str, message: &str| {
+            smol::fs::write(repo_dir.path().join(path), text)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(path)], HashMap::default())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                test_askpass_delegate(cx),
+                checkpoint_author_envs(),
+                cx.to_async(),
+            )
             .await
             .unwrap();
+        };
+        commit("base", "base", "Base").await;
+        let base = repo.head_sha().unwrap();
+        commit("a", "a", "Add a").await;
+        commit("b", "b", "Add b").await;
+        commit("a", "a2", "Change a").await;
+        commit("c", "c", "Add c").await;
+
+        let commits = repo.rebase_commits(base.clone()).await.unwrap();
+        assert_eq!(
+            commits
+                .iter()
+                .map(|entry| &entry.subject)
+                .collect::<Vec<_>>(),
+            ["Add a", "Add b", "Change a", "Add c"]
+        );
 
-        let checkpoint = repo.checkpoint().await.unwrap();
-
-        repo.stage_paths(
+        let step = |entry: &LogEntry, action, message: Option<&str>| RebaseStep {
+            sha: entry.sha.clone(),
+            action,
+            message: message.map(ToString::to_string),
+        };
+        repo.rebase(
+            base.clone(),
             vec![
-                RepoPath::from_str("new_file1"),
-                RepoPath::from_str("new_file2"),
+                step(&commits[0], RebaseAction::Reword, Some("Add a, reworded")),
+                step(&commits[2], RebaseAction::Fixup, None),
+                step(&commits[1], RebaseAction::Pick, None),
+                step(&commits[3], RebaseAction::Drop, None),
             ],
-            HashMap::default(),
+            checkpoint_author_envs(),
         )
         .await
         .unwrap();
-        repo.commit("Commit new files".into(), None, checkpoint_author_envs())
-            .await
-            .unwrap();
-
-        repo.restore_checkpoint(checkpoint).await.unwrap();
-        assert_eq!(repo.head_sha().unwrap(), initial_commit_sha);
+        assert_eq!(repo.rebase_status().await.unwrap(), None);
+        let commits = repo.rebase_commits(base.clone()).await.unwrap();
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("new_file1"))
-                .await
-                .unwrap(),
-            "content1"
+            commits
+                .iter()
+                .map(|entry| &entry.subject)
+                .collect::<Vec<_>>(),
+            ["Add a, reworded", "Add b"]
         );
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("new_file2"))
+            smol::fs::read_to_string(repo_dir.path().join("a"))
                 .await
                 .unwrap(),
-            "content2"
+            "a2"
         );
+        assert!(!repo_dir.path().join("c").exists());
+
+        // Applying the change to `a` before its creation conflicts.
+        commit("a", "a3", "Change a again").await;
+        let commits = repo.rebase_commits(base.clone()).await.unwrap();
+        repo.rebase(
+            base.clone(),
+            vec![
+                step(&commits[2], RebaseAction::Pick, None),
+                step(&commits[0], RebaseAction::Pick, None),
+                step(&commits[1], RebaseAction::Pick, None),
+            ],
+            checkpoint_author_envs(),
+        )
+        .await
+        .unwrap_err();
+        let status = repo.rebase_status().await.unwrap().unwrap();
+        assert_eq!(status.step, 1);
+        assert_eq!(status.total_steps, 3);
+
+        repo.rebase_command(RebaseCommand::Abort, checkpoint_author_envs())
+            .await
+            .unwrap();
+        assert_eq!(repo.rebase_status().await.unwrap(), None);
         assert_eq!(
-            repo.status_blocking(&[]).unwrap().entries.as_ref(),
-            &[
-                (RepoPath::from_str("new_file1"), FileStatus::Untracked),
-                (RepoPath::from_str("new_file2"), FileStatus::Untracked)
-            ]
+            smol::fs::read_to_string(repo_dir.path().join("a"))
+                .await
+                .unwrap(),
+            "a3"
         );
     }
 
     #[gpui::test]
-    async fn test_compare_checkpoints(cx: &mut TestAppContext) {
+    async fn test_commit_message_template(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
 
         let repo_dir = tempfile::tempdir().unwrap();
         git2::Repository::init(repo_dir.path()).unwrap();
         let repo =
             RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        assert_eq!(
+            repo.commit_message_template(HashMap::default())
+                .await
+                .unwrap(),
+            None
+        );
 
-        smol::fs::write(repo_dir.path().join("file1"), "content1")
+        smol::fs::write(repo_dir.path().join("template"), "Subject\n\n# Why?\n")
             .await
             .unwrap();
-        let checkpoint1 = repo.checkpoint().await.unwrap();
-
-        smol::fs::write(repo_dir.path().join("file2"), "content2")
-            .await
+        git2::Repository::open(repo_dir.path())
+            .unwrap()
+            .config()
+            .unwrap()
+            .set_str("commit.template", "template")
             .unwrap();
-        let checkpoint2 = repo.checkpoint().await.unwrap();
-
-        assert!(
-            !repo
-                .compare_checkpoints(checkpoint1, checkpoint2.clone())
+        assert_eq!(
+            repo.commit_message_template(HashMap::default())
                 .await
                 .unwrap()
+                .as_deref(),
+            Some("Subject\n\n# Why?\n")
         );
 
-        let checkpoint3 = repo.checkpoint().await.unwrap();
-        assert!(
-            repo.compare_checkpoints(checkpoint2, checkpoint3)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+
+            let hook_path = repo_dir.path().join(".git/hooks/prepare-commit-msg");
+            smol::fs::create_dir_all(hook_path.parent().unwrap())
                 .await
-                .unwrap()
-        );
+                .unwrap();
+            smol::fs::write(&hook_path, "#!/bin/sh\nsed -i.bak \"1s/^/[$2] /\" \"$1\"\n")
+                .await
+                .unwrap();
+            smol::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))
+                .await
+                .unwrap();
+            assert_eq!(
+                repo.commit_message_template(HashMap::default())
+                    .await
+                    .unwrap()
+                    .as_deref(),
+                Some("[template] Subject\n\n# Why?\n")
+            );
+        }
     }
 
-    #[test]
-    fn test_branches_parsing() {
-        // suppress "help: octal escapes are not supported, `\0` is always null"
-        #[allow(clippy::octal_escapes)]
-        let input = "*\0060964da10574cd9bf06463a53bf6e0769c5c45e\0\0refs/heads/zed-patches\0refs/remotes/origin/zed-patches\0\01733187470\0generated protobuf\n";
-        assert_eq!(
-            parse_branch_input(&input).unwrap(),
-            vec![Branch {
-                is_head: true,
-                name: "zed-patches".into(),
-                upstream: Some(Upstream {
-                    ref_name: "refs/remotes/origin/zed-patches".into(),
-                    tracking: UpstreamTracking::Tracked(UpstreamTrackingStatus {
-                        ahead: 0,
-                        behind: 0
-                    })
-                }),
-                most_recent_commit: Some(CommitSummary {
-                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
-                    subject: "generated protobuf".into(),
-                    commit_timestamp: 1733187470,
-                    has_parent: false,
-                })
-            }]
-        )
+    fn test_askpass_delegate(cx: &TestAppContext) -> AskPassDelegate {
+        AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {})
     }
 
     impl RealGitRepository {
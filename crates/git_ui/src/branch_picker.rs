@@ -1,11 +1,11 @@
 use anyhow::{Context as _, anyhow};
 use fuzzy::StringMatchCandidate;
 
-use git::repository::Branch;
+use git::repository::{Branch, DiffRevisions};
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
     IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, Window, rems,
+    Subscription, Task, WeakEntity, Window, rems,
 };
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
 use project::git_store::Repository;
@@ -17,10 +17,13 @@ use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
+use crate::commit_diff::CommitDiff;
+
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
     workspace.register_action(switch);
     workspace.register_action(checkout_branch);
+    workspace.register_action(compare);
 }
 
 pub fn checkout_branch(
@@ -50,7 +53,33 @@ pub fn open(
     let repository = workspace.project().read(cx).active_repository(cx).clone();
     let style = BranchListStyle::Modal;
     workspace.toggle_modal(window, cx, |window, cx| {
-        BranchList::new(repository, style, rems(34.), window, cx)
+        BranchList::new(
+            repository,
+            style,
+            BranchListMode::Switch,
+            rems(34.),
+            window,
+            cx,
+        )
+    })
+}
+
+/// Picks two revisions and opens the diff between them: first the base, then the branch,
+/// revision, or working tree compared against it.
+pub fn compare(
+    workspace: &mut Workspace,
+    _: &git::CompareBranches,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx).clone();
+    let style = BranchListStyle::Modal;
+    let mode = BranchListMode::Compare {
+        workspace: cx.weak_entity(),
+        base: None,
+    };
+    workspace.toggle_modal(window, cx, |window, cx| {
+        BranchList::new(repository, style, mode, rems(34.), window, cx)
     })
 }
 
@@ -60,7 +89,14 @@ pub fn popover(
     cx: &mut App,
 ) -> Entity<BranchList> {
     cx.new(|cx| {
-        let list = BranchList::new(repository, BranchListStyle::Popover, rems(20.), window, cx);
+        let list = BranchList::new(
+            repository,
+            BranchListStyle::Popover,
+            BranchListMode::Switch,
+            rems(20.),
+            window,
+            cx,
+        );
         list.focus_handle(cx).focus(window);
         list
     })
@@ -72,6 +108,15 @@ enum BranchListStyle {
     Popover,
 }
 
+enum BranchListMode {
+    Switch,
+    Compare {
+        workspace: WeakEntity<Workspace>,
+        /// The revision to compare against, once it has been picked.
+        base: Option<SharedString>,
+    },
+}
+
 pub struct BranchList {
     width: Rems,
     pub picker: Entity<Picker<BranchListDelegate>>,
@@ -82,6 +127,7 @@ impl BranchList {
     fn new(
         repository: Option<Entity<Repository>>,
         style: BranchListStyle,
+        mode: BranchListMode,
         width: Rems,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -113,7 +159,7 @@ impl BranchList {
         })
         .detach_and_log_err(cx);
 
-        let delegate = BranchListDelegate::new(repository.clone(), style);
+        let delegate = BranchListDelegate::new(repository.clone(), style, mode);
         let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
 
         let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
@@ -167,6 +213,7 @@ struct BranchEntry {
     branch: Branch,
     positions: Vec<usize>,
     is_new: bool,
+    is_working_tree: bool,
 }
 
 pub struct BranchListDelegate {
@@ -174,17 +221,19 @@ pub struct BranchListDelegate {
     all_branches: Option<Vec<Branch>>,
     repo: Option<Entity<Repository>>,
     style: BranchListStyle,
+    mode: BranchListMode,
     selected_index: usize,
     last_query: String,
     modifiers: Modifiers,
 }
 
 impl BranchListDelegate {
-    fn new(repo: Option<Entity<Repository>>, style: BranchListStyle) -> Self {
+    fn new(repo: Option<Entity<Repository>>, style: BranchListStyle, mode: BranchListMode) -> Self {
         Self {
             matches: vec![],
             repo,
             style,
+            mode,
             all_branches: None,
             selected_index: 0,
             last_query: Default::default(),
@@ -213,13 +262,66 @@ impl BranchListDelegate {
         });
         cx.emit(DismissEvent);
     }
+
+    fn compare_base(&self) -> Option<&SharedString> {
+        match &self.mode {
+            BranchListMode::Compare { base, .. } => base.as_ref(),
+            BranchListMode::Switch => None,
+        }
+    }
+
+    /// Picks the base of the comparison, or opens the diff against it when it's been picked.
+    ///
+    /// The diff starts from the merge base of the two revisions, unless `secondary` is set.
+    fn confirm_compare(
+        &mut self,
+        entry: BranchEntry,
+        secondary: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let BranchListMode::Compare { workspace, base } = &mut self.mode else {
+            return;
+        };
+        let Some(base) = base.clone() else {
+            *base = Some(entry.branch.name);
+            cx.defer_in(window, |picker, window, cx| {
+                picker.set_query("", window, cx);
+                picker.refresh_placeholder(window, cx);
+                picker.refresh(window, cx);
+            });
+            return;
+        };
+        let Some(repository) = self.repo.clone() else {
+            return;
+        };
+        let revisions = DiffRevisions {
+            base: base.to_string(),
+            target: (!entry.is_working_tree).then(|| entry.branch.name.to_string()),
+            merge_base: !secondary,
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                CommitDiff::open_revisions(revisions, repository, workspace, window, cx);
+            })
+            .log_err();
+        cx.emit(DismissEvent);
+    }
 }
 
 impl PickerDelegate for BranchListDelegate {
     type ListItem = ListItem;
 
     fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
-        "Select branch...".into()
+        match &self.mode {
+            BranchListMode::Switch => "Select branch...".into(),
+            BranchListMode::Compare { base: None, .. } => {
+                "Select a branch or revision to compare against...".into()
+            }
+            BranchListMode::Compare {
+                base: Some(base), ..
+            } => format!("Compare {base} with...").into(),
+        }
     }
 
     fn editor_position(&self) -> PickerEditorPosition {
@@ -266,6 +368,7 @@ impl PickerDelegate for BranchListDelegate {
                         branch,
                         positions: Vec::new(),
                         is_new: false,
+                        is_working_tree: false,
                     })
                     .collect()
             } else {
@@ -289,6 +392,7 @@ impl PickerDelegate for BranchListDelegate {
                     branch: all_branches[candidate.candidate_id].clone(),
                     positions: candidate.positions,
                     is_new: false,
+                    is_working_tree: false,
                 })
                 .collect()
             };
@@ -309,9 +413,26 @@ impl PickerDelegate for BranchListDelegate {
                             },
                             positions: Vec::new(),
                             is_new: true,
+                            is_working_tree: false,
                         })
                     }
                     let delegate = &mut picker.delegate;
+                    if query.is_empty() && delegate.compare_base().is_some() {
+                        matches.insert(
+                            0,
+                            BranchEntry {
+                                branch: Branch {
+                                    name: "Working Tree".into(),
+                                    is_head: false,
+                                    upstream: None,
+                                    most_recent_commit: None,
+                                },
+                                positions: Vec::new(),
+                                is_new: false,
+                                is_working_tree: true,
+                            },
+                        );
+                    }
                     delegate.matches = matches;
                     if delegate.matches.is_empty() {
                         delegate.selected_index = 0;
@@ -325,10 +446,14 @@ impl PickerDelegate for BranchListDelegate {
         })
     }
 
-    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
         let Some(entry) = self.matches.get(self.selected_index()) else {
             return;
         };
+        if let BranchListMode::Compare { .. } = self.mode {
+            self.confirm_compare(entry.clone(), secondary, window, cx);
+            return;
+        }
         if entry.is_new {
             self.create_branch(entry.branch.name.clone(), window, cx);
             return;
@@ -434,12 +559,15 @@ impl PickerDelegate for BranchListDelegate {
                                 .justify_between()
                                 .child(div().flex_shrink().overflow_x_hidden().child(
                                     if entry.is_new {
-                                        Label::new(format!(
-                                            "Create branch \"{}\"…",
-                                            entry.branch.name
-                                        ))
-                                        .single_line()
-                                        .into_any_element()
+                                        let label = match self.mode {
+                                            BranchListMode::Switch => {
+                                                format!("Create branch \"{}\"…", entry.branch.name)
+                                            }
+                                            BranchListMode::Compare { .. } => {
+                                                format!("Use revision \"{}\"", entry.branch.name)
+                                            }
+                                        };
+                                        Label::new(label).single_line().into_any_element()
                                     } else {
                                         HighlightedLabel::new(
                                             entry.branch.name.clone(),
@@ -460,7 +588,13 @@ impl PickerDelegate for BranchListDelegate {
                         )
                         .when(self.style == BranchListStyle::Modal, |el| {
                             el.child(div().max_w_96().child({
-                                let message = if entry.is_new {
+                                let message = if entry.is_working_tree {
+                                    "uncommitted changes, including unstaged ones".to_string()
+                                } else if entry.is_new
+                                    && matches!(self.mode, BranchListMode::Compare { .. })
+                                {
+                                    "a commit, tag, or other revision".to_string()
+                                } else if entry.is_new {
                                     if let Some(current_branch) =
                                         self.repo.as_ref().and_then(|repo| {
                                             repo.read(cx).current_branch().map(|b| b.name.clone())
@@ -0,0 +1,519 @@
+use anyhow::Result;
+use buffer_diff::BufferDiff;
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
+use futures::channel::oneshot;
+use git::{
+    SHORT_SHA_LENGTH,
+    repository::{CommitFileDiff, DiffRevisions, LogEntry, RepoPath, StashEntry},
+};
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, AsyncWindowContext, Entity, EventEmitter,
+    FocusHandle, Focusable, Render, Subscription, Task,
+};
+use language::{
+    Anchor, Buffer, BufferEvent, BufferId, Capability, DiskState, File, Language, LanguageRegistry,
+    OffsetRangeExt,
+};
+use multi_buffer::{MultiBuffer, PathKey};
+use project::{Project, WorktreeId, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::{
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+/// A multibuffer showing the changes recorded in a commit or a stash entry, the changes
+/// between two revisions, or the change of the commit checked out in a submodule.
+///
+/// The multibuffer is read-only, except when comparing a revision against the working tree, where
+/// it shows the project's buffers and keeps their diffs up to date as they are edited.
+pub struct CommitDiff {
+    title: SharedString,
+    tooltip: SharedString,
+    multibuffer: Entity<MultiBuffer>,
+    editor: Entity<Editor>,
+    recalculate_diff_tasks: HashMap<BufferId, Task<()>>,
+    _task: Task<Result<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The start of the text git shows for a submodule in diffs, followed by its commit.
+const SUBMODULE_TEXT_PREFIX: &str = "Subproject commit ";
+
+/// The file of a buffer holding the contents of a file in a commit, which doesn't exist on disk.
+struct CommitFile {
+    path: Arc<Path>,
+    worktree_id: WorktreeId,
+    is_deleted: bool,
+}
+
+impl CommitDiff {
+    pub fn open_stash(
+        entry: StashEntry,
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let load_files = repository.read(cx).stash_diff(entry.index);
+        Self::open(
+            entry.name().into(),
+            entry.message,
+            load_files,
+            false,
+            repository,
+            workspace,
+            window,
+            cx,
+        );
+    }
+
+    pub fn open_commit(
+        entry: LogEntry,
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let load_files = repository.read(cx).commit_diff(entry.sha.to_string());
+        Self::open(
+            entry
+                .sha
+                .chars()
+                .take(SHORT_SHA_LENGTH)
+                .collect::<String>()
+                .into(),
+            entry.subject,
+            load_files,
+            false,
+            repository,
+            workspace,
+            window,
+            cx,
+        );
+    }
+
+    pub fn open_revisions(
+        revisions: DiffRevisions,
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let base = revisions.base.clone();
+        let target = revisions
+            .target
+            .clone()
+            .unwrap_or_else(|| "Working Tree".to_string());
+        let (title, tooltip) = if revisions.merge_base {
+            (
+                format!("{base}...{target}"),
+                format!("Changes on {target} since it diverged from {base}"),
+            )
+        } else {
+            (
+                format!("{base}..{target}"),
+                format!("Changes between {base} and {target}"),
+            )
+        };
+        let is_working_tree = revisions.target.is_none();
+        let load_files = repository.read(cx).revision_diff(revisions);
+        Self::open(
+            title.into(),
+            tooltip.into(),
+            load_files,
+            is_working_tree,
+            repository,
+            workspace,
+            window,
+            cx,
+        );
+    }
+
+    pub fn open_submodule(
+        path: RepoPath,
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let load_file = repository.read(cx).submodule_diff(path.clone());
+        let (tx, load_files) = oneshot::channel();
+        cx.background_spawn(async move {
+            let files = match load_file.await {
+                Ok(file) => file.map(|file| vec![file]),
+                Err(error) => Err(error.into()),
+            };
+            tx.send(files).ok();
+        })
+        .detach();
+        Self::open(
+            path.to_string_lossy().to_string().into(),
+            format!("Commit checked out in submodule {}", path.display()).into(),
+            load_files,
+            false,
+            repository,
+            workspace,
+            window,
+            cx,
+        );
+    }
+
+    fn open(
+        title: SharedString,
+        tooltip: SharedString,
+        load_files: oneshot::Receiver<Result<Vec<CommitFileDiff>>>,
+        is_working_tree: bool,
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let commit_diff = cx.new(|cx| {
+            Self::new(
+                title,
+                tooltip,
+                load_files,
+                is_working_tree,
+                repository,
+                project,
+                window,
+                cx,
+            )
+        });
+        workspace.add_item_to_active_pane(Box::new(commit_diff), None, true, window, cx);
+    }
+
+    fn new(
+        title: SharedString,
+        tooltip: SharedString,
+        load_files: oneshot::Receiver<Result<Vec<CommitFileDiff>>>,
+        is_working_tree: bool,
+        repository: Entity<Repository>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let capability = if is_working_tree {
+            Capability::ReadWrite
+        } else {
+            Capability::ReadOnly
+        };
+        let multibuffer = cx.new(|_| MultiBuffer::new(capability));
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.disable_inline_diagnostics();
+            editor.set_expand_all_diff_hunks(cx);
+            editor
+        });
+
+        let language_registry = project.read(cx).languages().clone();
+        let task = cx.spawn_in(window, async move |this, cx| {
+            let files = load_files.await??;
+            for file in files {
+                let Some(project_path) = repository.update(cx, |repository, cx| {
+                    repository.repo_path_to_project_path(&file.path, cx)
+                })?
+                else {
+                    continue;
+                };
+                // Submodules, which can't be opened as buffers, are shown as the commit checked
+                // out in them like files of a commit are.
+                let is_submodule = file
+                    .new_text
+                    .as_ref()
+                    .is_some_and(|text| text.starts_with(SUBMODULE_TEXT_PREFIX));
+                if is_working_tree && file.new_text.is_some() && !is_submodule {
+                    let Some(buffer) = project
+                        .update(cx, |project, cx| project.open_buffer(project_path, cx))?
+                        .await
+                        .log_err()
+                    else {
+                        continue;
+                    };
+                    let language = buffer.read_with(cx, |buffer, _| buffer.language().cloned())?;
+                    let diff = build_diff(&buffer, file.old_text, language, cx).await?;
+                    this.update(cx, |this, cx| {
+                        this.track_buffer_edits(&buffer, &diff, cx);
+                        this.register_buffer(buffer, diff, cx);
+                    })?;
+                    continue;
+                }
+                let commit_file = Arc::new(CommitFile {
+                    path: project_path.path,
+                    worktree_id: project_path.worktree_id,
+                    is_deleted: file.new_text.is_none(),
+                });
+                let Some((buffer, diff)) =
+                    build_buffer_and_diff(file, commit_file, &language_registry, cx)
+                        .await
+                        .log_err()
+                else {
+                    continue;
+                };
+                this.update(cx, |this, cx| this.register_buffer(buffer, diff, cx))?;
+            }
+            Ok(())
+        });
+
+        Self {
+            title,
+            tooltip,
+            multibuffer,
+            editor,
+            recalculate_diff_tasks: HashMap::default(),
+            _task: task,
+            _subscriptions: Vec::new(),
+        }
+    }
+
+    /// Keeps the diff of a buffer from the working tree up to date as the buffer is edited.
+    fn track_buffer_edits(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        diff: &Entity<BufferDiff>,
+        cx: &mut Context<Self>,
+    ) {
+        let diff = diff.clone();
+        self._subscriptions
+            .push(cx.subscribe(buffer, move |this, buffer, event, cx| {
+                if let BufferEvent::Edited = event {
+                    let snapshot = buffer.read(cx).text_snapshot();
+                    let task = diff.update(cx, |diff, cx| diff.recalculate_diff(snapshot, cx));
+                    this.recalculate_diff_tasks
+                        .insert(buffer.read(cx).remote_id(), task);
+                }
+            }));
+    }
+
+    fn register_buffer(
+        &mut self,
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let Some(path) = snapshot.file().map(|file| file.path().clone()) else {
+            return;
+        };
+        let diff_hunk_ranges = diff
+            .read(cx)
+            .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot, cx)
+            .map(|diff_hunk| diff_hunk.buffer_range.to_point(&snapshot))
+            .collect::<Vec<_>>();
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            multibuffer.add_diff(diff, cx);
+            multibuffer.set_excerpts_for_path(
+                PathKey::namespaced("", path),
+                buffer,
+                diff_hunk_ranges,
+                editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                cx,
+            );
+        });
+    }
+}
+
+async fn build_buffer_and_diff(
+    file: CommitFileDiff,
+    commit_file: Arc<CommitFile>,
+    language_registry: &Arc<LanguageRegistry>,
+    cx: &mut AsyncWindowContext,
+) -> Result<(Entity<Buffer>, Entity<BufferDiff>)> {
+    let language = language_registry
+        .language_for_file_path(&commit_file.path)
+        .await
+        .ok();
+    let buffer = cx.new(|cx| {
+        let mut buffer = Buffer::local(file.new_text.unwrap_or_default(), cx);
+        buffer.set_language(language.clone(), cx);
+        buffer.file_updated(commit_file, cx);
+        buffer
+    })?;
+    let diff = build_diff(&buffer, file.old_text, language, cx).await?;
+    Ok((buffer, diff))
+}
+
+async fn build_diff(
+    buffer: &Entity<Buffer>,
+    old_text: Option<String>,
+    language: Option<Arc<Language>>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Entity<BufferDiff>> {
+    let base_buffer = cx.new(|cx| {
+        let mut buffer = Buffer::local(old_text.unwrap_or_default(), cx);
+        buffer.set_language(language, cx);
+        buffer
+    })?;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.text_snapshot())?;
+    let diff = cx.new(|cx| BufferDiff::new(&snapshot, cx))?;
+    diff.update(cx, |diff, cx| diff.set_base_text(base_buffer, snapshot, cx))?
+        .await
+        .ok();
+    Ok(diff)
+}
+
+impl File for CommitFile {
+    fn as_local(&self) -> Option<&dyn language::LocalFile> {
+        None
+    }
+
+    fn disk_state(&self) -> DiskState {
+        if self.is_deleted {
+            DiskState::Deleted
+        } else {
+            DiskState::New
+        }
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &App) -> PathBuf {
+        self.path.to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a App) -> &'a OsStr {
+        self.path.file_name().unwrap_or_default()
+    }
+
+    fn worktree_id(&self, _: &App) -> WorktreeId {
+        self.worktree_id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_proto(&self, _: &App) -> rpc::proto::File {
+        unimplemented!()
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
+
+impl EventEmitter<EditorEvent> for CommitDiff {}
+
+impl Focusable for CommitDiff {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for CommitDiff {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.tooltip.clone())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new(self.title.clone())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Commit Diff Opened")
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for CommitDiff {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div().size_full().child(self.editor.clone())
+    }
+}
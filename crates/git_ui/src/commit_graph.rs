@@ -0,0 +1,182 @@
+use git::repository::LogEntry;
+use gpui::SharedString;
+
+/// A line of the commit graph crossing half of a row, from a lane at one end of the half to a
+/// lane at the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// The part of the commit graph drawn next to a commit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphRow {
+    /// The lane of the row's commit.
+    pub lane: usize,
+    /// The edges between the top of the row and its middle, where the commit is drawn.
+    pub upper_edges: Vec<GraphEdge>,
+    /// The edges between the middle of the row and its bottom.
+    pub lower_edges: Vec<GraphEdge>,
+}
+
+impl GraphRow {
+    /// Returns the number of lanes the row spans.
+    pub fn lane_count(&self) -> usize {
+        self.upper_edges
+            .iter()
+            .chain(&self.lower_edges)
+            .map(|edge| edge.from.max(edge.to))
+            .max()
+            .unwrap_or_default()
+            .max(self.lane)
+            + 1
+    }
+}
+
+/// Assigns commits to lanes, expecting them to be pushed children before their parents.
+///
+/// Each lane is waiting for a commit, the parent of the last commit drawn in it, so that more
+/// commits can be pushed when the next page of the log is loaded.
+#[derive(Default)]
+pub struct CommitGraph {
+    lanes: Vec<Option<SharedString>>,
+    rows: Vec<GraphRow>,
+}
+
+impl CommitGraph {
+    pub fn rows(&self) -> &[GraphRow] {
+        &self.rows
+    }
+
+    pub fn push(&mut self, entry: &LogEntry) {
+        let mut lane = None;
+        let mut upper_edges = Vec::new();
+        for (ix, expected) in self.lanes.iter_mut().enumerate() {
+            match expected {
+                Some(sha) if *sha == entry.sha => {
+                    let lane = *lane.get_or_insert(ix);
+                    upper_edges.push(GraphEdge { from: ix, to: lane });
+                    *expected = None;
+                }
+                Some(_) => upper_edges.push(GraphEdge { from: ix, to: ix }),
+                None => {}
+            }
+        }
+        // A commit that no lane is waiting for is the tip of a branch.
+        let lane = lane.unwrap_or_else(|| self.free_lane());
+
+        let mut lower_edges = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, expected)| expected.is_some())
+            .map(|(ix, _)| GraphEdge { from: ix, to: ix })
+            .collect::<Vec<_>>();
+        for (parent_ix, parent) in entry.parent_shas.iter().enumerate() {
+            let to = if let Some(ix) = self.lane_for(parent) {
+                ix
+            } else if parent_ix == 0 {
+                self.lanes[lane] = Some(parent.clone());
+                lane
+            } else {
+                let ix = self.free_lane();
+                self.lanes[ix] = Some(parent.clone());
+                ix
+            };
+            lower_edges.push(GraphEdge { from: lane, to });
+        }
+
+        while self.lanes.last().is_some_and(|expected| expected.is_none()) {
+            self.lanes.pop();
+        }
+        self.rows.push(GraphRow {
+            lane,
+            upper_edges,
+            lower_edges,
+        });
+    }
+
+    fn lane_for(&self, sha: &SharedString) -> Option<usize> {
+        self.lanes
+            .iter()
+            .position(|expected| expected.as_ref() == Some(sha))
+    }
+
+    fn free_lane(&mut self) -> usize {
+        if let Some(ix) = self.lanes.iter().position(Option::is_none) {
+            ix
+        } else {
+            self.lanes.push(None);
+            self.lanes.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sha: &str, parents: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parent_shas: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            subject: SharedString::default(),
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+            commit_timestamp: 0,
+            ref_names: Vec::new(),
+        }
+    }
+
+    fn edges(edges: &[(usize, usize)]) -> Vec<GraphEdge> {
+        edges
+            .iter()
+            .map(|&(from, to)| GraphEdge { from, to })
+            .collect()
+    }
+
+    #[test]
+    fn test_commit_graph() {
+        let mut graph = CommitGraph::default();
+        for entry in [
+            entry("merge", &["b", "c"]),
+            entry("c", &["a"]),
+            entry("b", &["a"]),
+            entry("a", &[]),
+        ] {
+            graph.push(&entry);
+        }
+
+        assert_eq!(
+            graph.rows(),
+            [
+                GraphRow {
+                    lane: 0,
+                    upper_edges: Vec::new(),
+                    lower_edges: edges(&[(0, 0), (0, 1)]),
+                },
+                GraphRow {
+                    lane: 1,
+                    upper_edges: edges(&[(0, 0), (1, 1)]),
+                    lower_edges: edges(&[(0, 0), (1, 1)]),
+                },
+                GraphRow {
+                    lane: 0,
+                    upper_edges: edges(&[(0, 0), (1, 1)]),
+                    lower_edges: edges(&[(1, 1), (0, 1)]),
+                },
+                GraphRow {
+                    lane: 1,
+                    upper_edges: edges(&[(1, 1)]),
+                    lower_edges: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(graph.rows()[0].lane_count(), 2);
+        assert!(graph.lanes.is_empty());
+    }
+}
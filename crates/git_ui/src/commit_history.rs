@@ -0,0 +1,781 @@
+use crate::{
+    commit_diff::CommitDiff,
+    commit_graph::{CommitGraph, GraphRow},
+};
+use editor::{Editor, EditorEvent};
+use git::{
+    CherryPickCommit, RevertCommit, SHORT_SHA_LENGTH,
+    repository::{LogEntry, LogOptions, RebaseCommand, RepoPath, SequencerOperation},
+};
+use gpui::{
+    Action as _, App, Bounds, ClickEvent, Corner, DismissEvent, Entity, EventEmitter, FocusHandle,
+    Focusable, Hsla, KeyContext, ListSizingBehavior, MouseButton, MouseDownEvent, PathBuilder,
+    Pixels, Point, Subscription, Task, UniformListScrollHandle, WeakEntity, anchored, canvas,
+    deferred, fill, point, size, uniform_list,
+};
+use project::git_store::Repository;
+use std::{ops::Range, time::Duration};
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+use time_format::format_local_timestamp;
+use ui::{ContextMenu, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent},
+    notifications::DetachAndPromptErr,
+};
+
+const PAGE_SIZE: usize = 100;
+/// The number of rows left to scroll through before the next page is loaded.
+const LOAD_MORE_THRESHOLD: usize = 20;
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(300);
+const ROW_HEIGHT: Pixels = px(26.);
+const LANE_WIDTH: Pixels = px(14.);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::ViewHistory, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        CommitHistory::open(repository, None, None, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::FileHistory, window, cx| {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some((repository, path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+        else {
+            return;
+        };
+        let selection = editor.update(cx, |editor, cx| {
+            editor.selections.newest::<language::Point>(cx)
+        });
+        // When the selection isn't empty, only the commits changing the selected lines are listed.
+        let line_range = (!selection.is_empty()).then(|| {
+            let end_row = if selection.end.column == 0 && selection.end.row > selection.start.row {
+                selection.end.row
+            } else {
+                selection.end.row + 1
+            };
+            selection.start.row..end_row
+        });
+        CommitHistory::open(repository, Some(path), line_range, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::CherryPickContinue, window, cx| {
+        run_sequencer_command(
+            workspace,
+            SequencerOperation::CherryPick,
+            RebaseCommand::Continue,
+            window,
+            cx,
+        );
+    });
+    workspace.register_action(|workspace, _: &git::CherryPickSkip, window, cx| {
+        run_sequencer_command(
+            workspace,
+            SequencerOperation::CherryPick,
+            RebaseCommand::Skip,
+            window,
+            cx,
+        );
+    });
+    workspace.register_action(|workspace, _: &git::CherryPickAbort, window, cx| {
+        run_sequencer_command(
+            workspace,
+            SequencerOperation::CherryPick,
+            RebaseCommand::Abort,
+            window,
+            cx,
+        );
+    });
+    workspace.register_action(|workspace, _: &git::RevertContinue, window, cx| {
+        run_sequencer_command(
+            workspace,
+            SequencerOperation::Revert,
+            RebaseCommand::Continue,
+            window,
+            cx,
+        );
+    });
+    workspace.register_action(|workspace, _: &git::RevertSkip, window, cx| {
+        run_sequencer_command(
+            workspace,
+            SequencerOperation::Revert,
+            RebaseCommand::Skip,
+            window,
+            cx,
+        );
+    });
+    workspace.register_action(|workspace, _: &git::RevertAbort, window, cx| {
+        run_sequencer_command(
+            workspace,
+            SequencerOperation::Revert,
+            RebaseCommand::Abort,
+            window,
+            cx,
+        );
+    });
+}
+
+fn run_sequencer_command(
+    workspace: &mut Workspace,
+    operation: SequencerOperation,
+    command: RebaseCommand,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let error_message = match (operation, command) {
+        (SequencerOperation::CherryPick, RebaseCommand::Continue) => {
+            "Failed to continue cherry-pick"
+        }
+        (SequencerOperation::CherryPick, RebaseCommand::Abort) => "Failed to abort cherry-pick",
+        (SequencerOperation::Revert, RebaseCommand::Continue) => "Failed to continue revert",
+        (SequencerOperation::Revert, RebaseCommand::Abort) => "Failed to abort revert",
+        (_, RebaseCommand::Skip) => "Failed to skip commit",
+    };
+    let task = repository.update(cx, |repository, cx| {
+        repository.sequencer_command(operation, command, cx)
+    });
+    cx.spawn(async move |_, _| task.await?)
+        .detach_and_prompt_err(error_message, window, cx, |e, _, _| Some(e.to_string()));
+}
+
+/// A list of the commits of a repository, or of the ones changing a file, drawn next to the
+/// commit graph when the list isn't filtered.
+pub struct CommitHistory {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    path: Option<RepoPath>,
+    line_range: Option<Range<u32>>,
+    filter_editor: Entity<Editor>,
+    entries: Vec<LogEntry>,
+    /// The graph of the listed commits, which is only drawn when every commit is listed, as
+    /// the parents of the commits wouldn't be listed otherwise.
+    graph: Option<CommitGraph>,
+    has_more: bool,
+    error: Option<SharedString>,
+    selected_index: Option<usize>,
+    load_task: Option<Task<()>>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    _subscription: Subscription,
+}
+
+impl CommitHistory {
+    fn open(
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        line_range: Option<Range<u32>>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let workspace_handle = cx.weak_entity();
+        let history =
+            cx.new(|cx| Self::new(repository, path, line_range, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(history), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        line_range: Option<Range<u32>>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                "Filter by message, author:name, since:YYYY-MM-DD or until:YYYY-MM-DD",
+                cx,
+            );
+            editor
+        });
+        let subscription = cx.subscribe(&filter_editor, |this, _, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.reload(true, cx);
+            }
+        });
+
+        let mut this = Self {
+            repository,
+            workspace,
+            path,
+            line_range,
+            filter_editor,
+            entries: Vec::new(),
+            graph: None,
+            has_more: true,
+            error: None,
+            selected_index: None,
+            load_task: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            context_menu: None,
+            _subscription: subscription,
+        };
+        this.reload(false, cx);
+        this
+    }
+
+    fn title(&self) -> SharedString {
+        match &self.path {
+            Some(path) => format!("History of {}", path.display()).into(),
+            None => "History".into(),
+        }
+    }
+
+    /// Replaces the listed commits with the first page of the ones matching the filter.
+    fn reload(&mut self, debounce: bool, cx: &mut Context<Self>) {
+        self.load_task = None;
+        self.has_more = true;
+        self.load_page(0, debounce, cx);
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.load_task.is_none() && self.has_more && self.error.is_none() {
+            self.load_page(self.entries.len(), false, cx);
+        }
+    }
+
+    fn load_page(&mut self, skip: usize, debounce: bool, cx: &mut Context<Self>) {
+        let mut options = parse_filter(
+            &self.filter_editor.read(cx).text(cx),
+            UtcOffset::from_whole_seconds(chrono::Local::now().offset().local_minus_utc())
+                .unwrap_or(UtcOffset::UTC),
+        );
+        let show_graph = options == LogOptions::default();
+        options.skip = skip;
+        options.limit = PAGE_SIZE;
+        options.path = self.path.clone();
+        options.line_range = self.line_range.clone();
+
+        let repository = self.repository.clone();
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor().timer(FILTER_DEBOUNCE).await;
+            }
+            let Some(load) = repository
+                .update(cx, |repository, _| repository.log(options))
+                .log_err()
+            else {
+                return;
+            };
+            let entries = load.await;
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                if skip == 0 {
+                    this.entries.clear();
+                    this.graph = (show_graph && this.path.is_none()).then(CommitGraph::default);
+                    this.selected_index = None;
+                    this.error = None;
+                }
+                match entries {
+                    Ok(Ok(entries)) => {
+                        this.has_more = entries.len() == PAGE_SIZE;
+                        if let Some(graph) = this.graph.as_mut() {
+                            for entry in &entries {
+                                graph.push(entry);
+                            }
+                        }
+                        this.entries.extend(entries);
+                    }
+                    Ok(Err(error)) => this.error = Some(error.to_string().into()),
+                    Err(_) => this.error = Some("Failed to load the commits".into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        }));
+    }
+
+    fn select_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_index = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, gpui::ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self
+            .selected_index
+            .map_or(0, |ix| (ix + 1).min(self.entries.len().saturating_sub(1)));
+        if ix < self.entries.len() {
+            self.select_entry(ix, cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ix = self.selected_index.map_or(0, |ix| ix.saturating_sub(1));
+        if ix < self.entries.len() {
+            self.select_entry(ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_index {
+            self.open_commit(ix, window, cx);
+        }
+    }
+
+    fn open_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix).cloned() else {
+            return;
+        };
+        let repository = self.repository.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                CommitDiff::open_commit(entry, repository, workspace, window, cx);
+            })
+            .ok();
+    }
+
+    fn cherry_pick(&mut self, _: &CherryPickCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.apply_selected_commit(SequencerOperation::CherryPick, window, cx);
+    }
+
+    fn revert(&mut self, _: &RevertCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.apply_selected_commit(SequencerOperation::Revert, window, cx);
+    }
+
+    /// Cherry-picks or reverts the selected commit, which stops to let conflicts be resolved
+    /// from the git panel.
+    fn apply_selected_commit(
+        &mut self,
+        operation: SequencerOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.selected_index.and_then(|ix| self.entries.get(ix)) else {
+            return;
+        };
+        let commits = vec![entry.sha.to_string()];
+        let (task, error_message) = match operation {
+            SequencerOperation::CherryPick => (
+                self.repository
+                    .update(cx, |repository, cx| repository.cherry_pick(commits, cx)),
+                "Failed to cherry-pick commit",
+            ),
+            SequencerOperation::Revert => (
+                self.repository
+                    .update(cx, |repository, cx| repository.revert(commits, cx)),
+                "Failed to revert commit",
+            ),
+        };
+        cx.spawn(async move |this, cx| {
+            let result = task.await?;
+            this.update(cx, |this, cx| this.reload(false, cx))?;
+            result
+        })
+        .detach_and_prompt_err(error_message, window, cx, |e, _, _| Some(e.to_string()));
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_entry(ix, cx);
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .action("Open Commit", menu::Confirm.boxed_clone())
+                .separator()
+                .action("Cherry-Pick Commit", CherryPickCommit.boxed_clone())
+                .action("Revert Commit", RevertCommit.boxed_clone())
+        });
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("CommitHistory");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        lane_count: usize,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let entry = &self.entries[ix];
+        let is_selected = self.selected_index == Some(ix);
+        let commit_time = OffsetDateTime::from_unix_timestamp(entry.commit_timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let formatted_time = format_local_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+        let short_sha = entry.sha.chars().take(SHORT_SHA_LENGTH).collect::<String>();
+        let graph_row = self
+            .graph
+            .as_ref()
+            .and_then(|graph| graph.rows().get(ix))
+            .cloned();
+
+        h_flex()
+            .id(ElementId::Integer(ix))
+            .h(ROW_HEIGHT)
+            .w_full()
+            .px_2()
+            .gap_2()
+            .cursor_pointer()
+            .border_1()
+            .border_color(gpui::transparent_black())
+            .when(is_selected, |this| {
+                this.bg(cx.theme().colors().ghost_element_selected)
+            })
+            .when(
+                is_selected && self.focus_handle.contains_focused(window, cx),
+                |this| this.border_color(Color::Selected.color(cx)),
+            )
+            .hover(|style| {
+                if is_selected {
+                    style
+                } else {
+                    style.bg(cx.theme().colors().ghost_element_hover)
+                }
+            })
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                if event.down.button == MouseButton::Right {
+                    return;
+                }
+                this.select_entry(ix, cx);
+                if event.down.click_count > 1 {
+                    this.open_commit(ix, window, cx);
+                }
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_context_menu(event.position, ix, window, cx);
+                    cx.stop_propagation();
+                }),
+            )
+            .when_some(graph_row, |this, row| {
+                this.child(render_graph_row(row, lane_count, cx))
+            })
+            .children(entry.ref_names.iter().map(|ref_name| {
+                div()
+                    .px_1()
+                    .rounded_sm()
+                    .bg(cx.theme().colors().element_background)
+                    .child(
+                        Label::new(ref_name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Accent)
+                            .single_line(),
+                    )
+            }))
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(Label::new(entry.subject.clone()).single_line()),
+            )
+            .child(
+                div()
+                    .id("author")
+                    .child(
+                        Label::new(entry.author_name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                    .tooltip(Tooltip::text(entry.author_email.clone())),
+            )
+            .child(
+                Label::new(formatted_time)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .child(
+                Label::new(short_sha)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+    }
+}
+
+fn render_graph_row(row: GraphRow, lane_count: usize, cx: &App) -> impl IntoElement {
+    let lane_colors = (0..lane_count)
+        .map(|lane| {
+            cx.theme()
+                .players()
+                .color_for_participant(lane as u32)
+                .cursor
+        })
+        .collect::<Vec<Hsla>>();
+
+    canvas(
+        |_, _, _| {},
+        move |bounds, _, window, _| {
+            let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * (lane as f32 + 0.5);
+            let top = bounds.top();
+            let middle = bounds.center().y;
+            let bottom = bounds.bottom();
+
+            // Lines are colored after the lane they belong to, rather than the commit's lane.
+            let upper = row
+                .upper_edges
+                .iter()
+                .map(|edge| (edge, top, middle, edge.from));
+            let lower = row
+                .lower_edges
+                .iter()
+                .map(|edge| (edge, middle, bottom, edge.to));
+            for (edge, start_y, end_y, color_lane) in upper.chain(lower) {
+                let start = point(lane_x(edge.from), start_y);
+                let end = point(lane_x(edge.to), end_y);
+                let mut path = PathBuilder::stroke(px(1.5));
+                path.move_to(start);
+                if edge.from == edge.to {
+                    path.line_to(end);
+                } else {
+                    let control_y = (start_y + end_y) / 2.;
+                    path.cubic_bezier_to(
+                        end,
+                        Point::new(start.x, control_y),
+                        Point::new(end.x, control_y),
+                    );
+                }
+                if let Some(path) = path.build().log_err() {
+                    window.paint_path(path, lane_colors[color_lane]);
+                }
+            }
+
+            let radius = px(4.);
+            window.paint_quad(
+                fill(
+                    Bounds::centered_at(
+                        point(lane_x(row.lane), middle),
+                        size(radius * 2., radius * 2.),
+                    ),
+                    lane_colors[row.lane],
+                )
+                .corner_radii(radius),
+            );
+        },
+    )
+    .w(LANE_WIDTH * lane_count as f32)
+    .h_full()
+    .flex_none()
+}
+
+/// Parses a filter query, in which the words prefixed by `author:`, `since:` and `until:`
+/// filter the commits by author and date, and the other words match the commit messages.
+fn parse_filter(query: &str, offset: UtcOffset) -> LogOptions {
+    let timestamp = |date: Date| date.midnight().assume_offset(offset).unix_timestamp();
+    let mut options = LogOptions::default();
+    let mut message = Vec::new();
+    for word in query.split_whitespace() {
+        if let Some(author) = word.strip_prefix("author:") {
+            options.author = Some(author.to_string());
+        } else if let Some(date) = word.strip_prefix("since:").and_then(parse_date) {
+            options.since = Some(timestamp(date));
+        } else if let Some(date) = word.strip_prefix("until:").and_then(parse_date) {
+            // The whole day is included.
+            options.until = date.next_day().map(timestamp);
+        } else {
+            message.push(word);
+        }
+    }
+    if !message.is_empty() {
+        options.message = Some(message.join(" "));
+    }
+    options
+}
+
+fn parse_date(text: &str) -> Option<Date> {
+    let mut parts = text.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let day = parts.next()?.parse().ok()?;
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+impl EventEmitter<ItemEvent> for CommitHistory {}
+
+impl Focusable for CommitHistory {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CommitHistory {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _window: &Window, _cx: &App) -> Option<SharedString> {
+        Some(self.title())
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Commit History Opened")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for CommitHistory {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Icon::new(IconName::MagnifyingGlass)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1().child(self.filter_editor.clone()));
+
+        let contents = if self.entries.is_empty() {
+            let message: SharedString = if let Some(error) = self.error.clone() {
+                error
+            } else if self.load_task.is_some() {
+                "Loading commits…".into()
+            } else {
+                "No commits".into()
+            };
+            v_flex()
+                .flex_1()
+                .size_full()
+                .justify_center()
+                .child(h_flex().justify_center().child(Label::new(message)))
+                .into_any_element()
+        } else {
+            let lane_count = self.graph.as_ref().map_or(0, |graph| {
+                graph
+                    .rows()
+                    .iter()
+                    .map(GraphRow::lane_count)
+                    .max()
+                    .unwrap_or_default()
+            });
+            uniform_list(
+                cx.entity().clone(),
+                "commit-history-entries",
+                self.entries.len(),
+                move |this, range, window, cx| {
+                    if range.end + LOAD_MORE_THRESHOLD >= this.entries.len() {
+                        this.load_more(cx);
+                    }
+                    range
+                        .map(|ix| {
+                            this.render_entry(ix, lane_count, window, cx)
+                                .into_any_element()
+                        })
+                        .collect()
+                },
+            )
+            .with_sizing_behavior(ListSizingBehavior::Infer)
+            .track_scroll(self.scroll_handle.clone())
+            .size_full()
+            .into_any_element()
+        };
+
+        v_flex()
+            .id("commit-history")
+            .size_full()
+            .overflow_hidden()
+            .bg(cx.theme().colors().editor_background)
+            .key_context(self.dispatch_context())
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cherry_pick))
+            .on_action(cx.listener(Self::revert))
+            .track_focus(&self.focus_handle)
+            .child(header)
+            .child(contents)
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(parse_filter("", UtcOffset::UTC), LogOptions::default());
+        assert_eq!(
+            parse_filter(
+                "fix  crash author:alice since:2024-01-31 until:2024-02-01",
+                UtcOffset::UTC
+            ),
+            LogOptions {
+                author: Some("alice".into()),
+                message: Some("fix crash".into()),
+                since: Some(1706659200),
+                until: Some(1706832000),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            parse_filter("since:2024-13-01", UtcOffset::UTC).message,
+            Some("since:2024-13-01".into())
+        );
+    }
+}
@@ -153,6 +153,12 @@ impl CommitModal {
 
         let commit_message = commit_editor.read(cx).text(cx);
 
+        if commit_message.is_empty() {
+            git_panel.update(cx, |git_panel, cx| {
+                git_panel.load_commit_message_template(window, cx);
+            });
+        }
+
         if let Some(suggested_commit_message) = suggested_commit_message {
             if commit_message.is_empty() {
                 commit_editor.update(cx, |editor, cx| {
@@ -215,22 +221,31 @@ impl CommitModal {
     }
 
     pub fn render_footer(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let (can_commit, tooltip, commit_label, co_authors, generate_commit_message, active_repo) =
-            self.git_panel.update(cx, |git_panel, cx| {
-                let (can_commit, tooltip) = git_panel.configure_commit_button(cx);
-                let title = git_panel.commit_button_title();
-                let co_authors = git_panel.render_co_authors(cx);
-                let generate_commit_message = git_panel.render_generate_commit_message_button(cx);
-                let active_repo = git_panel.active_repository.clone();
-                (
-                    can_commit,
-                    tooltip,
-                    title,
-                    co_authors,
-                    generate_commit_message,
-                    active_repo,
-                )
-            });
+        let (
+            can_commit,
+            tooltip,
+            commit_label,
+            co_authors,
+            signoff,
+            generate_commit_message,
+            active_repo,
+        ) = self.git_panel.update(cx, |git_panel, cx| {
+            let (can_commit, tooltip) = git_panel.configure_commit_button(cx);
+            let title = git_panel.commit_button_title();
+            let co_authors = git_panel.render_co_authors(cx);
+            let signoff = git_panel.render_signoff(cx);
+            let generate_commit_message = git_panel.render_generate_commit_message_button(cx);
+            let active_repo = git_panel.active_repository.clone();
+            (
+                can_commit,
+                tooltip,
+                title,
+                co_authors,
+                signoff,
+                generate_commit_message,
+                active_repo,
+            )
+        });
 
         let branch = active_repo
             .as_ref()
@@ -313,6 +328,7 @@ impl CommitModal {
                             .child(branch_picker),
                     )
                     .children(generate_commit_message)
+                    .child(signoff)
                     .children(co_authors),
             )
             .child(div().flex_1())
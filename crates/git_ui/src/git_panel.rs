@@ -1,4 +1,5 @@
 use crate::askpass_modal::AskPassModal;
+use crate::commit_diff::CommitDiff;
 use crate::commit_modal::CommitModal;
 use crate::git_panel_settings::StatusStyle;
 use crate::project_diff::Diff;
@@ -21,12 +22,19 @@ use editor::{
 };
 use futures::StreamExt as _;
 use git::repository::{
-    Branch, CommitDetails, CommitSummary, DiffType, PushOptions, Remote, RemoteCommandOutput,
-    ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, PushOptions, RebaseStatus,
+    Remote, RemoteCommandOutput, ResetMode, SequencerOperation, Submodule, SubmoduleStatus,
+    Upstream, UpstreamTracking, UpstreamTrackingStatus,
 };
 use git::status::StageStatus;
+use git::{
+    AddRemote, CherryPickAbort, CherryPickContinue, CherryPickSkip, CompareBranches, CreateTag,
+    ExpandCommitEditor, InitSubmodules, InteractiveRebase, RebaseAbort, RebaseContinue, RebaseSkip,
+    RestoreTrackedFiles, RevertAbort, RevertContinue, RevertSkip, StageAll, StashAll, StashPop,
+    StashStaged, SyncSubmodules, TrashUntrackedFiles, UnstageAll, UpdateSubmodules, ViewHistory,
+    ViewStash, ViewWorktrees,
+};
 use git::{Commit, ToggleStaged, repository::RepoPath, status::FileStatus};
-use git::{ExpandCommitEditor, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll};
 use gpui::{
     Action, Animation, AnimationExt as _, Axis, ClickEvent, Corner, DismissEvent, Entity,
     EventEmitter, FocusHandle, Focusable, KeyContext, ListHorizontalSizingBehavior,
@@ -81,6 +89,7 @@ actions!(
         FocusEditor,
         FocusChanges,
         ToggleFillCoAuthors,
+        ToggleSignoff,
         GenerateCommitMessage
     ]
 );
@@ -116,7 +125,21 @@ fn git_panel_context_menu(
             .action("Stage All", StageAll.boxed_clone())
             .action("Unstage All", UnstageAll.boxed_clone())
             .separator()
+            .action("Stash All", StashAll.boxed_clone())
+            .action("Stash Staged", StashStaged.boxed_clone())
+            .action("Pop Stash", StashPop.boxed_clone())
+            .action("View Stash…", ViewStash.boxed_clone())
+            .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
+            .action("View History", ViewHistory.boxed_clone())
+            .action("Compare Branches…", CompareBranches.boxed_clone())
+            .action("Worktrees…", ViewWorktrees.boxed_clone())
+            .action("Interactive Rebase…", InteractiveRebase.boxed_clone())
+            .action("Create Tag…", CreateTag.boxed_clone())
+            .action("Add Remote…", AddRemote.boxed_clone())
+            .separator()
+            .action("Update Submodules", UpdateSubmodules.boxed_clone())
+            .action("Sync Submodules", SyncSubmodules.boxed_clone())
             .separator()
             .action("Discard Tracked Changes", RestoreTrackedFiles.boxed_clone())
             .action("Trash Untracked Files", TrashUntrackedFiles.boxed_clone())
@@ -294,8 +317,14 @@ pub struct GitPanel {
     pub(crate) commit_editor: Entity<Editor>,
     conflicted_count: usize,
     conflicted_staged_count: usize,
+    /// The progress of the rebase stopped in the active repository, if any.
+    rebase_status: Option<RebaseStatus>,
+    /// The cherry-pick or revert stopped in the active repository, if any.
+    sequencer_operation: Option<SequencerOperation>,
+    submodules: Vec<Submodule>,
     current_modifiers: Modifiers,
     add_coauthors: bool,
+    signoff: bool,
     generate_commit_message_task: Option<Task<Option<()>>>,
     entries: Vec<GitListEntry>,
     single_staged_entry: Option<GitStatusEntry>,
@@ -453,9 +482,13 @@ impl GitPanel {
             active_repository,
             commit_editor,
             conflicted_count: 0,
+            rebase_status: None,
+            sequencer_operation: None,
+            submodules: Vec::new(),
             conflicted_staged_count: 0,
             current_modifiers: window.modifiers(),
             add_coauthors: true,
+            signoff: false,
             generate_commit_message_task: None,
             entries: Vec::new(),
             focus_handle: cx.focus_handle(),
@@ -859,6 +892,15 @@ impl GitPanel {
             let workspace = self.workspace.upgrade()?;
             let git_repo = self.active_repository.as_ref()?;
 
+            if self.submodule_for_entry(entry).is_some() {
+                let repo_path = entry.repo_path.clone();
+                let repository = git_repo.clone();
+                workspace.update(cx, |workspace, cx| {
+                    CommitDiff::open_submodule(repo_path, repository, workspace, window, cx);
+                });
+                return Some(());
+            }
+
             if let Some(project_diff) = workspace.read(cx).active_item_as::<ProjectDiff>(cx) {
                 if let Some(project_path) = project_diff.read(cx).active_path(cx) {
                     if Some(&entry.repo_path)
@@ -897,7 +939,7 @@ impl GitPanel {
             let path = active_repo
                 .read(cx)
                 .repo_path_to_project_path(&entry.repo_path, cx)?;
-            if entry.status.is_deleted() {
+            if entry.status.is_deleted() || self.submodule_for_entry(entry).is_some() {
                 return None;
             }
 
@@ -1454,10 +1496,16 @@ impl GitPanel {
             self.fill_co_authors(&mut message, cx);
         }
 
+        let options = CommitOptions {
+            signoff: self.signoff,
+        };
+        let askpass = self.askpass_delegate("git commit", window, cx);
+
         let task = if self.has_staged_changes() {
             // Repository serializes all git operations, so we can just send a commit immediately
-            let commit_task =
-                active_repository.update(cx, |repo, cx| repo.commit(message.into(), None, cx));
+            let commit_task = active_repository.update(cx, |repo, cx| {
+                repo.commit(message.into(), None, options, askpass, cx)
+            });
             cx.background_spawn(async move { commit_task.await? })
         } else {
             let changed_files = self
@@ -1477,8 +1525,9 @@ impl GitPanel {
                 active_repository.update(cx, |repo, cx| repo.stage_entries(changed_files, cx));
             cx.spawn(async move |_, cx| {
                 stage_task.await?;
-                let commit_task = active_repository
-                    .update(cx, |repo, cx| repo.commit(message.into(), None, cx))?;
+                let commit_task = active_repository.update(cx, |repo, cx| {
+                    repo.commit(message.into(), None, options, askpass, cx)
+                })?;
                 commit_task.await?
             })
         };
@@ -1760,6 +1809,57 @@ impl GitPanel {
             .detach_and_log_err(cx);
     }
 
+    pub(crate) fn update_submodules(
+        &mut self,
+        paths: Vec<RepoPath>,
+        init: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Submodules Updated", init);
+        let guard = self.start_remote_operation();
+        let askpass = self.askpass_delegate("git submodule update", window, cx);
+        let this = cx.weak_entity();
+        window
+            .spawn(cx, async move |cx| {
+                let update = repo.update(cx, |repo, cx| {
+                    repo.update_submodules(paths, init, askpass, cx)
+                })?;
+
+                let remote_message = update.await?;
+                drop(guard);
+                this.update(cx, |this, cx| {
+                    let action = RemoteAction::UpdateSubmodules;
+                    match remote_message {
+                        Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                        Err(e) => {
+                            log::error!("Error while updating submodules {:?}", e);
+                            this.show_error_toast(action.name(), e, cx)
+                        }
+                    }
+
+                    anyhow::Ok(())
+                })
+                .ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn sync_submodules(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let sync = repo.update(cx, |repo, cx| repo.sync_submodules(Vec::new(), cx));
+        cx.spawn(async move |_, _| sync.await?)
+            .detach_and_prompt_err("Failed to sync submodules", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+    }
+
     pub(crate) fn git_init(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let worktrees = self
             .project
@@ -1854,7 +1954,7 @@ impl GitPanel {
         };
         telemetry::event!("Git Pulled");
         let branch = branch.clone();
-        let remote = self.get_current_remote(window, cx);
+        let remote = self.get_current_remote(true, window, cx);
         cx.spawn_in(window, async move |this, cx| {
             let remote = match remote.await {
                 Ok(Some(remote)) => remote,
@@ -1924,7 +2024,7 @@ impl GitPanel {
                 _ => None,
             }
         };
-        let remote = self.get_current_remote(window, cx);
+        let remote = self.get_current_remote(true, window, cx);
 
         cx.spawn_in(window, async move |this, cx| {
             let remote = match remote.await {
@@ -1975,6 +2075,63 @@ impl GitPanel {
         .detach_and_log_err(cx);
     }
 
+    pub(crate) fn push_tag(
+        &mut self,
+        tag_name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let remote = self.get_current_remote(false, window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {} {}", remote.name, tag_name), window, cx)
+            })?;
+
+            let guard = this
+                .update(cx, |this, _| this.start_remote_operation())
+                .ok();
+
+            let push = repo.update(cx, |repo, cx| {
+                repo.push_tag(tag_name.clone(), remote.name.clone(), askpass_delegate, cx)
+            })?;
+
+            let remote_output = push.await?;
+            drop(guard);
+
+            let action = RemoteAction::PushTag(tag_name, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn askpass_delegate(
         &self,
         operation: impl Into<SharedString>,
@@ -2003,8 +2160,11 @@ impl GitPanel {
         !self.project.read(cx).is_via_collab()
     }
 
+    /// Returns the remote to push to or pull from, prompting for it when there are several. When
+    /// `for_current_branch` is set, the remote of the current branch's upstream is preferred.
     fn get_current_remote(
         &mut self,
+        for_current_branch: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl Future<Output = anyhow::Result<Option<Remote>>> + use<> {
@@ -2019,11 +2179,16 @@ impl GitPanel {
 
             let mut current_remotes: Vec<Remote> = repo
                 .update(&mut cx, |repo, _| {
-                    let Some(current_branch) = repo.current_branch() else {
-                        return Err(anyhow::anyhow!("No active branch"));
+                    let branch_name = if for_current_branch {
+                        let Some(current_branch) = repo.current_branch() else {
+                            return Err(anyhow::anyhow!("No active branch"));
+                        };
+                        Some(current_branch.name.to_string())
+                    } else {
+                        None
                     };
 
-                    Ok(repo.get_remotes(Some(current_branch.name.to_string())))
+                    Ok(repo.get_remotes(branch_name))
                 })??
                 .await??;
 
@@ -2115,52 +2280,68 @@ impl GitPanel {
         cx.notify();
     }
 
+    fn toggle_signoff(&mut self, _: &ToggleSignoff, _: &mut Window, cx: &mut Context<Self>) {
+        self.signoff = !self.signoff;
+        cx.notify();
+    }
+
     fn fill_co_authors(&mut self, message: &mut String, cx: &mut Context<Self>) {
-        const CO_AUTHOR_PREFIX: &str = "Co-authored-by: ";
-
-        let existing_text = message.to_ascii_lowercase();
-        let lowercase_co_author_prefix = CO_AUTHOR_PREFIX.to_lowercase();
-        let mut ends_with_co_authors = false;
-        let existing_co_authors = existing_text
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.starts_with(&lowercase_co_author_prefix) {
-                    ends_with_co_authors = true;
-                    Some(line)
-                } else {
-                    ends_with_co_authors = false;
-                    None
-                }
-            })
-            .collect::<HashSet<_>>();
+        const CO_AUTHOR_KEY: &str = "Co-authored-by";
+
+        let existing_co_authors = git::commit::parse_trailers(message)
+            .into_iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case(CO_AUTHOR_KEY))
+            .map(|(_, value)| value.to_ascii_lowercase())
+            .collect::<Vec<_>>();
 
         let new_co_authors = self
             .potential_co_authors(cx)
             .into_iter()
             .filter(|(_, email)| {
+                let email = email.to_ascii_lowercase();
                 !existing_co_authors
                     .iter()
                     .any(|existing| existing.contains(email.as_str()))
             })
+            .map(|(name, email)| format!("{name} <{email}>"))
             .collect::<Vec<_>>();
 
-        if new_co_authors.is_empty() {
-            return;
-        }
+        let trailers = new_co_authors
+            .iter()
+            .map(|co_author| (CO_AUTHOR_KEY, co_author.as_str()))
+            .collect::<Vec<_>>();
+        git::commit::append_trailers(message, &trailers);
+    }
 
-        if !ends_with_co_authors {
-            message.push('\n');
-        }
-        for (name, email) in new_co_authors {
-            message.push('\n');
-            message.push_str(CO_AUTHOR_PREFIX);
-            message.push_str(&name);
-            message.push_str(" <");
-            message.push_str(&email);
-            message.push('>');
+    /// Fills an empty commit message with the repository's commit template, as run through
+    /// its `prepare-commit-msg` hook.
+    pub(crate) fn load_commit_message_template(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_repository) = self.active_repository.as_ref() else {
+            return;
+        };
+        if !self.commit_editor.read(cx).text(cx).is_empty() {
+            return;
         }
-        message.push('\n');
+        let template = active_repository.update(cx, |repo, cx| repo.commit_message_template(cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(template) = template.await?? else {
+                return anyhow::Ok(());
+            };
+            this.update_in(cx, |this, window, cx| {
+                this.commit_editor.update(cx, |editor, cx| {
+                    if editor.text(cx).is_empty() {
+                        editor.set_text(template, window, cx);
+                        editor.move_to_beginning(&editor::actions::MoveToBeginning, window, cx);
+                    }
+                });
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
     }
 
     fn schedule_update(
@@ -2180,6 +2361,9 @@ impl GitPanel {
                             git_panel.clear_pending();
                         }
                         git_panel.update_visible_entries(cx);
+                        git_panel.update_rebase_status(cx);
+                        git_panel.update_sequencer_operation(cx);
+                        git_panel.update_submodule_list(cx);
                         git_panel.update_scrollbar_properties(window, cx);
                     })
                     .ok();
@@ -2187,6 +2371,66 @@ impl GitPanel {
         });
     }
 
+    fn update_rebase_status(&mut self, cx: &mut Context<Self>) {
+        let Some(active_repo) = self.active_repository.as_ref() else {
+            self.rebase_status = None;
+            return;
+        };
+        let rebase_status = active_repo.read(cx).rebase_status();
+        cx.spawn(async move |git_panel, cx| {
+            let rebase_status = rebase_status.await??;
+            git_panel.update(cx, |git_panel, cx| {
+                if git_panel.rebase_status != rebase_status {
+                    git_panel.rebase_status = rebase_status;
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn update_sequencer_operation(&mut self, cx: &mut Context<Self>) {
+        let Some(active_repo) = self.active_repository.as_ref() else {
+            self.sequencer_operation = None;
+            return;
+        };
+        let sequencer_status = active_repo.read(cx).sequencer_status();
+        cx.spawn(async move |git_panel, cx| {
+            let sequencer_operation = sequencer_status.await??;
+            git_panel.update(cx, |git_panel, cx| {
+                if git_panel.sequencer_operation != sequencer_operation {
+                    git_panel.sequencer_operation = sequencer_operation;
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn update_submodule_list(&mut self, cx: &mut Context<Self>) {
+        let Some(active_repo) = self.active_repository.as_ref() else {
+            self.submodules.clear();
+            return;
+        };
+        let submodules = active_repo.update(cx, |repo, cx| repo.submodules(cx));
+        cx.spawn(async move |git_panel, cx| {
+            let submodules = submodules.await??;
+            git_panel.update(cx, |git_panel, cx| {
+                if git_panel.submodules != submodules {
+                    git_panel.submodules = submodules;
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn submodule_for_entry(&self, entry: &GitStatusEntry) -> Option<&Submodule> {
+        self.submodules
+            .iter()
+            .find(|submodule| submodule.path == entry.repo_path)
+    }
+
     fn reopen_commit_buffer(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let Some(active_repo) = self.active_repository.as_ref() else {
             return;
@@ -2718,6 +2962,22 @@ impl GitPanel {
         }
     }
 
+    pub(crate) fn render_signoff(&self, cx: &Context<Self>) -> AnyElement {
+        IconButton::new("signoff", IconName::Check)
+            .shape(ui::IconButtonShape::Square)
+            .icon_color(Color::Disabled)
+            .selected_icon_color(Color::Selected)
+            .toggle_state(self.signoff)
+            .tooltip(move |window, cx| {
+                Tooltip::for_action("Add Signed-off-by", &ToggleSignoff, window, cx)
+            })
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.signoff = !this.signoff;
+                cx.notify();
+            }))
+            .into_any_element()
+    }
+
     pub fn configure_commit_button(&self, cx: &mut Context<Self>) -> (bool, &'static str) {
         if self.has_unstaged_conflicts() {
             (false, "You must resolve conflicts before committing")
@@ -2862,6 +3122,7 @@ impl GitPanel {
         let panel_editor_style = panel_editor_style(true, window, cx);
 
         let enable_coauthors = self.render_co_authors(cx);
+        let signoff = self.render_signoff(cx);
         let title = self.commit_button_title();
 
         let editor_focus_handle = self.commit_editor.focus_handle(cx);
@@ -2923,32 +3184,38 @@ impl GitPanel {
                                     .unwrap_or_else(|| div().into_any_element()),
                             )
                             .child(
-                                h_flex().gap_0p5().children(enable_coauthors).child(
-                                    panel_filled_button(title)
-                                        .tooltip(move |window, cx| {
-                                            if can_commit {
-                                                Tooltip::for_action_in(
-                                                    tooltip,
-                                                    &Commit,
-                                                    &commit_tooltip_focus_handle,
-                                                    window,
-                                                    cx,
-                                                )
-                                            } else {
-                                                Tooltip::simple(tooltip, cx)
-                                            }
-                                        })
-                                        .disabled(!can_commit || self.modal_open)
-                                        .on_click({
-                                            cx.listener(move |this, _: &ClickEvent, window, cx| {
-                                                telemetry::event!(
-                                                    "Git Committed",
-                                                    source = "Git Panel"
-                                                );
-                                                this.commit_changes(window, cx)
+                                h_flex()
+                                    .gap_0p5()
+                                    .child(signoff)
+                                    .children(enable_coauthors)
+                                    .child(
+                                        panel_filled_button(title)
+                                            .tooltip(move |window, cx| {
+                                                if can_commit {
+                                                    Tooltip::for_action_in(
+                                                        tooltip,
+                                                        &Commit,
+                                                        &commit_tooltip_focus_handle,
+                                                        window,
+                                                        cx,
+                                                    )
+                                                } else {
+                                                    Tooltip::simple(tooltip, cx)
+                                                }
                                             })
-                                        }),
-                                ),
+                                            .disabled(!can_commit || self.modal_open)
+                                            .on_click({
+                                                cx.listener(
+                                                    move |this, _: &ClickEvent, window, cx| {
+                                                        telemetry::event!(
+                                                            "Git Committed",
+                                                            source = "Git Panel"
+                                                        );
+                                                        this.commit_changes(window, cx)
+                                                    },
+                                                )
+                                            }),
+                                    ),
                             ),
                     )
                     .child(
@@ -2997,6 +3264,195 @@ impl GitPanel {
         Some(footer)
     }
 
+    fn render_rebase_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let status = self.rebase_status.as_ref()?;
+        let title = match &status.branch {
+            Some(branch) => format!("Rebasing {branch} ({}/{})", status.step, status.total_steps),
+            None => format!("Rebasing ({}/{})", status.step, status.total_steps),
+        };
+        let has_unresolved_conflicts = self.conflicted_count > self.conflicted_staged_count;
+
+        Some(
+            h_flex()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().status().warning_background)
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(title).size(LabelSize::Small).single_line()),
+                )
+                .child(
+                    panel_button("Abort")
+                        .tooltip(Tooltip::for_action_title_in(
+                            "git rebase --abort",
+                            &RebaseAbort,
+                            &self.focus_handle,
+                        ))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(RebaseAbort.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    panel_button("Skip")
+                        .tooltip(Tooltip::for_action_title_in(
+                            "git rebase --skip",
+                            &RebaseSkip,
+                            &self.focus_handle,
+                        ))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(RebaseSkip.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    panel_filled_button("Continue")
+                        .tooltip(Tooltip::for_action_title_in(
+                            if has_unresolved_conflicts {
+                                "Resolve and stage the conflicts to continue"
+                            } else {
+                                "git rebase --continue"
+                            },
+                            &RebaseContinue,
+                            &self.focus_handle,
+                        ))
+                        .disabled(has_unresolved_conflicts)
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(RebaseContinue.boxed_clone(), cx)
+                        }),
+                ),
+        )
+    }
+
+    fn render_submodules_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let uninitialized_count = self
+            .submodules
+            .iter()
+            .filter(|submodule| submodule.status == SubmoduleStatus::Uninitialized)
+            .count();
+        if uninitialized_count == 0 {
+            return None;
+        }
+        let title = if uninitialized_count == 1 {
+            "1 submodule not initialized".to_string()
+        } else {
+            format!("{uninitialized_count} submodules not initialized")
+        };
+
+        Some(
+            h_flex()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(title).size(LabelSize::Small).single_line()),
+                )
+                .child(
+                    panel_filled_button("Initialize")
+                        .tooltip(Tooltip::for_action_title_in(
+                            "git submodule update --init --recursive",
+                            &InitSubmodules,
+                            &self.focus_handle,
+                        ))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(InitSubmodules.boxed_clone(), cx)
+                        }),
+                ),
+        )
+    }
+
+    fn render_sequencer_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let (title, commands, continue_action, skip_action, abort_action) =
+            match self.sequencer_operation? {
+                SequencerOperation::CherryPick => (
+                    "Cherry-picking",
+                    [
+                        "git cherry-pick --continue",
+                        "git cherry-pick --skip",
+                        "git cherry-pick --abort",
+                    ],
+                    CherryPickContinue.boxed_clone(),
+                    CherryPickSkip.boxed_clone(),
+                    CherryPickAbort.boxed_clone(),
+                ),
+                SequencerOperation::Revert => (
+                    "Reverting",
+                    [
+                        "git revert --continue",
+                        "git revert --skip",
+                        "git revert --abort",
+                    ],
+                    RevertContinue.boxed_clone(),
+                    RevertSkip.boxed_clone(),
+                    RevertAbort.boxed_clone(),
+                ),
+            };
+        let [continue_command, skip_command, abort_command] = commands;
+        let has_unresolved_conflicts = self.conflicted_count > self.conflicted_staged_count;
+
+        Some(
+            h_flex()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().status().warning_background)
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(title).size(LabelSize::Small).single_line()),
+                )
+                .child(
+                    panel_button("Abort")
+                        .tooltip(Tooltip::for_action_title_in(
+                            abort_command,
+                            &*abort_action,
+                            &self.focus_handle,
+                        ))
+                        .on_click(move |_, window, cx| {
+                            window.dispatch_action(abort_action.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    panel_button("Skip")
+                        .tooltip(Tooltip::for_action_title_in(
+                            skip_command,
+                            &*skip_action,
+                            &self.focus_handle,
+                        ))
+                        .on_click(move |_, window, cx| {
+                            window.dispatch_action(skip_action.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    panel_filled_button("Continue")
+                        .tooltip(Tooltip::for_action_title_in(
+                            if has_unresolved_conflicts {
+                                "Resolve and stage the conflicts to continue"
+                            } else {
+                                continue_command
+                            },
+                            &*continue_action,
+                            &self.focus_handle,
+                        ))
+                        .disabled(has_unresolved_conflicts)
+                        .on_click(move |_, window, cx| {
+                            window.dispatch_action(continue_action.boxed_clone(), cx)
+                        }),
+                ),
+        )
+    }
+
     fn render_previous_commit(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
         let active_repository = self.active_repository.as_ref()?;
         let branch = active_repository.read(cx).current_branch()?;
@@ -3467,14 +3923,27 @@ impl GitPanel {
         } else {
             "Restore File"
         };
+        let submodule_path = self
+            .submodule_for_entry(entry)
+            .map(|submodule| submodule.path.clone());
+        let this = cx.weak_entity();
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
-            context_menu
+            let context_menu = context_menu
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
                 .separator()
-                .action("Open Diff", Confirm.boxed_clone())
-                .action("Open File", SecondaryConfirm.boxed_clone())
+                .action("Open Diff", Confirm.boxed_clone());
+            if let Some(submodule_path) = submodule_path {
+                context_menu.entry("Update Submodule", None, move |window, cx| {
+                    this.update(cx, |this, cx| {
+                        this.update_submodules(vec![submodule_path.clone()], true, window, cx);
+                    })
+                    .ok();
+                })
+            } else {
+                context_menu.action("Open File", SecondaryConfirm.boxed_clone())
+            }
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -3534,6 +4003,7 @@ impl GitPanel {
         let has_conflict = status.is_conflicted();
         let is_modified = status.is_modified();
         let is_deleted = status.is_deleted();
+        let is_submodule = self.submodule_for_entry(entry).is_some();
 
         let label_color = if status_style == StatusStyle::LabelColor {
             if has_conflict {
@@ -3733,7 +4203,16 @@ impl GitPanel {
                     .child(
                         self.entry_label(display_name.clone(), label_color)
                             .when(status.is_deleted(), |this| this.strikethrough()),
-                    ),
+                    )
+                    .when(is_submodule, |this| {
+                        this.child(
+                            div().pl_1().child(
+                                Icon::new(IconName::Folder)
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Muted),
+                            ),
+                        )
+                    }),
             )
             .into_any_element()
     }
@@ -3799,6 +4278,9 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::focus_changes_list))
             .on_action(cx.listener(Self::focus_editor))
             .on_action(cx.listener(Self::expand_commit_editor))
+            .when(has_write_access, |git_panel| {
+                git_panel.on_action(cx.listener(Self::toggle_signoff))
+            })
             .when(has_write_access && has_co_authors, |git_panel| {
                 git_panel.on_action(cx.listener(Self::toggle_fill_co_authors))
             })
@@ -3818,6 +4300,9 @@ impl Render for GitPanel {
                 v_flex()
                     .size_full()
                     .children(self.render_panel_header(window, cx))
+                    .children(self.render_rebase_banner(cx))
+                    .children(self.render_sequencer_banner(cx))
+                    .children(self.render_submodules_banner(cx))
                     .map(|this| {
                         if has_entries {
                             this.child(self.render_entries(has_write_access, window, cx))
@@ -3958,6 +4443,7 @@ impl GitPanelMessageTooltip {
                         message: details.message.clone(),
                         ..Default::default()
                     }),
+                    signature: details.signature.clone(),
                 };
 
                 this.update_in(cx, |this: &mut GitPanelMessageTooltip, window, cx| {
@@ -16,14 +16,22 @@ use workspace::Workspace;
 
 mod askpass_modal;
 pub mod branch_picker;
+mod commit_diff;
+mod commit_graph;
+pub mod commit_history;
 mod commit_modal;
 pub mod git_panel;
 mod git_panel_settings;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+pub mod rebase_editor;
 pub(crate) mod remote_output;
+pub mod remote_picker;
 pub mod repository_selector;
+pub mod stash_picker;
+pub mod tag_picker;
+pub mod worktree_picker;
 
 actions!(git, [ResetOnboarding]);
 
@@ -36,6 +44,12 @@ pub fn init(cx: &mut App) {
         git_panel::register(workspace);
         repository_selector::register(workspace);
         branch_picker::register(workspace);
+        stash_picker::register(workspace);
+        commit_history::register(workspace);
+        rebase_editor::register(workspace);
+        worktree_picker::register(workspace);
+        tag_picker::register(workspace);
+        remote_picker::register(workspace);
 
         let project = workspace.project().read(cx);
         if project.is_read_only(cx) {
@@ -74,7 +88,31 @@ pub fn init(cx: &mut App) {
                     panel.pull(window, cx);
                 });
             });
+            workspace.register_action(|workspace, _: &git::InitSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.update_submodules(Vec::new(), true, window, cx);
+                });
+            });
+            workspace.register_action(|workspace, _: &git::UpdateSubmodules, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.update_submodules(Vec::new(), false, window, cx);
+                });
+            });
         }
+        workspace.register_action(|workspace, _: &git::SyncSubmodules, window, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.sync_submodules(window, cx);
+            });
+        });
         workspace.register_action(|workspace, action: &git::StageAll, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
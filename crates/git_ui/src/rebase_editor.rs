@@ -0,0 +1,527 @@
+use editor::Editor;
+use git::{
+    SHORT_SHA_LENGTH,
+    repository::{LogEntry, RebaseAction, RebaseCommand, RebaseStep},
+};
+use gpui::{App, Entity, EventEmitter, FocusHandle, Focusable, Point, Task};
+use project::git_store::Repository;
+use ui::{ContextMenu, DropdownMenu, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent},
+};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::InteractiveRebase, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        RebaseEditor::open(repository, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RebaseContinue, window, cx| {
+        run_rebase_command(workspace, RebaseCommand::Continue, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RebaseSkip, window, cx| {
+        run_rebase_command(workspace, RebaseCommand::Skip, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RebaseAbort, window, cx| {
+        run_rebase_command(workspace, RebaseCommand::Abort, window, cx);
+    });
+}
+
+fn run_rebase_command(
+    workspace: &mut Workspace,
+    command: RebaseCommand,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let error_message = match command {
+        RebaseCommand::Continue => "Failed to continue rebase",
+        RebaseCommand::Skip => "Failed to skip commit",
+        RebaseCommand::Abort => "Failed to abort rebase",
+    };
+    let task = repository.update(cx, |repository, cx| repository.rebase_command(command, cx));
+    cx.spawn(async move |_, _| task.await?)
+        .detach_and_prompt_err(error_message, window, cx, |e, _, _| Some(e.to_string()));
+}
+
+struct RebaseEntry {
+    commit: LogEntry,
+    action: RebaseAction,
+    /// The editor of the new message, while the commit is reworded.
+    message_editor: Option<Entity<Editor>>,
+}
+
+#[derive(Clone)]
+struct DraggedRebaseEntry {
+    ix: usize,
+    subject: SharedString,
+    click_offset: Point<Pixels>,
+}
+
+impl Render for DraggedRebaseEntry {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .pl(self.click_offset.x + px(12.))
+            .pt(self.click_offset.y + px(12.))
+            .child(
+                div()
+                    .py_1()
+                    .px_2()
+                    .rounded_lg()
+                    .bg(cx.theme().colors().background)
+                    .child(Label::new(self.subject.clone())),
+            )
+    }
+}
+
+/// A plan of the commits to replay onto an upstream, which can be reordered, squashed, reworded
+/// or dropped before running the rebase.
+pub struct RebaseEditor {
+    repository: Entity<Repository>,
+    upstream_editor: Entity<Editor>,
+    /// The upstream the listed commits were loaded for.
+    upstream: Option<String>,
+    entries: Vec<RebaseEntry>,
+    error: Option<SharedString>,
+    load_task: Option<Task<()>>,
+    rebase_task: Option<Task<()>>,
+    focus_handle: FocusHandle,
+}
+
+impl RebaseEditor {
+    fn open(
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let editor = cx.new(|cx| Self::new(repository, window, cx));
+        workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+    }
+
+    fn new(repository: Entity<Repository>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let upstream = repository
+            .read(cx)
+            .current_branch()
+            .and_then(|branch| branch.upstream.as_ref())
+            .map(|upstream| {
+                upstream
+                    .ref_name
+                    .strip_prefix("refs/remotes/")
+                    .unwrap_or(&upstream.ref_name)
+                    .to_string()
+            });
+        let upstream_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Upstream, e.g. origin/main or HEAD~5", cx);
+            if let Some(upstream) = &upstream {
+                editor.set_text(upstream.as_str(), window, cx);
+            }
+            editor
+        });
+
+        let mut this = Self {
+            repository,
+            upstream_editor,
+            upstream: None,
+            entries: Vec::new(),
+            error: None,
+            load_task: None,
+            rebase_task: None,
+            focus_handle: cx.focus_handle(),
+        };
+        if upstream.is_some() {
+            this.load(cx);
+        }
+        this
+    }
+
+    /// Lists the commits a rebase onto the upstream in the editor would apply, discarding the
+    /// current plan.
+    fn load(&mut self, cx: &mut Context<Self>) {
+        let upstream = self.upstream_editor.read(cx).text(cx).trim().to_string();
+        if upstream.is_empty() {
+            return;
+        }
+        let commits = self.repository.update(cx, |repository, _| {
+            repository.rebase_commits(upstream.clone())
+        });
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let commits = commits.await;
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                match commits {
+                    Ok(Ok(commits)) => {
+                        this.upstream = Some(upstream);
+                        this.error = None;
+                        this.entries = commits
+                            .into_iter()
+                            .map(|commit| RebaseEntry {
+                                commit,
+                                action: RebaseAction::Pick,
+                                message_editor: None,
+                            })
+                            .collect();
+                    }
+                    Ok(Err(error)) => {
+                        this.upstream = None;
+                        this.entries.clear();
+                        this.error = Some(error.to_string().into());
+                    }
+                    Err(_) => this.error = Some("Failed to load the commits".into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self
+            .upstream_editor
+            .focus_handle(cx)
+            .contains_focused(window, cx)
+        {
+            self.load(cx);
+        }
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.action = action;
+        if action != RebaseAction::Reword {
+            entry.message_editor = None;
+        } else if entry.message_editor.is_none() {
+            let commit = entry.commit.clone();
+            let message_editor = cx.new(|cx| {
+                let mut editor = Editor::auto_height(8, window, cx);
+                editor.set_text(commit.subject.clone(), window, cx);
+                editor
+            });
+            // The subject is replaced by the whole message once it's loaded.
+            let details = self
+                .repository
+                .update(cx, |repository, _| repository.show(commit.sha.to_string()));
+            let editor = message_editor.downgrade();
+            cx.spawn_in(window, async move |_, cx| {
+                let details = details.await??;
+                editor.update_in(cx, |editor, window, cx| {
+                    editor.set_text(details.message.trim_end(), window, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+            window.focus(&message_editor.focus_handle(cx));
+            self.entries[ix].message_editor = Some(message_editor);
+        }
+        cx.notify();
+    }
+
+    fn move_entry(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        if from == to || from >= self.entries.len() || to >= self.entries.len() {
+            return;
+        }
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        cx.notify();
+    }
+
+    fn steps(&self, cx: &App) -> Vec<RebaseStep> {
+        self.entries
+            .iter()
+            .map(|entry| RebaseStep {
+                sha: entry.commit.sha.clone(),
+                action: entry.action,
+                message: entry
+                    .message_editor
+                    .as_ref()
+                    .map(|editor| editor.read(cx).text(cx)),
+            })
+            .collect()
+    }
+
+    fn start_rebase(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(upstream) = self.upstream.clone() else {
+            return;
+        };
+        if self.rebase_task.is_some() {
+            return;
+        }
+        let steps = self.steps(cx);
+        let rebase = self
+            .repository
+            .update(cx, |repository, cx| repository.rebase(upstream, steps, cx));
+        self.rebase_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = rebase.await;
+            this.update_in(cx, |this, window, cx| {
+                this.rebase_task = None;
+                match result {
+                    // The rebased commits are listed again, to be rebased further if needed.
+                    Ok(Ok(())) => this.load(cx),
+                    Ok(Err(error)) => {
+                        window
+                            .prompt(
+                                gpui::PromptLevel::Warning,
+                                "Rebase stopped",
+                                Some(error.to_string().as_str()),
+                                &["Ok"],
+                                cx,
+                            )
+                            .detach();
+                        this.load(cx);
+                    }
+                    Err(_) => {}
+                }
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn render_action_menu(
+        &self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let this = cx.entity().downgrade();
+        DropdownMenu::new(
+            ("rebase-action", ix),
+            action.label(),
+            ContextMenu::build(window, cx, move |mut menu, _, _| {
+                for action in RebaseAction::ALL {
+                    let this = this.clone();
+                    menu = menu.entry(action.label(), None, move |window, cx| {
+                        this.update(cx, |this, cx| this.set_action(ix, action, window, cx))
+                            .ok();
+                    });
+                }
+                menu
+            }),
+        )
+    }
+
+    fn render_entry(&self, ix: usize, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let short_sha = entry
+            .commit
+            .sha
+            .chars()
+            .take(SHORT_SHA_LENGTH)
+            .collect::<String>();
+        let is_dropped = entry.action == RebaseAction::Drop;
+        let dragged = DraggedRebaseEntry {
+            ix,
+            subject: entry.commit.subject.clone(),
+            click_offset: Point::default(),
+        };
+
+        v_flex()
+            .id(("rebase-entry", ix))
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .on_drag(dragged, |dragged, click_offset, _, cx| {
+                cx.new(|_| DraggedRebaseEntry {
+                    click_offset,
+                    ..dragged.clone()
+                })
+            })
+            .drag_over::<DraggedRebaseEntry>(|style, _, _, cx| {
+                style.bg(cx.theme().colors().drop_target_background)
+            })
+            .on_drop(
+                cx.listener(move |this, dragged: &DraggedRebaseEntry, _, cx| {
+                    this.move_entry(dragged.ix, ix, cx);
+                }),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Icon::new(IconName::Menu)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(self.render_action_menu(ix, entry.action, window, cx))
+                    .child(
+                        Label::new(short_sha)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .child(
+                        div().flex_1().overflow_hidden().child(
+                            Label::new(entry.commit.subject.clone())
+                                .single_line()
+                                .when(is_dropped, |label| {
+                                    label.strikethrough().color(Color::Disabled)
+                                }),
+                        ),
+                    )
+                    .child(
+                        Label::new(entry.commit.author_name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                    .child(
+                        IconButton::new(("move-up", ix), IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .disabled(ix == 0)
+                            .tooltip(Tooltip::text("Move Up"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.move_entry(ix, ix.saturating_sub(1), cx);
+                            })),
+                    )
+                    .child(
+                        IconButton::new(("move-down", ix), IconName::ArrowDown)
+                            .icon_size(IconSize::Small)
+                            .disabled(ix + 1 == self.entries.len())
+                            .tooltip(Tooltip::text("Move Down"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.move_entry(ix, ix + 1, cx);
+                            })),
+                    ),
+            )
+            .when_some(entry.message_editor.clone(), |this, editor| {
+                this.child(
+                    div()
+                        .ml_6()
+                        .p_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .child(editor),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<ItemEvent> for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RebaseEditor {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _window: &Window, _cx: &App) -> Option<SharedString> {
+        Some("Interactive Rebase".into())
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Interactive Rebase Opened")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new("Onto").color(Color::Muted))
+            .child(div().flex_1().child(self.upstream_editor.clone()))
+            .child(
+                Button::new("load-commits", "Load Commits")
+                    .disabled(self.load_task.is_some())
+                    .on_click(cx.listener(|this, _, _, cx| this.load(cx))),
+            );
+
+        let contents = if self.entries.is_empty() {
+            let message: SharedString = if let Some(error) = self.error.clone() {
+                error
+            } else if self.load_task.is_some() {
+                "Loading commits…".into()
+            } else if self.upstream.is_some() {
+                "No commits to rebase".into()
+            } else {
+                "Enter the upstream to rebase onto".into()
+            };
+            v_flex()
+                .flex_1()
+                .size_full()
+                .justify_center()
+                .child(h_flex().justify_center().child(Label::new(message)))
+                .into_any_element()
+        } else {
+            v_flex()
+                .id("rebase-entries")
+                .flex_1()
+                .size_full()
+                .overflow_y_scroll()
+                .children((0..self.entries.len()).map(|ix| self.render_entry(ix, window, cx)))
+                .into_any_element()
+        };
+
+        let footer = h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_end()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(format!("{} commits", self.entries.len()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Button::new("start-rebase", "Start Rebase")
+                    .style(ButtonStyle::Filled)
+                    .disabled(
+                        self.entries.is_empty()
+                            || self.load_task.is_some()
+                            || self.rebase_task.is_some(),
+                    )
+                    .on_click(cx.listener(|this, _, window, cx| this.start_rebase(window, cx))),
+            );
+
+        v_flex()
+            .key_context("RebaseEditor")
+            .size_full()
+            .overflow_hidden()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::confirm))
+            .track_focus(&self.focus_handle)
+            .child(header)
+            .child(contents)
+            .child(footer)
+    }
+}
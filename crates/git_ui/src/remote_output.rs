@@ -9,6 +9,8 @@ pub enum RemoteAction {
     Fetch,
     Pull(Remote),
     Push(SharedString, Remote),
+    PushTag(SharedString, Remote),
+    UpdateSubmodules,
 }
 
 impl RemoteAction {
@@ -16,7 +18,8 @@ impl RemoteAction {
         match self {
             RemoteAction::Fetch => "fetch",
             RemoteAction::Pull(_) => "pull",
-            RemoteAction::Push(_, _) => "push",
+            RemoteAction::Push(_, _) | RemoteAction::PushTag(_, _) => "push",
+            RemoteAction::UpdateSubmodules => "update submodules",
         }
     }
 }
@@ -148,5 +151,31 @@ pub fn format_output(action: &RemoteAction, output: RemoteCommandOutput) -> Succ
                 }
             }
         }
+        RemoteAction::UpdateSubmodules => {
+            if output.stdout.is_empty() && output.stderr.is_empty() {
+                SuccessMessage {
+                    message: "Submodules already up to date".into(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: "Updated submodules".into(),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
+        RemoteAction::PushTag(tag_name, remote_ref) => {
+            if output.stderr.starts_with("Everything up to date") {
+                SuccessMessage {
+                    message: output.stderr.trim().to_owned(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: format!("Pushed tag {} to {}", tag_name, remote_ref.name),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
     }
 }
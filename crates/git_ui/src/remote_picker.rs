@@ -0,0 +1,393 @@
+use anyhow::Context as _;
+use fuzzy::StringMatchCandidate;
+
+use git::repository::Remote;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    ParentElement, Render, SharedString, Styled, Subscription, Task, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::AddRemote, window, cx| {
+        open(RemoteListMode::Add, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RenameRemote, window, cx| {
+        open(RemoteListMode::Rename { from: None }, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::RemoveRemote, window, cx| {
+        open(RemoteListMode::Remove, workspace, window, cx);
+    });
+}
+
+fn open(
+    mode: RemoteListMode,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    workspace.toggle_modal(window, cx, |window, cx| {
+        RemoteList::new(mode, repository, rems(34.), window, cx)
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RemoteListMode {
+    /// Adds a remote from a query of the form `<name> <url>`.
+    Add,
+    Rename {
+        /// The remote to rename, once it has been picked.
+        from: Option<SharedString>,
+    },
+    Remove,
+}
+
+pub struct RemoteList {
+    width: Rems,
+    pub picker: Entity<Picker<RemoteListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl RemoteList {
+    fn new(
+        mode: RemoteListMode,
+        repository: Option<Entity<Repository>>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = RemoteListDelegate::new(mode, repository);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        picker.update(cx, |picker, cx| picker.delegate.reload_entries(window, cx));
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for RemoteList {}
+impl EventEmitter<DismissEvent> for RemoteList {}
+
+impl Focusable for RemoteList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RemoteList {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(self.width).child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum RemoteMatch {
+    Remote {
+        remote: Remote,
+        positions: Vec<usize>,
+    },
+    New {
+        name: String,
+        url: String,
+    },
+    Rename {
+        from: SharedString,
+        to: String,
+    },
+}
+
+pub struct RemoteListDelegate {
+    mode: RemoteListMode,
+    matches: Vec<RemoteMatch>,
+    all_remotes: Option<Vec<Remote>>,
+    repo: Option<Entity<Repository>>,
+    selected_index: usize,
+}
+
+impl RemoteListDelegate {
+    fn new(mode: RemoteListMode, repo: Option<Entity<Repository>>) -> Self {
+        Self {
+            mode,
+            matches: Vec::new(),
+            all_remotes: None,
+            repo,
+            selected_index: 0,
+        }
+    }
+
+    fn reload_entries(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let remotes_request = self
+            .repo
+            .as_ref()
+            .map(|repo| repo.read(cx).get_remotes(None));
+        cx.spawn_in(window, async move |picker, cx| {
+            let remotes = remotes_request.context("No active repository")?.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_remotes = Some(remotes);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn remove_remote(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(RemoteMatch::Remote { remote, .. }) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = remote.name.to_string();
+        let remove = repo.update(cx, |repo, cx| repo.remove_remote(name, cx));
+        cx.spawn_in(window, async move |picker, cx| {
+            remove.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload_entries(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to remove remote", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+}
+
+impl PickerDelegate for RemoteListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match &self.mode {
+            RemoteListMode::Add => "Type a name and a URL to add a remote...".into(),
+            RemoteListMode::Rename { from: None } => "Select a remote to rename...".into(),
+            RemoteListMode::Rename { from: Some(from) } => {
+                format!("Type a new name for {from}...").into()
+            }
+            RemoteListMode::Remove => "Select a remote to remove...".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_remotes) = self.all_remotes.clone() else {
+            return Task::ready(());
+        };
+        let mode = self.mode.clone();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let query = query.trim();
+            let matches = match mode {
+                RemoteListMode::Rename { from: Some(from) } => {
+                    if query.is_empty() || query.contains(char::is_whitespace) {
+                        Vec::new()
+                    } else {
+                        vec![RemoteMatch::Rename {
+                            from,
+                            to: query.to_string(),
+                        }]
+                    }
+                }
+                RemoteListMode::Add => match query.split_once(char::is_whitespace) {
+                    Some((name, url)) => vec![RemoteMatch::New {
+                        name: name.to_string(),
+                        url: url.trim().to_string(),
+                    }],
+                    None => all_remotes
+                        .into_iter()
+                        .map(|remote| RemoteMatch::Remote {
+                            remote,
+                            positions: Vec::new(),
+                        })
+                        .collect(),
+                },
+                RemoteListMode::Rename { from: None } | RemoteListMode::Remove => {
+                    if query.is_empty() {
+                        all_remotes
+                            .into_iter()
+                            .map(|remote| RemoteMatch::Remote {
+                                remote,
+                                positions: Vec::new(),
+                            })
+                            .collect()
+                    } else {
+                        let candidates = all_remotes
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, remote)| StringMatchCandidate::new(ix, &remote.name))
+                            .collect::<Vec<StringMatchCandidate>>();
+                        fuzzy::match_strings(
+                            &candidates,
+                            query,
+                            true,
+                            10000,
+                            &Default::default(),
+                            cx.background_executor().clone(),
+                        )
+                        .await
+                        .into_iter()
+                        .map(|candidate| RemoteMatch::Remote {
+                            remote: all_remotes[candidate.candidate_id].clone(),
+                            positions: candidate.positions,
+                        })
+                        .collect()
+                    }
+                }
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let ix = self.selected_index();
+        let Some(remote_match) = self.matches.get(ix).cloned() else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let (task, error_message) = match remote_match {
+            RemoteMatch::Remote { remote, .. } => match &mut self.mode {
+                RemoteListMode::Add => return,
+                RemoteListMode::Remove => {
+                    self.remove_remote(ix, window, cx);
+                    return;
+                }
+                RemoteListMode::Rename { from } => {
+                    *from = Some(remote.name);
+                    cx.defer_in(window, |picker, window, cx| {
+                        picker.set_query("", window, cx);
+                        picker.refresh_placeholder(window, cx);
+                        picker.refresh(window, cx);
+                    });
+                    return;
+                }
+            },
+            RemoteMatch::New { name, url } => (
+                repo.update(cx, |repo, cx| repo.add_remote(name, url, cx)),
+                "Failed to add remote",
+            ),
+            RemoteMatch::Rename { from, to } => (
+                repo.update(cx, |repo, cx| repo.rename_remote(from.to_string(), to, cx)),
+                "Failed to rename remote",
+            ),
+        };
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err(error_message, window, cx, |e, _, _| Some(e.to_string()));
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let (remote, positions) = match &self.matches[ix] {
+            RemoteMatch::New { name, url } => {
+                return Some(
+                    ListItem::new(SharedString::from(format!("remote-{ix}")))
+                        .inset(true)
+                        .spacing(ListItemSpacing::Sparse)
+                        .toggle_state(selected)
+                        .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                        .child(
+                            Label::new(format!("Add remote \"{name}\" at {url}…")).single_line(),
+                        ),
+                );
+            }
+            RemoteMatch::Rename { from, to } => {
+                return Some(
+                    ListItem::new(SharedString::from(format!("remote-{ix}")))
+                        .inset(true)
+                        .spacing(ListItemSpacing::Sparse)
+                        .toggle_state(selected)
+                        .child(Label::new(format!("Rename \"{from}\" to \"{to}\"…")).single_line()),
+                );
+            }
+            RemoteMatch::Remote { remote, positions } => (remote, positions),
+        };
+
+        let remove_button = IconButton::new(("remove-remote", ix), IconName::Trash)
+            .icon_size(IconSize::Small)
+            .on_click(cx.listener(move |this, _, window, cx| {
+                cx.stop_propagation();
+                window.prevent_default();
+                this.delegate.remove_remote(ix, window, cx);
+            }))
+            .tooltip(Tooltip::text("Remove Remote"))
+            .into_any_element();
+
+        Some(
+            ListItem::new(SharedString::from(format!("remote-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Server).color(Color::Muted))
+                .child(HighlightedLabel::new(remote.name.clone(), positions.clone()).truncate())
+                .map(|el| {
+                    if selected {
+                        el.end_slot::<AnyElement>(remove_button)
+                    } else {
+                        el.end_hover_slot::<AnyElement>(remove_button)
+                    }
+                }),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        match &self.mode {
+            RemoteListMode::Rename { from: Some(_) } => Some("Type a name without spaces".into()),
+            _ => Some("No remotes".into()),
+        }
+    }
+}
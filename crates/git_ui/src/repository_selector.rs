@@ -3,7 +3,10 @@ use gpui::{
 };
 use itertools::Itertools;
 use picker::{Picker, PickerDelegate};
-use project::{Project, git_store::Repository};
+use project::{
+    Project,
+    git_store::{GitStore, Repository},
+};
 use std::sync::Arc;
 use ui::{ListItem, ListItemSpacing, prelude::*};
 use workspace::{ModalView, Workspace};
@@ -37,11 +40,17 @@ impl RepositorySelector {
         cx: &mut Context<Self>,
     ) -> Self {
         let git_store = project_handle.read(cx).git_store().clone();
-        let repository_entries = git_store.update(cx, |git_store, _cx| {
+        let repository_entries = git_store.update(cx, |git_store, cx| {
             git_store
                 .repositories()
                 .values()
                 .cloned()
+                .sorted_by_key(|repo| {
+                    repo.read(cx)
+                        .repository_entry
+                        .work_directory_abs_path
+                        .clone()
+                })
                 .collect::<Vec<_>>()
         });
         let filtered_repositories = repository_entries.clone();
@@ -55,6 +64,7 @@ impl RepositorySelector {
 
         let delegate = RepositorySelectorDelegate {
             repository_selector: cx.entity().downgrade(),
+            git_store,
             repository_entries,
             filtered_repositories,
             selected_index: 0,
@@ -119,6 +129,7 @@ impl ModalView for RepositorySelector {}
 
 pub struct RepositorySelectorDelegate {
     repository_selector: WeakEntity<RepositorySelector>,
+    git_store: Entity<GitStore>,
     repository_entries: Vec<Entity<Repository>>,
     filtered_repositories: Vec<Entity<Repository>>,
     selected_index: usize,
@@ -225,12 +236,34 @@ impl PickerDelegate for RepositorySelectorDelegate {
     ) -> Option<Self::ListItem> {
         let repo_info = self.filtered_repositories.get(ix)?;
         let display_name = repo_info.read(cx).display_name();
+
+        // Repositories nested inside another one (such as submodules) are
+        // indented under their parent.
+        let git_store = self.git_store.read(cx);
+        let parent = git_store.parent_repository(repo_info, cx);
+        let mut depth = 0;
+        let mut ancestor = parent.clone();
+        while let Some(repository) = ancestor {
+            depth += 1;
+            ancestor = git_store.parent_repository(&repository, cx);
+        }
+
         Some(
             ListItem::new(ix)
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .toggle_state(selected)
-                .child(Label::new(display_name)),
+                .indent_level(depth)
+                .child(h_flex().gap_1().child(Label::new(display_name)).when_some(
+                    parent,
+                    |this, parent| {
+                        this.child(
+                            Label::new(format!("in {}", parent.read(cx).display_name()))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    },
+                )),
         )
     }
 }
@@ -0,0 +1,437 @@
+use anyhow::Context as _;
+use fuzzy::StringMatchCandidate;
+
+use git::repository::{StashEntry, StashOptions};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    ParentElement, Render, SharedString, Styled, Subscription, Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_diff::CommitDiff;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+    workspace.register_action(|workspace, _: &git::StashAll, window, cx| {
+        let options = StashOptions {
+            include_untracked: true,
+            ..Default::default()
+        };
+        stash(workspace, options, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::StashStaged, window, cx| {
+        let options = StashOptions {
+            staged: true,
+            ..Default::default()
+        };
+        stash(workspace, options, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::StashPop, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        let pop = repository.update(cx, |repository, cx| repository.stash_pop(0, cx));
+        cx.spawn(async move |_, _| pop.await?)
+            .detach_and_prompt_err("Failed to pop stash", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+    });
+}
+
+fn stash(
+    workspace: &mut Workspace,
+    options: StashOptions,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let push = repository.update(cx, |repository, cx| repository.stash_push(options, cx));
+    cx.spawn(async move |_, _| push.await?)
+        .detach_and_prompt_err("Failed to stash changes", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::ViewStash,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let weak_workspace = cx.weak_entity();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        StashList::new(repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+pub struct StashList {
+    width: Rems,
+    pub picker: Entity<Picker<StashListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl StashList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = StashListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        picker.update(cx, |picker, cx| picker.delegate.reload_entries(window, cx));
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for StashList {}
+impl EventEmitter<DismissEvent> for StashList {}
+
+impl Focusable for StashList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for StashList {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(self.width).child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StashListEntry {
+    entry: StashEntry,
+    positions: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum StashMatch {
+    Entry(StashListEntry),
+    /// Stashes the current changes with the query as message.
+    New(String),
+}
+
+pub struct StashListDelegate {
+    matches: Vec<StashMatch>,
+    all_entries: Option<Vec<StashEntry>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl StashListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_entries: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    fn reload_entries(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let entries_request = self.repo.as_ref().map(|repo| repo.read(cx).stash_entries());
+        cx.spawn_in(window, async move |picker, cx| {
+            let entries = entries_request.context("No active repository")?.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_entries = Some(entries);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn stash(&self, message: String, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let options = StashOptions {
+            message: Some(message),
+            include_untracked: true,
+            staged: false,
+        };
+        let push = repo.update(cx, |repo, cx| repo.stash_push(options, cx));
+        cx.spawn(async move |_, _| push.await?)
+            .detach_and_prompt_err("Failed to stash changes", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+        cx.emit(DismissEvent);
+    }
+
+    fn drop_entry(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(StashMatch::Entry(entry)) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let index = entry.entry.index;
+        let drop_task = repo.update(cx, |repo, cx| repo.stash_drop(index, cx));
+        cx.spawn_in(window, async move |picker, cx| {
+            drop_task.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload_entries(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to drop stash", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn show_diff(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(StashMatch::Entry(entry)) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let entry = entry.entry.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                CommitDiff::open_stash(entry, repo, workspace, window, cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for StashListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a stash entry, or type a message to stash changes...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_entries) = self.all_entries.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<StashMatch> = if query.is_empty() {
+                all_entries
+                    .into_iter()
+                    .map(|entry| {
+                        StashMatch::Entry(StashListEntry {
+                            entry,
+                            positions: Vec::new(),
+                        })
+                    })
+                    .collect()
+            } else {
+                let candidates = all_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| StringMatchCandidate::new(ix, &entry.message))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| {
+                    StashMatch::Entry(StashListEntry {
+                        entry: all_entries[candidate.candidate_id].clone(),
+                        positions: candidate.positions,
+                    })
+                })
+                .collect()
+            };
+            if !query.is_empty() {
+                matches.push(StashMatch::New(query));
+            }
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(stash_match) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        let entry = match stash_match {
+            StashMatch::New(message) => {
+                self.stash(message.clone(), window, cx);
+                return;
+            }
+            StashMatch::Entry(entry) => entry.entry.clone(),
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+
+        // Applying keeps the entry in the stash, while popping drops it once applied.
+        let (task, error_message) = if secondary {
+            (
+                repo.update(cx, |repo, cx| repo.stash_apply(entry.index, cx)),
+                "Failed to apply stash",
+            )
+        } else {
+            (
+                repo.update(cx, |repo, cx| repo.stash_pop(entry.index, cx)),
+                "Failed to pop stash",
+            )
+        };
+        cx.spawn(async move |_, _| task.await?)
+            .detach_and_prompt_err(error_message, window, cx, |e, _, _| Some(e.to_string()));
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = match &self.matches[ix] {
+            StashMatch::New(message) => {
+                return Some(
+                    ListItem::new(SharedString::from(format!("stash-{ix}")))
+                        .inset(true)
+                        .spacing(ListItemSpacing::Sparse)
+                        .toggle_state(selected)
+                        .child(
+                            Label::new(format!("Stash changes as \"{message}\"…")).single_line(),
+                        ),
+                );
+            }
+            StashMatch::Entry(entry) => entry,
+        };
+
+        let commit_time = OffsetDateTime::from_unix_timestamp(entry.entry.commit_timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let formatted_time = format_local_timestamp(
+            commit_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+
+        let actions = h_flex()
+            .gap_1()
+            .child(
+                IconButton::new(("view-stash", ix), IconName::Diff)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        this.delegate.show_diff(ix, window, cx);
+                    }))
+                    .tooltip(Tooltip::text("View Changes")),
+            )
+            .child(
+                IconButton::new(("drop-stash", ix), IconName::Trash)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        window.prevent_default();
+                        this.delegate.drop_entry(ix, window, cx);
+                    }))
+                    .tooltip(Tooltip::text("Drop Stash Entry")),
+            )
+            .into_any_element();
+
+        Some(
+            ListItem::new(SharedString::from(format!("stash-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .overflow_x_hidden()
+                        .gap_2()
+                        .justify_between()
+                        .child(
+                            div().flex_shrink().overflow_x_hidden().child(
+                                HighlightedLabel::new(
+                                    entry.entry.message.clone(),
+                                    entry.positions.clone(),
+                                )
+                                .truncate(),
+                            ),
+                        )
+                        .child(
+                            Label::new(formatted_time)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .map(|el| {
+                    if selected {
+                        el.end_slot::<AnyElement>(actions)
+                    } else {
+                        el.end_hover_slot::<AnyElement>(actions)
+                    }
+                }),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No stash entries".into())
+    }
+}
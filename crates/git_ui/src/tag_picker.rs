@@ -0,0 +1,449 @@
+use anyhow::Context as _;
+use fuzzy::StringMatchCandidate;
+
+use git::{SHORT_SHA_LENGTH, repository::Tag};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    ParentElement, Render, SharedString, Styled, Subscription, Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::git_panel::GitPanel;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::CreateTag, window, cx| {
+        open(TagListMode::Create, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::DeleteTag, window, cx| {
+        open(TagListMode::Delete, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::PushTag, window, cx| {
+        open(TagListMode::Push, workspace, window, cx);
+    });
+}
+
+fn open(
+    mode: TagListMode,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let weak_workspace = cx.weak_entity();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        TagList::new(mode, repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+/// What confirming an existing tag does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TagListMode {
+    /// Pushes the tag, while the query can be confirmed to create a new one.
+    Create,
+    Delete,
+    Push,
+}
+
+pub struct TagList {
+    width: Rems,
+    pub picker: Entity<Picker<TagListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TagList {
+    fn new(
+        mode: TagListMode,
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TagListDelegate::new(mode, repository, workspace);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        picker.update(cx, |picker, cx| picker.delegate.reload_entries(window, cx));
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for TagList {}
+impl EventEmitter<DismissEvent> for TagList {}
+
+impl Focusable for TagList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for TagList {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(self.width).child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TagListEntry {
+    tag: Tag,
+    positions: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+enum TagMatch {
+    Tag(TagListEntry),
+    /// Tags `HEAD` with the name before the query's first space, annotated with the rest of the
+    /// query if there is any.
+    New(String),
+}
+
+pub struct TagListDelegate {
+    mode: TagListMode,
+    matches: Vec<TagMatch>,
+    all_tags: Option<Vec<Tag>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl TagListDelegate {
+    fn new(
+        mode: TagListMode,
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+    ) -> Self {
+        Self {
+            mode,
+            matches: Vec::new(),
+            all_tags: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    fn reload_entries(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let tags_request = self.repo.as_ref().map(|repo| repo.read(cx).tags());
+        cx.spawn_in(window, async move |picker, cx| {
+            let tags = tags_request.context("No active repository")?.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_tags = Some(tags);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn create_tag(&self, query: &str, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let (name, message) = match query.split_once(' ') {
+            Some((name, message)) => (name, Some(message.trim().to_string())),
+            None => (query, None),
+        };
+        let name = name.to_string();
+        let create = repo.update(cx, |repo, cx| repo.create_tag(name, None, message, cx));
+        cx.spawn(async move |_, _| create.await?)
+            .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+        cx.emit(DismissEvent);
+    }
+
+    fn delete_tag(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(TagMatch::Tag(entry)) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = entry.tag.name.to_string();
+        let delete = repo.update(cx, |repo, cx| repo.delete_tag(name, cx));
+        cx.spawn_in(window, async move |picker, cx| {
+            delete.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload_entries(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn push_tag(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(TagMatch::Tag(entry)) = self.matches.get(ix) else {
+            return;
+        };
+        let name = entry.tag.name.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let Some(panel) = workspace.panel::<GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| panel.push_tag(name, window, cx));
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn can_push(&self, cx: &App) -> bool {
+        self.workspace
+            .upgrade()
+            .is_some_and(|workspace| !workspace.read(cx).project().read(cx).is_via_collab())
+    }
+}
+
+impl PickerDelegate for TagListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self.mode {
+            TagListMode::Create => "Type a name and optional message to create a tag...".into(),
+            TagListMode::Delete => "Select a tag to delete...".into(),
+            TagListMode::Push => "Select a tag to push...".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+        let mode = self.mode;
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let query = query.trim().to_string();
+            let name_query = query.split(' ').next().unwrap_or_default().to_string();
+            let mut matches: Vec<TagMatch> = if name_query.is_empty() {
+                all_tags
+                    .iter()
+                    .cloned()
+                    .map(|tag| {
+                        TagMatch::Tag(TagListEntry {
+                            tag,
+                            positions: Vec::new(),
+                        })
+                    })
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &name_query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| {
+                    TagMatch::Tag(TagListEntry {
+                        tag: all_tags[candidate.candidate_id].clone(),
+                        positions: candidate.positions,
+                    })
+                })
+                .collect()
+            };
+            if mode == TagListMode::Create
+                && !name_query.is_empty()
+                && !all_tags.iter().any(|tag| tag.name == name_query)
+            {
+                matches.insert(0, TagMatch::New(query));
+            }
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let ix = self.selected_index();
+        match self.matches.get(ix) {
+            Some(TagMatch::New(query)) => {
+                let query = query.clone();
+                self.create_tag(&query, window, cx);
+            }
+            Some(TagMatch::Tag(_)) => match self.mode {
+                TagListMode::Delete => self.delete_tag(ix, window, cx),
+                TagListMode::Create | TagListMode::Push => {
+                    if self.can_push(cx) {
+                        self.push_tag(ix, window, cx);
+                    }
+                }
+            },
+            None => {}
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = match &self.matches[ix] {
+            TagMatch::New(query) => {
+                let label = match query.split_once(' ') {
+                    Some((name, message)) => {
+                        format!("Create tag \"{name}\" at HEAD with message \"{message}\"…")
+                    }
+                    None => format!("Create tag \"{query}\" at HEAD…"),
+                };
+                return Some(
+                    ListItem::new(SharedString::from(format!("tag-{ix}")))
+                        .inset(true)
+                        .spacing(ListItemSpacing::Sparse)
+                        .toggle_state(selected)
+                        .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                        .child(Label::new(label).single_line()),
+                );
+            }
+            TagMatch::Tag(entry) => entry,
+        };
+
+        let tag_time = OffsetDateTime::from_unix_timestamp(entry.tag.timestamp)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let formatted_time = format_local_timestamp(
+            tag_time,
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+        let short_sha = entry
+            .tag
+            .sha
+            .chars()
+            .take(SHORT_SHA_LENGTH)
+            .collect::<String>();
+
+        let actions = h_flex()
+            .gap_1()
+            .when(self.can_push(cx), |this| {
+                this.child(
+                    IconButton::new(("push-tag", ix), IconName::ArrowUp)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            this.delegate.push_tag(ix, window, cx);
+                        }))
+                        .tooltip(Tooltip::text("Push Tag")),
+                )
+            })
+            .child(
+                IconButton::new(("delete-tag", ix), IconName::Trash)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        window.prevent_default();
+                        this.delegate.delete_tag(ix, window, cx);
+                    }))
+                    .tooltip(Tooltip::text("Delete Tag")),
+            )
+            .into_any_element();
+
+        Some(
+            ListItem::new(SharedString::from(format!("tag-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .overflow_x_hidden()
+                        .gap_2()
+                        .justify_between()
+                        .child(
+                            h_flex()
+                                .flex_shrink()
+                                .overflow_x_hidden()
+                                .gap_2()
+                                .child(
+                                    HighlightedLabel::new(
+                                        entry.tag.name.clone(),
+                                        entry.positions.clone(),
+                                    )
+                                    .truncate(),
+                                )
+                                .child(
+                                    Label::new(entry.tag.subject.clone())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                        .truncate(),
+                                ),
+                        )
+                        .child(
+                            Label::new(format!("{short_sha} · {formatted_time}"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .map(|el| {
+                    if selected {
+                        el.end_slot::<AnyElement>(actions)
+                    } else {
+                        el.end_hover_slot::<AnyElement>(actions)
+                    }
+                }),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No tags".into())
+    }
+}
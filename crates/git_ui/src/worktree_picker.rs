@@ -0,0 +1,515 @@
+use anyhow::Context as _;
+use fuzzy::StringMatchCandidate;
+
+use git::repository::{Branch, GitWorktree};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    ParentElement, Render, SharedString, Styled, Subscription, Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::{path::PathBuf, sync::Arc};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+    workspace.register_action(|workspace, _: &git::PruneWorktrees, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        let prune = repository.update(cx, |repository, cx| repository.prune_worktrees(cx));
+        cx.spawn(async move |_, _| prune.await?)
+            .detach_and_prompt_err("Failed to prune worktrees", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+    });
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::ViewWorktrees,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let weak_workspace = cx.weak_entity();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        WorktreeList::new(repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+pub struct WorktreeList {
+    width: Rems,
+    pub picker: Entity<Picker<WorktreeListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl WorktreeList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = WorktreeListDelegate::new(repository, workspace);
+        // Worktrees take more space than branches, as their path is shown below their name.
+        let picker = cx.new(|cx| Picker::list(delegate, window, cx));
+        picker.update(cx, |picker, cx| picker.delegate.reload_entries(window, cx));
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for WorktreeList {}
+impl EventEmitter<DismissEvent> for WorktreeList {}
+
+impl Focusable for WorktreeList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WorktreeList {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(self.width).child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum WorktreeMatch {
+    Worktree {
+        worktree: GitWorktree,
+        positions: Vec<usize>,
+    },
+    /// A branch that isn't checked out in any worktree yet.
+    Branch {
+        name: SharedString,
+        positions: Vec<usize>,
+    },
+    /// Creates a branch named after the query, in a new worktree.
+    NewBranch(String),
+}
+
+/// The worktrees of the repository and the branches a worktree can be created for.
+#[derive(Clone)]
+struct WorktreeEntries {
+    worktrees: Vec<GitWorktree>,
+    branches: Vec<SharedString>,
+}
+
+impl WorktreeEntries {
+    fn candidate_label(&self, ix: usize) -> String {
+        if let Some(worktree) = self.worktrees.get(ix) {
+            worktree_label(worktree)
+        } else {
+            self.branches[ix - self.worktrees.len()].to_string()
+        }
+    }
+}
+
+pub struct WorktreeListDelegate {
+    matches: Vec<WorktreeMatch>,
+    all_entries: Option<WorktreeEntries>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl WorktreeListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_entries: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    fn reload_entries(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let requests = self.repo.as_ref().map(|repo| {
+            let repo = repo.read(cx);
+            (repo.worktrees(), repo.branches())
+        });
+        cx.spawn_in(window, async move |picker, cx| {
+            let (worktrees_request, branches_request) = requests.context("No active repository")?;
+            let worktrees = worktrees_request.await??;
+            let mut branches = branches_request.await??;
+            branches.sort_by_key(|branch| {
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| 0 - commit.commit_timestamp)
+            });
+            let branches = branches
+                .into_iter()
+                .filter(|branch| !is_checked_out(branch, &worktrees))
+                .map(|branch| branch.name)
+                .collect();
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_entries = Some(WorktreeEntries {
+                    worktrees,
+                    branches,
+                });
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Returns where to create the worktree of a branch: next to the main worktree, in a
+    /// directory named after both the main worktree and the branch.
+    fn new_worktree_path(&self, branch: &str) -> Option<PathBuf> {
+        let main_worktree = self
+            .all_entries
+            .as_ref()?
+            .worktrees
+            .iter()
+            .find(|worktree| worktree.is_main)?;
+        let main_name = main_worktree.path.file_name()?.to_string_lossy();
+        let branch = branch.replace(['/', '\\'], "-");
+        Some(
+            main_worktree
+                .path
+                .parent()?
+                .join(format!("{main_name}-{branch}")),
+        )
+    }
+
+    fn create_worktree(
+        &self,
+        branch: String,
+        create_branch: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(path) = self.new_worktree_path(&branch) else {
+            return;
+        };
+        let create = repo.update(cx, |repo, cx| {
+            repo.create_worktree(path.clone(), branch, create_branch, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            create.await??;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    recent_projects::open_paths_on_project_host(vec![path], workspace, window, cx)
+                })?
+                .await
+        })
+        .detach_and_prompt_err("Failed to create worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn open_worktree(&self, worktree: GitWorktree, window: &mut Window, cx: &mut App) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                recent_projects::open_paths_on_project_host(
+                    vec![worktree.path],
+                    workspace,
+                    window,
+                    cx,
+                )
+                .detach_and_prompt_err(
+                    "Failed to open worktree",
+                    window,
+                    cx,
+                    |e, _, _| Some(e.to_string()),
+                );
+            })
+            .log_err();
+    }
+
+    fn remove_worktree(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(WorktreeMatch::Worktree { worktree, .. }) = self.matches.get(ix) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let path = worktree.path.clone();
+        let remove = repo.update(cx, |repo, cx| repo.remove_worktree(path, false, cx));
+        cx.spawn_in(window, async move |picker, cx| {
+            remove.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload_entries(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to remove worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+}
+
+fn is_checked_out(branch: &Branch, worktrees: &[GitWorktree]) -> bool {
+    worktrees
+        .iter()
+        .any(|worktree| worktree.branch.as_ref() == Some(&branch.name))
+}
+
+fn worktree_label(worktree: &GitWorktree) -> String {
+    let name = worktree
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| worktree.path.to_string_lossy().to_string());
+    match &worktree.branch {
+        Some(branch) => format!("{name} ({branch})"),
+        None => name,
+    }
+}
+
+impl PickerDelegate for WorktreeListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a worktree, or a branch to create one for...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_entries) = self.all_entries.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let worktree_count = all_entries.worktrees.len();
+            let to_match = |ix: usize, positions: Vec<usize>| {
+                if let Some(worktree) = all_entries.worktrees.get(ix) {
+                    WorktreeMatch::Worktree {
+                        worktree: worktree.clone(),
+                        positions,
+                    }
+                } else {
+                    WorktreeMatch::Branch {
+                        name: all_entries.branches[ix - worktree_count].clone(),
+                        positions,
+                    }
+                }
+            };
+            let candidate_count = worktree_count + all_entries.branches.len();
+            let mut matches: Vec<WorktreeMatch> = if query.is_empty() {
+                (0..candidate_count)
+                    .map(|ix| to_match(ix, Vec::new()))
+                    .collect()
+            } else {
+                let candidates = (0..candidate_count)
+                    .map(|ix| StringMatchCandidate::new(ix, &all_entries.candidate_label(ix)))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| to_match(candidate.candidate_id, candidate.positions))
+                .collect()
+            };
+            let branch_exists = all_entries
+                .branches
+                .iter()
+                .any(|branch| branch.as_ref() == query)
+                || all_entries
+                    .worktrees
+                    .iter()
+                    .any(|worktree| worktree.branch.as_deref() == Some(query.as_str()));
+            if !query.is_empty() && !branch_exists {
+                matches.push(WorktreeMatch::NewBranch(query));
+            }
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(worktree_match) = self.matches.get(self.selected_index()).cloned() else {
+            return;
+        };
+        match worktree_match {
+            WorktreeMatch::Worktree { worktree, .. } => {
+                if worktree.is_prunable {
+                    return;
+                }
+                self.open_worktree(worktree, window, cx);
+                cx.emit(DismissEvent);
+            }
+            WorktreeMatch::Branch { name, .. } => {
+                self.create_worktree(name.to_string(), false, window, cx);
+            }
+            WorktreeMatch::NewBranch(name) => {
+                self.create_worktree(name, true, window, cx);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let list_item = ListItem::new(SharedString::from(format!("worktree-{ix}")))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+
+        let (worktree, positions) = match &self.matches[ix] {
+            WorktreeMatch::NewBranch(name) => {
+                return Some(list_item.child(
+                    Label::new(format!("Create worktree for new branch \"{name}\"…")).single_line(),
+                ));
+            }
+            WorktreeMatch::Branch { name, positions } => {
+                return Some(
+                    list_item.child(
+                        h_flex()
+                            .w_full()
+                            .overflow_x_hidden()
+                            .gap_2()
+                            .justify_between()
+                            .child(div().flex_shrink().overflow_x_hidden().child(
+                                HighlightedLabel::new(name.clone(), positions.clone()).truncate(),
+                            ))
+                            .child(
+                                Label::new("create worktree")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    ),
+                );
+            }
+            WorktreeMatch::Worktree {
+                worktree,
+                positions,
+            } => (worktree, positions),
+        };
+
+        let status = if worktree.is_main {
+            Some("main worktree")
+        } else if worktree.is_prunable {
+            Some("missing")
+        } else if worktree.is_locked {
+            Some("locked")
+        } else if worktree.branch.is_none() {
+            Some("detached")
+        } else {
+            None
+        };
+
+        Some(
+            list_item
+                .child(
+                    v_flex()
+                        .w_full()
+                        .overflow_x_hidden()
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .gap_2()
+                                .justify_between()
+                                .child(
+                                    div().flex_shrink().overflow_x_hidden().child(
+                                        HighlightedLabel::new(
+                                            worktree_label(worktree),
+                                            positions.clone(),
+                                        )
+                                        .truncate(),
+                                    ),
+                                )
+                                .when_some(status, |el, status| {
+                                    el.child(
+                                        Label::new(status)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                }),
+                        )
+                        .child(
+                            Label::new(worktree.path.to_string_lossy().to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                )
+                .when(!worktree.is_main, |el| {
+                    let remove_button = IconButton::new(("remove-worktree", ix), IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            window.prevent_default();
+                            this.delegate.remove_worktree(ix, window, cx);
+                        }))
+                        .tooltip(Tooltip::text("Remove Worktree"))
+                        .into_any_element();
+                    if selected {
+                        el.end_slot::<AnyElement>(remove_button)
+                    } else {
+                        el.end_hover_slot::<AnyElement>(remove_button)
+                    }
+                }),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No worktrees".into())
+    }
+}
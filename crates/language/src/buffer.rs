@@ -3873,6 +3873,98 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges of the syntax nodes captured by the folds query that intersect the given range.
+    /// The buffer can be associated with multiple languages, and the folds query associated with each
+    /// will be run on the relevant section of the buffer.
+    pub fn syntax_fold_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = Range<usize>> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar.fold_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.fold_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut fold_ranges = Vec::new();
+        iter::from_fn(move || {
+            loop {
+                if let Some(fold_range) = fold_ranges.pop() {
+                    return Some(fold_range);
+                }
+
+                let mat = syntax_matches.peek()?;
+                if let Some(config) = configs[mat.grammar_index] {
+                    fold_ranges.extend(
+                        mat.captures
+                            .iter()
+                            .filter(|capture| capture.index == config.fold_capture_ix)
+                            .map(|capture| capture.node.byte_range()),
+                    );
+                }
+                syntax_matches.advance();
+            }
+        })
+    }
+
+    /// Returns the ranges of the syntax nodes captured by the links query that intersect the given range.
+    /// Each range covers the text of a URL or a path, without any surrounding quotes.
+    pub fn syntax_links<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = Range<usize>> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar.link_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.link_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut links = Vec::new();
+        iter::from_fn(move || {
+            loop {
+                if let Some(link) = links.pop() {
+                    return Some(link);
+                }
+
+                let mat = syntax_matches.peek()?;
+                if let Some(config) = configs[mat.grammar_index] {
+                    links.extend(
+                        mat.captures
+                            .iter()
+                            .filter(|capture| capture.index == config.link_capture_ix)
+                            .map(|capture| self.trim_link_quotes(capture.node.byte_range())),
+                    );
+                }
+                syntax_matches.advance();
+            }
+        })
+    }
+
+    fn trim_link_quotes(&self, mut range: Range<usize>) -> Range<usize> {
+        if range.len() >= 2 {
+            let first = self.chars_at(range.start).next();
+            let last = self.reversed_chars_at(range.end).next();
+            if matches!(
+                (first, last),
+                (Some('"'), Some('"')) | (Some('\''), Some('\'')) | (Some('<'), Some('>'))
+            ) {
+                range.start += 1;
+                range.end -= 1;
+            }
+        }
+        range
+    }
+
     pub fn injections_intersecting_range<T: ToOffset>(
         &self,
         range: Range<T>,
@@ -969,6 +969,40 @@ fn test_text_objects(cx: &mut App) {
     )
 }
 
+#[gpui::test]
+fn test_syntax_fold_ranges(cx: &mut App) {
+    let (text, ranges) = marked_text_ranges(
+        indoc! {r#"
+            impl Hello {
+                fn say(
+                    «&self»,
+                ) -> u8 {
+                    1
+                }
+            }"#
+        },
+        false,
+    );
+
+    let buffer =
+        cx.new(|cx| Buffer::local(text.clone(), cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    let mut fold_ranges = snapshot
+        .syntax_fold_ranges(ranges[0].clone())
+        .map(|range| &text[range])
+        .collect::<Vec<_>>();
+    fold_ranges.sort_by_key(|text| text.len());
+
+    assert_eq!(
+        fold_ranges,
+        &[
+            "fn say(\n        &self,\n    ) -> u8 {\n        1\n    }",
+            "impl Hello {\n    fn say(\n        &self,\n    ) -> u8 {\n        1\n    }\n}",
+        ],
+    )
+}
+
 #[gpui::test]
 fn test_enclosing_bracket_ranges(cx: &mut App) {
     let mut assert = |selection_text, range_markers| {
@@ -3410,6 +3444,12 @@ fn rust_lang() -> Language {
         "#,
     )
     .unwrap()
+    .with_fold_query(
+        r#"
+        [(function_item) (impl_item) (block)] @fold
+        "#,
+    )
+    .unwrap()
     .with_outline_query(
         r#"
         (line_comment) @annotation
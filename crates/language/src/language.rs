@@ -1020,6 +1020,8 @@ pub struct Grammar {
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub text_object_config: Option<TextObjectConfig>,
+    pub fold_config: Option<FoldConfig>,
+    pub link_config: Option<LinkConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
     pub(crate) injection_config: Option<InjectionConfig>,
     pub(crate) override_config: Option<OverrideConfig>,
@@ -1083,6 +1085,16 @@ pub struct TextObjectConfig {
     pub text_objects_by_capture_ix: Vec<(u32, TextObject)>,
 }
 
+pub struct FoldConfig {
+    pub query: Query,
+    pub fold_capture_ix: u32,
+}
+
+pub struct LinkConfig {
+    pub query: Query,
+    pub link_capture_ix: u32,
+}
+
 #[derive(Debug)]
 pub struct EmbeddingConfig {
     pub query: Query,
@@ -1171,6 +1183,8 @@ impl Language {
                     brackets_config: None,
                     outline_config: None,
                     text_object_config: None,
+                    fold_config: None,
+                    link_config: None,
                     embedding_config: None,
                     indents_config: None,
                     injection_config: None,
@@ -1248,6 +1262,16 @@ impl Language {
                 .with_text_object_query(query.as_ref())
                 .context("Error loading textobject query")?;
         }
+        if let Some(query) = queries.folds {
+            self = self
+                .with_fold_query(query.as_ref())
+                .context("Error loading folds query")?;
+        }
+        if let Some(query) = queries.links {
+            self = self
+                .with_link_query(query.as_ref())
+                .context("Error loading links query")?;
+        }
         Ok(self)
     }
 
@@ -1343,6 +1367,38 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_fold_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut fold_capture_ix = None;
+        get_capture_indices(&query, &mut [("fold", &mut fold_capture_ix)]);
+        if let Some(fold_capture_ix) = fold_capture_ix {
+            grammar.fold_config = Some(FoldConfig {
+                query,
+                fold_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn with_link_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut link_capture_ix = None;
+        get_capture_indices(&query, &mut [("link", &mut link_capture_ix)]);
+        if let Some(link_capture_ix) = link_capture_ix {
+            grammar.link_config = Some(LinkConfig {
+                query,
+                link_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
     pub fn with_embedding_query(mut self, source: &str) -> Result<Self> {
         let grammar = self
             .grammar_mut()
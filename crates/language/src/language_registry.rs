@@ -197,6 +197,8 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("redactions", |q| &mut q.redactions),
     ("runnables", |q| &mut q.runnables),
     ("textobjects", |q| &mut q.text_objects),
+    ("folds", |q| &mut q.folds),
+    ("links", |q| &mut q.links),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -212,6 +214,8 @@ pub struct LanguageQueries {
     pub redactions: Option<Cow<'static, str>>,
     pub runnables: Option<Cow<'static, str>>,
     pub text_objects: Option<Cow<'static, str>>,
+    pub folds: Option<Cow<'static, str>>,
+    pub links: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
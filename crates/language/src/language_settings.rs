@@ -128,6 +128,12 @@ pub struct LanguageSettings {
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
+    /// Semantic token related settings.
+    pub semantic_tokens: SemanticTokensSettings,
+    /// Where the foldable ranges of the buffer come from.
+    pub fold_source: FoldSource,
+    /// Where the ranges to select when expanding or shrinking selections come from.
+    pub selection_range_source: SelectionRangeSource,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
     /// Whether to automatically surround text with brackets.
@@ -474,6 +480,19 @@ pub struct LanguageSettingsContent {
     /// Inlay hint related settings.
     #[serde(default)]
     pub inlay_hints: Option<InlayHintSettings>,
+    /// Semantic token related settings.
+    #[serde(default)]
+    pub semantic_tokens: Option<SemanticTokensSettings>,
+    /// Where the foldable ranges of the buffer come from.
+    ///
+    /// Default: tree_sitter
+    #[serde(default)]
+    pub fold_source: Option<FoldSource>,
+    /// Where the ranges to select when expanding or shrinking selections come from.
+    ///
+    /// Default: tree_sitter
+    #[serde(default)]
+    pub selection_range_source: Option<SelectionRangeSource>,
     /// Whether to automatically type closing characters for you. For example,
     /// when you type (, Zed will automatically add a closing ) at the correct position.
     ///
@@ -735,6 +754,37 @@ pub enum ShowWhitespaceSetting {
     Boundary,
 }
 
+/// Controls where the foldable ranges of a buffer come from.
+///
+/// When the selected source is not available for a buffer, e.g. because its language
+/// has no folds query or its language servers don't support folding ranges, folding
+/// falls back to the next source: language server, then tree-sitter, then indentation.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldSource {
+    /// Fold ranges of lines that are indented more than the line they start on.
+    Indentation,
+    /// Fold the syntax nodes captured by the `folds.scm` query of the language.
+    #[default]
+    TreeSitter,
+    /// Fold the ranges reported by the language server via `textDocument/foldingRange`.
+    LanguageServer,
+}
+
+/// Controls where the ranges to select when expanding a selection come from.
+///
+/// When the language server doesn't support selection ranges for a buffer, or doesn't
+/// provide a larger range for a selection, the syntax tree is used instead.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionRangeSource {
+    /// Select the enclosing syntax nodes of the buffer's syntax tree.
+    #[default]
+    TreeSitter,
+    /// Select the ranges reported by the language server via `textDocument/selectionRange`.
+    LanguageServer,
+}
+
 /// Controls which formatter should be used when formatting code.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub enum SelectedFormatter {
@@ -1001,6 +1051,23 @@ fn scroll_debounce_ms() -> u64 {
     50
 }
 
+/// The settings for semantic tokens reported by language servers.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SemanticTokensSettings {
+    /// Whether to highlight the buffer with the semantic tokens of its language server,
+    /// on top of the syntax highlighting.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+    /// Overrides for the theme syntax style used for a semantic token, keyed by the token
+    /// type, optionally followed by a modifier (e.g. `"variable.mutable"`).
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub token_styles: HashMap<String, String>,
+}
+
 /// The task settings for a particular language.
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize, JsonSchema)]
 pub struct LanguageTaskConfig {
@@ -1416,6 +1483,12 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens.clone());
+    merge(&mut settings.fold_source, src.fold_source);
+    merge(
+        &mut settings.selection_range_source,
+        src.selection_range_source,
+    );
     merge(
         &mut settings.show_completions_on_input,
         src.show_completions_on_input,
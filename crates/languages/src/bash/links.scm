@@ -0,0 +1,4 @@
+(command
+  name: (command_name) @_command
+  argument: (word) @link
+  (#any-of? @_command "source" "."))
@@ -0,0 +1,12 @@
+[
+  (function_definition)
+  (compound_statement)
+  (field_declaration_list)
+  (enumerator_list)
+  (initializer_list)
+  (parameter_list)
+  (argument_list)
+  (preproc_if)
+  (preproc_ifdef)
+  (comment)
+] @fold
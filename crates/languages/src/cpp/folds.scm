@@ -0,0 +1,14 @@
+[
+  (function_definition)
+  (compound_statement)
+  (declaration_list)
+  (field_declaration_list)
+  (enumerator_list)
+  (initializer_list)
+  (parameter_list)
+  (argument_list)
+  (template_declaration)
+  (preproc_if)
+  (preproc_ifdef)
+  (comment)
+] @fold
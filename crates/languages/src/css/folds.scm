@@ -0,0 +1,5 @@
+[
+  (block)
+  (keyframe_block_list)
+  (comment)
+] @fold
@@ -0,0 +1,2 @@
+(import_statement
+  (string_value) @link)
@@ -0,0 +1,16 @@
+[
+  (function_declaration)
+  (method_declaration)
+  (func_literal)
+  (block)
+  (import_spec_list)
+  (const_declaration)
+  (var_declaration)
+  (type_declaration)
+  (field_declaration_list)
+  (interface_type)
+  (literal_value)
+  (parameter_list)
+  (argument_list)
+  (comment)
+] @fold
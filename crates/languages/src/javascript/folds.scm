@@ -0,0 +1,17 @@
+[
+  (function_declaration)
+  (generator_function_declaration)
+  (class_declaration)
+  (method_definition)
+  (statement_block)
+  (class_body)
+  (switch_body)
+  (object)
+  (array)
+  (arguments)
+  (formal_parameters)
+  (named_imports)
+  (template_string)
+  (jsx_element)
+  (comment)
+] @fold
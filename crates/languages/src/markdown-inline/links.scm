@@ -0,0 +1 @@
+(link_destination) @link
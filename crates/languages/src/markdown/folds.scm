@@ -0,0 +1,5 @@
+[
+  (section)
+  (fenced_code_block)
+  (list)
+] @fold
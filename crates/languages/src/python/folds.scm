@@ -0,0 +1,21 @@
+[
+  (function_definition)
+  (class_definition)
+  (if_statement)
+  (elif_clause)
+  (else_clause)
+  (for_statement)
+  (while_statement)
+  (try_statement)
+  (except_clause)
+  (finally_clause)
+  (with_statement)
+  (match_statement)
+  (case_clause)
+  (parameters)
+  (argument_list)
+  (list)
+  (dictionary)
+  (tuple)
+  (string)
+] @fold
@@ -0,0 +1,20 @@
+[
+  (mod_item)
+  (foreign_mod_item)
+  (function_item)
+  (struct_item)
+  (enum_item)
+  (union_item)
+  (trait_item)
+  (impl_item)
+  (macro_definition)
+  (macro_invocation)
+  (block)
+  (match_block)
+  (use_list)
+  (parameters)
+  (arguments)
+  (array_expression)
+  (field_initializer_list)
+  (block_comment)
+] @fold
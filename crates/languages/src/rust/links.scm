@@ -0,0 +1,4 @@
+(macro_invocation
+  macro: (identifier) @_macro
+  (token_tree . (string_literal) @link)
+  (#any-of? @_macro "include" "include_str" "include_bytes"))
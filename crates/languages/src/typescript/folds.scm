@@ -0,0 +1,21 @@
+[
+  (function_declaration)
+  (generator_function_declaration)
+  (class_declaration)
+  (abstract_class_declaration)
+  (interface_declaration)
+  (enum_declaration)
+  (internal_module)
+  (method_definition)
+  (statement_block)
+  (class_body)
+  (switch_body)
+  (object)
+  (object_type)
+  (array)
+  (arguments)
+  (formal_parameters)
+  (named_imports)
+  (template_string)
+  (comment)
+] @fold
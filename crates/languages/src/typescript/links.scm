@@ -0,0 +1,10 @@
+(import_statement
+  source: (string) @link)
+
+(export_statement
+  source: (string) @link)
+
+(call_expression
+  function: (identifier) @_function
+  arguments: (arguments . (string) @link)
+  (#eq? @_function "require"))
@@ -0,0 +1,6 @@
+[
+  (block_mapping_pair)
+  (block_sequence_item)
+  (flow_mapping)
+  (flow_sequence)
+] @fold
@@ -86,6 +86,7 @@ pub struct LanguageServer {
     process_name: Arc<str>,
     binary: LanguageServerBinary,
     capabilities: RwLock<ServerCapabilities>,
+    extra_capabilities: RwLock<ExtraServerCapabilities>,
     /// Configuration sent to the server, stored for display in the language server logs
     /// buffer. This is represented as the message sent to the LSP in order to avoid cloning it (can
     /// be large in cases like sending schemas to the json server).
@@ -290,11 +291,30 @@ impl<F: Future> LspRequestFuture<F::Output> for LspRequest<F> {
     }
 }
 
+/// Capabilities reported by the server that are not part of [`ServerCapabilities`].
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraServerCapabilities {
+    pub type_hierarchy_provider: Option<OneOf<bool, TypeHierarchyOptions>>,
+}
+
+/// The `initialize` request, with the result left untyped so that capabilities
+/// missing from [`ServerCapabilities`] can be read from it as well.
+enum InitializeRaw {}
+
+impl request::Request for InitializeRaw {
+    type Params = InitializeParams;
+    type Result = Value;
+    const METHOD: &'static str = request::Initialize::METHOD;
+}
+
 /// Combined capabilities of the server and the adapter.
 #[derive(Debug)]
 pub struct AdapterServerCapabilities {
     // Reported capabilities by the server
     pub server_capabilities: ServerCapabilities,
+    // Reported capabilities by the server, that are not modelled by `ServerCapabilities`
+    pub extra_capabilities: ExtraServerCapabilities,
     // List of code actions supported by the LspAdapter matching the server
     pub code_action_kinds: Option<Vec<CodeActionKind>>,
 }
@@ -462,6 +482,7 @@ impl LanguageServer {
                 .unwrap_or_default(),
             binary,
             capabilities: Default::default(),
+            extra_capabilities: Default::default(),
             configuration,
             code_action_kinds,
             next_id: Default::default(),
@@ -635,11 +656,14 @@ impl LanguageServer {
                         refresh_support: Some(true),
                     }),
                     diagnostic: Some(DiagnosticWorkspaceClientCapabilities {
-                        refresh_support: None,
+                        refresh_support: Some(true),
                     }),
                     code_lens: Some(CodeLensWorkspaceClientCapabilities {
                         refresh_support: Some(true),
                     }),
+                    semantic_tokens: Some(SemanticTokensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     workspace_edit: Some(WorkspaceEditClientCapabilities {
                         resource_operations: Some(vec![
                             ResourceOperationKind::Create,
@@ -778,6 +802,82 @@ impl LanguageServer {
                         hierarchical_document_symbol_support: Some(true),
                         ..DocumentSymbolClientCapabilities::default()
                     }),
+                    semantic_tokens: Some(SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(false),
+                        requests: SemanticTokensClientCapabilitiesRequests {
+                            range: None,
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: vec![
+                            SemanticTokenType::NAMESPACE,
+                            SemanticTokenType::TYPE,
+                            SemanticTokenType::CLASS,
+                            SemanticTokenType::ENUM,
+                            SemanticTokenType::INTERFACE,
+                            SemanticTokenType::STRUCT,
+                            SemanticTokenType::TYPE_PARAMETER,
+                            SemanticTokenType::PARAMETER,
+                            SemanticTokenType::VARIABLE,
+                            SemanticTokenType::PROPERTY,
+                            SemanticTokenType::ENUM_MEMBER,
+                            SemanticTokenType::EVENT,
+                            SemanticTokenType::FUNCTION,
+                            SemanticTokenType::METHOD,
+                            SemanticTokenType::MACRO,
+                            SemanticTokenType::KEYWORD,
+                            SemanticTokenType::MODIFIER,
+                            SemanticTokenType::COMMENT,
+                            SemanticTokenType::STRING,
+                            SemanticTokenType::NUMBER,
+                            SemanticTokenType::REGEXP,
+                            SemanticTokenType::OPERATOR,
+                            SemanticTokenType::DECORATOR,
+                        ],
+                        token_modifiers: vec![
+                            SemanticTokenModifier::DECLARATION,
+                            SemanticTokenModifier::DEFINITION,
+                            SemanticTokenModifier::READONLY,
+                            SemanticTokenModifier::STATIC,
+                            SemanticTokenModifier::DEPRECATED,
+                            SemanticTokenModifier::ABSTRACT,
+                            SemanticTokenModifier::ASYNC,
+                            SemanticTokenModifier::MODIFICATION,
+                            SemanticTokenModifier::DOCUMENTATION,
+                            SemanticTokenModifier::DEFAULT_LIBRARY,
+                        ],
+                        formats: vec![TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        server_cancel_support: Some(true),
+                        augments_syntax_tokens: Some(true),
+                    }),
+                    folding_range: Some(FoldingRangeClientCapabilities {
+                        line_folding_only: Some(true),
+                        ..FoldingRangeClientCapabilities::default()
+                    }),
+                    document_link: Some(DocumentLinkClientCapabilities {
+                        dynamic_registration: None,
+                        tooltip_support: Some(true),
+                    }),
+                    call_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    inline_value: Some(InlineValueClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    color_provider: Some(DocumentColorClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    selection_range: Some(SelectionRangeClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    type_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    diagnostic: Some(DiagnosticClientCapabilities {
+                        dynamic_registration: Some(true),
+                        related_document_support: Some(true),
+                    }),
                     ..TextDocumentClientCapabilities::default()
                 }),
                 experimental: Some(json!({
@@ -817,11 +917,21 @@ impl LanguageServer {
         cx: &App,
     ) -> Task<Result<Arc<Self>>> {
         cx.spawn(async move |_| {
-            let response = self.request::<request::Initialize>(params).await?;
+            let response = self.request::<InitializeRaw>(params).await?;
+            let extra_capabilities = response
+                .get("capabilities")
+                .cloned()
+                .map(serde_json::from_value::<ExtraServerCapabilities>)
+                .transpose()
+                .log_err()
+                .flatten()
+                .unwrap_or_default();
+            let response = serde_json::from_value::<InitializeResult>(response)?;
             if let Some(info) = response.server_info {
                 self.process_name = info.name.into();
             }
             self.capabilities = RwLock::new(response.capabilities);
+            self.extra_capabilities = RwLock::new(extra_capabilities);
             self.configuration = configuration;
 
             self.notify::<notification::Initialized>(&InitializedParams {})?;
@@ -1050,6 +1160,7 @@ impl LanguageServer {
     pub fn adapter_server_capabilities(&self) -> AdapterServerCapabilities {
         AdapterServerCapabilities {
             server_capabilities: self.capabilities(),
+            extra_capabilities: self.extra_capabilities(),
             code_action_kinds: self.code_action_kinds(),
         }
     }
@@ -1058,6 +1169,15 @@ impl LanguageServer {
         update(self.capabilities.write().deref_mut());
     }
 
+    /// Get the reported capabilities of the running language server, that are not part of [`ServerCapabilities`].
+    pub fn extra_capabilities(&self) -> ExtraServerCapabilities {
+        self.extra_capabilities.read().clone()
+    }
+
+    pub fn update_extra_capabilities(&self, update: impl FnOnce(&mut ExtraServerCapabilities)) {
+        update(self.extra_capabilities.write().deref_mut());
+    }
+
     pub fn configuration(&self) -> &Value {
         &self.configuration.settings
     }
@@ -4,7 +4,7 @@
 use anyhow::{Result, anyhow};
 use breakpoints_in_file::BreakpointsInFile;
 use collections::BTreeMap;
-use dap::client::SessionId;
+use dap::{StackFrameId, client::SessionId};
 use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, Subscription, Task};
 use language::{Buffer, BufferSnapshot, proto::serialize_anchor as serialize_text_anchor};
 use rpc::{
@@ -70,10 +70,21 @@ enum BreakpointStoreMode {
     Local(LocalBreakpointStore),
     Remote(RemoteBreakpointStore),
 }
+
+/// The stack frame selected in the debugger, which editors highlight and show inline values for.
+#[derive(Clone, Debug)]
+pub struct ActiveStackFrame {
+    pub session_id: SessionId,
+    pub stack_frame_id: StackFrameId,
+    pub path: Arc<Path>,
+    pub position: text::Anchor,
+}
+
 pub struct BreakpointStore {
     breakpoints: BTreeMap<Arc<Path>, BreakpointsInFile>,
+    non_source_breakpoints: NonSourceBreakpoints,
     downstream_client: Option<(AnyProtoClient, u64)>,
-    active_stack_frame: Option<(SessionId, Arc<Path>, text::Anchor)>,
+    active_stack_frame: Option<ActiveStackFrame>,
     // E.g ssh
     mode: BreakpointStoreMode,
 }
@@ -82,10 +93,13 @@ impl BreakpointStore {
     pub fn init(client: &AnyProtoClient) {
         client.add_entity_request_handler(Self::handle_toggle_breakpoint);
         client.add_entity_message_handler(Self::handle_breakpoints_for_file);
+        client.add_entity_request_handler(Self::handle_set_non_source_breakpoints);
+        client.add_entity_message_handler(Self::handle_non_source_breakpoints);
     }
     pub fn local(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            non_source_breakpoints: NonSourceBreakpoints::default(),
             mode: BreakpointStoreMode::Local(LocalBreakpointStore {
                 worktree_store,
                 buffer_store,
@@ -98,6 +112,7 @@ impl BreakpointStore {
     pub(crate) fn remote(upstream_project_id: u64, upstream_client: AnyProtoClient) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            non_source_breakpoints: NonSourceBreakpoints::default(),
             mode: BreakpointStoreMode::Remote(RemoteBreakpointStore {
                 upstream_client,
                 _upstream_project_id: upstream_project_id,
@@ -202,6 +217,41 @@ impl BreakpointStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_set_non_source_breakpoints(
+        this: Entity<Project>,
+        message: TypedEnvelope<proto::SetNonSourceBreakpoints>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let breakpoints = this.update(&mut cx, |this, _| this.breakpoint_store())?;
+        let non_source_breakpoints = NonSourceBreakpoints::from_proto(
+            message.payload.exception_breakpoints,
+            message.payload.function_breakpoints,
+            message.payload.data_breakpoints,
+        );
+        breakpoints.update(&mut cx, |this, cx| {
+            this.set_non_source_breakpoints(non_source_breakpoints, cx);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_non_source_breakpoints(
+        this: Entity<Project>,
+        message: TypedEnvelope<proto::NonSourceBreakpoints>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let breakpoints = cx.update(|cx| this.read(cx).breakpoint_store())?;
+        breakpoints.update(&mut cx, |this, cx| {
+            this.non_source_breakpoints = NonSourceBreakpoints::from_proto(
+                message.payload.exception_breakpoints,
+                message.payload.function_breakpoints,
+                message.payload.data_breakpoints,
+            );
+            cx.emit(BreakpointStoreEvent::NonSourceBreakpointsUpdated);
+            cx.notify();
+        })?;
+        Ok(())
+    }
+
     pub(crate) fn broadcast(&self) {
         if let Some((client, project_id)) = &self.downstream_client {
             for (path, breakpoint_set) in &self.breakpoints {
@@ -215,6 +265,7 @@ impl BreakpointStore {
                         .collect(),
                 });
             }
+            let _ = client.send(self.non_source_breakpoints.to_proto(*project_id));
         }
     }
 
@@ -406,6 +457,140 @@ impl BreakpointStore {
         let breakpoint_paths = self.breakpoints.keys().cloned().collect();
         self.breakpoints.clear();
         cx.emit(BreakpointStoreEvent::BreakpointsCleared(breakpoint_paths));
+
+        // Exception filters are settings of the adapter rather than breakpoints, so those are kept.
+        if !self.non_source_breakpoints.function_breakpoints.is_empty()
+            || !self.non_source_breakpoints.data_breakpoints.is_empty()
+        {
+            self.set_non_source_breakpoints(
+                NonSourceBreakpoints {
+                    exception_breakpoints: self
+                        .non_source_breakpoints
+                        .exception_breakpoints
+                        .clone(),
+                    function_breakpoints: Vec::new(),
+                    data_breakpoints: Vec::new(),
+                },
+                cx,
+            );
+        }
+    }
+
+    pub fn non_source_breakpoints(&self) -> &NonSourceBreakpoints {
+        &self.non_source_breakpoints
+    }
+
+    /// Enables or disables the exception filter with the id given, overriding the adapter's default for it.
+    pub fn set_exception_breakpoint(
+        &mut self,
+        breakpoint: ExceptionBreakpoint,
+        cx: &mut Context<Self>,
+    ) {
+        let mut breakpoints = self.non_source_breakpoints.clone();
+        match breakpoints
+            .exception_breakpoints
+            .iter_mut()
+            .find(|other| other.filter_id == breakpoint.filter_id)
+        {
+            Some(other) => *other = breakpoint,
+            None => breakpoints.exception_breakpoints.push(breakpoint),
+        }
+        self.set_non_source_breakpoints(breakpoints, cx);
+    }
+
+    /// Adds a breakpoint on the function with the name given, or updates the existing one.
+    pub fn set_function_breakpoint(
+        &mut self,
+        breakpoint: FunctionBreakpoint,
+        cx: &mut Context<Self>,
+    ) {
+        let mut breakpoints = self.non_source_breakpoints.clone();
+        match breakpoints
+            .function_breakpoints
+            .iter_mut()
+            .find(|other| other.name == breakpoint.name)
+        {
+            Some(other) => *other = breakpoint,
+            None => breakpoints.function_breakpoints.push(breakpoint),
+        }
+        self.set_non_source_breakpoints(breakpoints, cx);
+    }
+
+    pub fn remove_function_breakpoint(&mut self, name: &str, cx: &mut Context<Self>) {
+        let mut breakpoints = self.non_source_breakpoints.clone();
+        breakpoints
+            .function_breakpoints
+            .retain(|breakpoint| breakpoint.name.as_ref() != name);
+        self.set_non_source_breakpoints(breakpoints, cx);
+    }
+
+    /// Adds a data breakpoint (a watchpoint) for the adapter's data id given, or updates the existing one.
+    pub fn set_data_breakpoint(&mut self, breakpoint: DataBreakpoint, cx: &mut Context<Self>) {
+        let mut breakpoints = self.non_source_breakpoints.clone();
+        match breakpoints
+            .data_breakpoints
+            .iter_mut()
+            .find(|other| other.data_id == breakpoint.data_id)
+        {
+            Some(other) => *other = breakpoint,
+            None => breakpoints.data_breakpoints.push(breakpoint),
+        }
+        self.set_non_source_breakpoints(breakpoints, cx);
+    }
+
+    pub fn remove_data_breakpoint(&mut self, data_id: &str, cx: &mut Context<Self>) {
+        let mut breakpoints = self.non_source_breakpoints.clone();
+        breakpoints
+            .data_breakpoints
+            .retain(|breakpoint| breakpoint.data_id.as_ref() != data_id);
+        self.set_non_source_breakpoints(breakpoints, cx);
+    }
+
+    /// Drops the data breakpoints whose data ids were only valid in the session given, once it has ended.
+    pub fn remove_session_data_breakpoints(
+        &mut self,
+        session_id: SessionId,
+        cx: &mut Context<Self>,
+    ) {
+        let mut breakpoints = self.non_source_breakpoints.clone();
+        breakpoints
+            .data_breakpoints
+            .retain(|breakpoint| breakpoint.session_id != Some(session_id));
+        self.set_non_source_breakpoints(breakpoints, cx);
+    }
+
+    /// Replaces all exception, function and data breakpoints, propagating them to the collaborators.
+    pub fn set_non_source_breakpoints(
+        &mut self,
+        breakpoints: NonSourceBreakpoints,
+        cx: &mut Context<Self>,
+    ) {
+        if self.non_source_breakpoints == breakpoints {
+            return;
+        }
+        self.non_source_breakpoints = breakpoints;
+
+        if let BreakpointStoreMode::Remote(remote) = &self.mode {
+            let message = self
+                .non_source_breakpoints
+                .to_proto(remote._upstream_project_id);
+            cx.background_spawn(
+                remote
+                    .upstream_client
+                    .request(proto::SetNonSourceBreakpoints {
+                        project_id: message.project_id,
+                        exception_breakpoints: message.exception_breakpoints,
+                        function_breakpoints: message.function_breakpoints,
+                        data_breakpoints: message.data_breakpoints,
+                    }),
+            )
+            .detach();
+        } else if let Some((client, project_id)) = &self.downstream_client {
+            let _ = client.send(self.non_source_breakpoints.to_proto(*project_id));
+        }
+
+        cx.emit(BreakpointStoreEvent::NonSourceBreakpointsUpdated);
+        cx.notify();
     }
 
     pub fn breakpoints<'a>(
@@ -434,7 +619,7 @@ impl BreakpointStore {
             })
     }
 
-    pub fn active_position(&self) -> Option<&(SessionId, Arc<Path>, text::Anchor)> {
+    pub fn active_position(&self) -> Option<&ActiveStackFrame> {
         self.active_stack_frame.as_ref()
     }
 
@@ -445,7 +630,7 @@ impl BreakpointStore {
     ) {
         if let Some(session_id) = session_id {
             self.active_stack_frame
-                .take_if(|(id, _, _)| *id == session_id);
+                .take_if(|frame| frame.session_id == session_id);
         } else {
             self.active_stack_frame.take();
         }
@@ -454,11 +639,7 @@ impl BreakpointStore {
         cx.notify();
     }
 
-    pub fn set_active_position(
-        &mut self,
-        position: (SessionId, Arc<Path>, text::Anchor),
-        cx: &mut Context<Self>,
-    ) {
+    pub fn set_active_position(&mut self, position: ActiveStackFrame, cx: &mut Context<Self>) {
         self.active_stack_frame = Some(position);
         cx.emit(BreakpointStoreEvent::ActiveDebugLineChanged);
         cx.notify();
@@ -593,6 +774,7 @@ pub enum BreakpointStoreEvent {
     ActiveDebugLineChanged,
     BreakpointsUpdated(Arc<Path>, BreakpointUpdatedReason),
     BreakpointsCleared(Vec<Arc<Path>>),
+    NonSourceBreakpointsUpdated,
 }
 
 impl EventEmitter<BreakpointStoreEvent> for BreakpointStore {}
@@ -632,6 +814,20 @@ impl BreakpointState {
             BreakpointState::Disabled => 1,
         }
     }
+
+    fn to_proto(self) -> i32 {
+        match self {
+            BreakpointState::Enabled => proto::BreakpointState::Enabled.into(),
+            BreakpointState::Disabled => proto::BreakpointState::Disabled.into(),
+        }
+    }
+
+    fn from_proto(state: i32) -> Self {
+        match proto::BreakpointState::from_i32(state) {
+            Some(proto::BreakpointState::Disabled) => BreakpointState::Disabled,
+            None | Some(proto::BreakpointState::Enabled) => BreakpointState::Enabled,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -674,10 +870,7 @@ impl Breakpoint {
     fn to_proto(&self, _path: &Path, position: &text::Anchor) -> Option<client::proto::Breakpoint> {
         Some(client::proto::Breakpoint {
             position: Some(serialize_text_anchor(position)),
-            state: match self.state {
-                BreakpointState::Enabled => proto::BreakpointState::Enabled.into(),
-                BreakpointState::Disabled => proto::BreakpointState::Disabled.into(),
-            },
+            state: self.state.to_proto(),
             message: self.message.as_ref().map(|s| String::from(s.as_ref())),
             condition: self.condition.as_ref().map(|s| String::from(s.as_ref())),
             hit_condition: self
@@ -689,10 +882,7 @@ impl Breakpoint {
 
     fn from_proto(breakpoint: client::proto::Breakpoint) -> Option<Self> {
         Some(Self {
-            state: match proto::BreakpointState::from_i32(breakpoint.state) {
-                Some(proto::BreakpointState::Disabled) => BreakpointState::Disabled,
-                None | Some(proto::BreakpointState::Enabled) => BreakpointState::Enabled,
-            },
+            state: BreakpointState::from_proto(breakpoint.state),
             message: breakpoint.message.map(Into::into),
             condition: breakpoint.condition.map(Into::into),
             hit_condition: breakpoint.hit_condition.map(Into::into),
@@ -737,3 +927,298 @@ impl From<SourceBreakpoint> for dap::SourceBreakpoint {
         }
     }
 }
+
+/// Breakpoints that are not set on a source line, but on thrown exceptions, function names or variable values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NonSourceBreakpoints {
+    /// Exception filters the user has toggled; the adapter's defaults are used for the other ones.
+    pub exception_breakpoints: Vec<ExceptionBreakpoint>,
+    pub function_breakpoints: Vec<FunctionBreakpoint>,
+    pub data_breakpoints: Vec<DataBreakpoint>,
+}
+
+impl NonSourceBreakpoints {
+    /// Splits the enabled exception filters, out of the ones the adapter supports, into the plain ones
+    /// and the ones with a condition, for a `setExceptionBreakpoints` request.
+    pub fn exception_filters(
+        &self,
+        filters: &[dap::ExceptionBreakpointsFilter],
+        supports_filter_options: bool,
+    ) -> (Vec<String>, Vec<dap::ExceptionFilterOptions>) {
+        let mut plain_filters = Vec::new();
+        let mut filter_options = Vec::new();
+        for filter in filters {
+            let breakpoint = self
+                .exception_breakpoints
+                .iter()
+                .find(|breakpoint| breakpoint.filter_id.as_ref() == filter.filter);
+            let enabled = match breakpoint {
+                Some(breakpoint) => breakpoint.is_enabled(),
+                None => filter.default.unwrap_or(false),
+            };
+            if !enabled {
+                continue;
+            }
+            match breakpoint.and_then(|breakpoint| breakpoint.condition.as_ref()) {
+                Some(condition) if supports_filter_options => {
+                    filter_options.push(dap::ExceptionFilterOptions {
+                        filter_id: filter.filter.clone(),
+                        condition: Some(String::from(condition.as_ref())),
+                        mode: None,
+                    })
+                }
+                _ => plain_filters.push(filter.filter.clone()),
+            }
+        }
+        (plain_filters, filter_options)
+    }
+
+    fn to_proto(&self, project_id: u64) -> proto::NonSourceBreakpoints {
+        proto::NonSourceBreakpoints {
+            project_id,
+            exception_breakpoints: self
+                .exception_breakpoints
+                .iter()
+                .map(|breakpoint| proto::ExceptionBreakpoint {
+                    filter_id: breakpoint.filter_id.to_string(),
+                    state: breakpoint.state.to_proto(),
+                    condition: breakpoint.condition.as_ref().map(|s| s.to_string()),
+                })
+                .collect(),
+            function_breakpoints: self
+                .function_breakpoints
+                .iter()
+                .map(|breakpoint| proto::FunctionBreakpoint {
+                    name: breakpoint.name.to_string(),
+                    state: breakpoint.state.to_proto(),
+                    condition: breakpoint.condition.as_ref().map(|s| s.to_string()),
+                    hit_condition: breakpoint.hit_condition.as_ref().map(|s| s.to_string()),
+                })
+                .collect(),
+            data_breakpoints: self
+                .data_breakpoints
+                .iter()
+                .map(|breakpoint| proto::DataBreakpoint {
+                    data_id: breakpoint.data_id.to_string(),
+                    session_id: breakpoint
+                        .session_id
+                        .map(|session_id| session_id.to_proto()),
+                    description: breakpoint.description.to_string(),
+                    state: breakpoint.state.to_proto(),
+                    access_type: breakpoint
+                        .access_type
+                        .map(|access_type| access_type.to_proto()),
+                    condition: breakpoint.condition.as_ref().map(|s| s.to_string()),
+                    hit_condition: breakpoint.hit_condition.as_ref().map(|s| s.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    fn from_proto(
+        exception_breakpoints: Vec<proto::ExceptionBreakpoint>,
+        function_breakpoints: Vec<proto::FunctionBreakpoint>,
+        data_breakpoints: Vec<proto::DataBreakpoint>,
+    ) -> Self {
+        Self {
+            exception_breakpoints: exception_breakpoints
+                .into_iter()
+                .map(|breakpoint| ExceptionBreakpoint {
+                    filter_id: breakpoint.filter_id.into(),
+                    state: BreakpointState::from_proto(breakpoint.state),
+                    condition: breakpoint.condition.map(Into::into),
+                })
+                .collect(),
+            function_breakpoints: function_breakpoints
+                .into_iter()
+                .map(|breakpoint| FunctionBreakpoint {
+                    name: breakpoint.name.into(),
+                    state: BreakpointState::from_proto(breakpoint.state),
+                    condition: breakpoint.condition.map(Into::into),
+                    hit_condition: breakpoint.hit_condition.map(Into::into),
+                })
+                .collect(),
+            data_breakpoints: data_breakpoints
+                .into_iter()
+                .map(|breakpoint| DataBreakpoint {
+                    data_id: breakpoint.data_id.into(),
+                    session_id: breakpoint.session_id.map(SessionId::from_proto),
+                    description: breakpoint.description.into(),
+                    state: BreakpointState::from_proto(breakpoint.state),
+                    access_type: breakpoint
+                        .access_type
+                        .and_then(DataBreakpointAccessType::from_proto),
+                    condition: breakpoint.condition.map(Into::into),
+                    hit_condition: breakpoint.hit_condition.map(Into::into),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// An exception filter advertised by the debug adapter (e.g. "uncaught exceptions"), toggled by the user.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ExceptionBreakpoint {
+    pub filter_id: Arc<str>,
+    /// Only stop on the exceptions the condition holds for, if the adapter supports conditions for the filter.
+    pub condition: Option<BreakpointMessage>,
+    pub state: BreakpointState,
+}
+
+impl ExceptionBreakpoint {
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.state.is_enabled()
+    }
+}
+
+/// Breakpoint on the entry of a function, looked up by its name.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FunctionBreakpoint {
+    pub name: Arc<str>,
+    pub condition: Option<BreakpointMessage>,
+    pub hit_condition: Option<BreakpointMessage>,
+    pub state: BreakpointState,
+}
+
+impl FunctionBreakpoint {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+        }
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.state.is_enabled()
+    }
+}
+
+impl From<FunctionBreakpoint> for dap::FunctionBreakpoint {
+    fn from(bp: FunctionBreakpoint) -> Self {
+        Self {
+            name: String::from(bp.name.as_ref()),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
+
+/// How a variable has to be accessed for a data breakpoint to stop.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum DataBreakpointAccessType {
+    Write,
+    Read,
+    ReadWrite,
+}
+
+impl DataBreakpointAccessType {
+    #[inline]
+    pub fn to_int(&self) -> i32 {
+        match self {
+            DataBreakpointAccessType::Write => 0,
+            DataBreakpointAccessType::Read => 1,
+            DataBreakpointAccessType::ReadWrite => 2,
+        }
+    }
+
+    pub fn from_int(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(DataBreakpointAccessType::Write),
+            1 => Some(DataBreakpointAccessType::Read),
+            2 => Some(DataBreakpointAccessType::ReadWrite),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataBreakpointAccessType::Write => "Break on Value Change",
+            DataBreakpointAccessType::Read => "Break on Value Read",
+            DataBreakpointAccessType::ReadWrite => "Break on Value Access",
+        }
+    }
+
+    fn to_proto(self) -> i32 {
+        match self {
+            DataBreakpointAccessType::Write => proto::DataBreakpointAccessType::Write.into(),
+            DataBreakpointAccessType::Read => proto::DataBreakpointAccessType::Read.into(),
+            DataBreakpointAccessType::ReadWrite => {
+                proto::DataBreakpointAccessType::ReadWrite.into()
+            }
+        }
+    }
+
+    fn from_proto(access_type: i32) -> Option<Self> {
+        match proto::DataBreakpointAccessType::from_i32(access_type)? {
+            proto::DataBreakpointAccessType::Write => Some(DataBreakpointAccessType::Write),
+            proto::DataBreakpointAccessType::Read => Some(DataBreakpointAccessType::Read),
+            proto::DataBreakpointAccessType::ReadWrite => Some(DataBreakpointAccessType::ReadWrite),
+        }
+    }
+}
+
+impl From<DataBreakpointAccessType> for dap::DataBreakpointAccessType {
+    fn from(access_type: DataBreakpointAccessType) -> Self {
+        match access_type {
+            DataBreakpointAccessType::Write => dap::DataBreakpointAccessType::Write,
+            DataBreakpointAccessType::Read => dap::DataBreakpointAccessType::Read,
+            DataBreakpointAccessType::ReadWrite => dap::DataBreakpointAccessType::ReadWrite,
+        }
+    }
+}
+
+impl From<dap::DataBreakpointAccessType> for DataBreakpointAccessType {
+    fn from(access_type: dap::DataBreakpointAccessType) -> Self {
+        match access_type {
+            dap::DataBreakpointAccessType::Write => DataBreakpointAccessType::Write,
+            dap::DataBreakpointAccessType::Read => DataBreakpointAccessType::Read,
+            dap::DataBreakpointAccessType::ReadWrite => DataBreakpointAccessType::ReadWrite,
+        }
+    }
+}
+
+/// Breakpoint on a variable's value (a watchpoint), identified by the data id the adapter has assigned to it.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct DataBreakpoint {
+    pub data_id: Arc<str>,
+    /// The session the data id was assigned in, as data ids are only valid in that session, unless the adapter
+    /// allows persisting them (`None`). Such breakpoints are only sent to that session, and dropped when it ends.
+    pub session_id: Option<SessionId>,
+    /// Human-readable name of the data, as described by the adapter.
+    pub description: Arc<str>,
+    /// `None` for the adapter's default access type, which is usually a write.
+    pub access_type: Option<DataBreakpointAccessType>,
+    pub condition: Option<BreakpointMessage>,
+    pub hit_condition: Option<BreakpointMessage>,
+    pub state: BreakpointState,
+}
+
+impl DataBreakpoint {
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.state.is_enabled()
+    }
+}
+
+impl From<DataBreakpoint> for dap::DataBreakpoint {
+    fn from(bp: DataBreakpoint) -> Self {
+        Self {
+            data_id: String::from(bp.data_id.as_ref()),
+            access_type: bp.access_type.map(Into::into),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
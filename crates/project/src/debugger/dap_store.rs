@@ -747,6 +747,9 @@ impl DapStore {
         let Some(session) = self.sessions.remove(&session_id) else {
             return Task::ready(Err(anyhow!("Could not find session: {:?}", session_id)));
         };
+        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.remove_session_data_breakpoints(session_id, cx)
+        });
 
         let shutdown_children = session
             .read(cx)
@@ -2,12 +2,12 @@ use crate::project_settings::ProjectSettings;
 
 use super::breakpoint_store::{BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason};
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetVariableValueCommand, StackTraceCommand, StepBackCommand, StepCommand,
-    StepInCommand, StepOutCommand, TerminateCommand, TerminateThreadsCommand, ThreadsCommand,
-    VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DapCommand, DataBreakpointInfoCommand,
+    DisconnectCommand, EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand,
+    LocationsCommand, ModulesCommand, NextCommand, PauseCommand, RestartCommand,
+    RestartStackFrameCommand, ScopesCommand, SetVariableValueCommand, StackTraceCommand,
+    StepBackCommand, StepCommand, StepInCommand, StepOutCommand, TerminateCommand,
+    TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapAdapterDelegate;
 use anyhow::{Result, anyhow};
@@ -493,6 +493,99 @@ impl LocalMode {
         })
     }
 
+    fn send_non_source_breakpoints(
+        &self,
+        capabilities: &Capabilities,
+        ignore_breakpoints: bool,
+        cx: &App,
+    ) -> Task<()> {
+        let session_id = self.client.id();
+        let breakpoints = self
+            .breakpoint_store
+            .read_with(cx, |store, _| store.non_source_breakpoints().clone());
+        let mut breakpoint_tasks = Vec::new();
+
+        if dap_command::SetExceptionBreakpoints::is_supported(capabilities) {
+            let (filters, filter_options) = if ignore_breakpoints {
+                Default::default()
+            } else {
+                breakpoints.exception_filters(
+                    capabilities
+                        .exception_breakpoint_filters
+                        .as_deref()
+                        .unwrap_or_default(),
+                    capabilities
+                        .supports_exception_filter_options
+                        .unwrap_or_default(),
+                )
+            };
+            breakpoint_tasks.push(
+                self.request(
+                    dap_command::SetExceptionBreakpoints {
+                        filters,
+                        filter_options,
+                    },
+                    cx.background_executor().clone(),
+                )
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            );
+        }
+
+        if dap_command::SetFunctionBreakpoints::is_supported(capabilities) {
+            let breakpoints = breakpoints
+                .function_breakpoints
+                .into_iter()
+                .filter(|bp| !ignore_breakpoints && bp.is_enabled())
+                .map(Into::into)
+                .collect();
+            breakpoint_tasks.push(
+                self.request(
+                    dap_command::SetFunctionBreakpoints { breakpoints },
+                    cx.background_executor().clone(),
+                )
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            );
+        }
+
+        if dap_command::SetDataBreakpoints::is_supported(capabilities) {
+            let breakpoints = breakpoints
+                .data_breakpoints
+                .into_iter()
+                .filter(|bp| {
+                    !ignore_breakpoints
+                        && bp.is_enabled()
+                        && bp.session_id.is_none_or(|id| id == session_id)
+                })
+                .map(Into::into)
+                .collect();
+            breakpoint_tasks.push(
+                self.request(
+                    dap_command::SetDataBreakpoints { breakpoints },
+                    cx.background_executor().clone(),
+                )
+                .map(|result| result.map(|_| ()))
+                .boxed(),
+            );
+        }
+
+        cx.background_spawn(async move {
+            futures::future::join_all(breakpoint_tasks)
+                .await
+                .iter()
+                .for_each(|res| match res {
+                    Ok(_) => {}
+                    Err(err) => {
+                        log::warn!(
+                            "Set exception, function or data breakpoints request failed: {}",
+                            err
+                        );
+                    }
+                });
+        })
+    }
+
     async fn get_adapter_binary(
         registry: &Arc<DapRegistry>,
         config: &DebugAdapterConfig,
@@ -578,11 +671,14 @@ impl LocalMode {
 
         let configuration_sequence = cx.spawn({
             let this = self.clone();
+            let capabilities = capabilities.clone();
             async move |cx| {
                 initialized_rx.await?;
                 // todo(debugger) figure out if we want to handle a breakpoint response error
                 // This will probably consist of letting a user know that breakpoints failed to be set
                 cx.update(|cx| this.send_all_breakpoints(false, cx))?.await;
+                cx.update(|cx| this.send_non_source_breakpoints(&capabilities, false, cx))?
+                    .await;
 
                 if configuration_done_supported {
                     this.request(ConfigurationDone, cx.background_executor().clone())
@@ -1378,6 +1474,34 @@ impl Session {
         self.ignore_breakpoints
     }
 
+    /// Asks the adapter whether a data breakpoint can be set on the variable, and for the data id to set it with.
+    pub fn data_breakpoint_info(
+        &self,
+        variables_reference: VariableReference,
+        name: String,
+        frame_id: Option<StackFrameId>,
+        cx: &App,
+    ) -> Task<Result<dap::DataBreakpointInfoResponse>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "Data breakpoints are not supported in remote sessions yet"
+            )));
+        };
+        if !DataBreakpointInfoCommand::is_supported(&self.capabilities) {
+            return Task::ready(Err(anyhow!(
+                "The debug adapter does not support data breakpoints"
+            )));
+        }
+        local.request(
+            DataBreakpointInfoCommand {
+                variables_reference,
+                name,
+                frame_id,
+            },
+            cx.background_executor().clone(),
+        )
+    }
+
     pub fn toggle_ignore_breakpoints(&mut self, cx: &mut App) -> Task<()> {
         self.set_ignore_breakpoints(!self.ignore_breakpoints, cx)
     }
@@ -1390,7 +1514,12 @@ impl Session {
         self.ignore_breakpoints = ignore;
 
         if let Some(local) = self.as_local() {
-            local.send_all_breakpoints(ignore, cx)
+            let source_breakpoints = local.send_all_breakpoints(ignore, cx);
+            let non_source_breakpoints =
+                local.send_non_source_breakpoints(&self.capabilities, ignore, cx);
+            cx.background_spawn(async move {
+                futures::future::join(source_breakpoints, non_source_breakpoints).await;
+            })
         } else {
             // todo(debugger): We need to propagate this change to downstream sessions and send a message to upstream sessions
             unimplemented!()
@@ -1888,6 +2017,55 @@ impl Session {
         .detach();
     }
 
+    /// Evaluates an expression in the given stack frame without echoing it to the debug console,
+    /// returning the textual result.
+    pub fn evaluate_silently(
+        &self,
+        expression: String,
+        stack_frame_id: StackFrameId,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<String>> {
+        let evaluation = self.request(
+            EvaluateCommand {
+                expression,
+                context: Some(EvaluateArgumentsContext::Watch),
+                frame_id: Some(stack_frame_id),
+                source: None,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        );
+        cx.background_spawn(async move { evaluation.await.map(|response| response.result) })
+    }
+
+    /// Returns the value of the variable with the given name in the non-expensive scopes of the
+    /// given stack frame. Scopes and variables that haven't been fetched yet are requested, and
+    /// [`SessionEvent::Variables`] is emitted once they arrive.
+    pub fn variable_value(
+        &mut self,
+        stack_frame_id: StackFrameId,
+        name: &str,
+        case_sensitive: bool,
+        cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let scopes = self.scopes(stack_frame_id, cx).to_vec();
+        scopes
+            .iter()
+            .filter(|scope| !scope.expensive)
+            .find_map(|scope| {
+                self.variables(scope.variables_reference, cx)
+                    .into_iter()
+                    .find(|variable| {
+                        if case_sensitive {
+                            variable.name == name
+                        } else {
+                            variable.name.eq_ignore_ascii_case(name)
+                        }
+                    })
+                    .map(|variable| variable.value)
+            })
+    }
+
     pub fn location(
         &mut self,
         reference: u64,
@@ -1988,6 +2166,16 @@ fn create_local_session(
                 local.unset_breakpoints_from_paths(paths, cx).detach();
             }
         }
+        BreakpointStoreEvent::NonSourceBreakpointsUpdated => {
+            if let Some(local) = (!this.ignore_breakpoints)
+                .then(|| this.as_local())
+                .flatten()
+            {
+                local
+                    .send_non_source_breakpoints(&this.capabilities, false, cx)
+                    .detach();
+            }
+        }
         BreakpointStoreEvent::ActiveDebugLineChanged => {}
     })
     .detach();
@@ -1,3 +1,4 @@
+pub mod conflicts;
 pub mod git_traversal;
 
 use crate::{
@@ -21,8 +22,11 @@ use git::{
     blame::Blame,
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, DiffType, GitRepository, GitRepositoryCheckpoint, PushOptions,
-        Remote, RemoteCommandOutput, RepoPath, ResetMode,
+        Branch, CommitDetails, CommitFileDiff, CommitOptions, CommitSignature, DiffRevisions,
+        DiffType, GitRepository, GitRepositoryCheckpoint, GitWorktree, LogEntry, LogOptions,
+        PushOptions, RebaseAction, RebaseCommand, RebaseStatus, RebaseStep, Remote,
+        RemoteCommandOutput, RepoPath, ResetMode, SequencerOperation, SignatureStatus, StashEntry,
+        StashOptions, Submodule, SubmoduleStatus, Tag,
     },
     status::FileStatus,
 };
@@ -37,7 +41,9 @@ use language::{
 use parking_lot::Mutex;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, FromProto, SSH_PROJECT_ID, ToProto, git_reset},
+    proto::{
+        self, FromProto, SSH_PROJECT_ID, ToProto, git_rebase_command, git_rebase_step, git_reset,
+    },
 };
 use serde::Deserialize;
 use settings::WorktreeId;
@@ -89,6 +95,8 @@ struct BufferDiffState {
 
     head_text: Option<Arc<String>>,
     index_text: Option<Arc<String>>,
+    /// The line endings of the index text before it was normalized.
+    index_line_ending: Option<text::LineEnding>,
     head_changed: bool,
     index_changed: bool,
     language_changed: bool,
@@ -287,6 +295,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_stage);
         client.add_entity_request_handler(Self::handle_unstage);
         client.add_entity_request_handler(Self::handle_commit);
+        client.add_entity_request_handler(Self::handle_commit_message_template);
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_checkout_files);
@@ -295,6 +304,38 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_askpass);
         client.add_entity_request_handler(Self::handle_check_for_pushed_commits);
         client.add_entity_request_handler(Self::handle_git_diff);
+        client.add_entity_request_handler(Self::handle_stash_list);
+        client.add_entity_request_handler(Self::handle_stash_push);
+        client.add_entity_request_handler(Self::handle_stash_diff);
+        client.add_entity_request_handler(Self::handle_stash_apply);
+        client.add_entity_request_handler(Self::handle_stash_pop);
+        client.add_entity_request_handler(Self::handle_stash_drop);
+        client.add_entity_request_handler(Self::handle_log);
+        client.add_entity_request_handler(Self::handle_commit_diff);
+        client.add_entity_request_handler(Self::handle_revision_diff);
+        client.add_entity_request_handler(Self::handle_worktree_list);
+        client.add_entity_request_handler(Self::handle_create_worktree);
+        client.add_entity_request_handler(Self::handle_remove_worktree);
+        client.add_entity_request_handler(Self::handle_prune_worktrees);
+        client.add_entity_request_handler(Self::handle_tag_list);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_push_tag);
+        client.add_entity_request_handler(Self::handle_add_remote);
+        client.add_entity_request_handler(Self::handle_rename_remote);
+        client.add_entity_request_handler(Self::handle_remove_remote);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_sequencer_command);
+        client.add_entity_request_handler(Self::handle_sequencer_status);
+        client.add_entity_request_handler(Self::handle_submodules);
+        client.add_entity_request_handler(Self::handle_update_submodules);
+        client.add_entity_request_handler(Self::handle_sync_submodules);
+        client.add_entity_request_handler(Self::handle_submodule_diff);
+        client.add_entity_request_handler(Self::handle_rebase_commits);
+        client.add_entity_request_handler(Self::handle_rebase);
+        client.add_entity_request_handler(Self::handle_rebase_command);
+        client.add_entity_request_handler(Self::handle_rebase_status);
         client.add_entity_request_handler(Self::handle_open_unstaged_diff);
         client.add_entity_request_handler(Self::handle_open_uncommitted_diff);
         client.add_entity_message_handler(Self::handle_update_diff_bases);
@@ -1161,35 +1202,42 @@ impl GitStore {
         event: &BufferDiffEvent,
         cx: &mut Context<Self>,
     ) {
-        if let BufferDiffEvent::HunksStagedOrUnstaged(new_index_text) = event {
-            let buffer_id = diff.read(cx).buffer_id;
-            if let Some(diff_state) = self.diffs.get(&buffer_id) {
-                diff_state.update(cx, |diff_state, _| {
-                    diff_state.hunk_staging_operation_count += 1;
-                });
+        let (new_index_text, staged_lines) = match event {
+            BufferDiffEvent::HunksStagedOrUnstaged(new_index_text) => (new_index_text, false),
+            BufferDiffEvent::LinesStagedOrUnstaged(new_index_text) => (new_index_text, true),
+            _ => return,
+        };
+        let buffer_id = diff.read(cx).buffer_id;
+        let mut index_line_ending = None;
+        if let Some(diff_state) = self.diffs.get(&buffer_id) {
+            diff_state.update(cx, |diff_state, _| {
+                diff_state.hunk_staging_operation_count += 1;
+                index_line_ending = diff_state.index_line_ending;
+            });
+        }
+        if let Some((repo, path)) = self.repository_and_path_for_buffer_id(buffer_id, cx) {
+            let mut new_index_text = new_index_text.as_ref().map(|rope| rope.to_string());
+            // Lines are staged into the normalized index text, so the line endings the index
+            // stores are restored for them.
+            if staged_lines && index_line_ending == Some(text::LineEnding::Windows) {
+                new_index_text = new_index_text.map(|text| text.replace('\n', "\r\n"));
             }
-            if let Some((repo, path)) = self.repository_and_path_for_buffer_id(buffer_id, cx) {
-                let recv = repo.update(cx, |repo, cx| {
-                    log::debug!("updating index text for buffer {}", path.display());
-                    repo.spawn_set_index_text_job(
-                        path,
-                        new_index_text.as_ref().map(|rope| rope.to_string()),
-                        cx,
-                    )
-                });
-                let diff = diff.downgrade();
-                cx.spawn(async move |this, cx| {
-                    if let Ok(Err(error)) = cx.background_spawn(recv).await {
-                        diff.update(cx, |diff, cx| {
-                            diff.clear_pending_hunks(cx);
-                        })
+            let recv = repo.update(cx, |repo, cx| {
+                log::debug!("updating index text for buffer {}", path.display());
+                repo.spawn_set_index_text_job(path, new_index_text, cx)
+            });
+            let diff = diff.downgrade();
+            cx.spawn(async move |this, cx| {
+                if let Ok(Err(error)) = cx.background_spawn(recv).await {
+                    diff.update(cx, |diff, cx| {
+                        diff.clear_pending_hunks(cx);
+                    })
+                    .ok();
+                    this.update(cx, |_, cx| cx.emit(GitEvent::IndexWriteError(error)))
                         .ok();
-                        this.update(cx, |_, cx| cx.emit(GitEvent::IndexWriteError(error)))
-                            .ok();
-                    }
-                })
-                .detach();
-            }
+                }
+            })
+            .detach();
         }
     }
 
@@ -1392,6 +1440,33 @@ impl GitStore {
         &self.repositories
     }
 
+    /// Returns the repository whose work directory is the closest one containing the work
+    /// directory of `repository`, such as the superproject of a submodule.
+    pub fn parent_repository(
+        &self,
+        repository: &Entity<Repository>,
+        cx: &App,
+    ) -> Option<Entity<Repository>> {
+        let work_directory = &repository.read(cx).repository_entry.work_directory_abs_path;
+        self.repositories
+            .values()
+            .filter(|candidate| {
+                let candidate_directory =
+                    &candidate.read(cx).repository_entry.work_directory_abs_path;
+                candidate_directory != work_directory
+                    && work_directory.starts_with(candidate_directory)
+            })
+            .max_by_key(|candidate| {
+                candidate
+                    .read(cx)
+                    .repository_entry
+                    .work_directory_abs_path
+                    .components()
+                    .count()
+            })
+            .cloned()
+    }
+
     pub fn status_for_buffer_id(&self, buffer_id: BufferId, cx: &App) -> Option<FileStatus> {
         let (repo, path) = self.repository_and_path_for_buffer_id(buffer_id, cx)?;
         let status = repo.read(cx).repository_entry.status_for_path(&path)?;
@@ -1770,18 +1845,45 @@ impl GitStore {
         let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
         let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
 
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+
         let message = SharedString::from(envelope.payload.message);
         let name = envelope.payload.name.map(SharedString::from);
         let email = envelope.payload.email.map(SharedString::from);
+        let options = CommitOptions {
+            signoff: envelope.payload.signoff,
+        };
 
         repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.commit(message, name.zip(email), cx)
+                repository_handle.commit(message, name.zip(email), options, askpass, cx)
             })?
             .await??;
         Ok(proto::Ack {})
     }
 
+    async fn handle_commit_message_template(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCommitMessageTemplate>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitMessageTemplateResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let template = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.commit_message_template(cx)
+            })?
+            .await??;
+        Ok(proto::GitCommitMessageTemplateResponse { template })
+    }
+
     async fn handle_get_remotes(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GetRemotes>,
@@ -1882,6 +1984,7 @@ impl GitStore {
             commit_timestamp: commit.commit_timestamp,
             committer_email: commit.committer_email.into(),
             committer_name: commit.committer_name.into(),
+            signature: commit.signature.map(commit_signature_to_proto),
         })
     }
 
@@ -2013,6 +2116,12 @@ impl GitStore {
         let diff_type = match envelope.payload.diff_type() {
             proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
             proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
+            proto::git_diff::DiffType::Revisions => DiffType::Revisions(diff_revisions_from_proto(
+                envelope
+                    .payload
+                    .revisions
+                    .ok_or_else(|| anyhow!("missing revisions"))?,
+            )),
         };
 
         let mut diff = repository_handle
@@ -2028,380 +2137,1083 @@ impl GitStore {
         Ok(proto::GitDiffResponse { diff })
     }
 
-    async fn handle_open_unstaged_diff(
+    async fn handle_stash_list(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::OpenUnstagedDiff>,
+        envelope: TypedEnvelope<proto::GitStashList>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenUnstagedDiffResponse> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        let diff = this
-            .update(&mut cx, |this, cx| {
-                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
-                Some(this.open_unstaged_diff(buffer, cx))
+    ) -> Result<proto::GitStashListResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_entries()
             })?
-            .ok_or_else(|| anyhow!("no such buffer"))?
-            .await?;
-        this.update(&mut cx, |this, _| {
-            let shared_diffs = this
-                .shared_diffs
-                .entry(request.original_sender_id.unwrap_or(request.sender_id))
-                .or_default();
-            shared_diffs.entry(buffer_id).or_default().unstaged = Some(diff.clone());
-        })?;
-        let staged_text = diff.read_with(&cx, |diff, _| diff.base_text_string())?;
-        Ok(proto::OpenUnstagedDiffResponse { staged_text })
+            .await??;
+
+        Ok(proto::GitStashListResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| proto::GitStashEntry {
+                    index: entry.index as u64,
+                    sha: entry.sha.into(),
+                    message: entry.message.into(),
+                    commit_timestamp: entry.commit_timestamp,
+                })
+                .collect(),
+        })
     }
 
-    async fn handle_open_uncommitted_diff(
+    async fn handle_stash_push(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::OpenUncommittedDiff>,
+        envelope: TypedEnvelope<proto::GitStashPush>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenUncommittedDiffResponse> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        let diff = this
-            .update(&mut cx, |this, cx| {
-                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
-                Some(this.open_uncommitted_diff(buffer, cx))
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let options = StashOptions {
+            message: envelope.payload.message,
+            include_untracked: envelope.payload.include_untracked,
+            staged: envelope.payload.staged,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_push(options, cx)
             })?
-            .ok_or_else(|| anyhow!("no such buffer"))?
-            .await?;
-        this.update(&mut cx, |this, _| {
-            let shared_diffs = this
-                .shared_diffs
-                .entry(request.original_sender_id.unwrap_or(request.sender_id))
-                .or_default();
-            shared_diffs.entry(buffer_id).or_default().uncommitted = Some(diff.clone());
-        })?;
-        diff.read_with(&cx, |diff, cx| {
-            use proto::open_uncommitted_diff_response::Mode;
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-            let unstaged_diff = diff.secondary_diff();
-            let index_snapshot = unstaged_diff.and_then(|diff| {
-                let diff = diff.read(cx);
-                diff.base_text_exists().then(|| diff.base_text())
-            });
+    async fn handle_stash_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitStashDiffResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
 
-            let mode;
-            let staged_text;
-            let committed_text;
-            if diff.base_text_exists() {
-                let committed_snapshot = diff.base_text();
-                committed_text = Some(committed_snapshot.text());
-                if let Some(index_text) = index_snapshot {
-                    if index_text.remote_id() == committed_snapshot.remote_id() {
-                        mode = Mode::IndexMatchesHead;
-                        staged_text = None;
-                    } else {
-                        mode = Mode::IndexAndHead;
-                        staged_text = Some(index_text.text());
-                    }
-                } else {
-                    mode = Mode::IndexAndHead;
-                    staged_text = None;
-                }
-            } else {
-                mode = Mode::IndexAndHead;
-                committed_text = None;
-                staged_text = index_snapshot.as_ref().map(|buffer| buffer.text());
-            }
+        let files = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_diff(envelope.payload.index as usize)
+            })?
+            .await??;
 
-            proto::OpenUncommittedDiffResponse {
-                committed_text,
-                staged_text,
-                mode: mode.into(),
-            }
+        Ok(proto::GitStashDiffResponse {
+            files: files
+                .into_iter()
+                .map(|file| proto::GitCommitFileDiff {
+                    path: file.path.to_string_lossy().to_string(),
+                    old_text: file.old_text,
+                    new_text: file.new_text,
+                })
+                .collect(),
         })
     }
 
-    async fn handle_update_diff_bases(
+    async fn handle_stash_apply(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::UpdateDiffBases>,
+        envelope: TypedEnvelope<proto::GitStashApply>,
         mut cx: AsyncApp,
-    ) -> Result<()> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        this.update(&mut cx, |this, cx| {
-            if let Some(diff_state) = this.diffs.get_mut(&buffer_id) {
-                if let Some(buffer) = this.buffer_store.read(cx).get(buffer_id) {
-                    let buffer = buffer.read(cx).text_snapshot();
-                    diff_state.update(cx, |diff_state, cx| {
-                        diff_state.handle_base_texts_updated(buffer, request.payload, cx);
-                    })
-                }
-            }
-        })
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_apply(envelope.payload.index as usize, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_blame_buffer(
+    async fn handle_stash_pop(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::BlameBuffer>,
+        envelope: TypedEnvelope<proto::GitStashPop>,
         mut cx: AsyncApp,
-    ) -> Result<proto::BlameBufferResponse> {
-        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
-        let version = deserialize_version(&envelope.payload.version);
-        let buffer = this.read_with(&cx, |this, cx| {
-            this.buffer_store.read(cx).get_existing(buffer_id)
-        })??;
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(version.clone())
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_pop(envelope.payload.index as usize, cx)
             })?
-            .await?;
-        let blame = this
-            .update(&mut cx, |this, cx| {
-                this.blame_buffer(&buffer, Some(version), cx)
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_drop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashDrop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_drop(envelope.payload.index as usize, cx)
             })?
-            .await?;
-        Ok(serialize_blame_buffer_response(blame))
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_get_permalink_to_line(
+    async fn handle_log(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GetPermalinkToLine>,
+        envelope: TypedEnvelope<proto::GitLog>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GetPermalinkToLineResponse> {
-        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
-        // let version = deserialize_version(&envelope.payload.version);
-        let selection = {
-            let proto_selection = envelope
-                .payload
-                .selection
-                .context("no selection to get permalink for defined")?;
-            proto_selection.start as u32..proto_selection.end as u32
+    ) -> Result<proto::GitLogResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+        let options = LogOptions {
+            skip: payload.skip as usize,
+            limit: payload.limit as usize,
+            path: payload.path.as_deref().map(RepoPath::from_str),
+            line_range: payload
+                .line_range_start
+                .zip(payload.line_range_end)
+                .map(|(start, end)| start..end),
+            author: payload.author,
+            message: payload.message,
+            since: payload.since,
+            until: payload.until,
         };
-        let buffer = this.read_with(&cx, |this, cx| {
-            this.buffer_store.read(cx).get_existing(buffer_id)
-        })??;
-        let permalink = this
-            .update(&mut cx, |this, cx| {
-                this.get_permalink_to_line(&buffer, selection, cx)
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log(options)
             })?
-            .await?;
-        Ok(proto::GetPermalinkToLineResponse {
-            permalink: permalink.to_string(),
-        })
-    }
+            .await??;
 
-    fn repository_for_request(
-        this: &Entity<Self>,
-        work_directory_id: ProjectEntryId,
-        cx: &mut AsyncApp,
-    ) -> Result<Entity<Repository>> {
-        this.update(cx, |this, cx| {
-            this.repositories
-                .values()
-                .find(|repository_handle| {
-                    repository_handle
-                        .read(cx)
-                        .repository_entry
-                        .work_directory_id()
-                        == work_directory_id
-                })
-                .context("missing repository handle")
-                .cloned()
-        })?
+        Ok(proto::GitLogResponse {
+            entries: entries.into_iter().map(log_entry_to_proto).collect(),
+        })
     }
 
-    pub fn repo_snapshots(&self, cx: &App) -> HashMap<ProjectEntryId, RepositoryEntry> {
-        self.repositories
-            .iter()
-            .map(|(id, repo)| (*id, repo.read(cx).repository_entry.clone()))
-            .collect()
+    async fn handle_commit_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCommitDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitDiffResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let files = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit_diff(envelope.payload.commit)
+            })?
+            .await??;
+
+        Ok(proto::GitCommitDiffResponse {
+            files: files
+                .into_iter()
+                .map(|file| proto::GitCommitFileDiff {
+                    path: file.path.to_string_lossy().to_string(),
+                    old_text: file.old_text,
+                    new_text: file.new_text,
+                })
+                .collect(),
+        })
     }
-}
 
-impl BufferDiffState {
-    fn buffer_language_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
-        self.language = buffer.read(cx).language().cloned();
-        self.language_changed = true;
-        let _ = self.recalculate_diffs(
-            buffer.read(cx).text_snapshot(),
-            self.hunk_staging_operation_count,
-            cx,
+    async fn handle_revision_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevisionDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitDiffResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let revisions = diff_revisions_from_proto(
+            envelope
+                .payload
+                .revisions
+                .ok_or_else(|| anyhow!("missing revisions"))?,
         );
-    }
 
-    fn unstaged_diff(&self) -> Option<Entity<BufferDiff>> {
-        self.unstaged_diff.as_ref().and_then(|set| set.upgrade())
-    }
+        let files = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revision_diff(revisions)
+            })?
+            .await??;
 
-    fn uncommitted_diff(&self) -> Option<Entity<BufferDiff>> {
-        self.uncommitted_diff.as_ref().and_then(|set| set.upgrade())
+        Ok(proto::GitCommitDiffResponse {
+            files: files
+                .into_iter()
+                .map(|file| proto::GitCommitFileDiff {
+                    path: file.path.to_string_lossy().to_string(),
+                    old_text: file.old_text,
+                    new_text: file.new_text,
+                })
+                .collect(),
+        })
     }
 
-    fn handle_base_texts_updated(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        message: proto::UpdateDiffBases,
-        cx: &mut Context<Self>,
-    ) {
-        use proto::update_diff_bases::Mode;
-
-        let Some(mode) = Mode::from_i32(message.mode) else {
-            return;
-        };
+    async fn handle_worktree_list(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitWorktreeList>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitWorktreeListResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
 
-        let diff_bases_change = match mode {
-            Mode::HeadOnly => DiffBasesChange::SetHead(message.committed_text),
-            Mode::IndexOnly => DiffBasesChange::SetIndex(message.staged_text),
-            Mode::IndexMatchesHead => DiffBasesChange::SetBoth(message.committed_text),
-            Mode::IndexAndHead => DiffBasesChange::SetEach {
-                index: message.staged_text,
-                head: message.committed_text,
-            },
-        };
+        let worktrees = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.worktrees()
+            })?
+            .await??;
 
-        let _ = self.diff_bases_changed(
-            buffer,
-            diff_bases_change,
-            self.hunk_staging_operation_count,
-            cx,
-        );
+        Ok(proto::GitWorktreeListResponse {
+            worktrees: worktrees.into_iter().map(worktree_to_proto).collect(),
+        })
     }
 
-    pub fn wait_for_recalculation(&mut self) -> Option<oneshot::Receiver<()>> {
-        if self.diff_updated_futures.is_empty() {
-            return None;
-        }
-        let (tx, rx) = oneshot::channel();
-        self.diff_updated_futures.push(tx);
-        Some(rx)
+    async fn handle_create_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.create_worktree(
+                    PathBuf::from(payload.path),
+                    payload.branch,
+                    payload.create_branch,
+                    cx,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    fn diff_bases_changed(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        diff_bases_change: DiffBasesChange,
-        prev_hunk_staging_operation_count: usize,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<()> {
-        match diff_bases_change {
-            DiffBasesChange::SetIndex(index) => {
-                self.index_text = index.map(|mut index| {
-                    text::LineEnding::normalize(&mut index);
-                    Arc::new(index)
-                });
-                self.index_changed = true;
-            }
-            DiffBasesChange::SetHead(head) => {
-                self.head_text = head.map(|mut head| {
-                    text::LineEnding::normalize(&mut head);
-                    Arc::new(head)
-                });
-                self.head_changed = true;
-            }
-            DiffBasesChange::SetBoth(text) => {
-                let text = text.map(|mut text| {
-                    text::LineEnding::normalize(&mut text);
-                    Arc::new(text)
-                });
-                self.head_text = text.clone();
-                self.index_text = text;
-                self.head_changed = true;
-                self.index_changed = true;
-            }
-            DiffBasesChange::SetEach { index, head } => {
-                self.index_text = index.map(|mut index| {
-                    text::LineEnding::normalize(&mut index);
-                    Arc::new(index)
-                });
-                self.index_changed = true;
-                self.head_text = head.map(|mut head| {
-                    text::LineEnding::normalize(&mut head);
-                    Arc::new(head)
-                });
-                self.head_changed = true;
-            }
-        }
+    async fn handle_remove_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
 
-        self.recalculate_diffs(buffer, prev_hunk_staging_operation_count, cx)
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.remove_worktree(PathBuf::from(payload.path), payload.force, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    fn recalculate_diffs(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        prev_hunk_staging_operation_count: usize,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<()> {
-        log::debug!("recalculate diffs");
-        let (tx, rx) = oneshot::channel();
-        self.diff_updated_futures.push(tx);
+    async fn handle_prune_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPruneWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
 
-        let language = self.language.clone();
-        let language_registry = self.language_registry.clone();
-        let unstaged_diff = self.unstaged_diff();
-        let uncommitted_diff = self.uncommitted_diff();
-        let head = self.head_text.clone();
-        let index = self.index_text.clone();
-        let index_changed = self.index_changed;
-        let head_changed = self.head_changed;
-        let language_changed = self.language_changed;
-        let index_matches_head = match (self.index_text.as_ref(), self.head_text.as_ref()) {
-            (Some(index), Some(head)) => Arc::ptr_eq(index, head),
-            (None, None) => true,
-            _ => false,
-        };
-        self.recalculate_diff_task = Some(cx.spawn(async move |this, cx| {
-            let mut new_unstaged_diff = None;
-            if let Some(unstaged_diff) = &unstaged_diff {
-                new_unstaged_diff = Some(
-                    BufferDiff::update_diff(
-                        unstaged_diff.clone(),
-                        buffer.clone(),
-                        index,
-                        index_changed,
-                        language_changed,
-                        language.clone(),
-                        language_registry.clone(),
-                        cx,
-                    )
-                    .await?,
-                );
-            }
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.prune_worktrees(cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-            let mut new_uncommitted_diff = None;
-            if let Some(uncommitted_diff) = &uncommitted_diff {
-                new_uncommitted_diff = if index_matches_head {
-                    new_unstaged_diff.clone()
-                } else {
-                    Some(
-                        BufferDiff::update_diff(
-                            uncommitted_diff.clone(),
-                            buffer.clone(),
-                            head,
-                            head_changed,
-                            language_changed,
-                            language.clone(),
-                            language_registry.clone(),
-                            cx,
-                        )
-                        .await?,
-                    )
-                }
-            }
+    async fn handle_tag_list(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitTagList>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagListResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
 
-            if this.update(cx, |this, _| {
-                this.hunk_staging_operation_count > prev_hunk_staging_operation_count
-            })? {
-                return Ok(());
-            }
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())?
+            .await??;
 
-            let unstaged_changed_range = if let Some((unstaged_diff, new_unstaged_diff)) =
-                unstaged_diff.as_ref().zip(new_unstaged_diff.clone())
-            {
-                unstaged_diff.update(cx, |diff, cx| {
-                    diff.set_snapshot(&buffer, new_unstaged_diff, language_changed, None, cx)
-                })?
-            } else {
-                None
-            };
+        Ok(proto::GitTagListResponse {
+            tags: tags
+                .into_iter()
+                .map(|tag| proto::GitTag {
+                    name: tag.name.to_string(),
+                    sha: tag.sha.to_string(),
+                    subject: tag.subject.to_string(),
+                    timestamp: tag.timestamp,
+                })
+                .collect(),
+        })
+    }
 
-            if let Some((uncommitted_diff, new_uncommitted_diff)) =
-                uncommitted_diff.as_ref().zip(new_uncommitted_diff.clone())
-            {
-                uncommitted_diff.update(cx, |uncommitted_diff, cx| {
-                    uncommitted_diff.set_snapshot(
-                        &buffer,
-                        new_uncommitted_diff,
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.create_tag(payload.name, payload.target, payload.message, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.delete_tag(payload.name, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_push_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPushTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            work_directory_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let name = envelope.payload.name.into();
+        let remote_name = envelope.payload.remote_name.into();
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.push_tag(name, remote_name, askpass, cx)
+            })?
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_add_remote(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitAddRemote>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.add_remote(payload.name, payload.url, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rename_remote(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRenameRemote>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rename_remote(payload.name, payload.new_name, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_remove_remote(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveRemote>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.remove_remote(payload.name, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.cherry_pick(payload.commits, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let payload = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.revert(payload.commits, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_sequencer_command(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSequencerCommand>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let operation = sequencer_operation_from_proto(envelope.payload.operation());
+        let command = match envelope.payload.command() {
+            git_rebase_command::Command::Continue => RebaseCommand::Continue,
+            git_rebase_command::Command::Skip => RebaseCommand::Skip,
+            git_rebase_command::Command::Abort => RebaseCommand::Abort,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.sequencer_command(operation, command, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_sequencer_status(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSequencerStatus>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSequencerStatusResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let operation = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.sequencer_status()
+            })?
+            .await??;
+
+        Ok(proto::GitSequencerStatusResponse {
+            operation: operation.map(|operation| sequencer_operation_to_proto(operation).into()),
+        })
+    }
+
+    async fn handle_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSubmodulesResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let submodules = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.submodules(cx)
+            })?
+            .await??;
+
+        Ok(proto::GitSubmodulesResponse {
+            submodules: submodules.into_iter().map(submodule_to_proto).collect(),
+        })
+    }
+
+    async fn handle_update_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitUpdateSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            work_directory_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_str(path))
+            .collect();
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.update_submodules(paths, envelope.payload.init, askpass, cx)
+            })?
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_sync_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSyncSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_str(path))
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.sync_submodules(paths, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_submodule_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSubmoduleDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitDiffResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+        let path = RepoPath::from_str(&envelope.payload.path);
+
+        let file = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.submodule_diff(path)
+            })?
+            .await??;
+
+        Ok(proto::GitCommitDiffResponse {
+            files: vec![proto::GitCommitFileDiff {
+                path: file.path.to_string_lossy().to_string(),
+                old_text: file.old_text,
+                new_text: file.new_text,
+            }],
+        })
+    }
+
+    async fn handle_rebase_commits(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseCommits>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLogResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_commits(envelope.payload.upstream)
+            })?
+            .await??;
+
+        Ok(proto::GitLogResponse {
+            entries: entries.into_iter().map(log_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let steps = envelope
+            .payload
+            .steps
+            .into_iter()
+            .map(|step| RebaseStep {
+                action: match step.action() {
+                    git_rebase_step::Action::Pick => RebaseAction::Pick,
+                    git_rebase_step::Action::Reword => RebaseAction::Reword,
+                    git_rebase_step::Action::Squash => RebaseAction::Squash,
+                    git_rebase_step::Action::Fixup => RebaseAction::Fixup,
+                    git_rebase_step::Action::Drop => RebaseAction::Drop,
+                },
+                sha: step.sha.into(),
+                message: step.message,
+            })
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase(envelope.payload.upstream, steps, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_command(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseCommand>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let command = match envelope.payload.command() {
+            git_rebase_command::Command::Continue => RebaseCommand::Continue,
+            git_rebase_command::Command::Skip => RebaseCommand::Skip,
+            git_rebase_command::Command::Abort => RebaseCommand::Abort,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.rebase_command(command, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_status(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseStatus>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseStatusResponse> {
+        let work_directory_id = ProjectEntryId::from_proto(envelope.payload.work_directory_id);
+        let repository_handle = Self::repository_for_request(&this, work_directory_id, &mut cx)?;
+
+        let status = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_status()
+            })?
+            .await??;
+
+        Ok(proto::GitRebaseStatusResponse {
+            progress: status.map(|status| proto::GitRebaseProgress {
+                branch: status.branch.map(|branch| branch.to_string()),
+                onto: status.onto.to_string(),
+                step: status.step as u64,
+                total_steps: status.total_steps as u64,
+            }),
+        })
+    }
+
+    async fn handle_open_unstaged_diff(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::OpenUnstagedDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenUnstagedDiffResponse> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        let diff = this
+            .update(&mut cx, |this, cx| {
+                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
+                Some(this.open_unstaged_diff(buffer, cx))
+            })?
+            .ok_or_else(|| anyhow!("no such buffer"))?
+            .await?;
+        this.update(&mut cx, |this, _| {
+            let shared_diffs = this
+                .shared_diffs
+                .entry(request.original_sender_id.unwrap_or(request.sender_id))
+                .or_default();
+            shared_diffs.entry(buffer_id).or_default().unstaged = Some(diff.clone());
+        })?;
+        let staged_text = diff.read_with(&cx, |diff, _| diff.base_text_string())?;
+        Ok(proto::OpenUnstagedDiffResponse { staged_text })
+    }
+
+    async fn handle_open_uncommitted_diff(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::OpenUncommittedDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenUncommittedDiffResponse> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        let diff = this
+            .update(&mut cx, |this, cx| {
+                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
+                Some(this.open_uncommitted_diff(buffer, cx))
+            })?
+            .ok_or_else(|| anyhow!("no such buffer"))?
+            .await?;
+        this.update(&mut cx, |this, _| {
+            let shared_diffs = this
+                .shared_diffs
+                .entry(request.original_sender_id.unwrap_or(request.sender_id))
+                .or_default();
+            shared_diffs.entry(buffer_id).or_default().uncommitted = Some(diff.clone());
+        })?;
+        diff.read_with(&cx, |diff, cx| {
+            use proto::open_uncommitted_diff_response::Mode;
+
+            let unstaged_diff = diff.secondary_diff();
+            let index_snapshot = unstaged_diff.and_then(|diff| {
+                let diff = diff.read(cx);
+                diff.base_text_exists().then(|| diff.base_text())
+            });
+
+            let mode;
+            let staged_text;
+            let committed_text;
+            if diff.base_text_exists() {
+                let committed_snapshot = diff.base_text();
+                committed_text = Some(committed_snapshot.text());
+                if let Some(index_text) = index_snapshot {
+                    if index_text.remote_id() == committed_snapshot.remote_id() {
+                        mode = Mode::IndexMatchesHead;
+                        staged_text = None;
+                    } else {
+                        mode = Mode::IndexAndHead;
+                        staged_text = Some(index_text.text());
+                    }
+                } else {
+                    mode = Mode::IndexAndHead;
+                    staged_text = None;
+                }
+            } else {
+                mode = Mode::IndexAndHead;
+                committed_text = None;
+                staged_text = index_snapshot.as_ref().map(|buffer| buffer.text());
+            }
+
+            proto::OpenUncommittedDiffResponse {
+                committed_text,
+                staged_text,
+                mode: mode.into(),
+            }
+        })
+    }
+
+    async fn handle_update_diff_bases(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::UpdateDiffBases>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        this.update(&mut cx, |this, cx| {
+            if let Some(diff_state) = this.diffs.get_mut(&buffer_id) {
+                if let Some(buffer) = this.buffer_store.read(cx).get(buffer_id) {
+                    let buffer = buffer.read(cx).text_snapshot();
+                    diff_state.update(cx, |diff_state, cx| {
+                        diff_state.handle_base_texts_updated(buffer, request.payload, cx);
+                    })
+                }
+            }
+        })
+    }
+
+    async fn handle_blame_buffer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::BlameBuffer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::BlameBufferResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let version = deserialize_version(&envelope.payload.version);
+        let buffer = this.read_with(&cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(version.clone())
+            })?
+            .await?;
+        let blame = this
+            .update(&mut cx, |this, cx| {
+                this.blame_buffer(&buffer, Some(version), cx)
+            })?
+            .await?;
+        Ok(serialize_blame_buffer_response(blame))
+    }
+
+    async fn handle_get_permalink_to_line(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetPermalinkToLine>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetPermalinkToLineResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        // let version = deserialize_version(&envelope.payload.version);
+        let selection = {
+            let proto_selection = envelope
+                .payload
+                .selection
+                .context("no selection to get permalink for defined")?;
+            proto_selection.start as u32..proto_selection.end as u32
+        };
+        let buffer = this.read_with(&cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let permalink = this
+            .update(&mut cx, |this, cx| {
+                this.get_permalink_to_line(&buffer, selection, cx)
+            })?
+            .await?;
+        Ok(proto::GetPermalinkToLineResponse {
+            permalink: permalink.to_string(),
+        })
+    }
+
+    fn repository_for_request(
+        this: &Entity<Self>,
+        work_directory_id: ProjectEntryId,
+        cx: &mut AsyncApp,
+    ) -> Result<Entity<Repository>> {
+        this.update(cx, |this, cx| {
+            this.repositories
+                .values()
+                .find(|repository_handle| {
+                    repository_handle
+                        .read(cx)
+                        .repository_entry
+                        .work_directory_id()
+                        == work_directory_id
+                })
+                .context("missing repository handle")
+                .cloned()
+        })?
+    }
+
+    pub fn repo_snapshots(&self, cx: &App) -> HashMap<ProjectEntryId, RepositoryEntry> {
+        self.repositories
+            .iter()
+            .map(|(id, repo)| (*id, repo.read(cx).repository_entry.clone()))
+            .collect()
+    }
+}
+
+impl BufferDiffState {
+    fn buffer_language_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        self.language = buffer.read(cx).language().cloned();
+        self.language_changed = true;
+        let _ = self.recalculate_diffs(
+            buffer.read(cx).text_snapshot(),
+            self.hunk_staging_operation_count,
+            cx,
+        );
+    }
+
+    fn unstaged_diff(&self) -> Option<Entity<BufferDiff>> {
+        self.unstaged_diff.as_ref().and_then(|set| set.upgrade())
+    }
+
+    fn uncommitted_diff(&self) -> Option<Entity<BufferDiff>> {
+        self.uncommitted_diff.as_ref().and_then(|set| set.upgrade())
+    }
+
+    fn handle_base_texts_updated(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        message: proto::UpdateDiffBases,
+        cx: &mut Context<Self>,
+    ) {
+        use proto::update_diff_bases::Mode;
+
+        let Some(mode) = Mode::from_i32(message.mode) else {
+            return;
+        };
+
+        let diff_bases_change = match mode {
+            Mode::HeadOnly => DiffBasesChange::SetHead(message.committed_text),
+            Mode::IndexOnly => DiffBasesChange::SetIndex(message.staged_text),
+            Mode::IndexMatchesHead => DiffBasesChange::SetBoth(message.committed_text),
+            Mode::IndexAndHead => DiffBasesChange::SetEach {
+                index: message.staged_text,
+                head: message.committed_text,
+            },
+        };
+
+        let _ = self.diff_bases_changed(
+            buffer,
+            diff_bases_change,
+            self.hunk_staging_operation_count,
+            cx,
+        );
+    }
+
+    pub fn wait_for_recalculation(&mut self) -> Option<oneshot::Receiver<()>> {
+        if self.diff_updated_futures.is_empty() {
+            return None;
+        }
+        let (tx, rx) = oneshot::channel();
+        self.diff_updated_futures.push(tx);
+        Some(rx)
+    }
+
+    fn diff_bases_changed(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        diff_bases_change: DiffBasesChange,
+        prev_hunk_staging_operation_count: usize,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<()> {
+        match diff_bases_change {
+            DiffBasesChange::SetIndex(index) => {
+                self.index_line_ending = index.as_deref().map(text::LineEnding::detect);
+                self.index_text = index.map(|mut index| {
+                    text::LineEnding::normalize(&mut index);
+                    Arc::new(index)
+                });
+                self.index_changed = true;
+            }
+            DiffBasesChange::SetHead(head) => {
+                self.head_text = head.map(|mut head| {
+                    text::LineEnding::normalize(&mut head);
+                    Arc::new(head)
+                });
+                self.head_changed = true;
+            }
+            DiffBasesChange::SetBoth(text) => {
+                self.index_line_ending = text.as_deref().map(text::LineEnding::detect);
+                let text = text.map(|mut text| {
+                    text::LineEnding::normalize(&mut text);
+                    Arc::new(text)
+                });
+                self.head_text = text.clone();
+                self.index_text = text;
+                self.head_changed = true;
+                self.index_changed = true;
+            }
+            DiffBasesChange::SetEach { index, head } => {
+                self.index_line_ending = index.as_deref().map(text::LineEnding::detect);
+                self.index_text = index.map(|mut index| {
+                    text::LineEnding::normalize(&mut index);
+                    Arc::new(index)
+                });
+                self.index_changed = true;
+                self.head_text = head.map(|mut head| {
+                    text::LineEnding::normalize(&mut head);
+                    Arc::new(head)
+                });
+                self.head_changed = true;
+            }
+        }
+
+        self.recalculate_diffs(buffer, prev_hunk_staging_operation_count, cx)
+    }
+
+    fn recalculate_diffs(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        prev_hunk_staging_operation_count: usize,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<()> {
+        log::debug!("recalculate diffs");
+        let (tx, rx) = oneshot::channel();
+        self.diff_updated_futures.push(tx);
+
+        let language = self.language.clone();
+        let language_registry = self.language_registry.clone();
+        let unstaged_diff = self.unstaged_diff();
+        let uncommitted_diff = self.uncommitted_diff();
+        let head = self.head_text.clone();
+        let index = self.index_text.clone();
+        let index_changed = self.index_changed;
+        let head_changed = self.head_changed;
+        let language_changed = self.language_changed;
+        let index_matches_head = match (self.index_text.as_ref(), self.head_text.as_ref()) {
+            (Some(index), Some(head)) => Arc::ptr_eq(index, head),
+            (None, None) => true,
+            _ => false,
+        };
+        self.recalculate_diff_task = Some(cx.spawn(async move |this, cx| {
+            let mut new_unstaged_diff = None;
+            if let Some(unstaged_diff) = &unstaged_diff {
+                new_unstaged_diff = Some(
+                    BufferDiff::update_diff(
+                        unstaged_diff.clone(),
+                        buffer.clone(),
+                        index,
+                        index_changed,
+                        language_changed,
+                        language.clone(),
+                        language_registry.clone(),
+                        cx,
+                    )
+                    .await?,
+                );
+            }
+
+            let mut new_uncommitted_diff = None;
+            if let Some(uncommitted_diff) = &uncommitted_diff {
+                new_uncommitted_diff = if index_matches_head {
+                    new_unstaged_diff.clone()
+                } else {
+                    Some(
+                        BufferDiff::update_diff(
+                            uncommitted_diff.clone(),
+                            buffer.clone(),
+                            head,
+                            head_changed,
+                            language_changed,
+                            language.clone(),
+                            language_registry.clone(),
+                            cx,
+                        )
+                        .await?,
+                    )
+                }
+            }
+
+            if this.update(cx, |this, _| {
+                this.hunk_staging_operation_count > prev_hunk_staging_operation_count
+            })? {
+                return Ok(());
+            }
+
+            let unstaged_changed_range = if let Some((unstaged_diff, new_unstaged_diff)) =
+                unstaged_diff.as_ref().zip(new_unstaged_diff.clone())
+            {
+                unstaged_diff.update(cx, |diff, cx| {
+                    diff.set_snapshot(&buffer, new_unstaged_diff, language_changed, None, cx)
+                })?
+            } else {
+                None
+            };
+
+            if let Some((uncommitted_diff, new_uncommitted_diff)) =
+                uncommitted_diff.as_ref().zip(new_uncommitted_diff.clone())
+            {
+                uncommitted_diff.update(cx, |uncommitted_diff, cx| {
+                    uncommitted_diff.set_snapshot(
+                        &buffer,
+                        new_uncommitted_diff,
                         language_changed,
                         unstaged_changed_range,
                         cx,
@@ -2409,391 +3221,1329 @@ impl BufferDiffState {
                 })?;
             }
 
-            if let Some(this) = this.upgrade() {
-                this.update(cx, |this, _| {
-                    this.index_changed = false;
-                    this.head_changed = false;
-                    this.language_changed = false;
-                    for tx in this.diff_updated_futures.drain(..) {
-                        tx.send(()).ok();
-                    }
-                })?;
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |this, _| {
+                    this.index_changed = false;
+                    this.head_changed = false;
+                    this.language_changed = false;
+                    for tx in this.diff_updated_futures.drain(..) {
+                        tx.send(()).ok();
+                    }
+                })?;
+            }
+
+            Ok(())
+        }));
+
+        rx
+    }
+}
+
+fn make_remote_delegate(
+    this: Entity<GitStore>,
+    project_id: u64,
+    work_directory_id: ProjectEntryId,
+    askpass_id: u64,
+    cx: &mut AsyncApp,
+) -> AskPassDelegate {
+    AskPassDelegate::new(cx, move |prompt, tx, cx| {
+        this.update(cx, |this, cx| {
+            let Some((client, _)) = this.downstream_client() else {
+                return;
+            };
+            let response = client.request(proto::AskPassRequest {
+                project_id,
+                work_directory_id: work_directory_id.to_proto(),
+                askpass_id,
+                prompt,
+            });
+            cx.spawn(async move |_, _| {
+                tx.send(response.await?.response).ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        })
+        .log_err();
+    })
+}
+
+impl GitStoreState {
+    fn load_staged_text(
+        &self,
+        buffer: &Entity<Buffer>,
+        buffer_store: &Entity<BufferStore>,
+        cx: &App,
+    ) -> Task<Result<Option<String>>> {
+        match self {
+            GitStoreState::Local { .. } => {
+                if let Some((worktree, path)) =
+                    buffer_store.read(cx).worktree_for_buffer(buffer, cx)
+                {
+                    worktree.read(cx).load_staged_file(path.as_ref(), cx)
+                } else {
+                    return Task::ready(Err(anyhow!("no such worktree")));
+                }
+            }
+            GitStoreState::Ssh {
+                upstream_client,
+                upstream_project_id: project_id,
+                ..
+            }
+            | GitStoreState::Remote {
+                upstream_client,
+                project_id,
+            } => {
+                let buffer_id = buffer.read(cx).remote_id();
+                let project_id = *project_id;
+                let client = upstream_client.clone();
+                cx.background_spawn(async move {
+                    let response = client
+                        .request(proto::OpenUnstagedDiff {
+                            project_id: project_id.to_proto(),
+                            buffer_id: buffer_id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.staged_text)
+                })
+            }
+        }
+    }
+
+    fn load_committed_text(
+        &self,
+        buffer: &Entity<Buffer>,
+        buffer_store: &Entity<BufferStore>,
+        cx: &App,
+    ) -> Task<Result<DiffBasesChange>> {
+        match self {
+            GitStoreState::Local { .. } => {
+                if let Some((worktree, path)) =
+                    buffer_store.read(cx).worktree_for_buffer(buffer, cx)
+                {
+                    let worktree = worktree.read(cx);
+                    let committed_text = worktree.load_committed_file(&path, cx);
+                    let staged_text = worktree.load_staged_file(&path, cx);
+                    cx.background_spawn(async move {
+                        let committed_text = committed_text.await?;
+                        let staged_text = staged_text.await?;
+                        let diff_bases_change = if committed_text == staged_text {
+                            DiffBasesChange::SetBoth(committed_text)
+                        } else {
+                            DiffBasesChange::SetEach {
+                                index: staged_text,
+                                head: committed_text,
+                            }
+                        };
+                        Ok(diff_bases_change)
+                    })
+                } else {
+                    Task::ready(Err(anyhow!("no such worktree")))
+                }
+            }
+            GitStoreState::Ssh {
+                upstream_client,
+                upstream_project_id: project_id,
+                ..
+            }
+            | GitStoreState::Remote {
+                upstream_client,
+                project_id,
+            } => {
+                use proto::open_uncommitted_diff_response::Mode;
+
+                let buffer_id = buffer.read(cx).remote_id();
+                let project_id = *project_id;
+                let client = upstream_client.clone();
+                cx.background_spawn(async move {
+                    let response = client
+                        .request(proto::OpenUncommittedDiff {
+                            project_id: project_id.to_proto(),
+                            buffer_id: buffer_id.to_proto(),
+                        })
+                        .await?;
+                    let mode =
+                        Mode::from_i32(response.mode).ok_or_else(|| anyhow!("Invalid mode"))?;
+                    let bases = match mode {
+                        Mode::IndexMatchesHead => DiffBasesChange::SetBoth(response.committed_text),
+                        Mode::IndexAndHead => DiffBasesChange::SetEach {
+                            head: response.committed_text,
+                            index: response.staged_text,
+                        },
+                    };
+                    Ok(bases)
+                })
+            }
+        }
+    }
+}
+
+impl Repository {
+    pub fn git_store(&self) -> Option<Entity<GitStore>> {
+        self.git_store.upgrade()
+    }
+
+    fn id(&self) -> ProjectEntryId {
+        self.repository_entry.work_directory_id()
+    }
+
+    pub fn current_branch(&self) -> Option<&Branch> {
+        self.repository_entry.branch()
+    }
+
+    pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
+        self.repository_entry.status_for_path(path)
+    }
+
+    fn send_job<F, Fut, R>(&self, job: F) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
+        Fut: Future<Output = R> + 'static,
+        R: Send + 'static,
+    {
+        self.send_keyed_job(None, job)
+    }
+
+    fn send_keyed_job<F, Fut, R>(&self, key: Option<GitJobKey>, job: F) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
+        Fut: Future<Output = R> + 'static,
+        R: Send + 'static,
+    {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        let git_repo = self.state.clone();
+        self.job_sender
+            .unbounded_send(GitJob {
+                key,
+                job: Box::new(|cx: &mut AsyncApp| {
+                    let job = job(git_repo, cx.clone());
+                    cx.spawn(async move |_| {
+                        let result = job.await;
+                        result_tx.send(result).ok();
+                    })
+                }),
+            })
+            .ok();
+        result_rx
+    }
+
+    /// This is the name that will be displayed in the repository selector for this repository.
+    pub fn display_name(&self) -> SharedString {
+        self.repository_entry
+            .work_directory_abs_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+            .into()
+    }
+
+    pub fn set_as_active_repository(&self, cx: &mut Context<Self>) {
+        let Some(git_store) = self.git_store.upgrade() else {
+            return;
+        };
+        let entity = cx.entity();
+        git_store.update(cx, |git_store, cx| {
+            let Some((&id, _)) = git_store
+                .repositories
+                .iter()
+                .find(|(_, handle)| *handle == &entity)
+            else {
+                return;
+            };
+            git_store.active_repo_id = Some(id);
+            cx.emit(GitEvent::ActiveRepositoryChanged);
+        });
+    }
+
+    pub fn cached_status(&self) -> impl '_ + Iterator<Item = StatusEntry> {
+        self.repository_entry.status()
+    }
+
+    pub fn load_index_text(&self, path: RepoPath) -> oneshot::Receiver<Option<String>> {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    git_repository.load_index_text(path).await
+                }
+                RepositoryState::Remote { .. } => None,
+            }
+        })
+    }
+
+    pub fn has_conflict(&self, path: &RepoPath) -> bool {
+        self.repository_entry
+            .current_merge_conflicts
+            .contains(&path)
+    }
+
+    pub fn repo_path_to_project_path(&self, path: &RepoPath, cx: &App) -> Option<ProjectPath> {
+        let git_store = self.git_store.upgrade()?;
+        let worktree_store = git_store.read(cx).worktree_store.read(cx);
+        let abs_path = self.repository_entry.work_directory_abs_path.join(&path.0);
+        let (worktree, relative_path) = worktree_store.find_worktree(abs_path, cx)?;
+        Some(ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path.into(),
+        })
+    }
+
+    pub fn project_path_to_repo_path(&self, path: &ProjectPath, cx: &App) -> Option<RepoPath> {
+        let git_store = self.git_store.upgrade()?;
+        let worktree_store = git_store.read(cx).worktree_store.read(cx);
+        let abs_path = worktree_store.absolutize(path, cx)?;
+        self.repository_entry.relativize_abs_path(&abs_path)
+    }
+
+    pub fn contains_sub_repo(&self, other: &Entity<Self>, cx: &App) -> bool {
+        other
+            .read(cx)
+            .repository_entry
+            .work_directory_abs_path
+            .starts_with(&self.repository_entry.work_directory_abs_path)
+    }
+
+    pub fn local_repository(&self) -> Option<Arc<dyn GitRepository>> {
+        match &self.state {
+            RepositoryState::Local(git_repository) => Some(git_repository.clone()),
+            RepositoryState::Remote { .. } => None,
+        }
+    }
+
+    pub fn open_commit_buffer(
+        &mut self,
+        languages: Option<Arc<LanguageRegistry>>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        if let Some(buffer) = self.commit_message_buffer.clone() {
+            return Task::ready(Ok(buffer));
+        }
+
+        if let RepositoryState::Remote {
+            project_id,
+            client,
+            work_directory_id,
+        } = self.state.clone()
+        {
+            let client = client.clone();
+            cx.spawn(async move |repository, cx| {
+                let request = client.request(proto::OpenCommitMessageBuffer {
+                    project_id: project_id.0,
+                    work_directory_id: work_directory_id.to_proto(),
+                });
+                let response = request.await.context("requesting to open commit buffer")?;
+                let buffer_id = BufferId::new(response.buffer_id)?;
+                let buffer = buffer_store
+                    .update(cx, |buffer_store, cx| {
+                        buffer_store.wait_for_remote_buffer(buffer_id, cx)
+                    })?
+                    .await?;
+                if let Some(language_registry) = languages {
+                    let git_commit_language =
+                        language_registry.language_for_name("Git Commit").await?;
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_language(Some(git_commit_language), cx);
+                    })?;
+                }
+                repository.update(cx, |repository, _| {
+                    repository.commit_message_buffer = Some(buffer.clone());
+                })?;
+                Ok(buffer)
+            })
+        } else {
+            self.open_local_commit_buffer(languages, buffer_store, cx)
+        }
+    }
+
+    fn open_local_commit_buffer(
+        &mut self,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        cx.spawn(async move |repository, cx| {
+            let buffer = buffer_store
+                .update(cx, |buffer_store, cx| buffer_store.create_buffer(cx))?
+                .await?;
+
+            if let Some(language_registry) = language_registry {
+                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
+                buffer.update(cx, |buffer, cx| {
+                    buffer.set_language(Some(git_commit_language), cx);
+                })?;
+            }
+
+            repository.update(cx, |repository, _| {
+                repository.commit_message_buffer = Some(buffer.clone());
+            })?;
+            Ok(buffer)
+        })
+    }
+
+    pub fn checkout_files(
+        &self,
+        commit: &str,
+        paths: Vec<RepoPath>,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let commit = commit.to_string();
+        let env = self.worktree_environment(cx);
+
+        self.send_job(|git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(repo) => repo.checkout_files(commit, paths, env.await).await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitCheckoutFiles {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            commit,
+                            paths: paths
+                                .into_iter()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .collect(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn reset(
+        &self,
+        commit: String,
+        reset_mode: ResetMode,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let commit = commit.to_string();
+        let env = self.worktree_environment(cx);
+        self.send_job(|git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local(git_repo) => {
+                    let env = env.await;
+                    git_repo.reset(commit, reset_mode, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitReset {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            commit,
+                            mode: match reset_mode {
+                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
+                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                            },
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn show(&self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
+        self.send_job(|git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(git_repository) => git_repository.show(commit).await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let resp = client
+                        .request(proto::GitShow {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(CommitDetails {
+                        sha: resp.sha.into(),
+                        message: resp.message.into(),
+                        commit_timestamp: resp.commit_timestamp,
+                        committer_email: resp.committer_email.into(),
+                        committer_name: resp.committer_name.into(),
+                        signature: resp.signature.map(commit_signature_from_proto),
+                    })
+                }
+            }
+        })
+    }
+
+    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
+        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
+    }
+
+    pub fn stage_entries(
+        &self,
+        entries: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        if entries.is_empty() {
+            return Task::ready(Ok(()));
+        }
+        let env = self.worktree_environment(cx);
+
+        let mut save_futures = Vec::new();
+        if let Some(buffer_store) = self.buffer_store(cx) {
+            buffer_store.update(cx, |buffer_store, cx| {
+                for path in &entries {
+                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
+                        continue;
+                    };
+                    if let Some(buffer) = buffer_store.get_by_path(&project_path, cx) {
+                        if buffer
+                            .read(cx)
+                            .file()
+                            .map_or(false, |file| file.disk_state().exists())
+                        {
+                            save_futures.push(buffer_store.save_buffer(buffer, cx));
+                        }
+                    }
+                }
+            })
+        }
+
+        cx.spawn(async move |this, cx| {
+            for save_future in save_futures {
+                save_future.await?;
+            }
+            let env = env.await;
+
+            this.update(cx, |this, _| {
+                this.send_job(|git_repo, _cx| async move {
+                    match git_repo {
+                        RepositoryState::Local(repo) => repo.stage_paths(entries, env).await,
+                        RepositoryState::Remote {
+                            project_id,
+                            client,
+                            work_directory_id,
+                        } => {
+                            client
+                                .request(proto::Stage {
+                                    project_id: project_id.0,
+                                    work_directory_id: work_directory_id.to_proto(),
+                                    paths: entries
+                                        .into_iter()
+                                        .map(|repo_path| repo_path.as_ref().to_proto())
+                                        .collect(),
+                                })
+                                .await
+                                .context("sending stage request")?;
+
+                            Ok(())
+                        }
+                    }
+                })
+            })?
+            .await??;
+
+            Ok(())
+        })
+    }
+
+    pub fn unstage_entries(
+        &self,
+        entries: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        if entries.is_empty() {
+            return Task::ready(Ok(()));
+        }
+        let env = self.worktree_environment(cx);
+
+        let mut save_futures = Vec::new();
+        if let Some(buffer_store) = self.buffer_store(cx) {
+            buffer_store.update(cx, |buffer_store, cx| {
+                for path in &entries {
+                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
+                        continue;
+                    };
+                    if let Some(buffer) = buffer_store.get_by_path(&project_path, cx) {
+                        if buffer
+                            .read(cx)
+                            .file()
+                            .map_or(false, |file| file.disk_state().exists())
+                        {
+                            save_futures.push(buffer_store.save_buffer(buffer, cx));
+                        }
+                    }
+                }
+            })
+        }
+
+        cx.spawn(async move |this, cx| {
+            for save_future in save_futures {
+                save_future.await?;
+            }
+            let env = env.await;
+
+            this.update(cx, |this, _| {
+                this.send_job(|git_repo, _cx| async move {
+                    match git_repo {
+                        RepositoryState::Local(repo) => repo.unstage_paths(entries, env).await,
+                        RepositoryState::Remote {
+                            project_id,
+                            client,
+                            work_directory_id,
+                        } => {
+                            client
+                                .request(proto::Unstage {
+                                    project_id: project_id.0,
+                                    work_directory_id: work_directory_id.to_proto(),
+                                    paths: entries
+                                        .into_iter()
+                                        .map(|repo_path| repo_path.as_ref().to_proto())
+                                        .collect(),
+                                })
+                                .await
+                                .context("sending unstage request")?;
+
+                            Ok(())
+                        }
+                    }
+                })
+            })?
+            .await??;
+
+            Ok(())
+        })
+    }
+
+    pub fn stage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
+        let to_stage = self
+            .repository_entry
+            .status()
+            .filter(|entry| !entry.status.staging().is_fully_staged())
+            .map(|entry| entry.repo_path.clone())
+            .collect();
+        self.stage_entries(to_stage, cx)
+    }
+
+    pub fn unstage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
+        let to_unstage = self
+            .repository_entry
+            .status()
+            .filter(|entry| entry.status.staging().has_staged())
+            .map(|entry| entry.repo_path.clone())
+            .collect();
+        self.unstage_entries(to_unstage, cx)
+    }
+
+    /// Get a count of all entries in the active repository, including
+    /// untracked files.
+    pub fn entry_count(&self) -> usize {
+        self.repository_entry.status_len()
+    }
+
+    fn worktree_environment(
+        &self,
+        cx: &mut App,
+    ) -> impl Future<Output = HashMap<String, String>> + 'static {
+        let task = self.project_environment.as_ref().and_then(|env| {
+            env.update(cx, |env, cx| {
+                env.get_environment(
+                    self.worktree_id,
+                    Some(
+                        self.repository_entry
+                            .work_directory_abs_path
+                            .as_path()
+                            .into(),
+                    ),
+                    cx,
+                )
+            })
+            .ok()
+        });
+        async move { OptionFuture::from(task).await.flatten().unwrap_or_default() }
+    }
+
+    pub fn commit(
+        &mut self,
+        message: SharedString,
+        name_and_email: Option<(SharedString, SharedString)>,
+        options: CommitOptions,
+        askpass: AskPassDelegate,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let env = self.worktree_environment(cx);
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(repo) => {
+                    let env = env.await;
+                    repo.commit(message, name_and_email, options, askpass, env, cx)
+                        .await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+                    let (name, email) = name_and_email.unzip();
+                    client
+                        .request(proto::Commit {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            message: String::from(message),
+                            name: name.map(String::from),
+                            email: email.map(String::from),
+                            signoff: options.signoff,
+                            askpass_id,
+                        })
+                        .await
+                        .context("sending commit request")?;
+
+                    Ok(())
+                }
             }
+        })
+    }
 
-            Ok(())
-        }));
+    /// Returns the message new commits start from, if the repository configures one.
+    pub fn commit_message_template(
+        &self,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<Option<String>>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(repo) => {
+                    let env = env.await;
+                    repo.commit_message_template(env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitCommitMessageTemplate {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                        })
+                        .await?;
 
-        rx
+                    Ok(response.template)
+                }
+            }
+        })
     }
-}
 
-fn make_remote_delegate(
-    this: Entity<GitStore>,
-    project_id: u64,
-    work_directory_id: ProjectEntryId,
-    askpass_id: u64,
-    cx: &mut AsyncApp,
-) -> AskPassDelegate {
-    AskPassDelegate::new(cx, move |prompt, tx, cx| {
-        this.update(cx, |this, cx| {
-            let Some((client, _)) = this.downstream_client() else {
-                return;
-            };
-            let response = client.request(proto::AskPassRequest {
-                project_id,
-                work_directory_id: work_directory_id.to_proto(),
-                askpass_id,
-                prompt,
-            });
-            cx.spawn(async move |_, _| {
-                tx.send(response.await?.response).ok();
-                anyhow::Ok(())
-            })
-            .detach_and_log_err(cx);
-        })
-        .log_err();
-    })
-}
+    pub fn fetch(
+        &mut self,
+        askpass: AskPassDelegate,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let env = self.worktree_environment(cx);
 
-impl GitStoreState {
-    fn load_staged_text(
-        &self,
-        buffer: &Entity<Buffer>,
-        buffer_store: &Entity<BufferStore>,
-        cx: &App,
-    ) -> Task<Result<Option<String>>> {
-        match self {
-            GitStoreState::Local { .. } => {
-                if let Some((worktree, path)) =
-                    buffer_store.read(cx).worktree_for_buffer(buffer, cx)
-                {
-                    worktree.read(cx).load_staged_file(path.as_ref(), cx)
-                } else {
-                    return Task::ready(Err(anyhow!("no such worktree")));
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.fetch(askpass, env, cx).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+
+                    let response = client
+                        .request(proto::Fetch {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            askpass_id,
+                        })
+                        .await
+                        .context("sending fetch request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
                 }
             }
-            GitStoreState::Ssh {
-                upstream_client,
-                upstream_project_id: project_id,
-                ..
+        })
+    }
+
+    pub fn push(
+        &mut self,
+        branch: SharedString,
+        remote: SharedString,
+        options: Option<PushOptions>,
+        askpass: AskPassDelegate,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let env = self.worktree_environment(cx);
+
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    // let askpass = AskPassSession::new(&executor, askpass).await?;
+                    git_repository
+                        .push(
+                            branch.to_string(),
+                            remote.to_string(),
+                            options,
+                            askpass,
+                            env,
+                            cx,
+                        )
+                        .await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+                    let response = client
+                        .request(proto::Push {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            askpass_id,
+                            branch_name: branch.to_string(),
+                            remote_name: remote.to_string(),
+                            options: options.map(|options| match options {
+                                PushOptions::Force => proto::push::PushOptions::Force,
+                                PushOptions::SetUpstream => proto::push::PushOptions::SetUpstream,
+                            } as i32),
+                        })
+                        .await
+                        .context("sending push request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                }
             }
-            | GitStoreState::Remote {
-                upstream_client,
-                project_id,
-            } => {
-                let buffer_id = buffer.read(cx).remote_id();
-                let project_id = *project_id;
-                let client = upstream_client.clone();
-                cx.background_spawn(async move {
+        })
+    }
+
+    pub fn pull(
+        &mut self,
+        branch: SharedString,
+        remote: SharedString,
+        askpass: AskPassDelegate,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let env = self.worktree_environment(cx);
+
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository
+                        .pull(branch.to_string(), remote.to_string(), askpass, env, cx)
+                        .await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
                     let response = client
-                        .request(proto::OpenUnstagedDiff {
-                            project_id: project_id.to_proto(),
-                            buffer_id: buffer_id.to_proto(),
+                        .request(proto::Pull {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            askpass_id,
+                            branch_name: branch.to_string(),
+                            remote_name: remote.to_string(),
                         })
-                        .await?;
-                    Ok(response.staged_text)
-                })
+                        .await
+                        .context("sending pull request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                }
             }
-        }
+        })
     }
 
-    fn load_committed_text(
+    fn spawn_set_index_text_job(
         &self,
-        buffer: &Entity<Buffer>,
-        buffer_store: &Entity<BufferStore>,
-        cx: &App,
-    ) -> Task<Result<DiffBasesChange>> {
-        match self {
-            GitStoreState::Local { .. } => {
-                if let Some((worktree, path)) =
-                    buffer_store.read(cx).worktree_for_buffer(buffer, cx)
-                {
-                    let worktree = worktree.read(cx);
-                    let committed_text = worktree.load_committed_file(&path, cx);
-                    let staged_text = worktree.load_staged_file(&path, cx);
-                    cx.background_spawn(async move {
-                        let committed_text = committed_text.await?;
-                        let staged_text = staged_text.await?;
-                        let diff_bases_change = if committed_text == staged_text {
-                            DiffBasesChange::SetBoth(committed_text)
-                        } else {
-                            DiffBasesChange::SetEach {
-                                index: staged_text,
-                                head: committed_text,
-                            }
-                        };
-                        Ok(diff_bases_change)
-                    })
-                } else {
-                    Task::ready(Err(anyhow!("no such worktree")))
+        path: RepoPath,
+        content: Option<String>,
+        cx: &mut App,
+    ) -> oneshot::Receiver<anyhow::Result<()>> {
+        let env = self.worktree_environment(cx);
+
+        self.send_keyed_job(
+            Some(GitJobKey::WriteIndex(path.clone())),
+            |git_repo, _cx| async {
+                match git_repo {
+                    RepositoryState::Local(repo) => {
+                        repo.set_index_text(path, content, env.await).await
+                    }
+                    RepositoryState::Remote {
+                        project_id,
+                        client,
+                        work_directory_id,
+                    } => {
+                        client
+                            .request(proto::SetIndexText {
+                                project_id: project_id.0,
+                                work_directory_id: work_directory_id.to_proto(),
+                                path: path.as_ref().to_proto(),
+                                text: content,
+                            })
+                            .await?;
+                        Ok(())
+                    }
                 }
-            }
-            GitStoreState::Ssh {
-                upstream_client,
-                upstream_project_id: project_id,
-                ..
-            }
-            | GitStoreState::Remote {
-                upstream_client,
-                project_id,
-            } => {
-                use proto::open_uncommitted_diff_response::Mode;
+            },
+        )
+    }
 
-                let buffer_id = buffer.read(cx).remote_id();
-                let project_id = *project_id;
-                let client = upstream_client.clone();
-                cx.background_spawn(async move {
+    pub fn get_remotes(
+        &self,
+        branch_name: Option<String>,
+    ) -> oneshot::Receiver<Result<Vec<Remote>>> {
+        self.send_job(|repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    git_repository.get_remotes(branch_name).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
                     let response = client
-                        .request(proto::OpenUncommittedDiff {
-                            project_id: project_id.to_proto(),
-                            buffer_id: buffer_id.to_proto(),
+                        .request(proto::GetRemotes {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            branch_name,
                         })
                         .await?;
-                    let mode =
-                        Mode::from_i32(response.mode).ok_or_else(|| anyhow!("Invalid mode"))?;
-                    let bases = match mode {
-                        Mode::IndexMatchesHead => DiffBasesChange::SetBoth(response.committed_text),
-                        Mode::IndexAndHead => DiffBasesChange::SetEach {
-                            head: response.committed_text,
-                            index: response.staged_text,
-                        },
-                    };
-                    Ok(bases)
-                })
-            }
-        }
-    }
-}
 
-impl Repository {
-    pub fn git_store(&self) -> Option<Entity<GitStore>> {
-        self.git_store.upgrade()
-    }
+                    let remotes = response
+                        .remotes
+                        .into_iter()
+                        .map(|remotes| git::repository::Remote {
+                            name: remotes.name.into(),
+                        })
+                        .collect();
 
-    fn id(&self) -> ProjectEntryId {
-        self.repository_entry.work_directory_id()
+                    Ok(remotes)
+                }
+            }
+        })
     }
 
-    pub fn current_branch(&self) -> Option<&Branch> {
+    pub fn branch(&self) -> Option<&Branch> {
         self.repository_entry.branch()
     }
 
-    pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
-        self.repository_entry.status_for_path(path)
-    }
-
-    fn send_job<F, Fut, R>(&self, job: F) -> oneshot::Receiver<R>
-    where
-        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
-        Fut: Future<Output = R> + 'static,
-        R: Send + 'static,
-    {
-        self.send_keyed_job(None, job)
-    }
+    pub fn branches(&self) -> oneshot::Receiver<Result<Vec<Branch>>> {
+        self.send_job(|repo, cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let git_repository = git_repository.clone();
+                    cx.background_spawn(async move { git_repository.branches().await })
+                        .await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitGetBranches {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                        })
+                        .await?;
 
-    fn send_keyed_job<F, Fut, R>(&self, key: Option<GitJobKey>, job: F) -> oneshot::Receiver<R>
-    where
-        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
-        Fut: Future<Output = R> + 'static,
-        R: Send + 'static,
-    {
-        let (result_tx, result_rx) = futures::channel::oneshot::channel();
-        let git_repo = self.state.clone();
-        self.job_sender
-            .unbounded_send(GitJob {
-                key,
-                job: Box::new(|cx: &mut AsyncApp| {
-                    let job = job(git_repo, cx.clone());
-                    cx.spawn(async move |_| {
-                        let result = job.await;
-                        result_tx.send(result).ok();
-                    })
-                }),
-            })
-            .ok();
-        result_rx
-    }
+                    let branches = response
+                        .branches
+                        .into_iter()
+                        .map(|branch| worktree::proto_to_branch(&branch))
+                        .collect();
 
-    /// This is the name that will be displayed in the repository selector for this repository.
-    pub fn display_name(&self) -> SharedString {
-        self.repository_entry
-            .work_directory_abs_path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string()
-            .into()
+                    Ok(branches)
+                }
+            }
+        })
     }
 
-    pub fn set_as_active_repository(&self, cx: &mut Context<Self>) {
-        let Some(git_store) = self.git_store.upgrade() else {
-            return;
-        };
-        let entity = cx.entity();
-        git_store.update(cx, |git_store, cx| {
-            let Some((&id, _)) = git_store
-                .repositories
-                .iter()
-                .find(|(_, handle)| *handle == &entity)
-            else {
-                return;
-            };
-            git_store.active_repo_id = Some(id);
-            cx.emit(GitEvent::ActiveRepositoryChanged);
-        });
-    }
+    pub fn diff(&self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
+        self.send_job(|repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.diff(diff_type).await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                    ..
+                } => {
+                    let (diff_type, revisions) = match diff_type {
+                        DiffType::HeadToIndex => (proto::git_diff::DiffType::HeadToIndex, None),
+                        DiffType::HeadToWorktree => {
+                            (proto::git_diff::DiffType::HeadToWorktree, None)
+                        }
+                        DiffType::Revisions(revisions) => (
+                            proto::git_diff::DiffType::Revisions,
+                            Some(diff_revisions_to_proto(revisions)),
+                        ),
+                    };
+                    let response = client
+                        .request(proto::GitDiff {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            diff_type: diff_type.into(),
+                            revisions,
+                        })
+                        .await?;
 
-    pub fn cached_status(&self) -> impl '_ + Iterator<Item = StatusEntry> {
-        self.repository_entry.status()
+                    Ok(response.diff)
+                }
+            }
+        })
     }
 
-    pub fn load_index_text(&self, path: RepoPath) -> oneshot::Receiver<Option<String>> {
-        self.send_job(move |repo, _cx| async move {
+    pub fn create_branch(&self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+        self.send_job(|repo, _cx| async move {
             match repo {
                 RepositoryState::Local(git_repository) => {
-                    git_repository.load_index_text(path).await
+                    git_repository.create_branch(branch_name).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitCreateBranch {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            branch_name,
+                        })
+                        .await?;
+
+                    Ok(())
                 }
-                RepositoryState::Remote { .. } => None,
             }
         })
     }
 
-    pub fn has_conflict(&self, path: &RepoPath) -> bool {
-        self.repository_entry
-            .current_merge_conflicts
-            .contains(&path)
+    pub fn change_branch(&self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+        self.send_job(|repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    git_repository.change_branch(branch_name).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitChangeBranch {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            branch_name,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn repo_path_to_project_path(&self, path: &RepoPath, cx: &App) -> Option<ProjectPath> {
-        let git_store = self.git_store.upgrade()?;
-        let worktree_store = git_store.read(cx).worktree_store.read(cx);
-        let abs_path = self.repository_entry.work_directory_abs_path.join(&path.0);
-        let (worktree, relative_path) = worktree_store.find_worktree(abs_path, cx)?;
-        Some(ProjectPath {
-            worktree_id: worktree.read(cx).id(),
-            path: relative_path.into(),
+    pub fn stash_entries(&self) -> oneshot::Receiver<Result<Vec<StashEntry>>> {
+        self.send_job(|repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.stash_entries().await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitStashList {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(|entry| StashEntry {
+                            index: entry.index as usize,
+                            sha: entry.sha.into(),
+                            message: entry.message.into(),
+                            commit_timestamp: entry.commit_timestamp,
+                        })
+                        .collect())
+                }
+            }
         })
     }
 
-    pub fn project_path_to_repo_path(&self, path: &ProjectPath, cx: &App) -> Option<RepoPath> {
-        let git_store = self.git_store.upgrade()?;
-        let worktree_store = git_store.read(cx).worktree_store.read(cx);
-        let abs_path = worktree_store.absolutize(path, cx)?;
-        self.repository_entry.relativize_abs_path(&abs_path)
+    pub fn stash_push(&self, options: StashOptions, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(|repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.stash_push(options, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitStashPush {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            message: options.message,
+                            include_untracked: options.include_untracked,
+                            staged: options.staged,
+                        })
+                        .await
+                        .context("sending stash request")?;
+
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn contains_sub_repo(&self, other: &Entity<Self>, cx: &App) -> bool {
-        other
-            .read(cx)
-            .repository_entry
-            .work_directory_abs_path
-            .starts_with(&self.repository_entry.work_directory_abs_path)
+    pub fn stash_diff(&self, index: usize) -> oneshot::Receiver<Result<Vec<CommitFileDiff>>> {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.stash_diff(index).await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitStashDiff {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            index: index as u64,
+                        })
+                        .await?;
+
+                    Ok(response
+                        .files
+                        .into_iter()
+                        .map(|file| CommitFileDiff {
+                            path: RepoPath::from_str(&file.path),
+                            old_text: file.old_text,
+                            new_text: file.new_text,
+                        })
+                        .collect())
+                }
+            }
+        })
     }
 
-    pub fn local_repository(&self) -> Option<Arc<dyn GitRepository>> {
-        match &self.state {
-            RepositoryState::Local(git_repository) => Some(git_repository.clone()),
-            RepositoryState::Remote { .. } => None,
-        }
+    /// Returns a page of the commits reachable from `HEAD`, children before their parents.
+    pub fn log(&self, options: LogOptions) -> oneshot::Receiver<Result<Vec<LogEntry>>> {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.log(options).await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            skip: options.skip as u64,
+                            limit: options.limit as u64,
+                            path: options.path.map(|path| path.to_string_lossy().to_string()),
+                            line_range_start: options.line_range.as_ref().map(|range| range.start),
+                            line_range_end: options.line_range.as_ref().map(|range| range.end),
+                            author: options.author,
+                            message: options.message,
+                            since: options.since,
+                            until: options.until,
+                        })
+                        .await?;
+
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(log_entry_from_proto)
+                        .collect())
+                }
+            }
+        })
     }
 
-    pub fn open_commit_buffer(
-        &mut self,
-        languages: Option<Arc<LanguageRegistry>>,
-        buffer_store: Entity<BufferStore>,
-        cx: &mut Context<Self>,
-    ) -> Task<Result<Entity<Buffer>>> {
-        if let Some(buffer) = self.commit_message_buffer.clone() {
-            return Task::ready(Ok(buffer));
-        }
+    pub fn commit_diff(&self, commit: String) -> oneshot::Receiver<Result<Vec<CommitFileDiff>>> {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.commit_diff(commit).await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitCommitDiff {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            commit,
+                        })
+                        .await?;
 
-        if let RepositoryState::Remote {
-            project_id,
-            client,
-            work_directory_id,
-        } = self.state.clone()
-        {
-            let client = client.clone();
-            cx.spawn(async move |repository, cx| {
-                let request = client.request(proto::OpenCommitMessageBuffer {
-                    project_id: project_id.0,
-                    work_directory_id: work_directory_id.to_proto(),
-                });
-                let response = request.await.context("requesting to open commit buffer")?;
-                let buffer_id = BufferId::new(response.buffer_id)?;
-                let buffer = buffer_store
-                    .update(cx, |buffer_store, cx| {
-                        buffer_store.wait_for_remote_buffer(buffer_id, cx)
-                    })?
-                    .await?;
-                if let Some(language_registry) = languages {
-                    let git_commit_language =
-                        language_registry.language_for_name("Git Commit").await?;
-                    buffer.update(cx, |buffer, cx| {
-                        buffer.set_language(Some(git_commit_language), cx);
-                    })?;
+                    Ok(response
+                        .files
+                        .into_iter()
+                        .map(|file| CommitFileDiff {
+                            path: RepoPath::from_str(&file.path),
+                            old_text: file.old_text,
+                            new_text: file.new_text,
+                        })
+                        .collect())
                 }
-                repository.update(cx, |repository, _| {
-                    repository.commit_message_buffer = Some(buffer.clone());
-                })?;
-                Ok(buffer)
-            })
-        } else {
-            self.open_local_commit_buffer(languages, buffer_store, cx)
-        }
+            }
+        })
     }
 
-    fn open_local_commit_buffer(
-        &mut self,
-        language_registry: Option<Arc<LanguageRegistry>>,
-        buffer_store: Entity<BufferStore>,
-        cx: &mut Context<Self>,
-    ) -> Task<Result<Entity<Buffer>>> {
-        cx.spawn(async move |repository, cx| {
-            let buffer = buffer_store
-                .update(cx, |buffer_store, cx| buffer_store.create_buffer(cx))?
-                .await?;
+    pub fn revision_diff(
+        &self,
+        revisions: DiffRevisions,
+    ) -> oneshot::Receiver<Result<Vec<CommitFileDiff>>> {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    git_repository.revision_diff(revisions).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitRevisionDiff {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            revisions: Some(diff_revisions_to_proto(revisions)),
+                        })
+                        .await?;
 
-            if let Some(language_registry) = language_registry {
-                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
-                buffer.update(cx, |buffer, cx| {
-                    buffer.set_language(Some(git_commit_language), cx);
-                })?;
+                    Ok(response
+                        .files
+                        .into_iter()
+                        .map(|file| CommitFileDiff {
+                            path: RepoPath::from_str(&file.path),
+                            old_text: file.old_text,
+                            new_text: file.new_text,
+                        })
+                        .collect())
+                }
             }
+        })
+    }
 
-            repository.update(cx, |repository, _| {
-                repository.commit_message_buffer = Some(buffer.clone());
-            })?;
-            Ok(buffer)
+    pub fn worktrees(&self) -> oneshot::Receiver<Result<Vec<GitWorktree>>> {
+        self.send_job(|repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.worktrees().await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitWorktreeList {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response
+                        .worktrees
+                        .into_iter()
+                        .map(worktree_from_proto)
+                        .collect())
+                }
+            }
         })
     }
 
-    pub fn checkout_files(
+    pub fn create_worktree(
         &self,
-        commit: &str,
-        paths: Vec<RepoPath>,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
         cx: &mut App,
     ) -> oneshot::Receiver<Result<()>> {
-        let commit = commit.to_string();
         let env = self.worktree_environment(cx);
-
-        self.send_job(|git_repo, _| async move {
-            match git_repo {
-                RepositoryState::Local(repo) => repo.checkout_files(commit, paths, env.await).await,
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository
+                        .create_worktree(path, branch, create_branch, env)
+                        .await
+                }
                 RepositoryState::Remote {
                     project_id,
                     client,
                     work_directory_id,
                 } => {
                     client
-                        .request(proto::GitCheckoutFiles {
+                        .request(proto::GitCreateWorktree {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            commit,
-                            paths: paths
-                                .into_iter()
-                                .map(|p| p.to_string_lossy().to_string())
-                                .collect(),
+                            path: path.to_string_lossy().to_string(),
+                            branch,
+                            create_branch,
                         })
                         .await?;
 
@@ -2803,19 +4553,18 @@ impl Repository {
         })
     }
 
-    pub fn reset(
+    pub fn remove_worktree(
         &self,
-        commit: String,
-        reset_mode: ResetMode,
+        path: PathBuf,
+        force: bool,
         cx: &mut App,
     ) -> oneshot::Receiver<Result<()>> {
-        let commit = commit.to_string();
         let env = self.worktree_environment(cx);
-        self.send_job(|git_repo, _| async move {
-            match git_repo {
-                RepositoryState::Local(git_repo) => {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
                     let env = env.await;
-                    git_repo.reset(commit, reset_mode, env).await
+                    git_repository.remove_worktree(path, force, env).await
                 }
                 RepositoryState::Remote {
                     project_id,
@@ -2823,14 +4572,11 @@ impl Repository {
                     work_directory_id,
                 } => {
                     client
-                        .request(proto::GitReset {
+                        .request(proto::GitRemoveWorktree {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            commit,
-                            mode: match reset_mode {
-                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
-                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
-                            },
+                            path: path.to_string_lossy().to_string(),
+                            force,
                         })
                         .await?;
 
@@ -2840,252 +4586,91 @@ impl Repository {
         })
     }
 
-    pub fn show(&self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
-        self.send_job(|git_repo, _cx| async move {
-            match git_repo {
-                RepositoryState::Local(git_repository) => git_repository.show(commit).await,
+    pub fn prune_worktrees(&self, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.prune_worktrees(env).await
+                }
                 RepositoryState::Remote {
                     project_id,
                     client,
                     work_directory_id,
                 } => {
-                    let resp = client
-                        .request(proto::GitShow {
+                    client
+                        .request(proto::GitPruneWorktrees {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            commit,
                         })
                         .await?;
 
-                    Ok(CommitDetails {
-                        sha: resp.sha.into(),
-                        message: resp.message.into(),
-                        commit_timestamp: resp.commit_timestamp,
-                        committer_email: resp.committer_email.into(),
-                        committer_name: resp.committer_name.into(),
-                    })
-                }
-            }
-        })
-    }
-
-    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
-        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
-    }
-
-    pub fn stage_entries(
-        &self,
-        entries: Vec<RepoPath>,
-        cx: &mut Context<Self>,
-    ) -> Task<anyhow::Result<()>> {
-        if entries.is_empty() {
-            return Task::ready(Ok(()));
-        }
-        let env = self.worktree_environment(cx);
-
-        let mut save_futures = Vec::new();
-        if let Some(buffer_store) = self.buffer_store(cx) {
-            buffer_store.update(cx, |buffer_store, cx| {
-                for path in &entries {
-                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
-                        continue;
-                    };
-                    if let Some(buffer) = buffer_store.get_by_path(&project_path, cx) {
-                        if buffer
-                            .read(cx)
-                            .file()
-                            .map_or(false, |file| file.disk_state().exists())
-                        {
-                            save_futures.push(buffer_store.save_buffer(buffer, cx));
-                        }
-                    }
-                }
-            })
-        }
-
-        cx.spawn(async move |this, cx| {
-            for save_future in save_futures {
-                save_future.await?;
-            }
-            let env = env.await;
-
-            this.update(cx, |this, _| {
-                this.send_job(|git_repo, _cx| async move {
-                    match git_repo {
-                        RepositoryState::Local(repo) => repo.stage_paths(entries, env).await,
-                        RepositoryState::Remote {
-                            project_id,
-                            client,
-                            work_directory_id,
-                        } => {
-                            client
-                                .request(proto::Stage {
-                                    project_id: project_id.0,
-                                    work_directory_id: work_directory_id.to_proto(),
-                                    paths: entries
-                                        .into_iter()
-                                        .map(|repo_path| repo_path.as_ref().to_proto())
-                                        .collect(),
-                                })
-                                .await
-                                .context("sending stage request")?;
-
-                            Ok(())
-                        }
-                    }
-                })
-            })?
-            .await??;
-
-            Ok(())
-        })
-    }
-
-    pub fn unstage_entries(
-        &self,
-        entries: Vec<RepoPath>,
-        cx: &mut Context<Self>,
-    ) -> Task<anyhow::Result<()>> {
-        if entries.is_empty() {
-            return Task::ready(Ok(()));
-        }
-        let env = self.worktree_environment(cx);
-
-        let mut save_futures = Vec::new();
-        if let Some(buffer_store) = self.buffer_store(cx) {
-            buffer_store.update(cx, |buffer_store, cx| {
-                for path in &entries {
-                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
-                        continue;
-                    };
-                    if let Some(buffer) = buffer_store.get_by_path(&project_path, cx) {
-                        if buffer
-                            .read(cx)
-                            .file()
-                            .map_or(false, |file| file.disk_state().exists())
-                        {
-                            save_futures.push(buffer_store.save_buffer(buffer, cx));
-                        }
-                    }
+                    Ok(())
                 }
-            })
-        }
-
-        cx.spawn(async move |this, cx| {
-            for save_future in save_futures {
-                save_future.await?;
             }
-            let env = env.await;
-
-            this.update(cx, |this, _| {
-                this.send_job(|git_repo, _cx| async move {
-                    match git_repo {
-                        RepositoryState::Local(repo) => repo.unstage_paths(entries, env).await,
-                        RepositoryState::Remote {
-                            project_id,
-                            client,
-                            work_directory_id,
-                        } => {
-                            client
-                                .request(proto::Unstage {
-                                    project_id: project_id.0,
-                                    work_directory_id: work_directory_id.to_proto(),
-                                    paths: entries
-                                        .into_iter()
-                                        .map(|repo_path| repo_path.as_ref().to_proto())
-                                        .collect(),
-                                })
-                                .await
-                                .context("sending unstage request")?;
-
-                            Ok(())
-                        }
-                    }
-                })
-            })?
-            .await??;
-
-            Ok(())
         })
     }
 
-    pub fn stage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
-        let to_stage = self
-            .repository_entry
-            .status()
-            .filter(|entry| !entry.status.staging().is_fully_staged())
-            .map(|entry| entry.repo_path.clone())
-            .collect();
-        self.stage_entries(to_stage, cx)
-    }
-
-    pub fn unstage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
-        let to_unstage = self
-            .repository_entry
-            .status()
-            .filter(|entry| entry.status.staging().has_staged())
-            .map(|entry| entry.repo_path.clone())
-            .collect();
-        self.unstage_entries(to_unstage, cx)
-    }
-
-    /// Get a count of all entries in the active repository, including
-    /// untracked files.
-    pub fn entry_count(&self) -> usize {
-        self.repository_entry.status_len()
-    }
-
-    fn worktree_environment(
-        &self,
-        cx: &mut App,
-    ) -> impl Future<Output = HashMap<String, String>> + 'static {
-        let task = self.project_environment.as_ref().and_then(|env| {
-            env.update(cx, |env, cx| {
-                env.get_environment(
-                    self.worktree_id,
-                    Some(
-                        self.repository_entry
-                            .work_directory_abs_path
-                            .as_path()
-                            .into(),
-                    ),
-                    cx,
-                )
-            })
-            .ok()
-        });
-        async move { OptionFuture::from(task).await.flatten().unwrap_or_default() }
+    pub fn tags(&self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        self.send_job(|repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.tags().await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitTagList {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response
+                        .tags
+                        .into_iter()
+                        .map(|tag| Tag {
+                            name: tag.name.into(),
+                            sha: tag.sha.into(),
+                            subject: tag.subject.into(),
+                            timestamp: tag.timestamp,
+                        })
+                        .collect())
+                }
+            }
+        })
     }
 
-    pub fn commit(
+    pub fn create_tag(
         &self,
-        message: SharedString,
-        name_and_email: Option<(SharedString, SharedString)>,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
         cx: &mut App,
     ) -> oneshot::Receiver<Result<()>> {
         let env = self.worktree_environment(cx);
-        self.send_job(|git_repo, _cx| async move {
-            match git_repo {
-                RepositoryState::Local(repo) => {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
                     let env = env.await;
-                    repo.commit(message, name_and_email, env).await
+                    git_repository.create_tag(name, target, message, env).await
                 }
                 RepositoryState::Remote {
                     project_id,
                     client,
                     work_directory_id,
                 } => {
-                    let (name, email) = name_and_email.unzip();
                     client
-                        .request(proto::Commit {
+                        .request(proto::GitCreateTag {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            message: String::from(message),
-                            name: name.map(String::from),
-                            email: email.map(String::from),
+                            name,
+                            target,
+                            message,
                         })
-                        .await
-                        .context("sending commit request")?;
+                        .await?;
 
                     Ok(())
                 }
@@ -3093,8 +4678,37 @@ impl Repository {
         })
     }
 
-    pub fn fetch(
+    pub fn delete_tag(&self, name: String, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.delete_tag(name, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitDeleteTag {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            name,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn push_tag(
         &mut self,
+        name: SharedString,
+        remote: SharedString,
         askpass: AskPassDelegate,
         cx: &mut App,
     ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
@@ -3106,7 +4720,9 @@ impl Repository {
             match git_repo {
                 RepositoryState::Local(git_repository) => {
                     let env = env.await;
-                    git_repository.fetch(askpass, env, cx).await
+                    git_repository
+                        .push_tag(name.to_string(), remote.to_string(), askpass, env, cx)
+                        .await
                 }
                 RepositoryState::Remote {
                     project_id,
@@ -3118,15 +4734,16 @@ impl Repository {
                         let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
                         debug_assert!(askpass_delegate.is_some());
                     });
-
                     let response = client
-                        .request(proto::Fetch {
+                        .request(proto::GitPushTag {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
                             askpass_id,
+                            name: name.to_string(),
+                            remote_name: remote.to_string(),
                         })
                         .await
-                        .context("sending fetch request")?;
+                        .context("sending push tag request")?;
 
                     Ok(RemoteCommandOutput {
                         stdout: response.stdout,
@@ -3137,32 +4754,166 @@ impl Repository {
         })
     }
 
-    pub fn push(
-        &mut self,
-        branch: SharedString,
-        remote: SharedString,
-        options: Option<PushOptions>,
-        askpass: AskPassDelegate,
+    pub fn add_remote(
+        &self,
+        name: String,
+        url: String,
         cx: &mut App,
-    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
-        let askpass_delegates = self.askpass_delegates.clone();
-        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+    ) -> oneshot::Receiver<Result<()>> {
         let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.add_remote(name, url, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitAddRemote {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            name,
+                            url,
+                        })
+                        .await?;
 
-        self.send_job(move |git_repo, cx| async move {
-            match git_repo {
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn rename_remote(
+        &self,
+        name: String,
+        new_name: String,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.rename_remote(name, new_name, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitRenameRemote {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            name,
+                            new_name,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn remove_remote(&self, name: String, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.remove_remote(name, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitRemoveRemote {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            name,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn cherry_pick(&self, commits: Vec<String>, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.cherry_pick(commits, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitCherryPick {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            commits,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn revert(&self, commits: Vec<String>, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.revert(commits, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitRevert {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            commits,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn sequencer_command(
+        &self,
+        operation: SequencerOperation,
+        command: RebaseCommand,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
                 RepositoryState::Local(git_repository) => {
                     let env = env.await;
-                    // let askpass = AskPassSession::new(&executor, askpass).await?;
                     git_repository
-                        .push(
-                            branch.to_string(),
-                            remote.to_string(),
-                            options,
-                            askpass,
-                            env,
-                            cx,
-                        )
+                        .sequencer_command(operation, command, env)
                         .await
                 }
                 RepositoryState::Remote {
@@ -3170,39 +4921,87 @@ impl Repository {
                     client,
                     work_directory_id,
                 } => {
-                    askpass_delegates.lock().insert(askpass_id, askpass);
-                    let _defer = util::defer(|| {
-                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
-                        debug_assert!(askpass_delegate.is_some());
-                    });
+                    client
+                        .request(proto::GitSequencerCommand {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            operation: sequencer_operation_to_proto(operation).into(),
+                            command: match command {
+                                RebaseCommand::Continue => git_rebase_command::Command::Continue,
+                                RebaseCommand::Skip => git_rebase_command::Command::Skip,
+                                RebaseCommand::Abort => git_rebase_command::Command::Abort,
+                            }
+                            .into(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Returns the cherry-pick or revert in progress, if any.
+    pub fn sequencer_status(&self) -> oneshot::Receiver<Result<Option<SequencerOperation>>> {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => git_repository.sequencer_status().await,
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
                     let response = client
-                        .request(proto::Push {
+                        .request(proto::GitSequencerStatus {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response
+                        .operation
+                        .map(|_| sequencer_operation_from_proto(response.operation())))
+                }
+            }
+        })
+    }
+
+    pub fn submodules(&self, cx: &mut App) -> oneshot::Receiver<Result<Vec<Submodule>>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.submodules(env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitSubmodules {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            askpass_id,
-                            branch_name: branch.to_string(),
-                            remote_name: remote.to_string(),
-                            options: options.map(|options| match options {
-                                PushOptions::Force => proto::push::PushOptions::Force,
-                                PushOptions::SetUpstream => proto::push::PushOptions::SetUpstream,
-                            } as i32),
                         })
-                        .await
-                        .context("sending push request")?;
+                        .await?;
 
-                    Ok(RemoteCommandOutput {
-                        stdout: response.stdout,
-                        stderr: response.stderr,
-                    })
+                    Ok(response
+                        .submodules
+                        .into_iter()
+                        .map(submodule_from_proto)
+                        .collect())
                 }
             }
         })
     }
 
-    pub fn pull(
+    /// Checks out the commits recorded for the submodules at `paths`, or for all of them if
+    /// `paths` is empty, cloning the uninitialized ones when `init` is set.
+    pub fn update_submodules(
         &mut self,
-        branch: SharedString,
-        remote: SharedString,
+        paths: Vec<RepoPath>,
+        init: bool,
         askpass: AskPassDelegate,
         cx: &mut App,
     ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
@@ -3215,7 +5014,7 @@ impl Repository {
                 RepositoryState::Local(git_repository) => {
                     let env = env.await;
                     git_repository
-                        .pull(branch.to_string(), remote.to_string(), askpass, env, cx)
+                        .update_submodules(paths, init, askpass, env, cx)
                         .await
                 }
                 RepositoryState::Remote {
@@ -3229,15 +5028,18 @@ impl Repository {
                         debug_assert!(askpass_delegate.is_some());
                     });
                     let response = client
-                        .request(proto::Pull {
+                        .request(proto::GitUpdateSubmodules {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
+                            paths: paths
+                                .iter()
+                                .map(|path| path.to_string_lossy().to_string())
+                                .collect(),
+                            init,
                             askpass_id,
-                            branch_name: branch.to_string(),
-                            remote_name: remote.to_string(),
                         })
                         .await
-                        .context("sending pull request")?;
+                        .context("sending update submodules request")?;
 
                     Ok(RemoteCommandOutput {
                         stdout: response.stdout,
@@ -3248,149 +5050,222 @@ impl Repository {
         })
     }
 
-    fn spawn_set_index_text_job(
+    pub fn sync_submodules(
         &self,
-        path: RepoPath,
-        content: Option<String>,
+        paths: Vec<RepoPath>,
         cx: &mut App,
-    ) -> oneshot::Receiver<anyhow::Result<()>> {
+    ) -> oneshot::Receiver<Result<()>> {
         let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.sync_submodules(paths, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitSyncSubmodules {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            paths: paths
+                                .iter()
+                                .map(|path| path.to_string_lossy().to_string())
+                                .collect(),
+                        })
+                        .await?;
 
-        self.send_keyed_job(
-            Some(GitJobKey::WriteIndex(path.clone())),
-            |git_repo, _cx| async {
-                match git_repo {
-                    RepositoryState::Local(repo) => {
-                        repo.set_index_text(path, content, env.await).await
-                    }
-                    RepositoryState::Remote {
-                        project_id,
-                        client,
-                        work_directory_id,
-                    } => {
-                        client
-                            .request(proto::SetIndexText {
-                                project_id: project_id.0,
-                                work_directory_id: work_directory_id.to_proto(),
-                                path: path.as_ref().to_proto(),
-                                text: content,
-                            })
-                            .await?;
-                        Ok(())
-                    }
+                    Ok(())
                 }
-            },
-        )
+            }
+        })
     }
 
-    pub fn get_remotes(
-        &self,
-        branch_name: Option<String>,
-    ) -> oneshot::Receiver<Result<Vec<Remote>>> {
-        self.send_job(|repo, _cx| async move {
+    /// Returns the commit checked out in the submodule at `path`, compared with the one `HEAD`
+    /// records for it.
+    pub fn submodule_diff(&self, path: RepoPath) -> oneshot::Receiver<Result<CommitFileDiff>> {
+        self.send_job(move |repo, _cx| async move {
             match repo {
-                RepositoryState::Local(git_repository) => {
-                    git_repository.get_remotes(branch_name).await
-                }
+                RepositoryState::Local(git_repository) => git_repository.submodule_diff(path).await,
                 RepositoryState::Remote {
                     project_id,
                     client,
                     work_directory_id,
                 } => {
                     let response = client
-                        .request(proto::GetRemotes {
+                        .request(proto::GitSubmoduleDiff {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            branch_name,
+                            path: path.to_string_lossy().to_string(),
                         })
                         .await?;
 
-                    let remotes = response
-                        .remotes
+                    let file = response
+                        .files
                         .into_iter()
-                        .map(|remotes| git::repository::Remote {
-                            name: remotes.name.into(),
+                        .next()
+                        .ok_or_else(|| anyhow!("missing submodule diff"))?;
+                    Ok(CommitFileDiff {
+                        path: RepoPath::from_str(&file.path),
+                        old_text: file.old_text,
+                        new_text: file.new_text,
+                    })
+                }
+            }
+        })
+    }
+
+    /// Returns the commits a rebase onto `upstream` would apply, oldest first.
+    pub fn rebase_commits(&self, upstream: String) -> oneshot::Receiver<Result<Vec<LogEntry>>> {
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    git_repository.rebase_commits(upstream).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    let response = client
+                        .request(proto::GitRebaseCommits {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            upstream,
                         })
-                        .collect();
+                        .await?;
 
-                    Ok(remotes)
+                    Ok(response
+                        .entries
+                        .into_iter()
+                        .map(log_entry_from_proto)
+                        .collect())
                 }
             }
         })
     }
 
-    pub fn branch(&self) -> Option<&Branch> {
-        self.repository_entry.branch()
+    pub fn rebase(
+        &self,
+        upstream: String,
+        steps: Vec<RebaseStep>,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.rebase(upstream, steps, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitRebase {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            upstream,
+                            steps: steps
+                                .into_iter()
+                                .map(|step| proto::GitRebaseStep {
+                                    sha: step.sha.to_string(),
+                                    action: match step.action {
+                                        RebaseAction::Pick => git_rebase_step::Action::Pick,
+                                        RebaseAction::Reword => git_rebase_step::Action::Reword,
+                                        RebaseAction::Squash => git_rebase_step::Action::Squash,
+                                        RebaseAction::Fixup => git_rebase_step::Action::Fixup,
+                                        RebaseAction::Drop => git_rebase_step::Action::Drop,
+                                    }
+                                    .into(),
+                                    message: step.message,
+                                })
+                                .collect(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn branches(&self) -> oneshot::Receiver<Result<Vec<Branch>>> {
-        self.send_job(|repo, cx| async move {
+    pub fn rebase_command(
+        &self,
+        command: RebaseCommand,
+        cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
             match repo {
                 RepositoryState::Local(git_repository) => {
-                    let git_repository = git_repository.clone();
-                    cx.background_spawn(async move { git_repository.branches().await })
-                        .await
+                    let env = env.await;
+                    git_repository.rebase_command(command, env).await
                 }
                 RepositoryState::Remote {
                     project_id,
                     client,
                     work_directory_id,
                 } => {
-                    let response = client
-                        .request(proto::GitGetBranches {
+                    client
+                        .request(proto::GitRebaseCommand {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
+                            command: match command {
+                                RebaseCommand::Continue => git_rebase_command::Command::Continue,
+                                RebaseCommand::Skip => git_rebase_command::Command::Skip,
+                                RebaseCommand::Abort => git_rebase_command::Command::Abort,
+                            }
+                            .into(),
                         })
                         .await?;
 
-                    let branches = response
-                        .branches
-                        .into_iter()
-                        .map(|branch| worktree::proto_to_branch(&branch))
-                        .collect();
-
-                    Ok(branches)
+                    Ok(())
                 }
             }
         })
     }
 
-    pub fn diff(&self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
-        self.send_job(|repo, _cx| async move {
+    /// Returns the progress of the rebase in progress, if any.
+    pub fn rebase_status(&self) -> oneshot::Receiver<Result<Option<RebaseStatus>>> {
+        self.send_job(move |repo, _cx| async move {
             match repo {
-                RepositoryState::Local(git_repository) => git_repository.diff(diff_type).await,
+                RepositoryState::Local(git_repository) => git_repository.rebase_status().await,
                 RepositoryState::Remote {
                     project_id,
                     client,
                     work_directory_id,
-                    ..
                 } => {
                     let response = client
-                        .request(proto::GitDiff {
+                        .request(proto::GitRebaseStatus {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            diff_type: match diff_type {
-                                DiffType::HeadToIndex => {
-                                    proto::git_diff::DiffType::HeadToIndex.into()
-                                }
-                                DiffType::HeadToWorktree => {
-                                    proto::git_diff::DiffType::HeadToWorktree.into()
-                                }
-                            },
                         })
                         .await?;
 
-                    Ok(response.diff)
+                    Ok(response.progress.map(|progress| RebaseStatus {
+                        branch: progress.branch.map(Into::into),
+                        onto: progress.onto.into(),
+                        step: progress.step as usize,
+                        total_steps: progress.total_steps as usize,
+                    }))
                 }
             }
         })
     }
 
-    pub fn create_branch(&self, branch_name: String) -> oneshot::Receiver<Result<()>> {
-        self.send_job(|repo, _cx| async move {
+    pub fn stash_apply(&self, index: usize, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
             match repo {
                 RepositoryState::Local(git_repository) => {
-                    git_repository.create_branch(branch_name).await
+                    let env = env.await;
+                    git_repository.stash_apply(index, env).await
                 }
                 RepositoryState::Remote {
                     project_id,
@@ -3398,10 +5273,10 @@ impl Repository {
                     work_directory_id,
                 } => {
                     client
-                        .request(proto::GitCreateBranch {
+                        .request(proto::GitStashApply {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            branch_name,
+                            index: index as u64,
                         })
                         .await?;
 
@@ -3411,11 +5286,13 @@ impl Repository {
         })
     }
 
-    pub fn change_branch(&self, branch_name: String) -> oneshot::Receiver<Result<()>> {
-        self.send_job(|repo, _cx| async move {
+    pub fn stash_pop(&self, index: usize, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
             match repo {
                 RepositoryState::Local(git_repository) => {
-                    git_repository.change_branch(branch_name).await
+                    let env = env.await;
+                    git_repository.stash_pop(index, env).await
                 }
                 RepositoryState::Remote {
                     project_id,
@@ -3423,10 +5300,37 @@ impl Repository {
                     work_directory_id,
                 } => {
                     client
-                        .request(proto::GitChangeBranch {
+                        .request(proto::GitStashPop {
                             project_id: project_id.0,
                             work_directory_id: work_directory_id.to_proto(),
-                            branch_name,
+                            index: index as u64,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn stash_drop(&self, index: usize, cx: &mut App) -> oneshot::Receiver<Result<()>> {
+        let env = self.worktree_environment(cx);
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(git_repository) => {
+                    let env = env.await;
+                    git_repository.stash_drop(index, env).await
+                }
+                RepositoryState::Remote {
+                    project_id,
+                    client,
+                    work_directory_id,
+                } => {
+                    client
+                        .request(proto::GitStashDrop {
+                            project_id: project_id.0,
+                            work_directory_id: work_directory_id.to_proto(),
+                            index: index as u64,
                         })
                         .await?;
 
@@ -3694,3 +5598,149 @@ fn deserialize_blame_buffer_response(
         remote_url: response.remote_url,
     })
 }
+
+fn log_entry_to_proto(entry: LogEntry) -> proto::GitLogEntry {
+    proto::GitLogEntry {
+        sha: entry.sha.to_string(),
+        parent_shas: entry
+            .parent_shas
+            .into_iter()
+            .map(|sha| sha.to_string())
+            .collect(),
+        subject: entry.subject.to_string(),
+        author_name: entry.author_name.to_string(),
+        author_email: entry.author_email.to_string(),
+        commit_timestamp: entry.commit_timestamp,
+        ref_names: entry
+            .ref_names
+            .into_iter()
+            .map(|ref_name| ref_name.to_string())
+            .collect(),
+    }
+}
+
+fn log_entry_from_proto(entry: proto::GitLogEntry) -> LogEntry {
+    LogEntry {
+        sha: entry.sha.into(),
+        parent_shas: entry.parent_shas.into_iter().map(Into::into).collect(),
+        subject: entry.subject.into(),
+        author_name: entry.author_name.into(),
+        author_email: entry.author_email.into(),
+        commit_timestamp: entry.commit_timestamp,
+        ref_names: entry.ref_names.into_iter().map(Into::into).collect(),
+    }
+}
+
+fn diff_revisions_to_proto(revisions: DiffRevisions) -> proto::GitDiffRevisions {
+    proto::GitDiffRevisions {
+        base: revisions.base,
+        target: revisions.target,
+        merge_base: revisions.merge_base,
+    }
+}
+
+fn diff_revisions_from_proto(revisions: proto::GitDiffRevisions) -> DiffRevisions {
+    DiffRevisions {
+        base: revisions.base,
+        target: revisions.target,
+        merge_base: revisions.merge_base,
+    }
+}
+
+fn worktree_to_proto(worktree: GitWorktree) -> proto::GitWorktree {
+    proto::GitWorktree {
+        path: worktree.path.to_string_lossy().to_string(),
+        sha: worktree.sha.map(|sha| sha.to_string()),
+        branch: worktree.branch.map(|branch| branch.to_string()),
+        is_main: worktree.is_main,
+        is_locked: worktree.is_locked,
+        is_prunable: worktree.is_prunable,
+    }
+}
+
+fn worktree_from_proto(worktree: proto::GitWorktree) -> GitWorktree {
+    GitWorktree {
+        path: PathBuf::from(worktree.path),
+        sha: worktree.sha.map(SharedString::from),
+        branch: worktree.branch.map(SharedString::from),
+        is_main: worktree.is_main,
+        is_locked: worktree.is_locked,
+        is_prunable: worktree.is_prunable,
+    }
+}
+
+fn sequencer_operation_to_proto(operation: SequencerOperation) -> proto::GitSequencerOperation {
+    match operation {
+        SequencerOperation::CherryPick => proto::GitSequencerOperation::CherryPick,
+        SequencerOperation::Revert => proto::GitSequencerOperation::Revert,
+    }
+}
+
+fn sequencer_operation_from_proto(operation: proto::GitSequencerOperation) -> SequencerOperation {
+    match operation {
+        proto::GitSequencerOperation::CherryPick => SequencerOperation::CherryPick,
+        proto::GitSequencerOperation::Revert => SequencerOperation::Revert,
+    }
+}
+
+fn commit_signature_to_proto(signature: CommitSignature) -> proto::GitCommitSignature {
+    use proto::git_commit_signature::Status;
+    let status = match signature.status {
+        SignatureStatus::Good => Status::Good,
+        SignatureStatus::Untrusted => Status::Untrusted,
+        SignatureStatus::Expired => Status::Expired,
+        SignatureStatus::Revoked => Status::Revoked,
+        SignatureStatus::Bad => Status::Bad,
+        SignatureStatus::Unverified => Status::Unverified,
+    };
+    proto::GitCommitSignature {
+        status: status.into(),
+        signer: signature.signer.into(),
+    }
+}
+
+fn commit_signature_from_proto(signature: proto::GitCommitSignature) -> CommitSignature {
+    use proto::git_commit_signature::Status;
+    let status = match signature.status() {
+        Status::Good => SignatureStatus::Good,
+        Status::Untrusted => SignatureStatus::Untrusted,
+        Status::Expired => SignatureStatus::Expired,
+        Status::Revoked => SignatureStatus::Revoked,
+        Status::Bad => SignatureStatus::Bad,
+        Status::Unverified => SignatureStatus::Unverified,
+    };
+    CommitSignature {
+        status,
+        signer: signature.signer.into(),
+    }
+}
+
+fn submodule_to_proto(submodule: Submodule) -> proto::GitSubmodule {
+    use proto::git_submodule::Status;
+    let status = match submodule.status {
+        SubmoduleStatus::Uninitialized => Status::Uninitialized,
+        SubmoduleStatus::UpToDate => Status::UpToDate,
+        SubmoduleStatus::Modified => Status::Modified,
+        SubmoduleStatus::Conflicted => Status::Conflicted,
+    };
+    proto::GitSubmodule {
+        path: submodule.path.to_string_lossy().to_string(),
+        sha: submodule.sha.into(),
+        status: status.into(),
+    }
+}
+
+fn submodule_from_proto(submodule: proto::GitSubmodule) -> Submodule {
+    use proto::git_submodule::Status;
+    let status = match submodule.status() {
+        Status::Uninitialized => SubmoduleStatus::Uninitialized,
+        Status::UpToDate => SubmoduleStatus::UpToDate,
+        Status::Modified => SubmoduleStatus::Modified,
+        Status::Conflicted => SubmoduleStatus::Conflicted,
+    };
+    Submodule {
+        path: RepoPath::from_str(&submodule.path),
+        sha: submodule.sha.into(),
+        status,
+    }
+}
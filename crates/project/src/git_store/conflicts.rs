@@ -0,0 +1,219 @@
+use std::ops::Range;
+
+use text::{Anchor, BufferSnapshot};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// A region of a buffer delimited by merge conflict markers.
+///
+/// The ranges of the sides of the conflict exclude the marker lines, while [`Self::range`]
+/// spans the whole region, including the newline after the closing marker.
+#[derive(Clone, Debug)]
+pub struct ConflictRegion {
+    pub range: Range<Anchor>,
+    pub ours: Range<Anchor>,
+    pub theirs: Range<Anchor>,
+    /// The common ancestor of both sides, when the conflict was written in the `diff3` style.
+    pub base: Option<Range<Anchor>>,
+    /// The name following the opening marker, usually `HEAD`.
+    pub ours_label: String,
+    /// The name following the closing marker, usually the branch or commit being merged.
+    pub theirs_label: String,
+}
+
+/// The way a [`ConflictRegion`] is resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    /// Keeps our side followed by theirs.
+    Both,
+}
+
+impl ConflictRegion {
+    /// Returns the text to replace the region with to resolve it.
+    pub fn resolved_text(&self, resolution: ConflictResolution, buffer: &BufferSnapshot) -> String {
+        let ours = buffer.text_for_range(self.ours.clone());
+        let theirs = buffer.text_for_range(self.theirs.clone());
+        match resolution {
+            ConflictResolution::Ours => ours.collect(),
+            ConflictResolution::Theirs => theirs.collect(),
+            ConflictResolution::Both => ours.chain(theirs).collect(),
+        }
+    }
+}
+
+/// Finds the regions of the buffer delimited by merge conflict markers. Regions that are
+/// missing a marker are ignored.
+pub fn conflict_regions(buffer: &BufferSnapshot) -> Vec<ConflictRegion> {
+    struct PendingRegion {
+        start: usize,
+        ours_start: usize,
+        ours_label: String,
+        base_start: Option<usize>,
+        ours_end: Option<usize>,
+        base_end: Option<usize>,
+        theirs_start: Option<usize>,
+    }
+
+    let text = buffer.text();
+    let mut regions = Vec::new();
+    let mut pending: Option<PendingRegion> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        if let Some(label) = marker_label(content, OURS_MARKER) {
+            pending = Some(PendingRegion {
+                start: line_start,
+                ours_start: offset,
+                ours_label: label.to_string(),
+                base_start: None,
+                ours_end: None,
+                base_end: None,
+                theirs_start: None,
+            });
+            continue;
+        }
+        let Some(region) = pending.as_mut() else {
+            continue;
+        };
+        if region.theirs_start.is_none() {
+            if marker_label(content, BASE_MARKER).is_some() && region.base_start.is_none() {
+                region.ours_end = Some(line_start);
+                region.base_start = Some(offset);
+            } else if content == SEPARATOR_MARKER {
+                if region.base_start.is_some() {
+                    region.base_end = Some(line_start);
+                } else {
+                    region.ours_end = Some(line_start);
+                }
+                region.theirs_start = Some(offset);
+            }
+        } else if let Some(label) = marker_label(content, THEIRS_MARKER) {
+            let region = pending.take().unwrap();
+            let (Some(ours_end), Some(theirs_start)) = (region.ours_end, region.theirs_start)
+            else {
+                continue;
+            };
+            let anchor_range = |range: Range<usize>| {
+                buffer.anchor_after(range.start)..buffer.anchor_before(range.end)
+            };
+            regions.push(ConflictRegion {
+                range: anchor_range(region.start..offset),
+                ours: anchor_range(region.ours_start..ours_end),
+                theirs: anchor_range(theirs_start..line_start),
+                base: region
+                    .base_start
+                    .zip(region.base_end)
+                    .map(|(start, end)| anchor_range(start..end)),
+                ours_label: region.ours_label,
+                theirs_label: label.to_string(),
+            });
+        }
+    }
+    regions
+}
+
+/// Returns the text following the marker if the line is a marker line.
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let label = line.strip_prefix(marker)?;
+    if label.is_empty() {
+        Some(label)
+    } else {
+        label.strip_prefix(' ')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer, BufferId, ToOffset as _};
+    use unindent::Unindent as _;
+
+    fn buffer(text: &str) -> BufferSnapshot {
+        Buffer::new(0, BufferId::new(1).unwrap(), text.to_string()).snapshot()
+    }
+
+    fn text(buffer: &BufferSnapshot, range: &Range<Anchor>) -> String {
+        buffer
+            .text_for_range(range.start.to_offset(buffer)..range.end.to_offset(buffer))
+            .collect()
+    }
+
+    #[test]
+    fn test_conflict_regions() {
+        let buffer = buffer(
+            &"
+            fn main() {
+            <<<<<<< HEAD
+                println!(\"ours\");
+            =======
+                println!(\"theirs\");
+            >>>>>>> feature
+            }
+            <<<<<<< HEAD
+            a
+            ||||||| base
+            b
+            =======
+            c
+            >>>>>>> feature
+            "
+            .unindent(),
+        );
+
+        let regions = conflict_regions(&buffer);
+        assert_eq!(regions.len(), 2);
+
+        assert_eq!(regions[0].ours_label, "HEAD");
+        assert_eq!(regions[0].theirs_label, "feature");
+        assert_eq!(text(&buffer, &regions[0].ours), "    println!(\"ours\");\n");
+        assert_eq!(
+            text(&buffer, &regions[0].theirs),
+            "    println!(\"theirs\");\n"
+        );
+        assert!(regions[0].base.is_none());
+        assert_eq!(
+            regions[0].resolved_text(ConflictResolution::Both, &buffer),
+            "    println!(\"ours\");\n    println!(\"theirs\");\n"
+        );
+
+        assert_eq!(text(&buffer, &regions[1].ours), "a\n");
+        assert_eq!(text(&buffer, regions[1].base.as_ref().unwrap()), "b\n");
+        assert_eq!(text(&buffer, &regions[1].theirs), "c\n");
+        assert_eq!(
+            text(&buffer, &regions[1].range),
+            "<<<<<<< HEAD\na\n||||||| base\nb\n=======\nc\n>>>>>>> feature\n"
+        );
+    }
+
+    #[test]
+    fn test_incomplete_conflict_regions() {
+        let buffer = buffer(
+            &"
+            <<<<<<< HEAD
+            a
+            =======
+            <<<<<<< HEAD
+            b
+            =======
+            c
+            >>>>>>> feature
+            =======
+            >>>>>>> feature
+            "
+            .unindent(),
+        );
+
+        let regions = conflict_regions(&buffer);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(text(&buffer, &regions[0].ours), "b\n");
+        assert_eq!(text(&buffer, &regions[0].theirs), "c\n");
+    }
+}
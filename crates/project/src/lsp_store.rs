@@ -3,8 +3,9 @@ pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
 
 use crate::{
-    CodeAction, Completion, CompletionSource, CoreCompletion, Hover, InlayHint, LspAction,
-    ProjectItem, ProjectPath, ProjectTransaction, ResolveState, Symbol, ToolchainStore,
+    BufferSemanticTokens, ChangeAnnotation, CodeAction, Completion, CompletionSource,
+    CoreCompletion, Hover, InlayHint, LspAction, ProjectItem, ProjectPath, ProjectTransaction,
+    RenameEdit, ResolveState, Symbol, ToolchainStore,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -100,6 +101,11 @@ pub use worktree::{
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+const DOCUMENT_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(50);
+/// The key the problems found in the tasks' output are stored under, next to the language servers' diagnostics.
+/// No language server is ever started with it and the lookups of servers by ID skip it;
+/// the problems of different tasks are told apart by their source.
+pub const TASK_DIAGNOSTICS_SOURCE_ID: LanguageServerId = LanguageServerId(usize::MAX);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FormatTrigger {
@@ -157,6 +163,9 @@ pub struct LocalLspStore {
         >,
     >,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    diagnostic_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, String>>,
+    document_diagnostics_tasks: HashMap<(BufferId, LanguageServerId), Task<()>>,
+    workspace_diagnostics_triggers: HashMap<LanguageServerId, Sender<()>>,
     _subscription: gpui::Subscription,
     lsp_tree: Entity<LanguageServerTree>,
     registered_buffers: HashMap<BufferId, usize>,
@@ -168,6 +177,9 @@ impl LocalLspStore {
         &self,
         id: LanguageServerId,
     ) -> Option<&Arc<LanguageServer>> {
+        if id == TASK_DIAGNOSTICS_SOURCE_ID {
+            return None;
+        }
         let language_server_state = self.language_servers.get(&id)?;
 
         match language_server_state {
@@ -696,6 +708,60 @@ impl LocalLspStore {
                                         anyhow::Ok(())
                                     })??;
                                 }
+                                "textDocument/prepareTypeHierarchy" => {
+                                    this.update(&mut cx, |this, _| {
+                                        if let Some(server) = this.language_server_for_id(server_id)
+                                        {
+                                            let options = reg
+                                                .register_options
+                                                .map(|options| {
+                                                    serde_json::from_value::<
+                                                        lsp::TypeHierarchyOptions,
+                                                    >(
+                                                        options
+                                                    )
+                                                })
+                                                .transpose()?;
+                                            let provider = match options {
+                                                None => OneOf::Left(true),
+                                                Some(options) => OneOf::Right(options),
+                                            };
+                                            server.update_extra_capabilities(|capabilities| {
+                                                capabilities.type_hierarchy_provider =
+                                                    Some(provider);
+                                            })
+                                        }
+                                        anyhow::Ok(())
+                                    })??;
+                                }
+                                "textDocument/diagnostic" => {
+                                    this.update(&mut cx, |this, cx| {
+                                        if let Some(server) = this.language_server_for_id(server_id)
+                                        {
+                                            let options = reg
+                                                .register_options
+                                                .map(|options| {
+                                                    serde_json::from_value::<
+                                                        lsp::DiagnosticRegistrationOptions,
+                                                    >(
+                                                        options
+                                                    )
+                                                })
+                                                .transpose()?
+                                                .unwrap_or_default();
+                                            server.update_capabilities(|capabilities| {
+                                                capabilities.diagnostic_provider = Some(
+                                                    lsp::DiagnosticServerCapabilities::RegistrationOptions(
+                                                        options,
+                                                    ),
+                                                );
+                                            });
+                                            this.start_workspace_diagnostics(server_id, cx);
+                                            this.pull_diagnostics_for_server(server_id, cx);
+                                        }
+                                        anyhow::Ok(())
+                                    })??;
+                                }
                                 _ => log::warn!("unhandled capability registration: {reg:?}"),
                             }
                         }
@@ -768,6 +834,29 @@ impl LocalLspStore {
                                         }
                                     })?;
                                 }
+                                "textDocument/prepareTypeHierarchy" => {
+                                    this.update(&mut cx, |this, _| {
+                                        if let Some(server) = this.language_server_for_id(server_id)
+                                        {
+                                            server.update_extra_capabilities(|capabilities| {
+                                                capabilities.type_hierarchy_provider = None;
+                                            })
+                                        }
+                                    })?;
+                                }
+                                "textDocument/diagnostic" => {
+                                    this.update(&mut cx, |this, _| {
+                                        if let Some(server) = this.language_server_for_id(server_id)
+                                        {
+                                            server.update_capabilities(|capabilities| {
+                                                capabilities.diagnostic_provider = None;
+                                            })
+                                        }
+                                        if let Some(local) = this.as_local_mut() {
+                                            local.workspace_diagnostics_triggers.remove(&server_id);
+                                        }
+                                    })?;
+                                }
                                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
                             }
                         }
@@ -843,6 +932,45 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.invalidate_semantic_tokens(Some(server_id));
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.pull_diagnostics_for_server(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -2924,6 +3052,113 @@ impl LocalLspStore {
         Ok(project_transaction)
     }
 
+    /// Resolves the text edits of a workspace edit without applying them, so that they can be
+    /// previewed. Returns `None` if the workspace edit also contains file operations.
+    pub(crate) async fn rename_edits_from_workspace_edit(
+        this: Entity<LspStore>,
+        edit: lsp::WorkspaceEdit,
+        lsp_adapter: Arc<CachedLspAdapter>,
+        language_server: Arc<LanguageServer>,
+        cx: &mut AsyncApp,
+    ) -> Result<Option<Vec<RenameEdit>>> {
+        let mut document_edits = Vec::new();
+        if let Some(document_changes) = edit.document_changes {
+            match document_changes {
+                lsp::DocumentChanges::Edits(edits) => document_edits = edits,
+                lsp::DocumentChanges::Operations(operations) => {
+                    for operation in operations {
+                        match operation {
+                            lsp::DocumentChangeOperation::Edit(edit) => document_edits.push(edit),
+                            lsp::DocumentChangeOperation::Op(_) => return Ok(None),
+                        }
+                    }
+                }
+            }
+        } else if let Some(changes) = edit.changes {
+            document_edits.extend(
+                changes
+                    .into_iter()
+                    .map(|(uri, edits)| lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri,
+                            version: None,
+                        },
+                        edits: edits.into_iter().map(Edit::Plain).collect(),
+                    }),
+            );
+        }
+
+        let annotations = edit.change_annotations.unwrap_or_default();
+        let mut rename_edits = Vec::new();
+        for document_edit in document_edits {
+            let buffer = this
+                .update(cx, |this, cx| {
+                    this.open_local_buffer_via_lsp(
+                        document_edit.text_document.uri.clone(),
+                        language_server.server_id(),
+                        lsp_adapter.name.clone(),
+                        cx,
+                    )
+                })?
+                .await?;
+
+            // Edits are resolved per annotation, as resolving them may split or merge them.
+            let mut edits_by_annotation = BTreeMap::<Option<String>, Vec<TextEdit>>::default();
+            for edit in document_edit.edits {
+                let (edit, annotation_id) = match edit {
+                    Edit::Plain(edit) => (edit, None),
+                    Edit::Annotated(edit) => (edit.text_edit, Some(edit.annotation_id)),
+                    Edit::Snippet(edit) => {
+                        let new_text = Snippet::parse(&edit.snippet.value)
+                            .map_or(edit.snippet.value, |snippet| snippet.text);
+                        (
+                            TextEdit {
+                                range: edit.range,
+                                new_text,
+                            },
+                            None,
+                        )
+                    }
+                };
+                let edits = edits_by_annotation.entry(annotation_id).or_default();
+                if !edits.contains(&edit) {
+                    edits.push(edit);
+                }
+            }
+
+            for (annotation_id, edits) in edits_by_annotation {
+                let annotation = annotation_id
+                    .and_then(|annotation_id| annotations.get(&annotation_id))
+                    .map(|annotation| ChangeAnnotation {
+                        label: annotation.label.clone(),
+                        description: annotation.description.clone(),
+                        needs_confirmation: annotation.needs_confirmation.unwrap_or(false),
+                    });
+                let edits = this
+                    .update(cx, |this, cx| {
+                        this.as_local_mut().unwrap().edits_from_lsp(
+                            &buffer,
+                            edits,
+                            language_server.server_id(),
+                            document_edit.text_document.version,
+                            cx,
+                        )
+                    })?
+                    .await?;
+                rename_edits.extend(edits.into_iter().map(|(range, new_text)| RenameEdit {
+                    location: Location {
+                        buffer: buffer.clone(),
+                        range,
+                    },
+                    new_text: new_text.to_string(),
+                    annotation: annotation.clone(),
+                }));
+            }
+        }
+
+        Ok(Some(rename_edits))
+    }
+
     async fn on_lsp_workspace_edit(
         this: WeakEntity<LspStore>,
         params: lsp::ApplyWorkspaceEditParams,
@@ -3352,6 +3587,17 @@ pub struct LspStore {
     _maintain_buffer_languages: Task<()>,
     diagnostic_summaries:
         HashMap<WorktreeId, HashMap<Arc<Path>, HashMap<LanguageServerId, DiagnosticSummary>>>,
+    semantic_tokens: HashMap<BufferId, CachedSemanticTokens>,
+}
+
+struct CachedSemanticTokens {
+    /// The buffer version the tokens were computed for.
+    version: clock::Global,
+    /// The result id to use as a base for the next delta request.
+    result_id: Option<String>,
+    /// The tokens in the LSP encoding, used to apply deltas.
+    data: Vec<lsp::SemanticToken>,
+    tokens: BufferSemanticTokens,
 }
 
 pub enum LspStoreEvent {
@@ -3370,6 +3616,7 @@ pub enum LspStoreEvent {
     Notification(String),
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         language_server_id: LanguageServerId,
         path: ProjectPath,
@@ -3426,6 +3673,8 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_get_semantic_tokens);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -3440,8 +3689,22 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetReferences>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<ResolveDocumentLink>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetInlineValues>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentColors>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetColorPresentations>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetRenameEdits>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
 
         client.add_entity_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
@@ -3543,6 +3806,9 @@ impl LspStore {
                 language_server_watcher_registrations: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffer_snapshots: Default::default(),
+                diagnostic_result_ids: Default::default(),
+                document_diagnostics_tasks: Default::default(),
+                workspace_diagnostics_triggers: Default::default(),
                 prettier_store,
                 environment,
                 http_client,
@@ -3565,6 +3831,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().r#gen(),
             diagnostic_summaries: Default::default(),
+            semantic_tokens: Default::default(),
             active_entry: None,
 
             _maintain_workspace_config,
@@ -3621,6 +3888,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().r#gen(),
             diagnostic_summaries: Default::default(),
+            semantic_tokens: Default::default(),
             active_entry: None,
             toolchain_store,
             _maintain_workspace_config,
@@ -3768,6 +4036,10 @@ impl LspStore {
             this.on_buffer_event(buffer, event, cx);
         })
         .detach();
+        cx.observe_release(buffer, |this, buffer, _| {
+            this.semantic_tokens.remove(&buffer.remote_id());
+        })
+        .detach();
 
         self.detect_language_for_buffer(buffer, cx);
         if let Some(local) = self.as_local_mut() {
@@ -3901,7 +4173,8 @@ impl LspStore {
                 *refcount += 1;
             }
 
-            if ignore_refcounts || *refcount == 1 {
+            let needs_registration = ignore_refcounts || *refcount == 1;
+            if needs_registration {
                 local.register_buffer_with_language_servers(buffer, cx);
             }
             if !ignore_refcounts {
@@ -3915,6 +4188,9 @@ impl LspStore {
                     *refcount -= 1;
                     if *refcount == 0 {
                         local.registered_buffers.remove(&buffer_id);
+                        local
+                            .document_diagnostics_tasks
+                            .retain(|(id, _), _| *id != buffer_id);
                         if let Some(file) = File::from_dyn(buffer.read(cx).file()).cloned() {
                             local.unregister_old_buffer_from_language_servers(&buffer, &file, cx);
                         }
@@ -3922,6 +4198,9 @@ impl LspStore {
                 })
                 .detach();
             }
+            if needs_registration {
+                self.pull_diagnostics_for_buffer(buffer, cx);
+            }
         } else if let Some((upstream_client, upstream_project_id)) = self.upstream_client() {
             let buffer_id = buffer.read(cx).remote_id().to_proto();
             cx.background_spawn(async move {
@@ -5594,6 +5873,174 @@ impl LspStore {
         }
     }
 
+    /// Returns the semantic tokens for the whole buffer, reusing the cached tokens if
+    /// the buffer did not change since they were fetched.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<BufferSemanticTokens>>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        let version = buffer.read(cx).version();
+        if let Some(cached) = self.semantic_tokens.get(&buffer_id) {
+            if cached.version == version {
+                return Task::ready(Ok(Some(cached.tokens.clone())));
+            }
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let response = if let Some((client, project_id)) = self.upstream_client() {
+            self.send_lsp_proto_request(buffer, client, project_id, GetSemanticTokens, cx)
+        } else {
+            self.request_semantic_tokens_locally(&buffer, cx)
+        };
+        cx.spawn(async move |this, cx| {
+            let response = match response.await {
+                Ok(response) => response,
+                Err(error) => {
+                    // Make sure the next request does not use a delta against a broken base.
+                    this.update(cx, |this, _| this.semantic_tokens.remove(&buffer_id))?;
+                    return Err(error.context("semantic tokens request"));
+                }
+            };
+            let Some(server_id) = response.server_id else {
+                this.update(cx, |this, _| this.semantic_tokens.remove(&buffer_id))?;
+                return Ok(None);
+            };
+
+            let tokens = cx
+                .background_spawn({
+                    let legend = response.legend.clone();
+                    let data = response.data.clone();
+                    async move { BufferSemanticTokens::from_lsp(server_id, legend, &data, &snapshot) }
+                })
+                .await;
+            this.update(cx, |this, _| {
+                let is_newer = this
+                    .semantic_tokens
+                    .get(&buffer_id)
+                    .map_or(true, |cached| !cached.version.changed_since(&version));
+                if is_newer {
+                    this.semantic_tokens.insert(
+                        buffer_id,
+                        CachedSemanticTokens {
+                            version,
+                            result_id: response.result_id,
+                            data: response.data,
+                            tokens: tokens.clone(),
+                        },
+                    );
+                }
+                Some(tokens)
+            })
+        })
+    }
+
+    fn request_semantic_tokens_locally(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<SemanticTokensResponse>> {
+        let Some(language_server) = buffer.update(cx, |buffer, cx| {
+            self.as_local()?
+                .language_servers_for_buffer(buffer, cx)
+                .find(|(_, server)| {
+                    GetSemanticTokens.check_capabilities(server.adapter_server_capabilities())
+                })
+                .map(|(_, server)| server.clone())
+        }) else {
+            return Task::ready(Ok(SemanticTokensResponse::default()));
+        };
+        let server_id = language_server.server_id();
+        let capabilities = language_server.capabilities();
+
+        let buffer_id = buffer.read(cx).remote_id();
+        let delta_base = self
+            .semantic_tokens
+            .get(&buffer_id)
+            .filter(|cached| {
+                cached.tokens.server_id == server_id
+                    && GetSemanticTokens::supports_delta(&capabilities)
+            })
+            .and_then(|cached| Some((cached.result_id.clone()?, cached.data.clone())));
+        let abs_path = File::from_dyn(buffer.read(cx).file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx));
+        let (Some((previous_result_id, previous_data)), Some(abs_path)) = (delta_base, abs_path)
+        else {
+            return self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::Other(server_id),
+                GetSemanticTokens,
+                cx,
+            );
+        };
+        let text_document = match make_text_document_identifier(&abs_path) {
+            Ok(text_document) => text_document,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let legend = Arc::new(
+            GetSemanticTokens::options(&capabilities)
+                .map(|options| options.legend.clone())
+                .unwrap_or_default(),
+        );
+
+        let buffer = buffer.clone();
+        cx.spawn(async move |this, cx| {
+            let response = language_server
+                .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                    lsp::SemanticTokensDeltaParams {
+                        text_document,
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                )
+                .await
+                .context("semantic tokens delta LSP request")?;
+            let (result_id, data) = match response {
+                Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                    (tokens.result_id, tokens.data)
+                }
+                Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => (
+                    delta.result_id,
+                    GetSemanticTokens::apply_edits(previous_data, delta.edits)?,
+                ),
+                Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                    (None, GetSemanticTokens::apply_edits(previous_data, edits)?)
+                }
+                None => {
+                    return this
+                        .update(cx, |this, cx| {
+                            this.request_lsp(
+                                buffer,
+                                LanguageServerToQuery::Other(server_id),
+                                GetSemanticTokens,
+                                cx,
+                            )
+                        })?
+                        .await;
+                }
+            };
+            Ok(SemanticTokensResponse {
+                server_id: Some(server_id),
+                legend,
+                result_id,
+                data,
+            })
+        })
+    }
+
+    /// Forces the next semantic tokens request to reach the language server, either for
+    /// the buffers highlighted by the given server, or for all buffers.
+    fn invalidate_semantic_tokens(&mut self, server_id: Option<LanguageServerId>) {
+        for cached in self.semantic_tokens.values_mut() {
+            if server_id.map_or(true, |server_id| cached.tokens.server_id == server_id) {
+                cached.version = clock::Global::new();
+            }
+        }
+    }
+
     pub fn signature_help<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -5982,7 +6429,8 @@ impl LspStore {
             )
         })?;
 
-        let buffer = buffer.read(cx);
+        let buffer_handle = buffer;
+        let buffer = buffer_handle.read(cx);
         let file = File::from_dyn(buffer.file())?;
         let abs_path = file.as_local()?.abs_path(cx);
         let uri = lsp::Url::from_file_path(abs_path).unwrap();
@@ -6068,6 +6516,7 @@ impl LspStore {
                 )
                 .log_err();
         }
+        self.pull_diagnostics_for_buffer(&buffer_handle, cx);
 
         None
     }
@@ -6108,6 +6557,7 @@ impl LspStore {
         });
         for language_server_id in language_servers {
             self.simulate_disk_based_diagnostics_events_if_needed(language_server_id, cx);
+            self.refresh_workspace_diagnostics(language_server_id);
         }
 
         None
@@ -6363,6 +6813,155 @@ impl LspStore {
         self.merge_diagnostic_entries(server_id, abs_path, version, diagnostics, |_| false, cx)
     }
 
+    /// Replaces the diagnostics reported by the previous run of the task with the problems given.
+    /// Relative problem paths are resolved against the task's working directory, `cwd`.
+    /// Problems are only tracked for local projects.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        cwd: Option<&Path>,
+        problems: Vec<task::TaskProblem>,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        // Only borrow the mode, so that the worktree store can be read while `local` is alive.
+        let LspStoreMode::Local(local) = &mut self.mode else {
+            return Ok(());
+        };
+        let is_from_task =
+            |diagnostic: &Diagnostic| diagnostic.source.as_deref() == Some(task_label);
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for (worktree_id, diagnostics_by_worktree_path) in &local.diagnostics {
+            let Some(worktree) = self
+                .worktree_store
+                .read(cx)
+                .worktree_for_id(*worktree_id, cx)
+            else {
+                continue;
+            };
+            let worktree_abs_path = worktree.read(cx).abs_path();
+            for (path, diagnostics_by_server_id) in diagnostics_by_worktree_path {
+                let reported_by_task = diagnostics_by_server_id
+                    .iter()
+                    .filter(|(server_id, _)| *server_id == TASK_DIAGNOSTICS_SOURCE_ID)
+                    .flat_map(|(_, diagnostics)| diagnostics)
+                    .any(|entry| is_from_task(&entry.diagnostic));
+                if reported_by_task {
+                    diagnostics_by_path.insert(worktree_abs_path.join(path), Vec::new());
+                }
+            }
+        }
+
+        for problem in problems {
+            let abs_path = if problem.path.is_absolute() {
+                problem.path
+            } else if let Some(cwd) = cwd {
+                cwd.join(&problem.path)
+            } else {
+                // Without a known working directory, pick the worktree that has the reported file.
+                let Some(worktree_abs_path) = self
+                    .worktree_store
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .find(|worktree| worktree.read(cx).entry_for_path(&problem.path).is_some())
+                    .map(|worktree| worktree.read(cx).abs_path())
+                else {
+                    continue;
+                };
+                worktree_abs_path.join(&problem.path)
+            };
+            let start = PointUtf16::new(problem.row, problem.column.unwrap_or(0));
+            let end = match problem.end {
+                Some((end_row, end_column)) => {
+                    PointUtf16::new(end_row, end_column.unwrap_or(u32::MAX))
+                }
+                None => match problem.column {
+                    Some(column) => PointUtf16::new(problem.row, column + 1),
+                    None => PointUtf16::new(problem.row, u32::MAX),
+                },
+            };
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end),
+                    diagnostic: Diagnostic {
+                        source: Some(task_label.to_string()),
+                        code: problem.code.map(lsp::NumberOrString::String),
+                        severity: match problem.severity {
+                            task::ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                            task::ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                            task::ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                            task::ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+                        },
+                        message: problem.message,
+                        group_id: post_inc(&mut local.next_diagnostic_group_id),
+                        is_primary: true,
+                        is_disk_based: true,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        for (abs_path, mut diagnostics) in diagnostics_by_path {
+            // Problems of the other tasks are kept: for open buffers, they are merged from the buffer,
+            // for the rest, from the stored worktree diagnostics.
+            let is_open = self
+                .worktree_store
+                .read(cx)
+                .find_worktree(&abs_path, cx)
+                .is_some_and(|(worktree, path)| {
+                    let project_path = ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path: path.into(),
+                    };
+                    self.buffer_store
+                        .read(cx)
+                        .get_by_path(&project_path, cx)
+                        .is_some()
+                });
+            if !is_open {
+                diagnostics.extend(self.stored_task_diagnostics(&abs_path, task_label, cx));
+            }
+            self.merge_diagnostic_entries(
+                TASK_DIAGNOSTICS_SOURCE_ID,
+                abs_path,
+                None,
+                diagnostics,
+                |diagnostic| !is_from_task(diagnostic),
+                cx,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns the stored problems of tasks other than the one given for the path.
+    fn stored_task_diagnostics(
+        &self,
+        abs_path: &Path,
+        task_label: &str,
+        cx: &App,
+    ) -> Vec<DiagnosticEntry<Unclipped<PointUtf16>>> {
+        let Some(local) = self.as_local() else {
+            return Vec::new();
+        };
+        let Some((worktree, path)) = self.worktree_store.read(cx).find_worktree(abs_path, cx)
+        else {
+            return Vec::new();
+        };
+        local
+            .diagnostics
+            .get(&worktree.read(cx).id())
+            .and_then(|diagnostics| diagnostics.get(path.as_path()))
+            .into_iter()
+            .flatten()
+            .filter(|(server_id, _)| *server_id == TASK_DIAGNOSTICS_SOURCE_ID)
+            .flat_map(|(_, diagnostics)| diagnostics)
+            .filter(|entry| entry.diagnostic.source.as_deref() != Some(task_label))
+            .cloned()
+            .collect()
+    }
+
     pub fn merge_diagnostic_entries<F: Fn(&Diagnostic) -> bool + Clone>(
         &mut self,
         server_id: LanguageServerId,
@@ -7485,6 +8084,9 @@ impl LspStore {
     }
 
     pub fn language_server_for_id(&self, id: LanguageServerId) -> Option<Arc<LanguageServer>> {
+        if id == TASK_DIAGNOSTICS_SOURCE_ID {
+            return None;
+        }
         let local_lsp_store = self.as_local()?;
         if let Some(LanguageServerState::Running { server, .. }) =
             local_lsp_store.language_servers.get(&id)
@@ -7860,6 +8462,56 @@ impl LspStore {
         })
     }
 
+    async fn handle_get_semantic_tokens(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+
+        this.update(&mut cx, |this, cx| this.semantic_tokens(buffer.clone(), cx))?
+            .await
+            .context("semantic tokens fetch")?;
+
+        this.update(&mut cx, |this, cx| {
+            let (response, version) = match this.semantic_tokens.get(&buffer_id) {
+                Some(cached) => (
+                    SemanticTokensResponse {
+                        server_id: Some(cached.tokens.server_id),
+                        legend: cached.tokens.legend.clone(),
+                        result_id: cached.result_id.clone(),
+                        data: cached.data.clone(),
+                    },
+                    cached.version.clone(),
+                ),
+                None => (SemanticTokensResponse::default(), buffer.read(cx).version()),
+            };
+            GetSemanticTokens::response_to_proto(response, this, sender_id, &version, cx)
+        })
+    }
+
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            this.invalidate_semantic_tokens(None);
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -8396,6 +9048,11 @@ impl LspStore {
             });
         }
         local.language_server_watched_paths.remove(&server_id);
+        local.diagnostic_result_ids.remove(&server_id);
+        local.workspace_diagnostics_triggers.remove(&server_id);
+        local
+            .document_diagnostics_tasks
+            .retain(|(_, id), _| *id != server_id);
         let server_state = local.language_servers.remove(&server_id);
         cx.notify();
         cx.emit(LspStoreEvent::LanguageServerRemoved(server_id));
@@ -8492,6 +9149,323 @@ impl LspStore {
         )
     }
 
+    /// Pulls the diagnostics of the given buffer from every language server that supports
+    /// `textDocument/diagnostic`.
+    pub fn pull_diagnostics_for_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let server_ids = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .filter(|(_, server)| server.capabilities().diagnostic_provider.is_some())
+                .map(|(_, server)| server.server_id())
+                .collect::<Vec<_>>()
+        });
+        for server_id in server_ids {
+            self.pull_document_diagnostics(buffer, server_id, cx);
+        }
+    }
+
+    /// Re-pulls the diagnostics of every open buffer, and of the workspace, from the given
+    /// language server.
+    fn pull_diagnostics_for_server(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let buffers = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter(|buffer| {
+                local
+                    .buffer_snapshots
+                    .get(&buffer.read(cx).remote_id())
+                    .is_some_and(|snapshots| snapshots.contains_key(&server_id))
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.pull_document_diagnostics(&buffer, server_id, cx);
+        }
+        self.refresh_workspace_diagnostics(server_id);
+    }
+
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(server) = local.running_language_server_for_id(server_id).cloned() else {
+            return;
+        };
+        let Some(identifier) = server
+            .capabilities()
+            .diagnostic_provider
+            .as_ref()
+            .map(|provider| diagnostic_options(provider).identifier.clone())
+        else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(abs_path) = File::from_dyn(buffer.read(cx).file())
+            .and_then(|file| Some(file.as_local()?.abs_path(cx)))
+        else {
+            return;
+        };
+        let Some(uri) = lsp::Url::from_file_path(&abs_path).log_err() else {
+            return;
+        };
+        let Some(version) = local
+            .buffer_snapshots
+            .get(&buffer_id)
+            .and_then(|snapshots| Some(snapshots.get(&server_id)?.last()?.version))
+        else {
+            return;
+        };
+        let previous_result_id = local
+            .diagnostic_result_ids
+            .get(&server_id)
+            .and_then(|result_ids| result_ids.get(&abs_path))
+            .cloned();
+
+        let task = cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(DOCUMENT_DIAGNOSTICS_DEBOUNCE)
+                .await;
+            let response = server
+                .request::<lsp::request::DocumentDiagnosticRequest>(lsp::DocumentDiagnosticParams {
+                    text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                    identifier,
+                    previous_result_id,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await;
+            let (report, related_documents) = match response {
+                Ok(lsp::DocumentDiagnosticReportResult::Report(
+                    lsp::DocumentDiagnosticReport::Full(report),
+                )) => (
+                    Some(lsp::DocumentDiagnosticReportKind::Full(
+                        report.full_document_diagnostic_report,
+                    )),
+                    report.related_documents,
+                ),
+                Ok(lsp::DocumentDiagnosticReportResult::Report(
+                    lsp::DocumentDiagnosticReport::Unchanged(report),
+                )) => (
+                    Some(lsp::DocumentDiagnosticReportKind::Unchanged(
+                        report.unchanged_document_diagnostic_report,
+                    )),
+                    report.related_documents,
+                ),
+                Ok(lsp::DocumentDiagnosticReportResult::Partial(report)) => {
+                    (None, report.related_documents)
+                }
+                Err(error) => {
+                    log::error!("failed to pull diagnostics for {uri}: {error:#}");
+                    return;
+                }
+            };
+            this.update(cx, |this, cx| {
+                if let Some(report) = report {
+                    this.apply_diagnostic_report(server_id, uri, Some(version), report, cx)
+                        .log_err();
+                }
+                for (uri, report) in related_documents.into_iter().flatten() {
+                    this.apply_diagnostic_report(server_id, uri, None, report, cx)
+                        .log_err();
+                }
+            })
+            .ok();
+        });
+        if let Some(local) = self.as_local_mut() {
+            local
+                .document_diagnostics_tasks
+                .insert((buffer_id, server_id), task);
+        }
+    }
+
+    /// Starts pulling `workspace/diagnostic` reports from the given language server, if it
+    /// supports them. A new report is requested whenever the workspace diagnostics are refreshed.
+    fn start_workspace_diagnostics(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(server) = local.running_language_server_for_id(server_id) else {
+            return;
+        };
+        let Some(options) = server
+            .capabilities()
+            .diagnostic_provider
+            .as_ref()
+            .map(diagnostic_options)
+            .filter(|options| options.workspace_diagnostics)
+            .cloned()
+        else {
+            local.workspace_diagnostics_triggers.remove(&server_id);
+            return;
+        };
+        if local
+            .workspace_diagnostics_triggers
+            .contains_key(&server_id)
+        {
+            return;
+        }
+
+        let (trigger_tx, trigger_rx) = smol::channel::bounded(1);
+        trigger_tx.try_send(()).ok();
+        local
+            .workspace_diagnostics_triggers
+            .insert(server_id, trigger_tx);
+        cx.spawn(async move |this, cx| {
+            while trigger_rx.recv().await.is_ok() {
+                let Some((server, previous_result_ids)) = this
+                    .update(cx, |this, _| {
+                        let local = this.as_local()?;
+                        let server = local.running_language_server_for_id(server_id)?.clone();
+                        let previous_result_ids = local
+                            .diagnostic_result_ids
+                            .get(&server_id)
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|(abs_path, result_id)| {
+                                Some(lsp::PreviousResultId {
+                                    uri: lsp::Url::from_file_path(abs_path).ok()?,
+                                    value: result_id.clone(),
+                                })
+                            })
+                            .collect();
+                        Some((server, previous_result_ids))
+                    })
+                    .ok()
+                    .flatten()
+                else {
+                    break;
+                };
+
+                let response = server
+                    .request::<lsp::request::WorkspaceDiagnosticRequest>(
+                        lsp::WorkspaceDiagnosticParams {
+                            identifier: options.identifier.clone(),
+                            previous_result_ids,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await;
+                let items = match response {
+                    Ok(lsp::WorkspaceDiagnosticReportResult::Report(report)) => report.items,
+                    Ok(lsp::WorkspaceDiagnosticReportResult::Partial(report)) => report.items,
+                    Err(error) => {
+                        log::error!("failed to pull workspace diagnostics: {error:#}");
+                        continue;
+                    }
+                };
+                let updated = this.update(cx, |this, cx| {
+                    for item in items {
+                        let (uri, report) = match item {
+                            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                                report.uri,
+                                lsp::DocumentDiagnosticReportKind::Full(
+                                    report.full_document_diagnostic_report,
+                                ),
+                            ),
+                            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                                report.uri,
+                                lsp::DocumentDiagnosticReportKind::Unchanged(
+                                    report.unchanged_document_diagnostic_report,
+                                ),
+                            ),
+                        };
+                        // Open documents are kept up to date by pulling their diagnostics directly.
+                        let is_open =
+                            uri.to_file_path()
+                                .ok()
+                                .and_then(|abs_path| this.get_buffer(&abs_path, cx))
+                                .is_some_and(|buffer| {
+                                    this.as_local().is_some_and(|local| {
+                                        local.buffer_snapshots.get(&buffer.remote_id()).is_some_and(
+                                            |snapshots| snapshots.contains_key(&server_id),
+                                        )
+                                    })
+                                });
+                        if !is_open {
+                            this.apply_diagnostic_report(server_id, uri, None, report, cx)
+                                .log_err();
+                        }
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn refresh_workspace_diagnostics(&self, server_id: LanguageServerId) {
+        if let Some(trigger) = self
+            .as_local()
+            .and_then(|local| local.workspace_diagnostics_triggers.get(&server_id))
+        {
+            trigger.try_send(()).ok();
+        }
+    }
+
+    fn apply_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let abs_path = uri
+            .to_file_path()
+            .map_err(|_| anyhow!("URI is not a file path: {uri}"))?;
+        let result_id = match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                let adapter = self
+                    .language_server_adapter_for_id(server_id)
+                    .context("language server not found")?;
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: report.items,
+                    version,
+                };
+                {
+                    let buffer = self.get_buffer(&abs_path, cx);
+                    adapter.process_diagnostics(&mut params, server_id, buffer);
+                }
+                self.update_diagnostics(
+                    server_id,
+                    params,
+                    &adapter.disk_based_diagnostic_sources,
+                    cx,
+                )?;
+                report.result_id
+            }
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => Some(report.result_id),
+        };
+
+        if let Some(local) = self.as_local_mut() {
+            let result_ids = local.diagnostic_result_ids.entry(server_id).or_default();
+            match result_id {
+                Some(result_id) => {
+                    result_ids.insert(abs_path, result_id);
+                }
+                None => {
+                    result_ids.remove(&abs_path);
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn merge_diagnostics<F: Fn(&Diagnostic) -> bool + Clone>(
         &mut self,
         language_server_id: LanguageServerId,
@@ -8778,6 +9752,8 @@ impl LspStore {
             }
         });
 
+        self.start_workspace_diagnostics(server_id, cx);
+        self.pull_diagnostics_for_server(server_id, cx);
         cx.notify();
     }
 
@@ -8913,6 +9889,9 @@ impl LspStore {
         &self,
         id: LanguageServerId,
     ) -> Option<Arc<CachedLspAdapter>> {
+        if id == TASK_DIAGNOSTICS_SOURCE_ID {
+            return None;
+        }
         self.as_local()
             .and_then(|local| local.language_servers.get(&id))
             .and_then(|language_server_state| match language_server_state {
@@ -9494,6 +10473,15 @@ impl LanguageServerWatchedPathsBuilder {
     }
 }
 
+fn diagnostic_options(provider: &lsp::DiagnosticServerCapabilities) -> &lsp::DiagnosticOptions {
+    match provider {
+        lsp::DiagnosticServerCapabilities::Options(options) => options,
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            &options.diagnostic_options
+        }
+    }
+}
+
 struct LspBufferSnapshot {
     version: i32,
     snapshot: TextBufferSnapshot,
@@ -63,7 +63,7 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{
-    Buffer, BufferEvent, Capability, CodeLabel, File as _, Language, LanguageName,
+    Bias, Buffer, BufferEvent, Capability, CodeLabel, File as _, Language, LanguageName,
     LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction,
     Unclipped, language_settings::InlayHintKind, proto::split_operations,
 };
@@ -119,7 +119,8 @@ pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
-    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskContexts, TaskSourceKind,
+    BasicContextProvider, ContextProviderWithTasks, Inventory, TaskContexts, TaskDependencyTree,
+    TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -297,6 +298,7 @@ pub enum Event {
     Rejoined,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -670,6 +672,202 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A navigable range in a buffer, such as a URL or a path to another file.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    /// The URL or path the link points to, or `None` if the link has to be resolved first.
+    pub target: Option<String>,
+    pub tooltip: Option<String>,
+    /// The language server that reported the link along with the link as it was reported,
+    /// which has to be sent back verbatim to resolve it. `None` for links found by a
+    /// `links.scm` query.
+    pub lsp_link: Option<(LanguageServerId, lsp::DocumentLink)>,
+}
+
+/// A color literal in a buffer, as reported by a language server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentColor {
+    pub range: Range<language::Anchor>,
+    pub color: lsp::Color,
+    /// The language server that reported the color, which also provides its presentations.
+    pub server_id: LanguageServerId,
+}
+
+/// A way to write a color in a buffer, offered by a language server to replace a
+/// [`DocumentColor`] with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorPresentation {
+    pub label: String,
+    /// The edit that writes the color, or `None` if the label should replace the color's range.
+    pub text_edit: Option<(Range<language::Anchor>, String)>,
+    pub additional_text_edits: Vec<(Range<language::Anchor>, String)>,
+}
+
+/// An edit of a rename, which can be previewed before it's applied.
+#[derive(Clone, Debug)]
+pub struct RenameEdit {
+    pub location: Location,
+    pub new_text: String,
+    pub annotation: Option<ChangeAnnotation>,
+}
+
+/// A description of a group of edits reported by a language server, some of which have to be
+/// confirmed by the user before they're applied.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeAnnotation {
+    pub label: String,
+    pub description: Option<String>,
+    pub needs_confirmation: bool,
+}
+
+/// A value to display next to the code while the debugger is stopped, as reported by
+/// a language server for the `textDocument/inlineValue` request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InlineValue {
+    pub range: Range<language::Anchor>,
+    pub kind: InlineValueKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InlineValueKind {
+    /// The value to display, as is.
+    Text(String),
+    /// A variable to look up in the stopped stack frame. When `variable_name` is `None`,
+    /// the name is the text of the value's range.
+    VariableLookup {
+        variable_name: Option<String>,
+        case_sensitive: bool,
+    },
+    /// An expression to evaluate in the stopped stack frame. When `expression` is `None`,
+    /// the expression is the text of the value's range.
+    EvaluatableExpression { expression: Option<String> },
+}
+
+/// A symbol that can be the root of a call hierarchy, as reported by a language server.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range enclosing the symbol, including its body.
+    pub location: Location,
+    /// The range to reveal when the symbol is selected, e.g. its name.
+    pub selection_range: Range<language::Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The item as reported by the language server, which has to be sent back
+    /// verbatim to query the calls of the item.
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+/// An incoming or outgoing call of a [`CallHierarchyItem`].
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, the callee for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The ranges at which the calls appear, always in the caller's buffer.
+    pub call_sites: Vec<Location>,
+}
+
+/// A symbol that is part of a type hierarchy, as reported by a language server.
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range enclosing the symbol, including its body.
+    pub location: Location,
+    /// The range to reveal when the symbol is selected, e.g. its name.
+    pub selection_range: Range<language::Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The item as reported by the language server, which has to be sent back
+    /// verbatim to query the supertypes and subtypes of the item.
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
+/// Semantic tokens reported by a language server for a single buffer.
+#[derive(Clone, Debug)]
+pub struct BufferSemanticTokens {
+    pub server_id: LanguageServerId,
+    /// The legend used to interpret token types and modifiers.
+    pub legend: Arc<lsp::SemanticTokensLegend>,
+    /// Tokens, sorted by their start position in the buffer.
+    pub tokens: Arc<[SemanticToken]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<language::Anchor>,
+    /// An index into the legend's token types.
+    pub token_type: u32,
+    /// A bitset of indices into the legend's token modifiers.
+    pub token_modifiers: u32,
+}
+
+impl BufferSemanticTokens {
+    /// Converts tokens in the LSP relative encoding into anchored tokens, so that
+    /// they keep tracking the right text while the buffer is being edited.
+    pub fn from_lsp(
+        server_id: LanguageServerId,
+        legend: Arc<lsp::SemanticTokensLegend>,
+        data: &[lsp::SemanticToken],
+        snapshot: &language::BufferSnapshot,
+    ) -> Self {
+        let mut tokens = Vec::with_capacity(data.len());
+        let mut line = 0;
+        let mut start = 0;
+        for token in data {
+            if token.delta_line > 0 {
+                line += token.delta_line;
+                start = token.delta_start;
+            } else {
+                start += token.delta_start;
+            }
+
+            let token_start = PointUtf16::new(line, start);
+            let token_end = PointUtf16::new(line, start + token.length);
+            let clipped_start = snapshot.clip_point_utf16(Unclipped(token_start), Bias::Left);
+            let clipped_end = snapshot.clip_point_utf16(Unclipped(token_end), Bias::Left);
+            if clipped_start != token_start || clipped_end <= clipped_start {
+                continue;
+            }
+
+            tokens.push(SemanticToken {
+                range: snapshot.anchor_after(clipped_start)..snapshot.anchor_before(clipped_end),
+                token_type: token.token_type,
+                token_modifiers: token.token_modifiers_bitset,
+            });
+        }
+
+        Self {
+            server_id,
+            legend,
+            tokens: tokens.into(),
+        }
+    }
+
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.legend
+            .token_types
+            .get(token.token_type as usize)
+            .map(|token_type| token_type.as_str())
+    }
+
+    pub fn token_modifiers<'a>(
+        &'a self,
+        token: &SemanticToken,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        let modifiers = token.token_modifiers;
+        self.legend
+            .token_modifiers
+            .iter()
+            .take(u32::BITS as usize)
+            .enumerate()
+            .filter(move |(ix, _)| modifiers & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -2717,6 +2915,7 @@ impl Project {
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
             LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -3272,6 +3471,96 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetIncomingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetOutgoingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSupertypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSubtypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
     fn document_highlights_impl(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -3309,6 +3598,126 @@ impl Project {
         )
     }
 
+    /// Returns the foldable line ranges reported by the buffer's language server, sorted by
+    /// their start. The ranges are anchored at the start of their first and last lines.
+    pub fn folding_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Range<language::Anchor>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetFoldingRanges,
+            cx,
+        )
+    }
+
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    /// Asks the language server that reported the given link for its target.
+    pub fn resolve_document_link(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        link: &DocumentLink,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<String>>> {
+        if let Some(target) = &link.target {
+            return Task::ready(Ok(Some(target.clone())));
+        }
+        let Some((server_id, lsp_link)) = link.lsp_link.clone() else {
+            return Task::ready(Ok(None));
+        };
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(server_id),
+            ResolveDocumentLink { lsp_link },
+            cx,
+        )
+    }
+
+    pub fn document_colors(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentColor>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetDocumentColors,
+            cx,
+        )
+    }
+
+    /// Asks the language server that reported the given color how it can be written as
+    /// `new_color`.
+    pub fn color_presentations(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        color: &DocumentColor,
+        new_color: lsp::Color,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<ColorPresentation>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(color.server_id),
+            GetColorPresentations {
+                range: color.range.clone(),
+                color: new_color,
+            },
+            cx,
+        )
+    }
+
+    /// Asks the language server for the ranges to select when expanding a selection from each
+    /// of the given positions, from the innermost to the outermost one.
+    pub fn selection_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<language::Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<language::Anchor>>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
+    /// Asks the language server for the values to show inline in the given range of the
+    /// buffer, while a debug session is stopped in the given stack frame.
+    pub fn inline_values(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        range: Range<language::Anchor>,
+        stopped_location: Range<language::Anchor>,
+        frame_id: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<InlineValue>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetInlineValues {
+                range,
+                stopped_location,
+                frame_id,
+            },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3528,6 +3937,25 @@ impl Project {
         )
     }
 
+    /// Asks the language server for the edits of renaming the symbol at the given position,
+    /// without applying them. Returns `None` if the rename also creates, renames or deletes
+    /// files, as those can only be applied with [`Project::perform_rename`].
+    pub fn rename_edits<T: ToPointUtf16>(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: T,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<RenameEdit>>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer,
+            LanguageServerToQuery::FirstCapable,
+            GetRenameEdits { position, new_name },
+            cx,
+        )
+    }
+
     pub fn on_type_format<T: ToPointUtf16>(
         &mut self,
         buffer: Entity<Buffer>,
@@ -3566,6 +3994,16 @@ impl Project {
         })
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<BufferSemanticTokens>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(buffer_handle, cx)
+        })
+    }
+
     pub fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) -> Receiver<SearchResult> {
         let (result_tx, result_rx) = smol::channel::unbounded();
 
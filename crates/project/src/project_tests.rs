@@ -1,12 +1,15 @@
 #![allow(clippy::format_collect)]
 
-use crate::{Event, task_inventory::TaskContexts, task_store::TaskSettingsLocation, *};
+use crate::{
+    Event, lsp_store::TASK_DIAGNOSTICS_SOURCE_ID, task_inventory::TaskContexts,
+    task_store::TaskSettingsLocation, *,
+};
 use buffer_diff::{
     BufferDiffEvent, DiffHunkSecondaryStatus, DiffHunkStatus, DiffHunkStatusKind, assert_hunks,
 };
 use fs::FakeFs;
 use futures::{StreamExt, future};
-use git::repository::RepoPath;
+use git::repository::{CommitFileDiff, RepoPath, StashOptions};
 use gpui::{App, BackgroundExecutor, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use language::{
@@ -25,7 +28,17 @@ use pretty_assertions::{assert_eq, assert_matches};
 use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
-use std::{mem, num::NonZeroU32, ops::Range, str::FromStr, sync::OnceLock, task::Poll};
+use std::{
+    mem,
+    num::NonZeroU32,
+    ops::Range,
+    str::FromStr,
+    sync::{
+        OnceLock,
+        atomic::{AtomicUsize, Ordering::SeqCst},
+    },
+    task::Poll,
+};
 use task::{ResolvedTask, TaskContext};
 use unindent::Unindent as _;
 use util::{
@@ -2288,6 +2301,158 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "let a = 1;",
+            "sub": { "b.rs": "let b = 2;" }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let problem = |path: &str, severity, message: &str| task::TaskProblem {
+        path: PathBuf::from(path),
+        row: 0,
+        column: Some(4),
+        end: None,
+        severity,
+        message: message.to_string(),
+        code: None,
+    };
+
+    // Relative paths are resolved against the task's working directory.
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics(
+                "build",
+                Some(Path::new(path!("/dir/sub"))),
+                vec![
+                    problem("b.rs", task::ProblemSeverity::Error, "build error"),
+                    problem(
+                        path!("/dir/a.rs"),
+                        task::ProblemSeverity::Warning,
+                        "build warning",
+                    ),
+                ],
+                cx,
+            )
+            .unwrap();
+        lsp_store
+            .update_task_diagnostics(
+                "lint",
+                None,
+                vec![problem("a.rs", task::ProblemSeverity::Error, "lint error")],
+                cx,
+            )
+            .unwrap();
+    });
+
+    let diagnostic_summaries = |cx: &mut gpui::TestAppContext| {
+        project.read_with(cx, |project, cx| {
+            let mut summaries = project.diagnostic_summaries(false, cx).collect::<Vec<_>>();
+            summaries.sort_by(|a, b| a.0.cmp(&b.0));
+            summaries
+        })
+    };
+    let buffer_a_messages = |cx: &mut gpui::TestAppContext| {
+        buffer_a.read_with(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| entry.diagnostic.message)
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(
+        diagnostic_summaries(cx),
+        [
+            (
+                ProjectPath {
+                    worktree_id,
+                    path: Arc::from(Path::new("a.rs")),
+                },
+                TASK_DIAGNOSTICS_SOURCE_ID,
+                DiagnosticSummary {
+                    error_count: 1,
+                    warning_count: 1,
+                }
+            ),
+            (
+                ProjectPath {
+                    worktree_id,
+                    path: Arc::from(Path::new(separator!("sub/b.rs"))),
+                },
+                TASK_DIAGNOSTICS_SOURCE_ID,
+                DiagnosticSummary {
+                    error_count: 1,
+                    warning_count: 0,
+                }
+            ),
+        ]
+    );
+    let mut messages = buffer_a_messages(cx);
+    messages.sort();
+    assert_eq!(messages, ["build warning", "lint error"]);
+
+    // The key the problems are stored under is not a language server.
+    lsp_store.read_with(cx, |lsp_store, _| {
+        assert!(
+            lsp_store
+                .language_server_for_id(TASK_DIAGNOSTICS_SOURCE_ID)
+                .is_none()
+        );
+        assert!(
+            lsp_store
+                .language_server_adapter_for_id(TASK_DIAGNOSTICS_SOURCE_ID)
+                .is_none()
+        );
+        assert!(
+            lsp_store
+                .language_server_statuses()
+                .all(|(server_id, _)| server_id != TASK_DIAGNOSTICS_SOURCE_ID)
+        );
+    });
+
+    // Clearing the problems of one task keeps the problems of the others.
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store
+            .update_task_diagnostics("build", None, Vec::new(), cx)
+            .unwrap();
+    });
+    assert_eq!(
+        diagnostic_summaries(cx),
+        [(
+            ProjectPath {
+                worktree_id,
+                path: Arc::from(Path::new("a.rs")),
+            },
+            TASK_DIAGNOSTICS_SOURCE_ID,
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 0,
+            }
+        )]
+    );
+    assert_eq!(buffer_a_messages(cx), ["lint error"]);
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4625,6 +4790,140 @@ async fn test_rename(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_rename_edits(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE + one::ONE;"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let response = project.update(cx, |project, cx| {
+        project.rename_edits(buffer.clone(), 7, "THREE".to_string(), cx)
+    });
+    fake_server
+        .set_request_handler::<lsp::request::Rename, _, _>(|_, _| async move {
+            let edit = |uri: &str, start, end, annotation: Option<&str>| lsp::TextDocumentEdit {
+                text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(uri).unwrap(),
+                    version: None,
+                },
+                edits: vec![match annotation {
+                    Some(annotation) => lsp::Edit::Annotated(lsp::AnnotatedTextEdit {
+                        text_edit: lsp::TextEdit::new(
+                            lsp::Range::new(
+                                lsp::Position::new(0, start),
+                                lsp::Position::new(0, end),
+                            ),
+                            "THREE".to_string(),
+                        ),
+                        annotation_id: annotation.to_string(),
+                    }),
+                    None => lsp::Edit::Plain(lsp::TextEdit::new(
+                        lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end)),
+                        "THREE".to_string(),
+                    )),
+                }],
+            };
+            Ok(Some(lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Edits(vec![
+                    edit(path!("/dir/one.rs"), 6, 9, None),
+                    edit(path!("/dir/two.rs"), 24, 27, Some("qualified")),
+                    edit(path!("/dir/two.rs"), 35, 38, Some("qualified")),
+                ])),
+                change_annotations: Some(
+                    [(
+                        "qualified".to_string(),
+                        lsp::ChangeAnnotation {
+                            label: "Qualified paths".to_string(),
+                            needs_confirmation: Some(true),
+                            description: None,
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    let edits = response.await.unwrap().unwrap();
+    assert_eq!(edits.len(), 3);
+
+    let edits = edits
+        .into_iter()
+        .map(|edit| {
+            let path = edit
+                .location
+                .buffer
+                .read_with(cx, |buffer, _| buffer.file().unwrap().path().clone());
+            let range = edit
+                .location
+                .buffer
+                .read_with(cx, |buffer, _| edit.location.range.to_offset(buffer));
+            (path, range, edit.new_text, edit.annotation)
+        })
+        .collect::<Vec<_>>();
+    let qualified = Some(ChangeAnnotation {
+        label: "Qualified paths".to_string(),
+        description: None,
+        needs_confirmation: true,
+    });
+    assert_eq!(
+        edits,
+        vec![
+            (Path::new("one.rs").into(), 6..9, "THREE".to_string(), None),
+            (
+                Path::new("two.rs").into(),
+                24..27,
+                "THREE".to_string(),
+                qualified.clone()
+            ),
+            (
+                Path::new("two.rs").into(),
+                35..38,
+                "THREE".to_string(),
+                qualified
+            ),
+        ]
+    );
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "const ONE: usize = 1;");
+    });
+}
+
 #[gpui::test]
 async fn test_search(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -5552,14 +5851,14 @@ async fn test_hovers_with_empty_parts(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_code_actions_only_kinds(cx: &mut gpui::TestAppContext) {
+async fn test_semantic_tokens_full_and_delta(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         path!("/dir"),
         json!({
-            "a.ts": "a",
+            "a.ts": "let a = 1;\nlet b = a;\n",
         }),
     )
     .await;
@@ -5572,7 +5871,21 @@ async fn test_code_actions_only_kinds(cx: &mut gpui::TestAppContext) {
         "TypeScript",
         FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![
+                                    lsp::SemanticTokenType::VARIABLE,
+                                    lsp::SemanticTokenType::KEYWORD,
+                                ],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::DECLARATION],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..lsp::SemanticTokensOptions::default()
+                        },
+                    ),
+                ),
                 ..lsp::ServerCapabilities::default()
             },
             ..FakeLspAdapter::default()
@@ -5592,61 +5905,1099 @@ async fn test_code_actions_only_kinds(cx: &mut gpui::TestAppContext) {
         .await
         .expect("failed to get the language server");
 
-    let mut request_handled = fake_server
-        .set_request_handler::<lsp::request::CodeActionRequest, _, _>(move |_, _| async move {
-            Ok(Some(vec![
-                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
-                    title: "organize imports".to_string(),
-                    kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
-                    ..lsp::CodeAction::default()
-                }),
-                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
-                    title: "fix code".to_string(),
-                    kind: Some(CodeActionKind::SOURCE_FIX_ALL),
-                    ..lsp::CodeAction::default()
-                }),
-            ]))
-        });
+    fn token(
+        delta_line: u32,
+        delta_start: u32,
+        length: u32,
+        token_type: u32,
+        token_modifiers_bitset: u32,
+    ) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset,
+        }
+    }
 
-    let code_actions_task = project.update(cx, |project, cx| {
-        project.code_actions(
-            &buffer,
-            0..buffer.read(cx).len(),
-            Some(vec![CodeActionKind::SOURCE_ORGANIZE_IMPORTS]),
-            cx,
-        )
+    let full_requests = Arc::new(AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::SemanticTokensFullRequest, _, _>({
+        let full_requests = full_requests.clone();
+        move |_, _| {
+            full_requests.fetch_add(1, SeqCst);
+            async move {
+                Ok(Some(lsp::SemanticTokensResult::Tokens(
+                    lsp::SemanticTokens {
+                        result_id: Some("1".to_string()),
+                        data: vec![
+                            token(0, 0, 3, 1, 0),
+                            token(0, 4, 1, 0, 1),
+                            token(1, 0, 3, 1, 0),
+                            token(0, 4, 1, 0, 1),
+                            token(0, 4, 1, 0, 0),
+                        ],
+                    },
+                )))
+            }
+        }
     });
 
-    let () = request_handled
-        .next()
-        .await
-        .expect("The code action request should have been triggered");
+    let tokens_to_points = |tokens: &BufferSemanticTokens, cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            tokens
+                .tokens
+                .iter()
+                .map(|token| {
+                    (
+                        token.range.to_point(buffer),
+                        tokens.token_type(token).unwrap().to_string(),
+                        tokens.token_modifiers(token).collect::<Vec<_>>().join(","),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
 
-    let code_actions = code_actions_task.await.unwrap();
-    assert_eq!(code_actions.len(), 1);
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap()
+        .expect("semantic tokens should be reported");
     assert_eq!(
-        code_actions[0].lsp_action.action_kind(),
-        Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS)
+        tokens_to_points(&tokens, cx),
+        vec![
+            (
+                Point::new(0, 0)..Point::new(0, 3),
+                "keyword".into(),
+                "".into()
+            ),
+            (
+                Point::new(0, 4)..Point::new(0, 5),
+                "variable".into(),
+                "declaration".into()
+            ),
+            (
+                Point::new(1, 0)..Point::new(1, 3),
+                "keyword".into(),
+                "".into()
+            ),
+            (
+                Point::new(1, 4)..Point::new(1, 5),
+                "variable".into(),
+                "declaration".into()
+            ),
+            (
+                Point::new(1, 8)..Point::new(1, 9),
+                "variable".into(),
+                "".into()
+            ),
+        ]
     );
-}
-
-#[gpui::test]
-async fn test_multiple_language_server_actions(cx: &mut gpui::TestAppContext) {
-    init_test(cx);
-
-    let fs = FakeFs::new(cx.executor());
-    fs.insert_tree(
-        path!("/dir"),
-        json!({
-            "a.tsx": "a",
-        }),
-    )
-    .await;
+    assert_eq!(full_requests.load(SeqCst), 1);
 
-    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        full_requests.load(SeqCst),
+        1,
+        "tokens should be reused while the buffer is unchanged"
+    );
 
-    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
-    language_registry.add(tsx_lang());
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(
+            [(Point::new(2, 0)..Point::new(2, 0), "let c = b;\n")],
+            None,
+            cx,
+        )
+    });
+    let mut delta_requests = fake_server
+        .set_request_handler::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+            |params, _| async move {
+                assert_eq!(params.previous_result_id, "1");
+                Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                    lsp::SemanticTokensDelta {
+                        result_id: Some("2".to_string()),
+                        edits: vec![lsp::SemanticTokensEdit {
+                            start: 25,
+                            delete_count: 0,
+                            data: Some(vec![
+                                token(1, 0, 3, 1, 0),
+                                token(0, 4, 1, 0, 1),
+                                token(0, 4, 1, 0, 0),
+                            ]),
+                        }],
+                    },
+                )))
+            },
+        );
+    let tokens = project.update(cx, |project, cx| {
+        project.semantic_tokens(buffer.clone(), cx)
+    });
+    delta_requests.next().await.unwrap();
+    let tokens = tokens.await.unwrap().unwrap();
+    assert_eq!(
+        tokens_to_points(&tokens, cx)[5..],
+        [
+            (
+                Point::new(2, 0)..Point::new(2, 3),
+                "keyword".into(),
+                "".into()
+            ),
+            (
+                Point::new(2, 4)..Point::new(2, 5),
+                "variable".into(),
+                "declaration".into()
+            ),
+            (
+                Point::new(2, 8)..Point::new(2, 9),
+                "variable".into(),
+                "".into()
+            ),
+        ]
+    );
+    assert_eq!(
+        full_requests.load(SeqCst),
+        1,
+        "edits should be fetched as a delta"
+    );
+}
+
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "function a() { b(); }\nfunction b() { c(); c(); }\n",
+            "c.ts": "export function c() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    fn item(name: &str, path: &str, line: u32) -> lsp::CallHierarchyItem {
+        let range = lsp::Range::new(lsp::Position::new(line, 9), lsp::Position::new(line, 10));
+        lsp::CallHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::from_file_path(path).unwrap(),
+            range,
+            selection_range: range,
+            data: None,
+        }
+    }
+
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(1, 9)
+            );
+            Ok(Some(vec![item("b", path!("/dir/a.ts"), 1)]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "b");
+            Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                from: item("a", path!("/dir/a.ts"), 0),
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 15),
+                    lsp::Position::new(0, 16),
+                )],
+            }]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::CallHierarchyOutgoingCalls, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "b");
+            Ok(Some(vec![lsp::CallHierarchyOutgoingCall {
+                to: item("c", path!("/dir/c.ts"), 0),
+                from_ranges: vec![
+                    lsp::Range::new(lsp::Position::new(1, 15), lsp::Position::new(1, 16)),
+                    lsp::Range::new(lsp::Position::new(1, 20), lsp::Position::new(1, 21)),
+                ],
+            }]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(1, 9), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
+
+    let incoming_calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(incoming_calls.len(), 1);
+    assert_eq!(incoming_calls[0].item.name, "a");
+    assert_eq!(
+        incoming_calls[0].call_sites[0].buffer, buffer,
+        "incoming call sites should be located in the caller"
+    );
+
+    let outgoing_calls = project
+        .update(cx, |project, cx| project.outgoing_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(outgoing_calls.len(), 1);
+    let callee_path = outgoing_calls[0]
+        .item
+        .location
+        .buffer
+        .read_with(cx, |buffer, cx| {
+            buffer.file().map(|file| file.full_path(cx))
+        });
+    assert_eq!(callee_path, Some(PathBuf::from(separator!("dir/c.ts"))));
+    let call_sites = buffer.read_with(cx, |buffer, _| {
+        outgoing_calls[0]
+            .call_sites
+            .iter()
+            .map(|call_site| call_site.range.to_point(buffer))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        call_sites,
+        vec![
+            Point::new(1, 15)..Point::new(1, 16),
+            Point::new(1, 20)..Point::new(1, 21),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "class A {}\nclass B extends A {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers =
+        language_registry.register_fake_lsp("TypeScript", FakeLspAdapter::default());
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    fn item(name: &str, line: u32) -> lsp::TypeHierarchyItem {
+        let range = lsp::Range::new(lsp::Position::new(line, 6), lsp::Position::new(line, 7));
+        lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::CLASS,
+            tags: None,
+            detail: None,
+            uri: lsp::Url::from_file_path(path!("/dir/a.ts")).unwrap(),
+            range,
+            selection_range: range,
+            data: None,
+        }
+    }
+
+    fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |_, _| async move { Ok(Some(vec![item("B", 1)])) },
+    );
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.name, "B");
+            Ok(Some(vec![item("A", 0)]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, Point::new(1, 6), cx)
+        })
+        .await
+        .unwrap();
+    assert!(
+        items.is_empty(),
+        "servers without type hierarchy support should not be queried"
+    );
+
+    fake_server
+        .request::<lsp::request::RegisterCapability>(lsp::RegistrationParams {
+            registrations: vec![lsp::Registration {
+                id: Default::default(),
+                method: "textDocument/prepareTypeHierarchy".to_string(),
+                register_options: None,
+            }],
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, Point::new(1, 6), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "B");
+
+    let supertypes = project
+        .update(cx, |project, cx| project.supertypes(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(supertypes.len(), 1);
+    assert_eq!(supertypes[0].name, "A");
+    let selection_range = buffer.read_with(cx, |buffer, _| {
+        supertypes[0].selection_range.to_point(buffer)
+    });
+    assert_eq!(selection_range, Point::new(0, 6)..Point::new(0, 7));
+}
+
+#[gpui::test]
+async fn test_inline_values(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "function f(a) {\n  const b = a + 1;\n  return b;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                inline_value_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    fake_server.set_request_handler::<crate::lsp_command::InlineValueRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.context.frame_id, 7);
+            assert_eq!(params.context.stopped_location.start.line, 2);
+            Ok(Some(vec![
+                lsp::InlineValue::VariableLookup(lsp::InlineValueVariableLookup {
+                    range: lsp::Range::new(lsp::Position::new(1, 8), lsp::Position::new(1, 9)),
+                    variable_name: None,
+                    case_sensitive_lookup: true,
+                }),
+                lsp::InlineValue::EvaluatableExpression(lsp::InlineValueEvaluatableExpression {
+                    range: lsp::Range::new(lsp::Position::new(1, 12), lsp::Position::new(1, 17)),
+                    expression: Some("a + 1".to_string()),
+                }),
+                lsp::InlineValue::Text(lsp::InlineValueText {
+                    range: lsp::Range::new(lsp::Position::new(0, 11), lsp::Position::new(0, 12)),
+                    text: "a = 1".to_string(),
+                }),
+            ]))
+        },
+    );
+
+    let (range, stopped_location) = buffer.read_with(cx, |buffer, _| {
+        (
+            buffer.anchor_before(Point::new(0, 0))..buffer.anchor_after(Point::new(2, 11)),
+            buffer.anchor_before(Point::new(2, 0))..buffer.anchor_after(Point::new(2, 11)),
+        )
+    });
+    let values = project
+        .update(cx, |project, cx| {
+            project.inline_values(&buffer, range, stopped_location, 7, cx)
+        })
+        .await
+        .unwrap();
+    let values = buffer.read_with(cx, |buffer, _| {
+        values
+            .into_iter()
+            .map(|value| (value.range.to_point(buffer), value.kind))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        values,
+        vec![
+            (
+                Point::new(1, 8)..Point::new(1, 9),
+                InlineValueKind::VariableLookup {
+                    variable_name: None,
+                    case_sensitive: true,
+                },
+            ),
+            (
+                Point::new(1, 12)..Point::new(1, 17),
+                InlineValueKind::EvaluatableExpression {
+                    expression: Some("a + 1".to_string()),
+                },
+            ),
+            (
+                Point::new(0, 11)..Point::new(0, 12),
+                InlineValueKind::Text("a = 1".to_string()),
+            ),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_document_colors(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "const red = \"#ff0000\";\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                color_provider: Some(lsp::ColorProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    let red = lsp::Color {
+        red: 1.,
+        green: 0.,
+        blue: 0.,
+        alpha: 1.,
+    };
+    let blue = lsp::Color {
+        red: 0.,
+        green: 0.,
+        blue: 1.,
+        alpha: 1.,
+    };
+    let color_range = lsp::Range::new(lsp::Position::new(0, 13), lsp::Position::new(0, 20));
+    fake_server.set_request_handler::<lsp::request::DocumentColor, _, _>(move |_, _| async move {
+        Ok(vec![lsp::ColorInformation {
+            range: color_range,
+            color: red,
+        }])
+    });
+    fake_server.set_request_handler::<lsp::request::ColorPresentationRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.color, blue);
+            assert_eq!(params.range, color_range);
+            Ok(vec![lsp::ColorPresentation {
+                label: "#0000ff".to_string(),
+                text_edit: Some(lsp::TextEdit {
+                    range: params.range,
+                    new_text: "#0000ff".to_string(),
+                }),
+                additional_text_edits: None,
+            }])
+        },
+    );
+
+    let colors = project
+        .update(cx, |project, cx| project.document_colors(&buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(colors.len(), 1);
+    assert_eq!(colors[0].color, red);
+    let range = buffer.read_with(cx, |buffer, _| colors[0].range.to_point(buffer));
+    assert_eq!(range, Point::new(0, 13)..Point::new(0, 20));
+
+    let presentations = project
+        .update(cx, |project, cx| {
+            project.color_presentations(&buffer, &colors[0], blue, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(presentations.len(), 1);
+    assert_eq!(presentations[0].label, "#0000ff");
+    let (edit_range, new_text) = presentations[0].text_edit.clone().unwrap();
+    let edit_range = buffer.read_with(cx, |buffer, _| edit_range.to_point(buffer));
+    assert_eq!(edit_range, Point::new(0, 13)..Point::new(0, 20));
+    assert_eq!(new_text, "#0000ff");
+}
+
+#[gpui::test]
+async fn test_selection_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "const a = f(b);\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    fake_server.set_request_handler::<lsp::request::SelectionRangeRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.positions, vec![lsp::Position::new(0, 13)]);
+            let range = |start, end| {
+                lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end))
+            };
+            Ok(Some(vec![lsp::SelectionRange {
+                range: range(12, 13),
+                parent: Some(Box::new(lsp::SelectionRange {
+                    range: range(10, 14),
+                    parent: Some(Box::new(lsp::SelectionRange {
+                        range: range(0, 15),
+                        parent: None,
+                    })),
+                })),
+            }]))
+        },
+    );
+
+    let position = buffer.read_with(cx, |buffer, _| buffer.anchor_before(Point::new(0, 13)));
+    let selection_ranges = project
+        .update(cx, |project, cx| {
+            project.selection_ranges(&buffer, vec![position], cx)
+        })
+        .await
+        .unwrap();
+    let selection_ranges = buffer.read_with(cx, |buffer, _| {
+        selection_ranges
+            .into_iter()
+            .map(|ranges| {
+                ranges
+                    .into_iter()
+                    .map(|range| range.to_point(buffer))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(
+        selection_ranges,
+        vec![vec![
+            Point::new(0, 12)..Point::new(0, 13),
+            Point::new(0, 10)..Point::new(0, 14),
+            Point::new(0, 0)..Point::new(0, 15),
+        ]]
+    );
+}
+
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "let a = 1;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    let request_count = Arc::new(AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let request_count = request_count.clone();
+        move |params, _| {
+            let request_count = request_count.clone();
+            async move {
+                assert_eq!(
+                    params.text_document.uri,
+                    lsp::Url::from_file_path(path!("/dir/a.rs")).unwrap()
+                );
+                let report = if request_count.fetch_add(1, SeqCst) == 0 {
+                    assert_eq!(params.previous_result_id, None);
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("1".to_string()),
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 4),
+                                    lsp::Position::new(0, 5),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "unused variable".to_string(),
+                                ..Default::default()
+                            }],
+                        },
+                    })
+                } else {
+                    assert_eq!(params.previous_result_id.as_deref(), Some("1"));
+                    lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport {
+                                    result_id: "1".to_string(),
+                                },
+                        },
+                    )
+                };
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        }
+    });
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+
+    assert_eq!(request_count.load(SeqCst), 1);
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| (entry.range, entry.diagnostic.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [(
+                Point::new(0, 4)..Point::new(0, 5),
+                "unused variable".to_string()
+            )]
+        );
+    });
+
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+
+    assert_eq!(request_count.load(SeqCst), 2);
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| entry.range)
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics, [Point::new(1, 4)..Point::new(1, 5)]);
+    });
+}
+
+#[gpui::test]
+async fn test_pull_workspace_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "let a = 1;",
+            "b.rs": "let b = 2;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let request_count = Arc::new(AtomicUsize::new(0));
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        workspace_diagnostics: true,
+                        ..lsp::DiagnosticOptions::default()
+                    },
+                )),
+                ..lsp::ServerCapabilities::default()
+            },
+            initializer: Some(Box::new({
+                let request_count = request_count.clone();
+                move |fake_server| {
+                    fake_server
+                        .set_request_handler::<lsp::request::DocumentDiagnosticRequest, _, _>(
+                            |_, _| async move {
+                                Ok(lsp::DocumentDiagnosticReportResult::Report(
+                                    lsp::DocumentDiagnosticReport::Full(
+                                        lsp::RelatedFullDocumentDiagnosticReport {
+                                            related_documents: None,
+                                            full_document_diagnostic_report:
+                                                lsp::FullDocumentDiagnosticReport {
+                                                    result_id: None,
+                                                    items: Vec::new(),
+                                                },
+                                        },
+                                    ),
+                                ))
+                            },
+                        );
+                    fake_server
+                        .set_request_handler::<lsp::request::WorkspaceDiagnosticRequest, _, _>({
+                            let request_count = request_count.clone();
+                            move |params, _| {
+                                let request_count = request_count.clone();
+                                async move {
+                                    let uri = lsp::Url::from_file_path(path!("/dir/b.rs")).unwrap();
+                                    let previous_result_ids = params
+                                        .previous_result_ids
+                                        .into_iter()
+                                        .map(|previous| (previous.uri, previous.value))
+                                        .collect::<Vec<_>>();
+                                    let report = match request_count.fetch_add(1, SeqCst) {
+                                        0 => {
+                                            assert!(previous_result_ids.is_empty());
+                                            lsp::WorkspaceDocumentDiagnosticReport::Full(
+                                                lsp::WorkspaceFullDocumentDiagnosticReport {
+                                                    uri,
+                                                    version: None,
+                                                    full_document_diagnostic_report:
+                                                        lsp::FullDocumentDiagnosticReport {
+                                                            result_id: Some("1".to_string()),
+                                                            items: vec![lsp::Diagnostic {
+                                                                range: lsp::Range::new(
+                                                                    lsp::Position::new(0, 4),
+                                                                    lsp::Position::new(0, 5),
+                                                                ),
+                                                                severity: Some(
+                                                                    lsp::DiagnosticSeverity::ERROR,
+                                                                ),
+                                                                message: "unused variable"
+                                                                    .to_string(),
+                                                                ..Default::default()
+                                                            }],
+                                                        },
+                                                },
+                                            )
+                                        }
+                                        1 => {
+                                            assert_eq!(
+                                                previous_result_ids,
+                                                [(uri.clone(), "1".to_string())]
+                                            );
+                                            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(
+                                                lsp::WorkspaceUnchangedDocumentDiagnosticReport {
+                                                    uri,
+                                                    version: None,
+                                                    unchanged_document_diagnostic_report:
+                                                        lsp::UnchangedDocumentDiagnosticReport {
+                                                            result_id: "2".to_string(),
+                                                        },
+                                                },
+                                            )
+                                        }
+                                        _ => {
+                                            assert_eq!(
+                                                previous_result_ids,
+                                                [(uri.clone(), "2".to_string())]
+                                            );
+                                            lsp::WorkspaceDocumentDiagnosticReport::Full(
+                                                lsp::WorkspaceFullDocumentDiagnosticReport {
+                                                    uri,
+                                                    version: None,
+                                                    full_document_diagnostic_report:
+                                                        lsp::FullDocumentDiagnosticReport {
+                                                            result_id: Some("3".to_string()),
+                                                            items: Vec::new(),
+                                                        },
+                                                },
+                                            )
+                                        }
+                                    };
+                                    Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                                        lsp::WorkspaceDiagnosticReport {
+                                            items: vec![report],
+                                        },
+                                    ))
+                                }
+                            }
+                        });
+                }
+            })),
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (_buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+
+    // The diagnostics of the unopened file are reported by the workspace pull.
+    assert_eq!(request_count.load(SeqCst), 1);
+    project.update(cx, |project, cx| {
+        assert_eq!(project.diagnostic_summary(false, cx).error_count, 1);
+    });
+
+    // An unchanged report keeps the diagnostics and stores the new result id.
+    fake_server
+        .server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+    assert_eq!(request_count.load(SeqCst), 2);
+    project.update(cx, |project, cx| {
+        assert_eq!(project.diagnostic_summary(false, cx).error_count, 1);
+    });
+
+    fake_server
+        .server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.executor().run_until_parked();
+    assert_eq!(request_count.load(SeqCst), 3);
+    project.update(cx, |project, cx| {
+        assert_eq!(project.diagnostic_summary(false, cx).error_count, 0);
+    });
+}
+
+#[gpui::test]
+async fn test_code_actions_only_kinds(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer_with_lsp(path!("/dir/a.ts"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let fake_server = fake_language_servers
+        .next()
+        .await
+        .expect("failed to get the language server");
+
+    let mut request_handled = fake_server
+        .set_request_handler::<lsp::request::CodeActionRequest, _, _>(move |_, _| async move {
+            Ok(Some(vec![
+                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                    title: "organize imports".to_string(),
+                    kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+                    ..lsp::CodeAction::default()
+                }),
+                lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+                    title: "fix code".to_string(),
+                    kind: Some(CodeActionKind::SOURCE_FIX_ALL),
+                    ..lsp::CodeAction::default()
+                }),
+            ]))
+        });
+
+    let code_actions_task = project.update(cx, |project, cx| {
+        project.code_actions(
+            &buffer,
+            0..buffer.read(cx).len(),
+            Some(vec![CodeActionKind::SOURCE_ORGANIZE_IMPORTS]),
+            cx,
+        )
+    });
+
+    let () = request_handled
+        .next()
+        .await
+        .expect("The code action request should have been triggered");
+
+    let code_actions = code_actions_task.await.unwrap();
+    assert_eq!(code_actions.len(), 1);
+    assert_eq!(
+        code_actions[0].lsp_action.action_kind(),
+        Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS)
+    );
+}
+
+#[gpui::test]
+async fn test_multiple_language_server_actions(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.tsx": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(tsx_lang());
     let language_server_names = [
         "TypeScriptServer",
         "TailwindServer",
@@ -6796,6 +8147,72 @@ async fn test_staging_hunks_with_delayed_fs_event(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_staging_lines_keeps_index_line_endings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "crlf.txt": "one\r\nTWO\r\nthree\r\n",
+            // Checked out with `core.autocrlf`, the index stores `\n`.
+            "autocrlf.txt": "one\r\nTWO\r\nthree\r\n",
+        }),
+    )
+    .await;
+    fs.set_head_and_index_for_repo(
+        "/dir/.git".as_ref(),
+        &[
+            ("crlf.txt".into(), "one\r\ntwo\r\nthree\r\n".into()),
+            ("autocrlf.txt".into(), "one\ntwo\nthree\n".into()),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    for path in ["crlf.txt", "autocrlf.txt"] {
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(Path::new("/dir").join(path), cx)
+            })
+            .await
+            .unwrap();
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let uncommitted_diff = project
+            .update(cx, |project, cx| {
+                project.open_uncommitted_diff(buffer.clone(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // Only the addition of the modified line is staged.
+        uncommitted_diff.update(cx, |diff, cx| {
+            let selection = buffer_diff::LineSelection {
+                buffer_rows: vec![1..2],
+                head_rows: Vec::new(),
+            };
+            diff.stage_or_unstage_lines(true, &selection, &snapshot, true, cx);
+        });
+        cx.run_until_parked();
+    }
+
+    let index_contents = fs
+        .with_git_state("/dir/.git".as_ref(), false, |state| {
+            state.index_contents.clone()
+        })
+        .unwrap();
+    assert_eq!(
+        index_contents[&RepoPath::from("crlf.txt")],
+        "one\r\ntwo\r\nTWO\r\nthree\r\n"
+    );
+    assert_eq!(
+        index_contents[&RepoPath::from("autocrlf.txt")],
+        "one\ntwo\nTWO\nthree\n"
+    );
+}
+
 #[gpui::test]
 async fn test_staging_lots_of_hunks_fast(cx: &mut gpui::TestAppContext) {
     use DiffHunkSecondaryStatus::*;
@@ -7015,6 +8432,89 @@ async fn test_single_file_diffs(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_stash_push_and_pop(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "A changed\n",
+            "b.txt": "B\n",
+        }),
+    )
+    .await;
+    fs.set_head_and_index_for_repo(
+        path!("/dir/.git").as_ref(),
+        &[
+            ("a.txt".into(), "A\n".into()),
+            ("b.txt".into(), "B\n".into()),
+        ],
+    );
+    fs.set_branch_name(path!("/dir/.git").as_ref(), Some("main"));
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+
+    repository
+        .update(cx, |repository, cx| {
+            repository.stash_push(
+                StashOptions {
+                    message: Some("wip".into()),
+                    ..StashOptions::default()
+                },
+                cx,
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fs.load(path!("/dir/a.txt").as_ref()).await.unwrap(), "A\n");
+    let entries = repository
+        .update(cx, |repository, _| repository.stash_entries())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| entry.message.as_ref())
+            .collect::<Vec<_>>(),
+        ["On main: wip"]
+    );
+    let diff = repository
+        .update(cx, |repository, _| repository.stash_diff(0))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        diff,
+        [CommitFileDiff {
+            path: "a.txt".into(),
+            old_text: Some("A\n".into()),
+            new_text: Some("A changed\n".into()),
+        }]
+    );
+
+    repository
+        .update(cx, |repository, cx| repository.stash_pop(0, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        fs.load(path!("/dir/a.txt").as_ref()).await.unwrap(),
+        "A changed\n"
+    );
+    let entries = repository
+        .update(cx, |repository, _| repository.stash_entries())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(entries.is_empty());
+}
+
 #[gpui::test]
 async fn test_repository_and_path_for_project_path(
     background_executor: BackgroundExecutor,
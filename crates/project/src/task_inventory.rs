@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet, VecDeque};
 use gpui::{App, AppContext as _, Entity, SharedString, Task};
 use itertools::Itertools;
@@ -57,6 +57,14 @@ pub enum TaskSourceKind {
     Language { name: SharedString },
 }
 
+/// A task template together with the tasks it depends on, as listed in its `depends_on` field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskDependencyTree {
+    pub task_source_kind: TaskSourceKind,
+    pub template: TaskTemplate,
+    pub dependencies: Vec<TaskDependencyTree>,
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -319,6 +327,27 @@ impl Inventory {
         self.last_scheduled_tasks.retain(|(_, task)| &task.id != id);
     }
 
+    /// Recursively looks up the tasks the template given depends on, among the tasks available for the worktree and the language given.
+    /// Dependencies from the same source kind as their dependent task are preferred, if there are multiple tasks with the same label.
+    /// Fails if any dependency cannot be found, or if the dependencies form a cycle.
+    pub fn resolve_dependencies(
+        &self,
+        task_source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        file: Option<Arc<dyn File>>,
+        language: Option<Arc<Language>>,
+        worktree: Option<WorktreeId>,
+        cx: &App,
+    ) -> Result<TaskDependencyTree> {
+        let available_tasks = self.list_tasks(file, language, worktree, cx);
+        dependency_tree(
+            task_source_kind,
+            template,
+            &available_tasks,
+            &mut Vec::new(),
+        )
+    }
+
     fn global_templates_from_settings(
         &self,
     ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
@@ -434,6 +463,55 @@ impl Inventory {
     }
 }
 
+fn dependency_tree(
+    task_source_kind: TaskSourceKind,
+    template: TaskTemplate,
+    available_tasks: &[(TaskSourceKind, TaskTemplate)],
+    dependent_labels: &mut Vec<String>,
+) -> Result<TaskDependencyTree> {
+    if let Some(cycle_start) = dependent_labels
+        .iter()
+        .position(|label| label == &template.label)
+    {
+        let cycle = dependent_labels[cycle_start..]
+            .iter()
+            .chain(Some(&template.label))
+            .join(" -> ");
+        anyhow::bail!("Task dependency cycle: {cycle}");
+    }
+
+    dependent_labels.push(template.label.clone());
+    let dependencies = template
+        .depends_on
+        .iter()
+        .map(|label| {
+            let (dependency_source_kind, dependency) = available_tasks
+                .iter()
+                .filter(|(_, task)| &task.label == label)
+                .min_by_key(|(kind, _)| kind != &task_source_kind)
+                .with_context(|| {
+                    format!(
+                        "Task `{}` depends on unknown task `{label}`",
+                        template.label
+                    )
+                })?;
+            dependency_tree(
+                dependency_source_kind.clone(),
+                dependency.clone(),
+                available_tasks,
+                dependent_labels,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    dependent_labels.pop();
+
+    Ok(TaskDependencyTree {
+        task_source_kind,
+        template,
+        dependencies,
+    })
+}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -964,6 +1042,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_task_dependency_resolution() {
+        let source_kind = TaskSourceKind::AbsPath {
+            id_base: "global tasks.json".into(),
+            abs_path: paths::tasks_file().clone(),
+        };
+        let task = |label: &str, depends_on: &[&str]| {
+            (
+                source_kind.clone(),
+                TaskTemplate {
+                    label: label.to_string(),
+                    command: "echo".to_string(),
+                    depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+                    ..TaskTemplate::default()
+                },
+            )
+        };
+        let available_tasks = vec![
+            task("configure", &[]),
+            task("build", &["configure"]),
+            task("test", &["configure"]),
+            task("ci", &["build", "test"]),
+            task("lint", &["missing"]),
+            task("ping", &["pong"]),
+            task("pong", &["ping"]),
+        ];
+        let labels = |tree: &TaskDependencyTree| {
+            fn collect(tree: &TaskDependencyTree, labels: &mut Vec<String>) {
+                for dependency in &tree.dependencies {
+                    collect(dependency, labels);
+                }
+                labels.push(tree.template.label.clone());
+            }
+            let mut labels = Vec::new();
+            collect(tree, &mut labels);
+            labels
+        };
+        let resolve = |label: &str| {
+            let (kind, template) = available_tasks
+                .iter()
+                .find(|(_, task)| task.label == label)
+                .cloned()
+                .unwrap();
+            dependency_tree(kind, template, &available_tasks, &mut Vec::new())
+        };
+
+        assert_eq!(labels(&resolve("configure").unwrap()), vec!["configure"]);
+        assert_eq!(
+            labels(&resolve("ci").unwrap()),
+            vec!["configure", "build", "configure", "test", "ci"],
+            "Shared dependencies are not a cycle"
+        );
+        assert_eq!(
+            resolve("lint").unwrap_err().to_string(),
+            "Task `lint` depends on unknown task `missing`"
+        );
+        assert_eq!(
+            resolve("ping").unwrap_err().to_string(),
+            "Task dependency cycle: ping -> pong -> ping"
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         if std::env::var("RUST_LOG").is_ok() {
             env_logger::try_init().ok();
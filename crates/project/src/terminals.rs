@@ -275,16 +275,27 @@ impl Project {
                 }
             }
             TerminalKind::Task(spawn_task) => {
+                let background = spawn_task
+                    .background
+                    .as_ref()
+                    .and_then(|background| background.compile().log_err());
                 let task_state = Some(TaskState {
                     id: spawn_task.id,
                     full_label: spawn_task.full_label,
                     label: spawn_task.label,
                     command_label: spawn_task.command_label,
                     hide: spawn_task.hide,
-                    status: TaskStatus::Running,
+                    status: if background.is_some() {
+                        TaskStatus::Building
+                    } else {
+                        TaskStatus::Running
+                    },
                     show_summary: spawn_task.show_summary,
                     show_command: spawn_task.show_command,
                     show_rerun: spawn_task.show_rerun,
+                    problem_matchers: spawn_task.problem_matchers,
+                    cwd: path.as_ref().map(|path| path.to_path_buf()),
+                    background,
                     completion_rx,
                 });
 
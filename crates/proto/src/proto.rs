@@ -336,6 +336,37 @@ messages!(
     (LeaveRoom, Foreground),
     (LinkedEditingRange, Background),
     (LinkedEditingRangeResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
+    (GetInlineValues, Background),
+    (GetInlineValuesResponse, Background),
+    (GetDocumentColors, Background),
+    (GetDocumentColorsResponse, Background),
+    (GetColorPresentations, Background),
+    (GetColorPresentationsResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
+    (GetRenameEdits, Background),
+    (GetRenameEditsResponse, Background),
     (ListRemoteDirectory, Background),
     (ListRemoteDirectoryResponse, Background),
     (ListToolchains, Foreground),
@@ -416,6 +447,8 @@ messages!(
     (SyncExtensionsResponse, Background),
     (BreakpointsForFile, Background),
     (ToggleBreakpoint, Foreground),
+    (NonSourceBreakpoints, Background),
+    (SetNonSourceBreakpoints, Foreground),
     (SynchronizeBuffers, Foreground),
     (SynchronizeBuffersResponse, Foreground),
     (SynchronizeContexts, Foreground),
@@ -471,6 +504,49 @@ messages!(
     (CheckForPushedCommitsResponse, Background),
     (GitDiff, Background),
     (GitDiffResponse, Background),
+    (GitStashList, Background),
+    (GitStashListResponse, Background),
+    (GitStashPush, Background),
+    (GitStashDiff, Background),
+    (GitStashDiffResponse, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitCommitDiff, Background),
+    (GitCommitDiffResponse, Background),
+    (GitRebaseCommits, Background),
+    (GitRebase, Background),
+    (GitRebaseCommand, Background),
+    (GitRebaseStatus, Background),
+    (GitRebaseStatusResponse, Background),
+    (GitRevisionDiff, Background),
+    (GitWorktreeList, Background),
+    (GitWorktreeListResponse, Background),
+    (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitPruneWorktrees, Background),
+    (GitTagList, Background),
+    (GitTagListResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitPushTag, Background),
+    (GitAddRemote, Background),
+    (GitRenameRemote, Background),
+    (GitRemoveRemote, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitSequencerCommand, Background),
+    (GitSequencerStatus, Background),
+    (GitSequencerStatusResponse, Background),
+    (GitCommitMessageTemplate, Background),
+    (GitCommitMessageTemplateResponse, Background),
+    (GitSubmodules, Background),
+    (GitSubmodulesResponse, Background),
+    (GitUpdateSubmodules, Background),
+    (GitSyncSubmodules, Background),
+    (GitSubmoduleDiff, Background),
+    (GitStashApply, Background),
+    (GitStashPop, Background),
+    (GitStashDrop, Background),
     (GitInit, Background),
 );
 
@@ -521,6 +597,22 @@ request_messages!(
     (GetSupermavenApiKey, GetSupermavenApiKeyResponse),
     (GetTypeDefinition, GetTypeDefinitionResponse),
     (LinkedEditingRange, LinkedEditingRangeResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (RefreshSemanticTokens, Ack),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
+    (GetInlineValues, GetInlineValuesResponse),
+    (GetDocumentColors, GetDocumentColorsResponse),
+    (GetColorPresentations, GetColorPresentationsResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
+    (GetRenameEdits, GetRenameEditsResponse),
     (ListRemoteDirectory, ListRemoteDirectoryResponse),
     (GetUsers, UsersResponse),
     (IncomingCall, Ack),
@@ -625,8 +717,42 @@ request_messages!(
     (GitChangeBranch, Ack),
     (CheckForPushedCommits, CheckForPushedCommitsResponse),
     (GitDiff, GitDiffResponse),
+    (GitStashList, GitStashListResponse),
+    (GitStashPush, Ack),
+    (GitStashDiff, GitStashDiffResponse),
+    (GitLog, GitLogResponse),
+    (GitCommitDiff, GitCommitDiffResponse),
+    (GitRebaseCommits, GitLogResponse),
+    (GitRebase, Ack),
+    (GitRebaseCommand, Ack),
+    (GitRebaseStatus, GitRebaseStatusResponse),
+    (GitRevisionDiff, GitCommitDiffResponse),
+    (GitWorktreeList, GitWorktreeListResponse),
+    (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitPruneWorktrees, Ack),
+    (GitTagList, GitTagListResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitPushTag, RemoteMessageResponse),
+    (GitAddRemote, Ack),
+    (GitRenameRemote, Ack),
+    (GitRemoveRemote, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitSequencerCommand, Ack),
+    (GitSequencerStatus, GitSequencerStatusResponse),
+    (GitCommitMessageTemplate, GitCommitMessageTemplateResponse),
+    (GitSubmodules, GitSubmodulesResponse),
+    (GitUpdateSubmodules, RemoteMessageResponse),
+    (GitSyncSubmodules, Ack),
+    (GitSubmoduleDiff, GitCommitDiffResponse),
+    (GitStashApply, Ack),
+    (GitStashPop, Ack),
+    (GitStashDrop, Ack),
     (GitInit, Ack),
     (ToggleBreakpoint, Ack),
+    (SetNonSourceBreakpoints, Ack),
 );
 
 entity_messages!(
@@ -668,6 +794,22 @@ entity_messages!(
     JoinProject,
     LeaveProject,
     LinkedEditingRange,
+    GetSemanticTokens,
+    RefreshSemanticTokens,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
+    GetFoldingRanges,
+    GetDocumentLinks,
+    ResolveDocumentLink,
+    GetInlineValues,
+    GetDocumentColors,
+    GetColorPresentations,
+    GetSelectionRanges,
+    GetRenameEdits,
     MultiLspQuery,
     RestartLanguageServers,
     OnTypeFormatting,
@@ -740,9 +882,44 @@ entity_messages!(
     GitCreateBranch,
     CheckForPushedCommits,
     GitDiff,
+    GitStashList,
+    GitStashPush,
+    GitStashDiff,
+    GitLog,
+    GitCommitDiff,
+    GitRebaseCommits,
+    GitRebase,
+    GitRebaseCommand,
+    GitRebaseStatus,
+    GitRevisionDiff,
+    GitWorktreeList,
+    GitCreateWorktree,
+    GitRemoveWorktree,
+    GitPruneWorktrees,
+    GitTagList,
+    GitCreateTag,
+    GitDeleteTag,
+    GitPushTag,
+    GitAddRemote,
+    GitRenameRemote,
+    GitRemoveRemote,
+    GitCherryPick,
+    GitRevert,
+    GitSequencerCommand,
+    GitSequencerStatus,
+    GitCommitMessageTemplate,
+    GitSubmodules,
+    GitUpdateSubmodules,
+    GitSyncSubmodules,
+    GitSubmoduleDiff,
+    GitStashApply,
+    GitStashPop,
+    GitStashDrop,
     GitInit,
     BreakpointsForFile,
     ToggleBreakpoint,
+    NonSourceBreakpoints,
+    SetNonSourceBreakpoints,
 );
 
 entity_messages!(
@@ -36,6 +36,36 @@ pub fn init(cx: &mut App) {
     cx.observe_new(DisconnectedOverlay::register).detach();
 }
 
+/// Opens the paths as a new workspace on the machine hosting the workspace's project, connecting
+/// over SSH when the project is remote.
+pub fn open_paths_on_project_host(
+    paths: Vec<PathBuf>,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<anyhow::Result<()>> {
+    let project = workspace.project().read(cx);
+    if project.is_via_collab() {
+        return Task::ready(Err(anyhow::anyhow!(
+            "Paths of a shared project can only be opened by its host"
+        )));
+    }
+    let Some(connection_options) = project.ssh_connection_options(cx) else {
+        return workspace.open_workspace_for_paths(false, paths, window, cx);
+    };
+    let app_state = workspace.app_state().clone();
+    cx.spawn_in(window, async move |_, cx| {
+        open_ssh_project(
+            connection_options,
+            paths,
+            app_state,
+            OpenOptions::default(),
+            cx,
+        )
+        .await
+    })
+}
+
 pub struct RecentProjects {
     pub picker: Entity<Picker<RecentProjectsDelegate>>,
     rem_width: f32,
@@ -138,6 +138,7 @@ impl TryFrom<DebugAdapterConfig> for DebugTaskDefinition {
             tcp_connection: def.tcp_connection,
             locator: def.locator,
             args: def.args,
+            pre_launch_task: None,
         })
     }
 }
@@ -176,6 +177,7 @@ impl DebugTaskDefinition {
             args: vec![],
             task_type,
             cwd,
+            depends_on: self.pre_launch_task.into_iter().collect(),
             ..Default::default()
         })
     }
@@ -215,6 +217,10 @@ pub struct DebugTaskDefinition {
     /// Args to pass to a debug adapter (only used in locator right now)
     #[serde(skip)]
     pub args: Vec<String>,
+    /// Label of the task to run before starting the debug session.
+    /// The session starts once the task finishes successfully or, for background tasks, once it is ready.
+    #[serde(default)]
+    pub pre_launch_task: Option<String>,
 }
 
 /// A group of Debug Tasks defined in a JSON file.
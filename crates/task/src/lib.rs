@@ -2,7 +2,9 @@
 #![deny(missing_docs)]
 
 mod debug_format;
+mod problem_matcher;
 pub mod static_source;
+mod task_input;
 mod task_template;
 mod vscode_format;
 
@@ -18,9 +20,14 @@ pub use debug_format::{
     AttachConfig, DebugAdapterConfig, DebugConnectionType, DebugRequestDisposition,
     DebugRequestType, DebugTaskDefinition, DebugTaskFile, LaunchConfig, TCPHost,
 };
+pub use problem_matcher::{
+    BackgroundMatcher, BackgroundPatterns, FileLocation, FileLocationKind, ProblemMatcher,
+    ProblemPattern, ProblemPatterns, ProblemSeverity, TaskProblem, TaskProblemMatcher,
+};
+pub use task_input::{PickStringOption, TaskInput, TaskInputKind};
 pub use task_template::{
-    DebugArgs, DebugArgsRequest, HideStrategy, RevealStrategy, TaskModal, TaskTemplate,
-    TaskTemplates, TaskType,
+    DebugArgs, DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskModal,
+    TaskTemplate, TaskTemplates, TaskType,
 };
 pub use vscode_format::VsCodeTaskFile;
 pub use zed_actions::RevealTarget;
@@ -68,6 +75,10 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Problem matchers to apply to the task output, with built-in ones resolved.
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Patterns that tell whether a background task is building or ready, `None` for regular tasks.
+    pub background: Option<BackgroundMatcher>,
 }
 
 /// A final form of the [`TaskTemplate`], that got resolved with a particular [`TaskContext`] and now is ready to spawn the actual task.
@@ -162,6 +173,70 @@ impl ResolvedTask {
             .map(|resolved| resolved.label.as_str())
             .unwrap_or_else(|| self.resolved_label.as_str())
     }
+
+    /// Inputs of the task that are still referenced in the resolved task, and have to be asked for before spawning it.
+    pub fn unresolved_inputs(&self) -> Vec<&TaskInput> {
+        let Some(resolved) = &self.resolved else {
+            return Vec::new();
+        };
+        let referenced_ids = [&resolved.label, &resolved.command]
+            .into_iter()
+            .chain(&resolved.args)
+            .chain(resolved.env.values())
+            .flat_map(|text| task_input::input_references(text))
+            .chain(
+                resolved
+                    .cwd
+                    .iter()
+                    .filter_map(|cwd| cwd.to_str())
+                    .flat_map(task_input::input_references),
+            )
+            .collect::<HashSet<_>>();
+        self.original_task
+            .inputs
+            .iter()
+            .filter(|input| referenced_ids.contains(input.id.as_str()))
+            .collect()
+    }
+
+    /// Substitutes the `${input:id}` references with the values the user entered for them.
+    /// Values of the password inputs are masked in the labels, but passed to the command as is.
+    pub fn resolve_inputs(&mut self, values: &HashMap<String, String>) {
+        let value = |id: &str| values.get(id).cloned();
+        let displayed_value = |id: &str| {
+            let is_password = self
+                .original_task
+                .inputs
+                .iter()
+                .any(|input| input.id == id && input.is_password());
+            if is_password {
+                values.get(id).map(|_| "******".to_string())
+            } else {
+                values.get(id).cloned()
+            }
+        };
+
+        self.resolved_label = task_input::substitute_inputs(&self.resolved_label, displayed_value);
+        let Some(resolved) = &mut self.resolved else {
+            return;
+        };
+        resolved.full_label = task_input::substitute_inputs(&resolved.full_label, displayed_value);
+        resolved.label = task_input::substitute_inputs(&resolved.label, displayed_value);
+        resolved.command_label =
+            task_input::substitute_inputs(&resolved.command_label, displayed_value);
+        resolved.command = task_input::substitute_inputs(&resolved.command, value);
+        for arg in &mut resolved.args {
+            *arg = task_input::substitute_inputs(arg, value);
+        }
+        for env_value in resolved.env.values_mut() {
+            *env_value = task_input::substitute_inputs(env_value, value);
+        }
+        if let Some(cwd) = &mut resolved.cwd {
+            if let Some(cwd_str) = cwd.to_str() {
+                *cwd = PathBuf::from(task_input::substitute_inputs(cwd_str, value));
+            }
+        }
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
@@ -0,0 +1,790 @@
+//! Problem matchers scan the output of a task for errors and warnings, to show them as diagnostics.
+//! The format of the user-defined matchers follows VS Code's `problemMatcher` schema.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A problem matcher, referenced from a task template.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum TaskProblemMatcher {
+    /// Name of a built-in problem matcher: `$rustc`, `$tsc`, `$tsc-watch` (a background one), `$gcc`, `$eslint-stylish`, `$eslint-compact`, `$pytest` or `$go`.
+    BuiltIn(String),
+    /// A user-defined problem matcher.
+    Custom(ProblemMatcher),
+}
+
+impl TaskProblemMatcher {
+    /// Returns the actual matcher definition, if the built-in matcher referenced exists.
+    /// Custom matchers, extending a built-in one, get the properties they do not override from it.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::BuiltIn(name) => ProblemMatcher::built_in(name),
+            Self::Custom(matcher) => match &matcher.base {
+                Some(base) => {
+                    let base = ProblemMatcher::built_in(base)?;
+                    Some(ProblemMatcher {
+                        base: None,
+                        owner: matcher.owner.clone().or(base.owner),
+                        source: matcher.source.clone().or(base.source),
+                        severity: matcher.severity.or(base.severity),
+                        file_location: matcher.file_location.clone().or(base.file_location),
+                        pattern: matcher.pattern.clone().or(base.pattern),
+                        background: matcher.background.clone().or(base.background),
+                    })
+                }
+                None => Some(matcher.clone()),
+            },
+        }
+    }
+}
+
+/// A definition of a problem matcher, compatible with VS Code's `problemMatcher` schema.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemMatcher {
+    /// Name of the built-in matcher to extend, e.g. `$tsc`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Name of the tool that reports the problems.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Human-readable source of the problems, shown in the diagnostics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Severity of the problems whose pattern does not capture one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<ProblemSeverity>,
+    /// How to resolve the file paths captured by the patterns.
+    /// By default, absolute paths are used as is and others are relative to the task's working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_location: Option<FileLocation>,
+    /// Pattern or a sequence of patterns, matching consecutive lines of the output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<ProblemPatterns>,
+    /// Patterns detecting when a background task starts and finishes a build.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<BackgroundMatcher>,
+}
+
+/// Patterns of a long-running (background) task, e.g. a watcher, that tell whether the task is building or ready.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundMatcher {
+    /// A regular expression, matching the output line printed when the task starts building.
+    #[serde(alias = "beginsPattern")]
+    pub begins_pattern: String,
+    /// A regular expression, matching the output line printed when the task is ready.
+    #[serde(alias = "endsPattern")]
+    pub ends_pattern: String,
+}
+
+impl BackgroundMatcher {
+    /// Compiles the patterns into regular expressions.
+    pub fn compile(&self) -> Result<BackgroundPatterns> {
+        Ok(BackgroundPatterns {
+            begins: Regex::new(&self.begins_pattern)
+                .with_context(|| format!("invalid begins pattern `{}`", self.begins_pattern))?,
+            ends: Regex::new(&self.ends_pattern)
+                .with_context(|| format!("invalid ends pattern `{}`", self.ends_pattern))?,
+        })
+    }
+}
+
+/// Compiled [`BackgroundMatcher`].
+#[derive(Clone, Debug)]
+pub struct BackgroundPatterns {
+    begins: Regex,
+    ends: Regex,
+}
+
+impl BackgroundPatterns {
+    /// Whether the task is building after printing the line given, if the line begins or ends a build.
+    pub fn is_building_after(&self, line: &str) -> Option<bool> {
+        if self.ends.is_match(line) {
+            Some(false)
+        } else if self.begins.is_match(line) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+/// One or more patterns of a problem matcher.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemPatterns {
+    /// A single pattern, matching one line of the output.
+    Single(ProblemPattern),
+    /// Patterns matching consecutive lines of the output, the last one may repeat (see [`ProblemPattern::loop`]).
+    Multiple(Vec<ProblemPattern>),
+}
+
+impl ProblemPatterns {
+    /// All patterns, in the order they should match the output.
+    pub fn patterns(&self) -> &[ProblemPattern] {
+        match self {
+            Self::Single(pattern) => std::slice::from_ref(pattern),
+            Self::Multiple(patterns) => patterns,
+        }
+    }
+}
+
+/// A regular expression, matching a single line of the output, with the indices of its capture groups.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line against.
+    pub regexp: String,
+    /// Capture group of the file path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<usize>,
+    /// Capture group of the whole location, as `line`, `line,column` or `line,column,endLine,endColumn`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<usize>,
+    /// Capture group of the 1-based line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Capture group of the 1-based column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// Capture group of the 1-based end line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    /// Capture group of the 1-based end column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    /// Capture group of the severity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<usize>,
+    /// Capture group of the problem code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<usize>,
+    /// Capture group of the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<usize>,
+    /// Whether the last pattern of a sequence can match multiple lines, each reporting a separate problem.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub r#loop: bool,
+}
+
+/// How to resolve the file paths reported by a problem matcher.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum FileLocation {
+    /// Paths are resolved relative to the task's working directory.
+    Kind(FileLocationKind),
+    /// Paths are resolved relative to the directory given.
+    WithBase(FileLocationKind, String),
+}
+
+impl Default for FileLocation {
+    fn default() -> Self {
+        Self::Kind(FileLocationKind::default())
+    }
+}
+
+/// Kind of the paths reported by a problem matcher.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FileLocationKind {
+    /// Paths are absolute.
+    Absolute,
+    /// Paths are relative to the base directory.
+    Relative,
+    /// Absolute paths are used as is, others are relative to the base directory.
+    #[default]
+    AutoDetect,
+}
+
+/// Severity of a problem found in the task output.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProblemSeverity {
+    /// An error.
+    #[default]
+    Error,
+    /// A warning.
+    Warning,
+    /// An informational message.
+    Info,
+    /// A hint.
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(severity: &str) -> Option<Self> {
+        match severity.trim().to_lowercase().as_str() {
+            "error" | "fatal" | "e" => Some(Self::Error),
+            "warning" | "warn" | "w" => Some(Self::Warning),
+            "info" | "information" | "note" | "i" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem, found in the task output by a [`ProblemMatcher`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskProblem {
+    /// Path to the file with the problem.
+    pub path: PathBuf,
+    /// 0-based line of the problem start.
+    pub row: u32,
+    /// 0-based column of the problem start, if reported.
+    pub column: Option<u32>,
+    /// 0-based line and column of the problem end, if reported.
+    pub end: Option<(u32, Option<u32>)>,
+    /// Severity of the problem.
+    pub severity: ProblemSeverity,
+    /// The problem description.
+    pub message: String,
+    /// The problem code, e.g. `E0308`.
+    pub code: Option<String>,
+}
+
+impl ProblemMatcher {
+    /// Looks up a built-in problem matcher by its name, e.g. `$rustc`.
+    pub fn built_in(name: &str) -> Option<Self> {
+        let matcher = match name {
+            "$rustc" | "$cargo" => Self {
+                base: None,
+                background: None,
+                owner: Some("rustc".to_string()),
+                source: Some("rustc".to_string()),
+                severity: None,
+                file_location: None,
+                pattern: Some(ProblemPatterns::Multiple(vec![
+                    ProblemPattern {
+                        regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                        severity: Some(1),
+                        code: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s*-->\s+(.+?):(\d+):(\d+)$".to_string(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                ])),
+            },
+            "$tsc-watch" => Self {
+                background: Some(BackgroundMatcher {
+                    begins_pattern:
+                        r"(Starting compilation in watch mode|File change detected\. Starting incremental compilation)\.\.\."
+                            .to_string(),
+                    ends_pattern: r"Found \d+ errors?\. Watching for file changes\.".to_string(),
+                }),
+                ..Self::built_in("$tsc")?
+            },
+            "$tsc" => Self {
+                base: None,
+                background: None,
+                owner: Some("typescript".to_string()),
+                source: Some("ts".to_string()),
+                severity: None,
+                file_location: None,
+                pattern: Some(ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    code: Some(5),
+                    message: Some(6),
+                    ..ProblemPattern::default()
+                })),
+            },
+            "$gcc" => Self {
+                base: None,
+                background: None,
+                owner: Some("cpp".to_string()),
+                source: Some("gcc".to_string()),
+                severity: None,
+                file_location: None,
+                pattern: Some(ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                })),
+            },
+            "$eslint-stylish" | "$eslint" => Self {
+                base: None,
+                background: None,
+                owner: Some("eslint".to_string()),
+                source: Some("eslint".to_string()),
+                severity: None,
+                file_location: Some(FileLocation::Kind(FileLocationKind::Absolute)),
+                pattern: Some(ProblemPatterns::Multiple(vec![
+                    ProblemPattern {
+                        regexp: r"^([^\s].*)$".to_string(),
+                        file: Some(1),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(\S*))?$"
+                            .to_string(),
+                        line: Some(1),
+                        column: Some(2),
+                        severity: Some(3),
+                        message: Some(4),
+                        code: Some(5),
+                        r#loop: true,
+                        ..ProblemPattern::default()
+                    },
+                ])),
+            },
+            "$eslint-compact" => Self {
+                base: None,
+                background: None,
+                owner: Some("eslint".to_string()),
+                source: Some("eslint".to_string()),
+                severity: None,
+                file_location: None,
+                pattern: Some(ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    code: Some(6),
+                    ..ProblemPattern::default()
+                })),
+            },
+            "$pytest" => Self {
+                base: None,
+                background: None,
+                owner: Some("python".to_string()),
+                source: Some("pytest".to_string()),
+                severity: Some(ProblemSeverity::Error),
+                file_location: None,
+                pattern: Some(ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^(.+\.py):(\d+): (.+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                })),
+            },
+            "$go" => Self {
+                base: None,
+                background: None,
+                owner: Some("go".to_string()),
+                source: Some("go".to_string()),
+                severity: Some(ProblemSeverity::Error),
+                file_location: None,
+                pattern: Some(ProblemPatterns::Single(ProblemPattern {
+                    regexp: r"^\s*([^\s:]+\.go):(\d+)(?::(\d+))?:\s+(.*)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    message: Some(4),
+                    ..ProblemPattern::default()
+                })),
+            },
+            _ => return None,
+        };
+        Some(matcher)
+    }
+
+    /// Finds all problems in the task output lines given.
+    /// Relative paths are resolved against the base directory of [`Self::file_location`], or `cwd` if there's none.
+    pub fn find_problems<'a>(
+        &self,
+        lines: impl IntoIterator<Item = &'a str>,
+        cwd: Option<&Path>,
+    ) -> Result<Vec<TaskProblem>> {
+        let Some(pattern) = &self.pattern else {
+            return Ok(Vec::new());
+        };
+        let patterns = pattern
+            .patterns()
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp)
+                    .with_context(|| format!("invalid problem pattern `{}`", pattern.regexp))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let Some(last_pattern_ix) = patterns.len().checked_sub(1) else {
+            return Ok(Vec::new());
+        };
+        let last_pattern_loops = pattern.patterns()[last_pattern_ix].r#loop;
+
+        let mut problems = Vec::new();
+        // Captures of the patterns matched so far in a multi-line sequence.
+        let mut matched = Vec::<PartialProblem>::new();
+        for line in lines {
+            let line = line.trim_end();
+            if matched.len() > last_pattern_ix {
+                // The sequence is complete, its last pattern may report more problems.
+                let mut partial = matched[..last_pattern_ix].iter().fold(
+                    PartialProblem::default(),
+                    |mut acc, partial| {
+                        acc.merge(partial);
+                        acc
+                    },
+                );
+                if let Some(captured) =
+                    self.capture(&patterns[last_pattern_ix], last_pattern_ix, line)
+                {
+                    partial.merge(&captured);
+                    problems.extend(self.to_problem(partial, cwd));
+                    continue;
+                }
+                matched.clear();
+            }
+
+            let next_ix = matched.len();
+            match self.capture(&patterns[next_ix], next_ix, line) {
+                Some(captured) => matched.push(captured),
+                None => {
+                    matched.clear();
+                    if next_ix > 0 {
+                        if let Some(captured) = self.capture(&patterns[0], 0, line) {
+                            matched.push(captured);
+                        }
+                    }
+                }
+            }
+
+            if matched.len() > last_pattern_ix {
+                let partial = matched
+                    .iter()
+                    .fold(PartialProblem::default(), |mut acc, partial| {
+                        acc.merge(partial);
+                        acc
+                    });
+                problems.extend(self.to_problem(partial, cwd));
+                if !last_pattern_loops {
+                    matched.clear();
+                }
+            }
+        }
+        Ok(problems)
+    }
+
+    fn capture(&self, regex: &Regex, pattern_ix: usize, line: &str) -> Option<PartialProblem> {
+        let pattern = &self.pattern.as_ref()?.patterns()[pattern_ix];
+        let captures = regex.captures(line)?;
+        let group = |ix: Option<usize>| {
+            ix.and_then(|ix| captures.get(ix))
+                .map(|m| m.as_str().trim())
+                .filter(|s| !s.is_empty())
+                .map(ToString::to_string)
+        };
+        let number = |ix: Option<usize>| group(ix).and_then(|s| s.parse::<u32>().ok());
+
+        let mut partial = PartialProblem {
+            file: group(pattern.file),
+            line: number(pattern.line),
+            column: number(pattern.column),
+            end_line: number(pattern.end_line),
+            end_column: number(pattern.end_column),
+            severity: group(pattern.severity).and_then(|s| ProblemSeverity::parse(&s)),
+            code: group(pattern.code),
+            message: group(pattern.message),
+        };
+        if let Some(location) = group(pattern.location) {
+            let mut numbers = location
+                .split(',')
+                .map(|part| part.trim().parse::<u32>().ok());
+            partial.line = numbers.next().flatten().or(partial.line);
+            partial.column = numbers.next().flatten().or(partial.column);
+            partial.end_line = numbers.next().flatten().or(partial.end_line);
+            partial.end_column = numbers.next().flatten().or(partial.end_column);
+        }
+        Some(partial)
+    }
+
+    fn to_problem(&self, partial: PartialProblem, cwd: Option<&Path>) -> Option<TaskProblem> {
+        let file = partial.file?;
+        let line = partial.line?;
+        let message = partial.message?;
+
+        let (kind, base) = match self.file_location.clone().unwrap_or_default() {
+            FileLocation::Kind(kind) => (kind, cwd.map(Path::to_path_buf)),
+            FileLocation::WithBase(kind, base) => (kind, Some(PathBuf::from(base))),
+        };
+        let path = PathBuf::from(file);
+        let path = match (kind, base) {
+            (FileLocationKind::Absolute, _) => path,
+            (_, _) if path.is_absolute() => path,
+            (_, Some(base)) => base.join(path),
+            (_, None) => path,
+        };
+
+        Some(TaskProblem {
+            path,
+            row: line.saturating_sub(1),
+            column: partial.column.map(|column| column.saturating_sub(1)),
+            end: partial.end_line.map(|end_line| {
+                (
+                    end_line.saturating_sub(1),
+                    partial.end_column.map(|column| column.saturating_sub(1)),
+                )
+            }),
+            severity: partial
+                .severity
+                .or(self.severity)
+                .unwrap_or(ProblemSeverity::Error),
+            message,
+            code: partial.code,
+        })
+    }
+}
+
+#[derive(Default)]
+struct PartialProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl PartialProblem {
+    fn merge(&mut self, other: &Self) {
+        fn merge_field<T: Clone>(this: &mut Option<T>, other: &Option<T>) {
+            if other.is_some() {
+                this.clone_from(other);
+            }
+        }
+        merge_field(&mut self.file, &other.file);
+        merge_field(&mut self.line, &other.line);
+        merge_field(&mut self.column, &other.column);
+        merge_field(&mut self.end_line, &other.end_line);
+        merge_field(&mut self.end_column, &other.end_column);
+        merge_field(&mut self.severity, &other.severity);
+        merge_field(&mut self.code, &other.code);
+        merge_field(&mut self.message, &other.message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(matcher: &str, output: &str) -> Vec<TaskProblem> {
+        ProblemMatcher::built_in(matcher)
+            .unwrap()
+            .find_problems(output.lines(), Some(Path::new("/project")))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rustc_matcher() {
+        let output = r#"   Compiling zed v0.1.0 (/project)
+error[E0308]: mismatched types
+ --> src/main.rs:4:18
+  |
+4 |     let x: u32 = "a";
+  |            ---   ^^^ expected `u32`, found `&str`
+
+warning: unused variable: `y`
+ --> /abs/src/lib.rs:10:9
+
+error: aborting due to 1 previous error
+"#;
+        assert_eq!(
+            find("$rustc", output),
+            vec![
+                TaskProblem {
+                    path: PathBuf::from("/project/src/main.rs"),
+                    row: 3,
+                    column: Some(17),
+                    end: None,
+                    severity: ProblemSeverity::Error,
+                    message: "mismatched types".to_string(),
+                    code: Some("E0308".to_string()),
+                },
+                TaskProblem {
+                    path: PathBuf::from("/abs/src/lib.rs"),
+                    row: 9,
+                    column: Some(8),
+                    end: None,
+                    severity: ProblemSeverity::Warning,
+                    message: "unused variable: `y`".to_string(),
+                    code: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_matchers() {
+        let problems = find(
+            "$tsc",
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("/project/src/index.ts"));
+        assert_eq!((problems[0].row, problems[0].column), (2, Some(6)));
+        assert_eq!(problems[0].code.as_deref(), Some("2322"));
+
+        let problems = find(
+            "$gcc",
+            "main.c:5:3: warning: implicit declaration of function 'foo'",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].severity, ProblemSeverity::Warning);
+        assert_eq!(
+            problems[0].message,
+            "implicit declaration of function 'foo'"
+        );
+
+        let problems = find(
+            "$go",
+            "# example\n./main.go:10:2: undefined: foo\n    main_test.go:12: want 1, got 2",
+        );
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].path, PathBuf::from("/project/./main.go"));
+        assert_eq!(problems[1].column, None);
+
+        let problems = find(
+            "$pytest",
+            "tests/test_math.py:12: AssertionError\nFAILED tests/test_math.py::test_add",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].row, 11);
+        assert_eq!(problems[0].message, "AssertionError");
+    }
+
+    #[test]
+    fn test_looping_matcher() {
+        let output = "/project/src/a.js\n  1:10  error    'x' is defined but never used  no-unused-vars\n  2:1   warning  Unexpected console statement   no-console\n\n/project/src/b.js\n  5:3  error  Missing semicolon  semi\n";
+        let problems = find("$eslint-stylish", output);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.row,
+                    problem.severity,
+                    problem.code.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/project/src/a.js".to_string(),
+                    0,
+                    ProblemSeverity::Error,
+                    Some("no-unused-vars".to_string())
+                ),
+                (
+                    "/project/src/a.js".to_string(),
+                    1,
+                    ProblemSeverity::Warning,
+                    Some("no-console".to_string())
+                ),
+                (
+                    "/project/src/b.js".to_string(),
+                    4,
+                    ProblemSeverity::Error,
+                    Some("semi".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_background_matcher() {
+        let background = ProblemMatcher::built_in("$tsc-watch")
+            .unwrap()
+            .background
+            .unwrap()
+            .compile()
+            .unwrap();
+        assert_eq!(
+            background.is_building_after(
+                "[12:00:00 PM] File change detected. Starting incremental compilation..."
+            ),
+            Some(true)
+        );
+        assert_eq!(
+            background
+                .is_building_after("[12:00:01 PM] Found 0 errors. Watching for file changes."),
+            Some(false)
+        );
+        assert_eq!(
+            background.is_building_after("src/a.ts(1,2): error TS1005: ';' expected."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matcher: TaskProblemMatcher = serde_json::from_str(
+            r#"{
+                "owner": "custom",
+                "fileLocation": ["relative", "/base"],
+                "severity": "warning",
+                "pattern": {
+                    "regexp": "^(.*)@(\\d+,\\d+,\\d+,\\d+): (.*)$",
+                    "file": 1,
+                    "location": 2,
+                    "message": 3
+                }
+            }"#,
+        )
+        .unwrap();
+        let problems = matcher
+            .resolve()
+            .unwrap()
+            .find_problems(["lib/x.txt@2,3,4,5: bad"], Some(Path::new("/project")))
+            .unwrap();
+        assert_eq!(
+            problems,
+            vec![TaskProblem {
+                path: PathBuf::from("/base/lib/x.txt"),
+                row: 1,
+                column: Some(2),
+                end: Some((3, Some(4))),
+                severity: ProblemSeverity::Warning,
+                message: "bad".to_string(),
+                code: None,
+            }]
+        );
+
+        let extended: TaskProblemMatcher = serde_json::from_str(
+            r#"{ "base": "$tsc", "fileLocation": ["relative", "/project/web"] }"#,
+        )
+        .unwrap();
+        let problems = extended
+            .resolve()
+            .unwrap()
+            .find_problems(
+                ["src/a.ts(1,2): error TS1005: ';' expected."],
+                Some(Path::new("/project")),
+            )
+            .unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, PathBuf::from("/project/web/src/a.ts"));
+
+        let built_in: TaskProblemMatcher = serde_json::from_str(r#""$rustc""#).unwrap();
+        assert_eq!(built_in, TaskProblemMatcher::BuiltIn("$rustc".to_string()));
+        assert!(
+            TaskProblemMatcher::BuiltIn("$unknown".to_string())
+                .resolve()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Inputs are values that the user is asked for right before a task is spawned.
+//! Task templates reference them as `${input:id}`, similar to VS Code's `inputs`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const INPUT_REFERENCE_PREFIX: &str = "${input:";
+
+/// A value to ask the user for, before the task that references it is spawned.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+pub struct TaskInput {
+    /// Name of the input, referenced in the task as `${input:id}`.
+    pub id: String,
+    /// How to ask the user for the value.
+    #[serde(flatten)]
+    pub kind: TaskInputKind,
+}
+
+/// How the value of a [`TaskInput`] is asked for.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInputKind {
+    /// Ask the user to type in a value.
+    #[serde(alias = "promptString")]
+    PromptString {
+        /// Text shown to the user when asking for the value.
+        #[serde(default)]
+        description: Option<String>,
+        /// Value to use when the user has not answered before.
+        #[serde(default)]
+        default: Option<String>,
+        /// Whether the value is a secret: it is masked when typed in, hidden from the task's labels and never remembered.
+        #[serde(default)]
+        password: bool,
+    },
+    /// Ask the user to pick a value from the list.
+    #[serde(alias = "pickString")]
+    PickString {
+        /// Text shown to the user when asking for the value.
+        #[serde(default)]
+        description: Option<String>,
+        /// Values to pick from.
+        #[serde(default)]
+        options: Vec<PickStringOption>,
+        /// A shell command, whose output lines are offered as more values to pick from.
+        #[serde(default)]
+        command: Option<String>,
+        /// Value to select when the user has not answered before.
+        #[serde(default)]
+        default: Option<String>,
+    },
+}
+
+/// A value to pick for a [`TaskInputKind::PickString`] input.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum PickStringOption {
+    /// A value, shown to the user as is.
+    Value(String),
+    /// A value with a human-readable label to show instead.
+    Labeled {
+        /// Text to show to the user.
+        label: String,
+        /// Value to substitute.
+        value: String,
+    },
+}
+
+impl PickStringOption {
+    /// Text to show to the user.
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Value(value) => value,
+            Self::Labeled { label, .. } => label,
+        }
+    }
+
+    /// Value to substitute.
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Value(value) | Self::Labeled { value, .. } => value,
+        }
+    }
+}
+
+impl TaskInput {
+    /// Text shown to the user when asking for the value, the input's id if none was set.
+    pub fn description(&self) -> &str {
+        let description = match &self.kind {
+            TaskInputKind::PromptString { description, .. }
+            | TaskInputKind::PickString { description, .. } => description.as_deref(),
+        };
+        description.unwrap_or(&self.id)
+    }
+
+    /// Value to use when the user has not answered before.
+    pub fn default_value(&self) -> Option<&str> {
+        match &self.kind {
+            TaskInputKind::PromptString { default, .. }
+            | TaskInputKind::PickString { default, .. } => default.as_deref(),
+        }
+    }
+
+    /// Whether the value is a secret, that should not be shown or remembered.
+    pub fn is_password(&self) -> bool {
+        matches!(
+            self.kind,
+            TaskInputKind::PromptString { password: true, .. }
+        )
+    }
+}
+
+/// Ids of all inputs referenced in the text as `${input:id}`.
+pub(crate) fn input_references(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices(INPUT_REFERENCE_PREFIX)
+        .filter_map(|(start, _)| {
+            let id_start = start + INPUT_REFERENCE_PREFIX.len();
+            let id_len = text[id_start..].find('}')?;
+            Some(&text[id_start..id_start + id_len])
+        })
+        .filter(|id| !id.is_empty())
+}
+
+/// Replaces every `${input:id}` reference in the text with the value for that id, if there is one.
+pub(crate) fn substitute_inputs(text: &str, values: impl Fn(&str) -> Option<String>) -> String {
+    let mut substituted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(INPUT_REFERENCE_PREFIX) {
+        let id_start = start + INPUT_REFERENCE_PREFIX.len();
+        let Some(id_len) = rest[id_start..].find('}') else {
+            break;
+        };
+        let reference_end = id_start + id_len + 1;
+        substituted.push_str(&rest[..start]);
+        match values(&rest[id_start..id_start + id_len]) {
+            Some(value) => substituted.push_str(&value),
+            None => substituted.push_str(&rest[start..reference_end]),
+        }
+        rest = &rest[reference_end..];
+    }
+    substituted.push_str(rest);
+    substituted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_references() {
+        assert_eq!(
+            input_references("deploy ${input:env} --tag ${input:tag}${input:} ${input:broken")
+                .collect::<Vec<_>>(),
+            vec!["env", "tag"]
+        );
+        assert_eq!(input_references("$ZED_FILE ${PATH}").count(), 0);
+    }
+
+    #[test]
+    fn test_substitute_inputs() {
+        let values = |id: &str| (id == "env").then(|| "staging".to_string());
+        assert_eq!(
+            substitute_inputs("deploy ${input:env} ${input:other} $HOME", values),
+            "deploy staging ${input:other} $HOME"
+        );
+        assert_eq!(
+            substitute_inputs("${input:env}${input:env", values),
+            "staging${input:env"
+        );
+    }
+
+    #[test]
+    fn test_deserializing_inputs() {
+        let inputs = serde_json_lenient::from_str::<Vec<TaskInput>>(
+            r#"[
+                { "id": "name", "type": "prompt_string", "description": "Name", "default": "zed" },
+                { "id": "token", "type": "promptString", "password": true },
+                {
+                    "id": "target",
+                    "type": "pick_string",
+                    "options": ["debug", { "label": "Release build", "value": "release" }],
+                    "command": "ls targets"
+                }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            inputs,
+            vec![
+                TaskInput {
+                    id: "name".to_string(),
+                    kind: TaskInputKind::PromptString {
+                        description: Some("Name".to_string()),
+                        default: Some("zed".to_string()),
+                        password: false,
+                    },
+                },
+                TaskInput {
+                    id: "token".to_string(),
+                    kind: TaskInputKind::PromptString {
+                        description: None,
+                        default: None,
+                        password: true,
+                    },
+                },
+                TaskInput {
+                    id: "target".to_string(),
+                    kind: TaskInputKind::PickString {
+                        description: None,
+                        options: vec![
+                            PickStringOption::Value("debug".to_string()),
+                            PickStringOption::Labeled {
+                                label: "Release build".to_string(),
+                                value: "release".to_string(),
+                            },
+                        ],
+                        command: Some("ls targets".to_string()),
+                        default: None,
+                    },
+                },
+            ]
+        );
+        assert_eq!(inputs[1].description(), "token");
+        assert!(inputs[1].is_password());
+    }
+}
@@ -9,8 +9,9 @@ use sha2::{Digest, Sha256};
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachConfig, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TCPHost, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX,
+    AttachConfig, BackgroundMatcher, FileLocation, ResolvedTask, RevealTarget, Shell,
+    SpawnInTerminal, TCPHost, TaskContext, TaskId, TaskInput, TaskProblemMatcher, VariableName,
+    ZED_VARIABLE_NAME_PREFIX, task_input::input_references,
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +26,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be omitted for compound tasks, that only run the tasks they depend on.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -73,6 +76,30 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to run before this one.
+    /// A task with dependencies may omit its `command`, to only run the tasks it depends on.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks from `depends_on`:
+    /// * `parallel` — spawn all of them at once (default)
+    /// * `sequence` — spawn them one after another, in the order they are listed
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Whether to keep running the remaining tasks when one of the dependencies fails.
+    #[serde(default)]
+    pub continue_on_dependency_failure: bool,
+    /// Problem matchers to turn the task output into diagnostics:
+    /// names of the built-in ones (e.g. `$rustc`) or custom definitions, following VS Code's `problemMatcher` schema.
+    #[serde(default)]
+    pub problem_matchers: Vec<TaskProblemMatcher>,
+    /// Makes the task a long-running one (e.g. a watcher), with the patterns that tell when it builds and when it is ready.
+    /// Tasks that depend on a background task wait for it to become ready instead of waiting for it to finish.
+    /// Defaults to the `background` patterns of the task's problem matchers, if there are any.
+    #[serde(default)]
+    pub background: Option<BackgroundMatcher>,
+    /// Values to ask the user for before the task is spawned, referenced in the task as `${input:id}`.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -145,6 +172,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How the dependencies of a task are spawned.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Spawn all dependencies at once.
+    #[default]
+    Parallel,
+    /// Spawn each dependency after the previous one has finished.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -162,6 +200,27 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether this task only runs the tasks it depends on, without a command of its own.
+    pub fn is_compound(&self) -> bool {
+        matches!(self.task_type, TaskType::Script)
+            && self.command.trim().is_empty()
+            && !self.depends_on.is_empty()
+    }
+
+    /// Inputs of the task that are referenced in its label, command, arguments, environment or working directory.
+    pub fn referenced_inputs(&self) -> impl Iterator<Item = &TaskInput> {
+        let referenced_ids = [&self.label, &self.command]
+            .into_iter()
+            .chain(&self.args)
+            .chain(self.env.values())
+            .chain(&self.cwd)
+            .flat_map(|text| input_references(text))
+            .collect::<HashSet<_>>();
+        self.inputs
+            .iter()
+            .filter(move |input| referenced_ids.contains(input.id.as_str()))
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -170,7 +229,9 @@ impl TaskTemplate {
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
         if self.label.trim().is_empty()
-            || (self.command.trim().is_empty() && matches!(self.task_type, TaskType::Script))
+            || (self.command.trim().is_empty()
+                && self.depends_on.is_empty()
+                && matches!(self.task_type, TaskType::Script))
         {
             return None;
         }
@@ -247,6 +308,32 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let mut problem_matchers = Vec::with_capacity(self.problem_matchers.len());
+        for problem_matcher in &self.problem_matchers {
+            let Some(mut problem_matcher) = problem_matcher
+                .resolve()
+                .with_context(|| format!("unknown problem matcher {problem_matcher:?}"))
+                .log_err()
+            else {
+                continue;
+            };
+            if let Some(FileLocation::WithBase(_, base)) = &mut problem_matcher.file_location {
+                *base = substitute_all_template_variables_in_str(
+                    base,
+                    &task_variables,
+                    &variable_names,
+                    &mut substituted_variables,
+                )?;
+            }
+            problem_matchers.push(problem_matcher);
+        }
+
+        let background = self.background.clone().or_else(|| {
+            problem_matchers
+                .iter()
+                .find_map(|problem_matcher| problem_matcher.background.clone())
+        });
+
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
             .log_err()?;
@@ -305,6 +392,8 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
+                background,
             }),
         })
     }
@@ -416,7 +505,7 @@ fn substitute_all_template_variables_in_map(
 mod tests {
     use std::{borrow::Cow, path::Path};
 
-    use crate::{TaskVariables, VariableName};
+    use crate::{PickStringOption, TaskInputKind, TaskVariables, VariableName};
 
     use super::*;
 
@@ -455,6 +544,157 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_compound_templates() {
+        let compound_task = TaskTemplate {
+            label: "build all".to_string(),
+            depends_on: vec!["build server".to_string(), "build client".to_string()],
+            ..TaskTemplate::default()
+        };
+        assert!(compound_task.is_compound());
+        let resolved_task = compound_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("should resolve a task without a command, but with dependencies");
+        assert_eq!(resolved_task.resolved_label, "build all");
+
+        let task_with_command = TaskTemplate {
+            command: "cargo test".to_string(),
+            ..compound_task
+        };
+        assert!(!task_with_command.is_compound());
+    }
+
+    #[test]
+    fn test_deserializing_compound_task_without_command() {
+        let templates = serde_json_lenient::from_str::<TaskTemplates>(
+            r#"[
+                { "label": "build server", "command": "cargo build" },
+                {
+                    "label": "build all",
+                    "depends_on": ["build server", "build client"],
+                    "depends_order": "sequence"
+                }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(templates.0.len(), 2);
+
+        let compound_task = &templates.0[1];
+        assert_eq!(compound_task.command, "");
+        assert!(compound_task.is_compound());
+        assert_eq!(compound_task.depends_order, DependsOrder::Sequence);
+        assert!(
+            compound_task
+                .resolve_task(TEST_ID_BASE, &TaskContext::default())
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_resolving_background_templates() {
+        let watch_task = TaskTemplate {
+            label: "watch".to_string(),
+            command: "tsc --watch".to_string(),
+            problem_matchers: vec![TaskProblemMatcher::BuiltIn("$tsc-watch".to_string())],
+            ..TaskTemplate::default()
+        };
+        let spawn_in_terminal = watch_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap()
+            .resolved
+            .unwrap();
+        assert_eq!(spawn_in_terminal.problem_matchers.len(), 1);
+        assert!(
+            spawn_in_terminal.background.is_some(),
+            "background patterns should come from the problem matcher"
+        );
+
+        let regular_task = TaskTemplate {
+            problem_matchers: vec![TaskProblemMatcher::BuiltIn("$tsc".to_string())],
+            ..watch_task
+        };
+        let spawn_in_terminal = regular_task
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap()
+            .resolved
+            .unwrap();
+        assert_eq!(spawn_in_terminal.background, None);
+    }
+
+    #[test]
+    fn test_resolving_inputs() {
+        let task = TaskTemplate {
+            label: "deploy to ${input:env}".to_string(),
+            command: "deploy".to_string(),
+            args: vec![
+                "--env=${input:env}".to_string(),
+                "--token".to_string(),
+                "${input:token}".to_string(),
+            ],
+            env: HashMap::from_iter([("DEPLOY_FILE".to_string(), "$ZED_FILE".to_string())]),
+            inputs: vec![
+                TaskInput {
+                    id: "env".to_string(),
+                    kind: TaskInputKind::PickString {
+                        description: None,
+                        options: vec![PickStringOption::Value("staging".to_string())],
+                        command: None,
+                        default: None,
+                    },
+                },
+                TaskInput {
+                    id: "token".to_string(),
+                    kind: TaskInputKind::PromptString {
+                        description: None,
+                        default: None,
+                        password: true,
+                    },
+                },
+                TaskInput {
+                    id: "unused".to_string(),
+                    kind: TaskInputKind::PromptString {
+                        description: None,
+                        default: None,
+                        password: false,
+                    },
+                },
+            ],
+            ..TaskTemplate::default()
+        };
+        assert_eq!(
+            task.referenced_inputs()
+                .map(|input| input.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["env", "token"]
+        );
+
+        let context = TaskContext {
+            task_variables: TaskVariables::from_iter([(VariableName::File, "main.rs".to_string())]),
+            ..TaskContext::default()
+        };
+        let mut resolved_task = task.resolve_task(TEST_ID_BASE, &context).unwrap();
+        assert_eq!(resolved_task.resolved_label, "deploy to ${input:env}");
+        assert_eq!(resolved_task.unresolved_inputs().len(), 2);
+
+        resolved_task.resolve_inputs(&HashMap::from_iter([
+            ("env".to_string(), "staging".to_string()),
+            ("token".to_string(), "secret".to_string()),
+        ]));
+        assert_eq!(resolved_task.resolved_label, "deploy to staging");
+        assert!(resolved_task.unresolved_inputs().is_empty());
+        let spawn_in_terminal = resolved_task.resolved.unwrap();
+        assert_eq!(spawn_in_terminal.label, "deploy to staging");
+        assert_eq!(
+            spawn_in_terminal.args,
+            vec!["--env=staging", "--token", "secret"]
+        );
+        assert_eq!(
+            spawn_in_terminal.command_label,
+            "deploy --env=staging --token ******"
+        );
+        assert_eq!(spawn_in_terminal.env["DEPLOY_FILE"], "main.rs");
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -1,9 +1,12 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    DependsOrder, FileLocation, TaskInput, TaskProblemMatcher, TaskTemplate, TaskTemplates,
+    VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +25,47 @@ struct VsCodeTaskDefinition {
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
+    depends_on: Option<DependsOn>,
+    #[serde(default)]
+    depends_order: DependsOrder,
+    problem_matcher: Option<ProblemMatchers>,
+}
+
+/// Code allows to list either a single task label or an array of them.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl DependsOn {
+    fn into_labels(self) -> Vec<String> {
+        match self {
+            DependsOn::Single(label) => vec![label],
+            DependsOn::Multiple(labels) => labels,
+        }
+    }
+}
+
+/// Code allows to reference either a single problem matcher or an array of them.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum ProblemMatchers {
+    Multiple(Vec<TaskProblemMatcher>),
+    Single(TaskProblemMatcher),
+    /// Definitions that Zed cannot use (e.g. named patterns), these are ignored instead of failing the whole task.
+    Unsupported(serde_json_lenient::Value),
+}
+
+impl ProblemMatchers {
+    fn into_matchers(self) -> Vec<TaskProblemMatcher> {
+        match self {
+            ProblemMatchers::Multiple(matchers) => matchers,
+            ProblemMatchers::Single(matcher) => vec![matcher],
+            ProblemMatchers::Unsupported(_) => Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -85,29 +129,43 @@ impl EnvVariableReplacer {
 
 impl VsCodeTaskDefinition {
     fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
-        };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        let depends_on = self
+            .depends_on
+            .map(DependsOn::into_labels)
+            .unwrap_or_default();
+        // `type` might not be set in tasks that use `dependsOn`: those only run the tasks they depend on.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let problem_matchers = self
+            .problem_matcher
+            .map(ProblemMatchers::into_matchers)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|mut matcher| {
+                // Custom matchers may resolve paths against a directory with Code variables in it.
+                if let TaskProblemMatcher::Custom(matcher) = &mut matcher {
+                    if let Some(FileLocation::WithBase(_, base)) = &mut matcher.file_location {
+                        *base = replacer.replace(base);
+                    }
+                }
+                matcher
+            })
+            .collect();
         let mut ret = TaskTemplate {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order: self.depends_order,
+            problem_matchers,
             ..Default::default()
         };
         if let Some(options) = self.options {
@@ -122,6 +180,9 @@ impl VsCodeTaskDefinition {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    /// Code's `command` inputs run editor commands, those are ignored instead of failing the whole file.
+    #[serde(default)]
+    inputs: Vec<serde_json_lenient::Value>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -140,10 +201,25 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(|input| {
+                serde_json_lenient::from_value::<TaskInput>(input.clone())
+                    .with_context(|| format!("unsupported task input {input}"))
+                    .log_err()
+            })
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
             .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
+            .map(|mut template| {
+                // Code declares inputs for the whole file, each task gets the ones it references.
+                template.inputs = inputs.clone();
+                template.inputs = template.referenced_inputs().cloned().collect();
+                template
+            })
             .collect();
         Ok(Self(templates))
     }
@@ -154,12 +230,32 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
-        vscode_format::{Command, VsCodeTaskDefinition},
+        DependsOrder, FileLocation, FileLocationKind, PickStringOption, ProblemMatcher, TaskInput,
+        TaskInputKind, TaskProblemMatcher, TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        vscode_format::{Command, DependsOn, ProblemMatchers, VsCodeTaskDefinition},
     };
 
     use super::EnvVariableReplacer;
 
+    fn built_in(name: &str) -> TaskProblemMatcher {
+        TaskProblemMatcher::BuiltIn(name.to_string())
+    }
+
+    fn extended(base: &str, file_location_base: &str) -> TaskProblemMatcher {
+        TaskProblemMatcher::Custom(ProblemMatcher {
+            base: Some(base.to_string()),
+            owner: None,
+            source: None,
+            severity: None,
+            file_location: Some(FileLocation::WithBase(
+                FileLocationKind::Relative,
+                file_location_base.to_string(),
+            )),
+            pattern: None,
+            background: None,
+        })
+    }
+
     fn compare_without_other_attributes(lhs: VsCodeTaskDefinition, rhs: VsCodeTaskDefinition) {
         assert_eq!(
             VsCodeTaskDefinition {
@@ -212,6 +308,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Multiple(vec![built_in("$tsc")])),
             },
             VsCodeTaskDefinition {
                 label: "tsc: watch ./src".to_string(),
@@ -226,6 +325,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Multiple(vec![built_in("$tsc-watch")])),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:compiler".to_string(),
@@ -234,6 +336,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Multiple(vec![built_in("$tsc")])),
             },
             VsCodeTaskDefinition {
                 label: "npm: build:tests".to_string(),
@@ -242,6 +347,9 @@ mod tests {
                 }),
                 other_attributes: Default::default(),
                 options: None,
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Multiple(vec![built_in("$tsc")])),
             },
         ];
 
@@ -257,6 +365,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![built_in("$tsc")],
                 ..Default::default()
             },
             TaskTemplate {
@@ -268,18 +377,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![built_in("$tsc-watch")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![built_in("$tsc")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![built_in("$tsc")],
                 ..Default::default()
             },
         ];
@@ -288,6 +400,111 @@ mod tests {
         assert_eq!(tasks.0, expected);
     }
 
+    #[test]
+    fn can_deserialize_depends_on() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "make",
+                        "dependsOn": "configure"
+                    },
+                    {
+                        "label": "ci",
+                        "dependsOn": ["build", "test"],
+                        "dependsOrder": "sequence"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "build".to_string(),
+                    command: "make".to_string(),
+                    depends_on: vec!["configure".to_string()],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "ci".to_string(),
+                    depends_on: vec!["build".to_string(), "test".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "deploy",
+                        "args": ["--env", "${input:environment}"]
+                    },
+                    {
+                        "label": "clean",
+                        "type": "shell",
+                        "command": "make clean"
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "environment",
+                        "type": "pickString",
+                        "description": "Where to deploy",
+                        "options": ["staging", "production"],
+                        "default": "staging"
+                    },
+                    {
+                        "id": "pickFile",
+                        "type": "command",
+                        "command": "extension.pickFile"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "deploy".to_string(),
+                    command: "deploy".to_string(),
+                    args: vec!["--env".to_string(), "${input:environment}".to_string()],
+                    inputs: vec![TaskInput {
+                        id: "environment".to_string(),
+                        kind: TaskInputKind::PickString {
+                            description: Some("Where to deploy".to_string()),
+                            options: vec![
+                                PickStringOption::Value("staging".to_string()),
+                                PickStringOption::Value("production".to_string()),
+                            ],
+                            command: None,
+                            default: Some("staging".to_string()),
+                        },
+                    }],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "clean".to_string(),
+                    command: "make clean".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
     #[test]
     fn can_deserialize_rust_analyzer_tasks() {
         const RUST_ANALYZER_TASKS: &str = include_str!("../test_data/rust-analyzer.json");
@@ -301,6 +518,12 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Single(extended(
+                    "$tsc-watch",
+                    "${workspaceFolder}/editors/code/",
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Extension".to_string(),
@@ -309,6 +532,12 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Single(extended(
+                    "$tsc",
+                    "${workspaceFolder}/editors/code/",
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server".to_string(),
@@ -318,6 +547,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Single(built_in("$rustc"))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release)".to_string(),
@@ -327,6 +559,9 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Single(built_in("$rustc"))),
             },
             VsCodeTaskDefinition {
                 label: "Pretest".to_string(),
@@ -335,18 +570,36 @@ mod tests {
                 }),
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: None,
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Single(extended(
+                    "$tsc",
+                    "${workspaceFolder}/editors/code/",
+                ))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Single(built_in("$rustc"))),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
                 other_attributes: Default::default(),
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: DependsOrder::Parallel,
+                problem_matcher: Some(ProblemMatchers::Single(built_in("$rustc"))),
             },
         ];
         assert_eq!(vscode_definitions.tasks.len(), expected.len());
@@ -360,28 +613,51 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![extended(
+                    "$tsc-watch",
+                    "${ZED_WORKTREE_ROOT}/editors/code/",
+                )],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![extended("$tsc", "${ZED_WORKTREE_ROOT}/editors/code/")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![built_in("$rustc")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![built_in("$rustc")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![extended("$tsc", "${ZED_WORKTREE_ROOT}/editors/code/")],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matchers: vec![built_in("$rustc")],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matchers: vec![built_in("$rustc")],
                 ..Default::default()
             },
         ];
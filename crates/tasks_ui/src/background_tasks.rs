@@ -0,0 +1,79 @@
+use gpui::{Context, IntoElement, Render, Subscription, Window};
+use ui::{ButtonLike, Icon, IconName, Label, Tooltip, h_flex, prelude::*};
+use workspace::{StatusItemView, Workspace, item::ItemHandle, tasks::BackgroundTask};
+
+/// A status bar item, showing the background tasks that are running and whether they are building or ready.
+pub struct BackgroundTasksIndicator {
+    tasks: Vec<BackgroundTask>,
+    _workspace_subscription: Option<Subscription>,
+}
+
+impl BackgroundTasksIndicator {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let workspace_subscription = workspace.weak_handle().upgrade().map(|workspace| {
+            cx.subscribe(&workspace, |this, workspace, event, cx| {
+                if let workspace::Event::BackgroundTasksChanged = event {
+                    this.tasks = Self::running_tasks(workspace.read(cx));
+                    cx.notify();
+                }
+            })
+        });
+        Self {
+            tasks: Self::running_tasks(workspace),
+            _workspace_subscription: workspace_subscription,
+        }
+    }
+
+    fn running_tasks(workspace: &Workspace) -> Vec<BackgroundTask> {
+        let mut tasks = workspace.background_tasks().cloned().collect::<Vec<_>>();
+        tasks.sort_by(|a, b| a.label.cmp(&b.label));
+        tasks
+    }
+}
+
+impl Render for BackgroundTasksIndicator {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        if self.tasks.is_empty() {
+            return div().into_any_element();
+        }
+
+        let (icon, color) = if self.tasks.iter().all(|task| task.ready) {
+            (IconName::Eye, Color::Success)
+        } else {
+            (IconName::ArrowCircle, Color::Accent)
+        };
+        let label = match self.tasks.as_slice() {
+            [task] => task.label.clone(),
+            tasks => format!("{} background tasks", tasks.len()),
+        };
+        let tooltip = self
+            .tasks
+            .iter()
+            .map(|task| {
+                let status = if task.ready { "ready" } else { "building" };
+                format!("{}: {status}", task.label)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        ButtonLike::new("background-tasks")
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Icon::new(icon).size(IconSize::Small).color(color))
+                    .child(Label::new(label).size(LabelSize::Small)),
+            )
+            .tooltip(Tooltip::text(tooltip))
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for BackgroundTasksIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
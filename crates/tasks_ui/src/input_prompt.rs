@@ -0,0 +1,403 @@
+use std::{cmp, path::Path, sync::Arc};
+
+use anyhow::{Context as _, bail};
+use collections::{HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, AsyncWindowContext, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use task::{PickStringOption, TaskInput, TaskInputKind};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+const LAST_INPUT_VALUES_KEY_PREFIX: &str = "task_input_values-";
+
+/// Asks the user for the values of the inputs given, one after another.
+/// The values entered for the same task in the same worktrees the last time are offered as defaults,
+/// except the password ones.
+/// Returns `None` if the user dismissed any of the prompts.
+pub(crate) async fn ask_for_inputs(
+    workspace: WeakEntity<Workspace>,
+    task_label: &str,
+    inputs: Vec<TaskInput>,
+    cwd: Option<&Path>,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<Option<HashMap<String, String>>> {
+    let worktree_roots = workspace.read_with(cx, |workspace, cx| {
+        workspace
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(",")
+    })?;
+    let key = format!("{LAST_INPUT_VALUES_KEY_PREFIX}{worktree_roots}-{task_label}");
+    let last_values = KEY_VALUE_STORE
+        .read_kvp(&key)
+        .log_err()
+        .flatten()
+        .and_then(|values| serde_json::from_str::<HashMap<String, String>>(&values).log_err())
+        .unwrap_or_default();
+
+    let mut values = HashMap::default();
+    let mut passwords = HashSet::default();
+    for input in inputs {
+        let default_value = last_values
+            .get(&input.id)
+            .map(String::as_str)
+            .or(input.default_value())
+            .map(ToOwned::to_owned);
+        let description = SharedString::from(input.description().to_owned());
+        let (tx, rx) = oneshot::channel();
+        match input.kind {
+            TaskInputKind::PromptString { password, .. } => {
+                if password {
+                    passwords.insert(input.id.clone());
+                }
+                workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        PromptStringModal::new(
+                            description,
+                            default_value.filter(|_| !password),
+                            password,
+                            tx,
+                            window,
+                            cx,
+                        )
+                    })
+                })?;
+            }
+            TaskInputKind::PickString {
+                mut options,
+                command,
+                ..
+            } => {
+                if let Some(command) = command {
+                    let is_local = workspace
+                        .read_with(cx, |workspace, cx| workspace.project().read(cx).is_local())?;
+                    if !is_local {
+                        bail!(
+                            "Input `{}` runs a command, which is only supported in local projects",
+                            input.id
+                        );
+                    }
+                    options.extend(command_output_options(&command, cwd).await?);
+                }
+                if options.is_empty() {
+                    bail!("Input `{}` has no options to pick from", input.id);
+                }
+                let delegate = PickStringDelegate::new(description, options, default_value, tx);
+                workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        PickStringModal::new(delegate, window, cx)
+                    })
+                })?;
+            }
+        }
+        let Ok(value) = rx.await else {
+            return Ok(None);
+        };
+        values.insert(input.id, value);
+    }
+
+    let values_to_remember = values
+        .iter()
+        .filter(|(id, _)| !passwords.contains(*id))
+        .collect::<HashMap<_, _>>();
+    KEY_VALUE_STORE
+        .write_kvp(key, serde_json::to_string(&values_to_remember)?)
+        .await
+        .log_err();
+    Ok(Some(values))
+}
+
+/// Runs the command in a shell, every non-empty line of its output becomes an option to pick.
+async fn command_output_options(
+    command: &str,
+    cwd: Option<&Path>,
+) -> anyhow::Result<Vec<PickStringOption>> {
+    let mut process = if cfg!(windows) {
+        let mut process = util::command::new_smol_command("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = util::command::new_smol_command("sh");
+        process.arg("-c");
+        process
+    };
+    process.arg(command);
+    if let Some(cwd) = cwd {
+        process.current_dir(cwd);
+    }
+    let output = process
+        .output()
+        .await
+        .with_context(|| format!("running input command `{command}`"))?;
+    if !output.status.success() {
+        bail!(
+            "Input command `{command}` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| PickStringOption::Value(line.to_owned()))
+        .collect())
+}
+
+/// Asks for a `prompt_string` input value, masking the text typed for passwords.
+struct PromptStringModal {
+    description: SharedString,
+    editor: Entity<Editor>,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl EventEmitter<DismissEvent> for PromptStringModal {}
+impl ModalView for PromptStringModal {}
+impl Focusable for PromptStringModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl PromptStringModal {
+    fn new(
+        description: SharedString,
+        default_value: Option<String>,
+        password: bool,
+        tx: oneshot::Sender<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_masked(password, cx);
+            if let Some(default_value) = default_value {
+                editor.set_text(default_value, window, cx);
+                editor.select_all(&editor::actions::SelectAll, window, cx);
+            }
+            editor
+        });
+        Self {
+            description,
+            editor,
+            tx: Some(tx),
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(self.editor.read(cx).text(cx)).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for PromptStringModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TaskInputPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::Play).size(IconSize::XSmall))
+                    .child(Label::new(self.description.clone()).size(LabelSize::Small)),
+            )
+            .child(
+                div()
+                    .py_2()
+                    .px_3()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().editor_background)
+                    .child(self.editor.clone()),
+            )
+    }
+}
+
+/// Asks for a `pick_string` input value.
+struct PickStringModal {
+    picker: Entity<Picker<PickStringDelegate>>,
+    _subscription: Subscription,
+}
+
+impl PickStringModal {
+    fn new(delegate: PickStringDelegate, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for PickStringModal {}
+impl EventEmitter<DismissEvent> for PickStringModal {}
+impl Focusable for PickStringModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for PickStringModal {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+struct PickStringDelegate {
+    description: Arc<str>,
+    options: Vec<PickStringOption>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl PickStringDelegate {
+    fn new(
+        description: SharedString,
+        options: Vec<PickStringOption>,
+        default_value: Option<String>,
+        tx: oneshot::Sender<String>,
+    ) -> Self {
+        let selected_index = default_value
+            .and_then(|default_value| {
+                options
+                    .iter()
+                    .position(|option| option.value() == default_value)
+            })
+            .unwrap_or(0);
+        Self {
+            description: description.as_ref().into(),
+            options,
+            matches: Vec::new(),
+            selected_index,
+            tx: Some(tx),
+        }
+    }
+}
+
+impl PickerDelegate for PickStringDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut Window, _: &mut App) -> Arc<str> {
+        self.description.clone()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, _: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(ix, option)| StringMatchCandidate::new(ix, option.label()))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = cmp::min(
+                        delegate.selected_index,
+                        delegate.matches.len().saturating_sub(1),
+                    );
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(option) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.options.get(hit.candidate_id))
+        else {
+            return;
+        };
+        if let Some(tx) = self.tx.take() {
+            tx.send(option.value().to_owned()).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        Some(
+            ListItem::new(SharedString::from(format!("task-input-option-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                )),
+        )
+    }
+}
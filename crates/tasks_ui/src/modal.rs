@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use crate::TaskContexts;
+use crate::{
+    TaskContexts,
+    task_chain::{run_after_dependencies, schedule_task_with_dependencies},
+};
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     Action, AnyElement, App, AppContext as _, Context, DismissEvent, Entity, EventEmitter,
@@ -344,19 +347,38 @@ impl PickerDelegate for TasksModalDelegate {
                                 });
                             }
                             _ => {
-                                project.update(cx, |project, cx| {
-                                    project
-                                        .start_debug_session(config.into(), cx)
-                                        .detach_and_log_err(cx);
-                                });
+                                run_after_dependencies(
+                                    workspace,
+                                    task_source_kind,
+                                    task,
+                                    &self.task_contexts,
+                                    move |_, _, task, cx| {
+                                        // Inputs are only known now, so the debug configuration is resolved again.
+                                        let Some(config): Option<DebugTaskDefinition> = task
+                                            .resolved_debug_adapter_config()
+                                            .and_then(|config| config.try_into().ok())
+                                        else {
+                                            return;
+                                        };
+                                        project.update(cx, |project, cx| {
+                                            project
+                                                .start_debug_session(config.into(), cx)
+                                                .detach_and_log_err(cx);
+                                        });
+                                    },
+                                    window,
+                                    cx,
+                                );
                             }
                         }
                     }
-                    _ => schedule_resolved_task(
+                    _ => schedule_task_with_dependencies(
                         workspace,
                         task_source_kind,
                         task,
+                        &self.task_contexts,
                         omit_history_entry,
+                        window,
                         cx,
                     ),
                 };
@@ -397,6 +419,13 @@ impl PickerDelegate for TasksModalDelegate {
                 tooltip_label_text.push_str(&resolved.command_label);
             }
         }
+        if !template.depends_on.is_empty() {
+            if !tooltip_label_text.trim().is_empty() {
+                tooltip_label_text.push('\n');
+            }
+            tooltip_label_text.push_str("Depends on: ");
+            tooltip_label_text.push_str(&template.depends_on.join(", "));
+        }
         let tooltip_label = if tooltip_label_text.trim().is_empty() {
             None
         } else {
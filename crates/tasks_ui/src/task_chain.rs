@@ -0,0 +1,422 @@
+use std::{cell::RefCell, rc::Rc};
+
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    channel::oneshot,
+    future::{LocalBoxFuture, Shared, join_all},
+};
+use gpui::{AsyncApp, Context, WeakEntity, Window};
+use project::{TaskContexts, TaskDependencyTree, TaskSourceKind};
+use task::{DependsOrder, ResolvedTask, TaskContext, TaskInput, TaskTemplate};
+use workspace::{Toast, Workspace, notifications::NotificationId, tasks::schedule_resolved_task};
+
+use crate::{file_and_language, input_prompt::ask_for_inputs};
+
+struct TaskChainNotification;
+
+/// Schedules the task given, after spawning all the tasks it depends on (see [`TaskTemplate::depends_on`]).
+/// Tasks without dependencies and inputs are scheduled right away.
+pub(crate) fn schedule_task_with_dependencies(
+    workspace: &mut Workspace,
+    task_source_kind: TaskSourceKind,
+    resolved_task: ResolvedTask,
+    task_contexts: &TaskContexts,
+    omit_history: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    run_after_dependencies(
+        workspace,
+        task_source_kind,
+        resolved_task,
+        task_contexts,
+        move |workspace, task_source_kind, resolved_task, cx| {
+            schedule_resolved_task(workspace, task_source_kind, resolved_task, omit_history, cx)
+        },
+        window,
+        cx,
+    );
+}
+
+/// Asks the user for the inputs that the task and its dependencies reference (see [`TaskTemplate::inputs`]),
+/// spawns all the tasks that the task given depends on, and calls `run` once they are done (or ready, for background tasks).
+/// `run` is called right away for tasks without dependencies and inputs.
+pub(crate) fn run_after_dependencies(
+    workspace: &mut Workspace,
+    task_source_kind: TaskSourceKind,
+    mut resolved_task: ResolvedTask,
+    task_contexts: &TaskContexts,
+    run: impl FnOnce(&mut Workspace, TaskSourceKind, ResolvedTask, &mut Context<Workspace>) + 'static,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let template = resolved_task.original_task().clone();
+    let dependency_tree = if template.depends_on.is_empty() {
+        None
+    } else {
+        let Some(task_inventory) = workspace
+            .project()
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            return;
+        };
+        let (file, language) = file_and_language(task_contexts, cx);
+        match task_inventory.read(cx).resolve_dependencies(
+            task_source_kind.clone(),
+            template.clone(),
+            file,
+            language,
+            task_contexts.worktree(),
+            cx,
+        ) {
+            Ok(dependency_tree) => Some(dependency_tree),
+            Err(e) => {
+                workspace.show_error(&e, cx);
+                return;
+            }
+        }
+    };
+
+    let mut inputs = resolved_task
+        .unresolved_inputs()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    if let Some(dependency_tree) = &dependency_tree {
+        collect_dependency_inputs(&dependency_tree.dependencies, &mut inputs);
+    }
+    if dependency_tree.is_none() && inputs.is_empty() {
+        run(workspace, task_source_kind, resolved_task, cx);
+        return;
+    }
+
+    let task_context = task_contexts.active_context().cloned().unwrap_or_default();
+    let cwd = resolved_task
+        .resolved
+        .as_ref()
+        .and_then(|resolved| resolved.cwd.clone())
+        .or_else(|| task_context.cwd.clone());
+    cx.spawn_in(window, async move |workspace, cx| {
+        let input_values = if inputs.is_empty() {
+            HashMap::default()
+        } else {
+            match ask_for_inputs(
+                workspace.clone(),
+                &template.label,
+                inputs,
+                cwd.as_deref(),
+                cx,
+            )
+            .await
+            {
+                Ok(Some(input_values)) => input_values,
+                // The user has dismissed the prompt, so the task is not run.
+                Ok(None) => return Ok(()),
+                Err(e) => {
+                    return workspace.update(cx, |workspace, cx| workspace.show_error(&e, cx));
+                }
+            }
+        };
+        resolved_task.resolve_inputs(&input_values);
+
+        let dependencies_succeeded = match dependency_tree {
+            Some(dependency_tree) => {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<TaskChainNotification>(),
+                            format!(
+                                "Running `{}` after {}",
+                                resolved_task.resolved_label,
+                                dependency_tree
+                                    .dependencies
+                                    .iter()
+                                    .map(|dependency| format!("`{}`", dependency.template.label))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        )
+                        .autohide(),
+                        cx,
+                    )
+                })?;
+                let chain = Rc::new(TaskChain {
+                    workspace: workspace.clone(),
+                    task_context,
+                    input_values,
+                    runs: RefCell::default(),
+                });
+                let dependencies_succeeded = chain
+                    .run_dependencies(&template, dependency_tree.dependencies, cx)
+                    .await;
+                // Finished runs reference the chain, drop them to avoid a reference cycle.
+                chain.runs.borrow_mut().clear();
+                dependencies_succeeded
+            }
+            None => true,
+        };
+        workspace.update(cx, |workspace, cx| {
+            if dependencies_succeeded {
+                run(workspace, task_source_kind, resolved_task, cx);
+            } else {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<TaskChainNotification>(),
+                        format!(
+                            "Task `{}` was not run, as one of its dependencies failed",
+                            resolved_task.resolved_label
+                        ),
+                    ),
+                    cx,
+                );
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Adds the inputs referenced by the dependencies, that were not added yet, to the ones to ask for.
+fn collect_dependency_inputs(dependencies: &[TaskDependencyTree], inputs: &mut Vec<TaskInput>) {
+    for dependency in dependencies {
+        for input in dependency.template.referenced_inputs() {
+            if !inputs.iter().any(|known_input| known_input.id == input.id) {
+                inputs.push(input.clone());
+            }
+        }
+        collect_dependency_inputs(&dependency.dependencies, inputs);
+    }
+}
+
+/// Spawns the dependencies of a single task, making sure that every task is spawned once,
+/// even if multiple tasks in the chain depend on it.
+struct TaskChain {
+    workspace: WeakEntity<Workspace>,
+    task_context: TaskContext,
+    /// Values the user has entered for the inputs of the tasks in the chain.
+    input_values: HashMap<String, String>,
+    runs: RefCell<HashMap<String, Shared<LocalBoxFuture<'static, bool>>>>,
+}
+
+impl TaskChain {
+    /// Runs all dependencies of the template given, returns whether the template can be spawned after them.
+    async fn run_dependencies(
+        self: &Rc<Self>,
+        template: &TaskTemplate,
+        dependencies: Vec<TaskDependencyTree>,
+        cx: &AsyncApp,
+    ) -> bool {
+        let all_succeeded = match template.depends_order {
+            DependsOrder::Parallel => join_all(
+                dependencies
+                    .into_iter()
+                    .map(|dependency| self.run(dependency, cx.clone())),
+            )
+            .await
+            .into_iter()
+            .all(|succeeded| succeeded),
+            DependsOrder::Sequence => {
+                let mut all_succeeded = true;
+                for dependency in dependencies {
+                    if !self.run(dependency, cx.clone()).await {
+                        all_succeeded = false;
+                        if !template.continue_on_dependency_failure {
+                            break;
+                        }
+                    }
+                }
+                all_succeeded
+            }
+        };
+        all_succeeded || template.continue_on_dependency_failure
+    }
+
+    fn run(
+        self: &Rc<Self>,
+        dependency: TaskDependencyTree,
+        cx: AsyncApp,
+    ) -> Shared<LocalBoxFuture<'static, bool>> {
+        let label = dependency.template.label.clone();
+        if let Some(run) = self.runs.borrow().get(&label) {
+            return run.clone();
+        }
+
+        let chain = self.clone();
+        let run = async move {
+            let TaskDependencyTree {
+                task_source_kind,
+                template,
+                dependencies,
+            } = dependency;
+            if !chain.run_dependencies(&template, dependencies, &cx).await {
+                return false;
+            }
+            if template.is_compound() {
+                return true;
+            }
+            chain.spawn(task_source_kind, template, cx).await
+        }
+        .boxed_local()
+        .shared();
+        self.runs.borrow_mut().insert(label, run.clone());
+        run
+    }
+
+    async fn spawn(
+        &self,
+        task_source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        mut cx: AsyncApp,
+    ) -> bool {
+        let completion = self
+            .workspace
+            .update(&mut cx, |workspace, cx| {
+                let mut resolved_task =
+                    template.resolve_task(&task_source_kind.to_id_base(), &self.task_context)?;
+                resolved_task.resolve_inputs(&self.input_values);
+                match workspace.background_task_ready(&resolved_task.id) {
+                    // Background tasks that are running already are not spawned again.
+                    Some(true) => {
+                        let (tx, rx) = oneshot::channel();
+                        tx.send(true).ok();
+                        Some(rx)
+                    }
+                    Some(false) => Some(workspace.wait_for_task(resolved_task.id)),
+                    None => {
+                        let completion = workspace.wait_for_task(resolved_task.id.clone());
+                        schedule_resolved_task(
+                            workspace,
+                            task_source_kind,
+                            resolved_task,
+                            false,
+                            cx,
+                        );
+                        Some(completion)
+                    }
+                }
+            })
+            .ok()
+            .flatten();
+        match completion {
+            Some(completion) => completion.await.unwrap_or(false),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use gpui::{Entity, TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project, TaskSourceKind};
+    use serde_json::json;
+    use util::path;
+    use workspace::Workspace;
+
+    use crate::{task_contexts, tests::init_test};
+
+    use super::run_after_dependencies;
+
+    #[gpui::test]
+    async fn test_dependency_that_fails_to_spawn(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".zed": {
+                    "tasks.json": r#"[
+                        {
+                            "label": "echo selection",
+                            "command": "echo $ZED_SELECTED_TEXT"
+                        },
+                        {
+                            "label": "deploy",
+                            "command": "echo deploy",
+                            "depends_on": ["echo selection"]
+                        },
+                        {
+                            "label": "deploy anyway",
+                            "command": "echo deploy",
+                            "depends_on": ["echo selection"],
+                            "continue_on_dependency_failure": true
+                        },
+                    ]"#,
+                },
+                "a.ts": "a"
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        cx.run_until_parked();
+
+        let ran_tasks = Rc::new(RefCell::new(Vec::new()));
+        run_task(&workspace, "deploy", &ran_tasks, cx).await;
+        assert_eq!(
+            ran_tasks.borrow().as_slice(),
+            &[] as &[String],
+            "Task should not run when its dependency cannot be spawned (there is no selected text to resolve it with)"
+        );
+
+        run_task(&workspace, "deploy anyway", &ran_tasks, cx).await;
+        assert_eq!(
+            ran_tasks.borrow().as_slice(),
+            &["deploy anyway".to_string()],
+            "The chain should finish, instead of waiting for a dependency that was never spawned"
+        );
+    }
+
+    async fn run_task(
+        workspace: &Entity<Workspace>,
+        label: &str,
+        ran_tasks: &Rc<RefCell<Vec<String>>>,
+        cx: &mut VisualTestContext,
+    ) {
+        let task_contexts = workspace
+            .update_in(cx, |workspace, window, cx| {
+                task_contexts(workspace, window, cx)
+            })
+            .await;
+        let ran_tasks = ran_tasks.clone();
+        workspace.update_in(cx, |workspace, window, cx| {
+            let (task_source_kind, template) = workspace
+                .project()
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .unwrap()
+                .read(cx)
+                .list_tasks(None, None, task_contexts.worktree(), cx)
+                .into_iter()
+                .find(|(_, template)| template.label == label)
+                .unwrap();
+            let resolved_task = template
+                .resolve_task(
+                    &task_source_kind.to_id_base(),
+                    task_contexts.active_context().unwrap(),
+                )
+                .unwrap();
+            run_after_dependencies(
+                workspace,
+                task_source_kind,
+                resolved_task,
+                &task_contexts,
+                move |_, _: TaskSourceKind, resolved_task, _| {
+                    ran_tasks.borrow_mut().push(resolved_task.resolved_label);
+                },
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+    }
+}
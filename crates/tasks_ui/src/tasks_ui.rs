@@ -1,17 +1,23 @@
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 
 use editor::Editor;
 use feature_flags::{Debugger, FeatureFlagViewExt};
 use gpui::{App, AppContext as _, Context, Entity, Task, Window};
+use language::{File, Language};
 use modal::{TaskOverrides, TasksModal};
 use project::{Location, TaskContexts, Worktree};
 use task::{RevealTarget, TaskContext, TaskId, TaskModal, TaskVariables, VariableName};
-use workspace::tasks::schedule_task;
+use task_chain::schedule_task_with_dependencies;
 use workspace::{Start, Workspace, tasks::schedule_resolved_task};
 
+mod background_tasks;
+mod input_prompt;
 mod modal;
+mod task_chain;
 
+pub use background_tasks::BackgroundTasksIndicator;
 pub use modal::{Rerun, Spawn};
 
 pub fn init(cx: &mut App) {
@@ -48,16 +54,19 @@ pub fn init(cx: &mut App) {
                             cx.spawn_in(window, async move |workspace, cx| {
                                 let task_contexts = task_contexts.await;
                                 let default_context = TaskContext::default();
+                                let resolved_task = original_task.resolve_task(
+                                    &task_source_kind.to_id_base(),
+                                    task_contexts.active_context().unwrap_or(&default_context),
+                                )?;
                                 workspace
-                                    .update_in(cx, |workspace, _, cx| {
-                                        schedule_task(
+                                    .update_in(cx, |workspace, window, cx| {
+                                        schedule_task_with_dependencies(
                                             workspace,
                                             task_source_kind,
-                                            &original_task,
-                                            task_contexts
-                                                .active_context()
-                                                .unwrap_or(&default_context),
+                                            resolved_task,
+                                            &task_contexts,
                                             false,
+                                            window,
                                             cx,
                                         )
                                     })
@@ -74,13 +83,34 @@ pub fn init(cx: &mut App) {
                                 }
                             }
 
-                            schedule_resolved_task(
-                                workspace,
-                                task_source_kind,
-                                last_scheduled_task,
-                                false,
-                                cx,
-                            );
+                            if last_scheduled_task.original_task().depends_on.is_empty() {
+                                schedule_resolved_task(
+                                    workspace,
+                                    task_source_kind,
+                                    last_scheduled_task,
+                                    false,
+                                    cx,
+                                );
+                                return;
+                            }
+                            let task_contexts = task_contexts(workspace, window, cx);
+                            cx.spawn_in(window, async move |workspace, cx| {
+                                let task_contexts = task_contexts.await;
+                                workspace
+                                    .update_in(cx, |workspace, window, cx| {
+                                        schedule_task_with_dependencies(
+                                            workspace,
+                                            task_source_kind,
+                                            last_scheduled_task,
+                                            &task_contexts,
+                                            false,
+                                            window,
+                                            cx,
+                                        )
+                                    })
+                                    .ok()
+                            })
+                            .detach()
                         }
                     } else {
                         toggle_modal(workspace, None, TaskModal::ScriptModal, window, cx).detach();
@@ -190,23 +220,14 @@ fn spawn_task_with_name(
             else {
                 return Vec::new();
             };
-            let (file, language) = task_contexts
-                .location()
-                .map(|location| {
-                    let buffer = location.buffer.read(cx);
-                    (
-                        buffer.file().cloned(),
-                        buffer.language_at(location.range.start),
-                    )
-                })
-                .unwrap_or_default();
+            let (file, language) = file_and_language(&task_contexts, cx);
             task_inventory
                 .read(cx)
                 .list_tasks(file, language, task_contexts.worktree(), cx)
         })?;
 
         let did_spawn = workspace
-            .update(cx, |workspace, cx| {
+            .update_in(cx, |workspace, window, cx| {
                 let (task_source_kind, mut target_task) =
                     tasks.into_iter().find(|(_, task)| task.label == name)?;
                 if let Some(overrides) = &overrides {
@@ -216,12 +237,15 @@ fn spawn_task_with_name(
                 }
                 let default_context = TaskContext::default();
                 let active_context = task_contexts.active_context().unwrap_or(&default_context);
-                schedule_task(
+                let resolved_task =
+                    target_task.resolve_task(&task_source_kind.to_id_base(), active_context)?;
+                schedule_task_with_dependencies(
                     workspace,
                     task_source_kind,
-                    &target_task,
-                    active_context,
+                    resolved_task,
+                    &task_contexts,
                     false,
+                    window,
                     cx,
                 );
                 Some(())
@@ -246,6 +270,22 @@ fn spawn_task_with_name(
     })
 }
 
+fn file_and_language(
+    task_contexts: &TaskContexts,
+    cx: &App,
+) -> (Option<Arc<dyn File>>, Option<Arc<Language>>) {
+    task_contexts
+        .location()
+        .map(|location| {
+            let buffer = location.buffer.read(cx);
+            (
+                buffer.file().cloned(),
+                buffer.language_at(location.range.start),
+            )
+        })
+        .unwrap_or_default()
+}
+
 fn task_contexts(workspace: &Workspace, window: &mut Window, cx: &mut App) -> Task<TaskContexts> {
     let active_item = workspace.active_item(cx);
     let active_worktree = active_item
@@ -43,10 +43,10 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, TaskId};
+use task::{BackgroundPatterns, HideStrategy, ProblemMatcher, Shell, TaskId, TaskProblem};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
-use util::{paths::home_dir, truncate_and_trailoff};
+use util::{ResultExt, paths::home_dir, truncate_and_trailoff};
 
 use std::{
     cmp::{self, min},
@@ -110,6 +110,7 @@ pub enum Event {
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
     TaskLocatorReady { task_id: TaskId, success: bool },
+    BackgroundTaskStatusChanged { task_id: TaskId, ready: bool },
 }
 
 #[derive(Clone, Debug)]
@@ -504,6 +505,7 @@ impl TerminalBuilder {
             vi_mode_enabled: false,
             debug_terminal,
             is_ssh_terminal,
+            background_task_check: None,
             python_venv_directory,
         };
 
@@ -662,6 +664,8 @@ pub struct Terminal {
     vi_mode_enabled: bool,
     debug_terminal: bool,
     is_ssh_terminal: bool,
+    /// The pending check of the background task's output for the lines that begin or end its builds.
+    background_task_check: Option<Task<()>>,
 }
 
 pub struct TaskState {
@@ -675,6 +679,9 @@ pub struct TaskState {
     pub show_summary: bool,
     pub show_command: bool,
     pub show_rerun: bool,
+    pub problem_matchers: Vec<ProblemMatcher>,
+    pub cwd: Option<PathBuf>,
+    pub background: Option<BackgroundPatterns>,
 }
 
 /// A status of the current terminal tab's task.
@@ -685,13 +692,22 @@ pub enum TaskStatus {
     Unknown,
     /// The task is started and running currently.
     Running,
+    /// The background task is running and building, its dependents wait for it.
+    Building,
+    /// The background task is running and ready, waiting for changes.
+    Idle,
     /// After the start, the task stopped running and reported its error code back.
     Completed { success: bool },
 }
 
 impl TaskStatus {
+    /// Whether the task process is still running.
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running | Self::Building | Self::Idle)
+    }
+
     fn register_terminal_exit(&mut self) {
-        if self == &Self::Running {
+        if self.is_running() {
             *self = Self::Unknown;
         }
     }
@@ -744,6 +760,7 @@ impl Terminal {
             }
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
+                self.schedule_background_task_status_update(cx);
 
                 if self.pty_info.has_changed() {
                     cx.emit(Event::TitleChanged);
@@ -1448,6 +1465,61 @@ impl Terminal {
         lines
     }
 
+    /// All lines of the terminal, including the scrollback, with the wrapped lines joined.
+    pub fn logical_lines(&self) -> Vec<String> {
+        let term = self.term.clone();
+        let terminal = term.lock_unfair();
+        let grid = terminal.grid();
+        let mut lines = Vec::new();
+
+        let mut current_line = grid.topmost_line().0;
+        let bottommost_line = grid.bottommost_line().0;
+        while current_line <= bottommost_line {
+            let mut logical_line_end = current_line;
+            while logical_line_end < bottommost_line
+                && grid[Line(logical_line_end)][Column(grid.columns() - 1)]
+                    .flags
+                    .contains(Flags::WRAPLINE)
+            {
+                logical_line_end += 1;
+            }
+            let logical_line = self.construct_logical_line(grid, current_line, logical_line_end);
+            lines.push(logical_line.trim_end().to_string());
+            current_line = logical_line_end + 1;
+        }
+        lines
+    }
+
+    /// Applies the problem matchers of the task to the terminal output.
+    pub fn task_problems(&self) -> Vec<TaskProblem> {
+        let Some(task) = self.task.as_ref() else {
+            return Vec::new();
+        };
+        if task.problem_matchers.is_empty() {
+            return Vec::new();
+        }
+
+        let mut lines = self.logical_lines();
+        // Only the latest build of a background task is relevant.
+        if let Some(background) = &task.background {
+            if let Some(build_start) = lines
+                .iter()
+                .rposition(|line| background.is_building_after(line) == Some(true))
+            {
+                lines.drain(..build_start);
+            }
+        }
+        task.problem_matchers
+            .iter()
+            .filter_map(|matcher| {
+                matcher
+                    .find_problems(lines.iter().map(String::as_str), task.cwd.as_deref())
+                    .log_err()
+            })
+            .flatten()
+            .collect()
+    }
+
     fn find_logical_line_start(&self, grid: &Grid<Cell>, current: i32, topmost: i32) -> i32 {
         let mut line_start = current;
         while line_start > topmost {
@@ -1849,7 +1921,7 @@ impl Terminal {
 
     pub fn wait_for_completed_task(&self, cx: &App) -> Task<()> {
         if let Some(task) = self.task() {
-            if task.status == TaskStatus::Running {
+            if task.status.is_running() {
                 let completion_receiver = task.completion_rx.clone();
                 return cx.spawn(async move |_| {
                     let _ = completion_receiver.recv().await;
@@ -1859,6 +1931,66 @@ impl Terminal {
         Task::ready(())
     }
 
+    /// Schedules a check of the background task's output, at most once per
+    /// [`BACKGROUND_TASK_CHECK_INTERVAL`], as chatty tasks wake the terminal up for every chunk of output.
+    fn schedule_background_task_status_update(&mut self, cx: &mut Context<Terminal>) {
+        if self.background_task_check.is_some()
+            || !self
+                .task
+                .as_ref()
+                .is_some_and(|task| task.background.is_some() && task.status.is_running())
+        {
+            return;
+        }
+        self.background_task_check = Some(cx.spawn(async move |terminal, cx| {
+            cx.background_executor()
+                .timer(BACKGROUND_TASK_CHECK_INTERVAL)
+                .await;
+            terminal
+                .update(cx, |terminal, cx| {
+                    terminal.background_task_check = None;
+                    terminal.update_background_task_status(cx);
+                })
+                .ok();
+        }));
+    }
+
+    /// Checks the latest output of a background task for the lines that begin or end its builds.
+    fn update_background_task_status(&mut self, cx: &mut Context<Terminal>) {
+        let Some(task) = self.task.as_ref() else {
+            return;
+        };
+        let Some(background) = task.background.as_ref() else {
+            return;
+        };
+        if !task.status.is_running() {
+            return;
+        }
+
+        let is_building = self
+            .last_n_non_empty_lines(BACKGROUND_TASK_LINES_TO_CHECK)
+            .iter()
+            .rev()
+            .find_map(|line| background.is_building_after(line));
+        let new_status = match is_building {
+            Some(true) => TaskStatus::Building,
+            Some(false) => TaskStatus::Idle,
+            None => return,
+        };
+
+        let Some(task) = self.task.as_mut() else {
+            return;
+        };
+        if task.status != new_status {
+            task.status = new_status;
+            cx.emit(Event::BackgroundTaskStatusChanged {
+                task_id: task.id.clone(),
+                ready: new_status == TaskStatus::Idle,
+            });
+            cx.notify();
+        }
+    }
+
     fn register_task_finished(&mut self, error_code: Option<i32>, cx: &mut Context<Terminal>) {
         self.completion_tx.try_send(()).ok();
         let task = match &mut self.task {
@@ -1870,7 +2002,7 @@ impl Terminal {
                 return;
             }
         };
-        if task.status != TaskStatus::Running {
+        if !task.status.is_running() {
             return;
         }
         match error_code {
@@ -1939,6 +2071,10 @@ fn is_path_surrounded_by_common_symbols(path: &str) -> bool {
 }
 
 const TASK_DELIMITER: &str = "⏵ ";
+/// How many of the last output lines to check for the begin and end patterns of a background task.
+const BACKGROUND_TASK_LINES_TO_CHECK: usize = 50;
+/// How often the output of a background task is checked at most.
+const BACKGROUND_TASK_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 fn task_summary(task: &TaskState, error_code: Option<i32>) -> (bool, String, String) {
     let escaped_full_label = task.full_label.replace("\r\n", "\r").replace('\n', "\r");
     let (success, task_line) = match error_code {
@@ -506,15 +506,13 @@ impl TerminalPanel {
         };
 
         if task.allow_concurrent_runs && task.use_new_terminal {
-            self.spawn_in_new_terminal(task, window, cx)
-                .detach_and_log_err(cx);
+            self.spawn_task_in_new_terminal(task, window, cx);
             return;
         }
 
         let mut terminals_for_task = self.terminals_for_task(&task.full_label, cx);
         let Some(existing) = terminals_for_task.pop() else {
-            self.spawn_in_new_terminal(task, window, cx)
-                .detach_and_log_err(cx);
+            self.spawn_task_in_new_terminal(task, window, cx);
             return;
         };
 
@@ -538,9 +536,7 @@ impl TerminalPanel {
                 wait_for_terminals_tasks(terminals_for_task, cx).await;
                 let task = terminal_panel.update_in(cx, |terminal_panel, window, cx| {
                     if task.use_new_terminal {
-                        terminal_panel
-                            .spawn_in_new_terminal(task, window, cx)
-                            .detach_and_log_err(cx);
+                        terminal_panel.spawn_task_in_new_terminal(task, window, cx);
                         None
                     } else {
                         Some(terminal_panel.replace_terminal(
@@ -560,6 +556,24 @@ impl TerminalPanel {
         );
     }
 
+    /// Spawns the task in a new terminal, reporting the task as failed if the terminal cannot be created.
+    fn spawn_task_in_new_terminal(
+        &mut self,
+        task: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let task_id = task.id.clone();
+        let spawn = self.spawn_in_new_terminal(task, window, cx);
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            if spawn.await.log_err().is_none() {
+                report_task_spawn_failure(&workspace, &task_id, cx);
+            }
+        })
+        .detach();
+    }
+
     pub fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
@@ -824,13 +838,18 @@ impl TerminalPanel {
                 })
                 .ok()
                 .flatten()?;
+            let task_id = spawn_task.id.clone();
             let new_terminal = project
                 .update(cx, |project, cx| {
                     project.create_terminal(TerminalKind::Task(spawn_task), window_handle, cx)
                 })
                 .ok()?
                 .await
-                .log_err()?;
+                .log_err();
+            let Some(new_terminal) = new_terminal else {
+                report_task_spawn_failure(&task_workspace, &task_id, cx);
+                return None;
+            };
             terminal_to_replace
                 .update_in(cx, |terminal_to_replace, window, cx| {
                     terminal_to_replace.set_terminal(new_terminal, window, cx);
@@ -1161,6 +1180,19 @@ async fn wait_for_terminals_tasks(
     let _: Vec<()> = join_all(pending_tasks).await;
 }
 
+/// Lets the tasks that wait for the task given know that it was not spawned.
+fn report_task_spawn_failure(
+    workspace: &WeakEntity<Workspace>,
+    task_id: &TaskId,
+    cx: &mut AsyncApp,
+) {
+    workspace
+        .update(cx, |workspace, cx| {
+            workspace.task_finished(task_id, false, cx)
+        })
+        .ok();
+}
+
 fn add_paths_to_terminal(
     pane: &mut Pane,
     paths: &[PathBuf],
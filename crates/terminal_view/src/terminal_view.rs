@@ -841,6 +841,7 @@ fn subscribe_for_terminal_events(
     cx: &mut Context<TerminalView>,
 ) -> Vec<Subscription> {
     let terminal_subscription = cx.observe(terminal, |_, _, cx| cx.notify());
+    let release_workspace = workspace.clone();
     let terminal_events_subscription = cx.subscribe_in(
         terminal,
         window,
@@ -983,17 +984,81 @@ fn subscribe_for_terminal_events(
                 cx.emit(SearchEvent::ActiveMatchChanged)
             }
             Event::TaskLocatorReady { task_id, success } => {
-                if *success {
-                    workspace
-                        .update(cx, |workspace, cx| {
-                            workspace.debug_task_ready(task_id, cx);
-                        })
-                        .log_err();
-                }
+                let terminal = this.terminal.clone();
+                workspace
+                    .update(cx, |workspace, cx| {
+                        report_task_problems(&terminal, workspace, cx);
+                        workspace.task_finished(task_id, *success, cx);
+                    })
+                    .log_err();
+            }
+
+            Event::BackgroundTaskStatusChanged { task_id, ready } => {
+                let terminal = this.terminal.clone();
+                workspace
+                    .update(cx, |workspace, cx| {
+                        if *ready {
+                            report_task_problems(&terminal, workspace, cx);
+                        }
+                        let task_label = terminal
+                            .read(cx)
+                            .task()
+                            .map(|task| task.label.clone())
+                            .unwrap_or_default();
+                        workspace.background_task_status_changed(task_id, task_label, *ready, cx);
+                    })
+                    .log_err();
             }
         },
     );
-    vec![terminal_subscription, terminal_events_subscription]
+    let release_subscription = cx.on_release(move |this, cx| {
+        // Tasks closed before they exit never report their exit status (and background tasks never finish on their own),
+        // consider them failed so that nothing waits for them forever.
+        let Some(task) = this.terminal.read(cx).task() else {
+            return;
+        };
+        if task.status.is_running() {
+            let task_id = task.id.clone();
+            release_workspace
+                .update(cx, |workspace, cx| {
+                    workspace.task_finished(&task_id, false, cx)
+                })
+                .ok();
+        }
+    });
+    vec![
+        terminal_subscription,
+        terminal_events_subscription,
+        release_subscription,
+    ]
+}
+
+/// Shows the problems that the task's problem matchers found in its output as diagnostics.
+fn report_task_problems(
+    terminal: &Entity<Terminal>,
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(task) = terminal.read(cx).task() else {
+        return;
+    };
+    if task.problem_matchers.is_empty() {
+        return;
+    }
+    let task_label = task.full_label.clone();
+    let cwd = task
+        .cwd
+        .clone()
+        .or_else(|| terminal.read(cx).working_directory());
+    let problems = terminal.read(cx).task_problems();
+    workspace
+        .project()
+        .read(cx)
+        .lsp_store()
+        .update(cx, |lsp_store, cx| {
+            lsp_store.update_task_diagnostics(&task_label, cwd.as_deref(), problems, cx)
+        })
+        .log_err();
 }
 
 #[derive(Debug, Clone)]
@@ -1345,6 +1410,16 @@ impl Item for TerminalView {
                     Color::Disabled,
                     TerminalView::rerun_button(&terminal_task),
                 ),
+                TaskStatus::Building => (
+                    IconName::ArrowCircle,
+                    Color::Accent,
+                    TerminalView::rerun_button(&terminal_task),
+                ),
+                TaskStatus::Idle => (
+                    IconName::Eye,
+                    Color::Success,
+                    TerminalView::rerun_button(&terminal_task),
+                ),
                 TaskStatus::Unknown => (
                     IconName::Warning,
                     Color::Warning,
@@ -1435,7 +1510,7 @@ impl Item for TerminalView {
 
     fn is_dirty(&self, cx: &gpui::App) -> bool {
         match self.terminal.read(cx).task() {
-            Some(task) => task.status == TaskStatus::Running,
+            Some(task) => task.status.is_running(),
             None => self.has_bell(),
         }
     }
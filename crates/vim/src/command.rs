@@ -1448,6 +1448,8 @@ impl ShellExec {
                         show_summary: false,
                         show_command: false,
                         show_rerun: false,
+                        problem_matchers: Vec::new(),
+                        background: None,
                     }),
                 });
             });
@@ -13,7 +13,10 @@ use client::DevServerProjectId;
 use db::{define_connection, query, sqlez::connection::Connection, sqlez_macros::sql};
 use gpui::{Axis, Bounds, WindowBounds, WindowId, point, size};
 use itertools::Itertools;
-use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+use project::debugger::breakpoint_store::{
+    BreakpointState, DataBreakpoint, DataBreakpointAccessType, ExceptionBreakpoint,
+    FunctionBreakpoint, NonSourceBreakpoints, SourceBreakpoint,
+};
 
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
@@ -541,6 +544,39 @@ define_connection! {
         ALTER TABLE breakpoints ADD COLUMN condition TEXT;
         ALTER TABLE breakpoints ADD COLUMN hit_condition TEXT;
     ),
+    sql!(
+        CREATE TABLE exception_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            filter_id TEXT NOT NULL,
+            condition TEXT,
+            state INTEGER NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+        CREATE TABLE function_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            condition TEXT,
+            hit_condition TEXT,
+            state INTEGER NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+        CREATE TABLE data_breakpoints (
+            workspace_id INTEGER NOT NULL,
+            data_id TEXT NOT NULL,
+            description TEXT NOT NULL,
+            access_type INTEGER,
+            condition TEXT,
+            hit_condition TEXT,
+            state INTEGER NOT NULL,
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+        );
+    ),
     ];
 }
 
@@ -629,6 +665,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            non_source_breakpoints: self.non_source_breakpoints(workspace_id),
             window_id,
         })
     }
@@ -683,6 +720,7 @@ impl WorkspaceDb {
             window_bounds,
             centered_layout: centered_layout.unwrap_or(false),
             breakpoints: self.breakpoints(workspace_id),
+            non_source_breakpoints: self.non_source_breakpoints(workspace_id),
             display,
             docks,
             session_id: None,
@@ -736,6 +774,93 @@ impl WorkspaceDb {
         }
     }
 
+    fn non_source_breakpoints(&self, workspace_id: WorkspaceId) -> NonSourceBreakpoints {
+        let exception_breakpoints = self
+            .select_bound::<WorkspaceId, (Arc<str>, Option<Arc<str>>, BreakpointStateWrapper)>(
+                sql! {
+                    SELECT filter_id, condition, state
+                    FROM exception_breakpoints
+                    WHERE workspace_id = ?
+                },
+            )
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id))
+            .context("Exception breakpoints query failed")
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(filter_id, condition, state)| ExceptionBreakpoint {
+                filter_id,
+                condition,
+                state: state.0.into_owned(),
+            })
+            .collect();
+
+        let function_breakpoints = self
+            .select_bound::<WorkspaceId, (
+                Arc<str>,
+                Option<Arc<str>>,
+                Option<Arc<str>>,
+                BreakpointStateWrapper,
+            )>(sql! {
+                SELECT name, condition, hit_condition, state
+                FROM function_breakpoints
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id))
+            .context("Function breakpoints query failed")
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(
+                |(name, condition, hit_condition, state)| FunctionBreakpoint {
+                    name,
+                    condition,
+                    hit_condition,
+                    state: state.0.into_owned(),
+                },
+            )
+            .collect();
+
+        let data_breakpoints = self
+            .select_bound::<WorkspaceId, (
+                Arc<str>,
+                Arc<str>,
+                Option<i32>,
+                Option<Arc<str>>,
+                Option<Arc<str>>,
+                BreakpointStateWrapper,
+            )>(sql! {
+                SELECT data_id, description, access_type, condition, hit_condition, state
+                FROM data_breakpoints
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id))
+            .context("Data breakpoints query failed")
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(
+                |(data_id, description, access_type, condition, hit_condition, state)| {
+                    DataBreakpoint {
+                        data_id,
+                        session_id: None,
+                        description,
+                        access_type: access_type.and_then(DataBreakpointAccessType::from_int),
+                        condition,
+                        hit_condition,
+                        state: state.0.into_owned(),
+                    }
+                },
+            )
+            .collect();
+
+        NonSourceBreakpoints {
+            exception_breakpoints,
+            function_breakpoints,
+            data_breakpoints,
+        }
+    }
+
     /// Saves a workspace using the worktree roots. Will garbage collect any workspaces
     /// that used this workspace previously
     pub(crate) async fn save_workspace(&self, workspace: SerializedWorkspace) {
@@ -776,6 +901,54 @@ impl WorkspaceDb {
 
                 }
 
+                conn.exec_bound(sql!(
+                    DELETE FROM exception_breakpoints WHERE workspace_id = ?1;
+                    DELETE FROM function_breakpoints WHERE workspace_id = ?1;
+                    DELETE FROM data_breakpoints WHERE workspace_id = ?1;))?(workspace.id)
+                .context("Clearing old exception, function and data breakpoints")?;
+                for bp in workspace.non_source_breakpoints.exception_breakpoints {
+                    conn.exec_bound(sql!(
+                        INSERT INTO exception_breakpoints (workspace_id, filter_id, condition, state)
+                        VALUES (?1, ?2, ?3, ?4);))?((
+                        workspace.id,
+                        bp.filter_id,
+                        bp.condition,
+                        BreakpointStateWrapper::from(bp.state),
+                    ))
+                    .context("Storing exception breakpoint")?;
+                }
+                for bp in workspace.non_source_breakpoints.function_breakpoints {
+                    conn.exec_bound(sql!(
+                        INSERT INTO function_breakpoints (workspace_id, name, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5);))?((
+                        workspace.id,
+                        bp.name,
+                        bp.condition,
+                        bp.hit_condition,
+                        BreakpointStateWrapper::from(bp.state),
+                    ))
+                    .context("Storing function breakpoint")?;
+                }
+                // Data ids assigned for a single session are meaningless in the next one.
+                for bp in workspace
+                    .non_source_breakpoints
+                    .data_breakpoints
+                    .into_iter()
+                    .filter(|bp| bp.session_id.is_none())
+                {
+                    conn.exec_bound(sql!(
+                        INSERT INTO data_breakpoints (workspace_id, data_id, description, access_type, condition, hit_condition, state)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);))?((
+                        workspace.id,
+                        bp.data_id,
+                        bp.description,
+                        bp.access_type.map(|access_type| access_type.to_int()),
+                        bp.condition,
+                        bp.hit_condition,
+                        BreakpointStateWrapper::from(bp.state),
+                    ))
+                    .context("Storing data breakpoint")?;
+                }
 
                 match workspace.location {
                     SerializedWorkspaceLocation::Local(local_paths, local_paths_order) => {
@@ -1419,6 +1592,7 @@ mod tests {
     use super::*;
     use crate::persistence::model::SerializedWorkspace;
     use crate::persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup};
+    use dap::client::SessionId;
     use db::open_test_db;
     use gpui;
 
@@ -1528,6 +1702,7 @@ mod tests {
                 );
                 map
             },
+            non_source_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1607,6 +1782,104 @@ mod tests {
         assert_eq!(loaded_breakpoints[4].path, Arc::from(path));
     }
 
+    #[gpui::test]
+    async fn test_non_source_breakpoints() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_non_source_breakpoints").await);
+        let id = db.next_id().await.unwrap();
+
+        let non_source_breakpoints = NonSourceBreakpoints {
+            exception_breakpoints: vec![
+                ExceptionBreakpoint {
+                    filter_id: "uncaught".into(),
+                    condition: None,
+                    state: BreakpointState::Disabled,
+                },
+                ExceptionBreakpoint {
+                    filter_id: "raised".into(),
+                    condition: Some("ValueError".into()),
+                    state: BreakpointState::Enabled,
+                },
+            ],
+            function_breakpoints: vec![FunctionBreakpoint {
+                name: "main".into(),
+                condition: Some("argc > 1".into()),
+                hit_condition: Some("2".into()),
+                state: BreakpointState::Enabled,
+            }],
+            data_breakpoints: vec![
+                DataBreakpoint {
+                    data_id: "counter-1".into(),
+                    session_id: None,
+                    description: "counter".into(),
+                    access_type: Some(DataBreakpointAccessType::ReadWrite),
+                    condition: None,
+                    hit_condition: None,
+                    state: BreakpointState::Enabled,
+                },
+                DataBreakpoint {
+                    data_id: "total-2".into(),
+                    session_id: None,
+                    description: "total".into(),
+                    access_type: None,
+                    condition: Some("total > 10".into()),
+                    hit_condition: None,
+                    state: BreakpointState::Disabled,
+                },
+            ],
+        };
+
+        let mut workspace = SerializedWorkspace {
+            id,
+            location: SerializedWorkspaceLocation::from_local_paths(["/tmp"]),
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            non_source_breakpoints: non_source_breakpoints.clone(),
+            session_id: None,
+            window_id: None,
+        };
+
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.non_source_breakpoints, non_source_breakpoints);
+
+        // Removed breakpoints are not restored
+        workspace
+            .non_source_breakpoints
+            .function_breakpoints
+            .clear();
+        workspace.non_source_breakpoints.data_breakpoints.remove(0);
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(
+            loaded.non_source_breakpoints,
+            workspace.non_source_breakpoints
+        );
+
+        // Data breakpoints that are only valid in the session they were added in are not persisted
+        let persisted_breakpoints = workspace.non_source_breakpoints.clone();
+        workspace
+            .non_source_breakpoints
+            .data_breakpoints
+            .push(DataBreakpoint {
+                data_id: "local-3".into(),
+                session_id: Some(SessionId(1)),
+                description: "local".into(),
+                access_type: None,
+                condition: None,
+                hit_condition: None,
+                state: BreakpointState::Enabled,
+            });
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.non_source_breakpoints, persisted_breakpoints);
+    }
+
     #[gpui::test]
     async fn test_next_id_stability() {
         env_logger::try_init().ok();
@@ -1686,6 +1959,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1699,6 +1973,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1804,6 +2079,7 @@ mod tests {
             center_group,
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -1839,6 +2115,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -1855,6 +2132,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: None,
             window_id: Some(2),
         };
@@ -1895,6 +2173,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -1935,6 +2214,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
         };
@@ -1948,6 +2228,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
         };
@@ -1961,6 +2242,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
         };
@@ -1974,6 +2256,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1992,6 +2275,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
         };
@@ -2005,6 +2289,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2063,6 +2348,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -2111,6 +2397,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
@@ -2203,6 +2490,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            non_source_breakpoints: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
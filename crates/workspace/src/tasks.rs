@@ -2,9 +2,19 @@ use gpui::Context;
 use project::TaskSourceKind;
 use remote::ConnectionState;
 use task::{ResolvedTask, TaskContext, TaskTemplate};
+use util::ResultExt as _;
 
 use crate::Workspace;
 
+/// A long-running task, e.g. a watcher, that reports when it is building and when it is ready.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundTask {
+    /// Human readable name of the task.
+    pub label: String,
+    /// Whether the task has finished its latest build.
+    pub ready: bool,
+}
+
 pub fn schedule_task(
     workspace: &mut Workspace,
     task_source_kind: TaskSourceKind,
@@ -13,19 +23,6 @@ pub fn schedule_task(
     omit_history: bool,
     cx: &mut Context<Workspace>,
 ) {
-    match workspace.project.read(cx).ssh_connection_state(cx) {
-        None | Some(ConnectionState::Connected) => {}
-        Some(
-            ConnectionState::Connecting
-            | ConnectionState::Disconnected
-            | ConnectionState::HeartbeatMissed
-            | ConnectionState::Reconnecting,
-        ) => {
-            log::warn!("Cannot schedule tasks when disconnected from a remote host");
-            return;
-        }
-    }
-
     if let Some(spawn_in_terminal) =
         task_to_resolve.resolve_task(&task_source_kind.to_id_base(), task_cx)
     {
@@ -46,7 +43,24 @@ pub fn schedule_resolved_task(
     omit_history: bool,
     cx: &mut Context<Workspace>,
 ) {
+    let ssh_connection_state = workspace.project.read(cx).ssh_connection_state(cx);
+    match ssh_connection_state {
+        None | Some(ConnectionState::Connected) => {}
+        Some(
+            ConnectionState::Connecting
+            | ConnectionState::Disconnected
+            | ConnectionState::HeartbeatMissed
+            | ConnectionState::Reconnecting,
+        ) => {
+            log::warn!("Cannot schedule tasks when disconnected from a remote host");
+            // Nothing is spawned, so the tasks that wait for this one would never hear back from it.
+            workspace.task_finished(&resolved_task.id, false, cx);
+            return;
+        }
+    }
+
     let debug_config = resolved_task.resolved_debug_adapter_config();
+    let is_compound = resolved_task.original_task().is_compound();
 
     if let Some(spawn_in_terminal) = resolved_task.resolved.take() {
         if let Some(debug_config) = debug_config {
@@ -55,6 +69,29 @@ pub fn schedule_resolved_task(
                 .insert(resolved_task.id.clone(), debug_config);
         }
 
+        // Problems found by the previous run of the task are outdated now.
+        if !spawn_in_terminal.problem_matchers.is_empty() {
+            let task_label = spawn_in_terminal.full_label.clone();
+            workspace
+                .project()
+                .read(cx)
+                .lsp_store()
+                .update(cx, |lsp_store, cx| {
+                    lsp_store.update_task_diagnostics(&task_label, None, Vec::new(), cx)
+                })
+                .log_err();
+        }
+
+        // Background tasks start building right away, their terminals report when they become ready.
+        if spawn_in_terminal.background.is_some() && !is_compound {
+            workspace.background_task_status_changed(
+                &spawn_in_terminal.id,
+                spawn_in_terminal.label.clone(),
+                false,
+                cx,
+            );
+        }
+
         if !omit_history {
             resolved_task.resolved = Some(spawn_in_terminal.clone());
             workspace.project().update(cx, |project, cx| {
@@ -68,8 +105,13 @@ pub fn schedule_resolved_task(
             });
         }
 
-        cx.emit(crate::Event::SpawnTask {
-            action: Box::new(spawn_in_terminal),
-        });
+        // Compound tasks have no command of their own, only their dependencies get spawned.
+        if !is_compound {
+            cx.emit(crate::Event::SpawnTask {
+                action: Box::new(spawn_in_terminal),
+            });
+        }
+    } else {
+        workspace.task_finished(&resolved_task.id, false, cx);
     }
 }
@@ -791,6 +791,8 @@ pub enum Event {
     SpawnTask {
         action: Box<SpawnInTerminal>,
     },
+    /// A background task has started, stopped, or switched between building and being ready.
+    BackgroundTasksChanged,
     OpenBundledFile {
         text: Cow<'static, str>,
         title: &'static str,
@@ -875,6 +877,8 @@ pub struct Workspace {
     _items_serializer: Task<Result<()>>,
     session_id: Option<String>,
     debug_task_queue: HashMap<task::TaskId, DebugAdapterConfig>,
+    task_completion_listeners: HashMap<task::TaskId, Vec<oneshot::Sender<bool>>>,
+    background_tasks: HashMap<task::TaskId, tasks::BackgroundTask>,
 }
 
 impl EventEmitter<Event> for Workspace {}
@@ -989,7 +993,9 @@ impl Workspace {
             &project.read(cx).breakpoint_store(),
             window,
             |workspace, _, evt, window, cx| {
-                if let BreakpointStoreEvent::BreakpointsUpdated(_, _) = evt {
+                if let BreakpointStoreEvent::BreakpointsUpdated(_, _)
+                | BreakpointStoreEvent::NonSourceBreakpointsUpdated = evt
+                {
                     workspace.serialize_workspace(window, cx);
                 }
             },
@@ -1187,6 +1193,8 @@ impl Workspace {
             session_id: Some(session_id),
             serialized_ssh_project: None,
             debug_task_queue: Default::default(),
+            task_completion_listeners: Default::default(),
+            background_tasks: Default::default(),
         }
     }
 
@@ -4710,8 +4718,12 @@ impl Workspace {
         };
 
         if let Some(location) = location {
-            let breakpoints = self.project.update(cx, |project, cx| {
-                project.breakpoint_store().read(cx).all_breakpoints(cx)
+            let (breakpoints, non_source_breakpoints) = self.project.update(cx, |project, cx| {
+                let breakpoint_store = project.breakpoint_store().read(cx);
+                (
+                    breakpoint_store.all_breakpoints(cx),
+                    breakpoint_store.non_source_breakpoints().clone(),
+                )
             });
 
             let center_group = build_serialized_pane_group(&self.center.root, window, cx);
@@ -4727,6 +4739,7 @@ impl Workspace {
                 centered_layout: self.centered_layout,
                 session_id: self.session_id.clone(),
                 breakpoints,
+                non_source_breakpoints,
                 window_id: Some(window.window_handle().window_id().as_u64()),
             };
             return window.spawn(cx, async move |_| {
@@ -4869,6 +4882,10 @@ impl Workspace {
                     project
                         .breakpoint_store()
                         .update(cx, |breakpoint_store, cx| {
+                            breakpoint_store.set_non_source_breakpoints(
+                                serialized_workspace.non_source_breakpoints,
+                                cx,
+                            );
                             breakpoint_store
                                 .with_serialized_breakpoints(serialized_workspace.breakpoints, cx)
                         })
@@ -5194,6 +5211,66 @@ impl Workspace {
             .ok();
     }
 
+    /// Returns a receiver that gets whether the task with the id given succeeded, once it finishes.
+    pub fn wait_for_task(&mut self, task_id: TaskId) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.task_completion_listeners
+            .entry(task_id)
+            .or_default()
+            .push(tx);
+        rx
+    }
+
+    pub fn task_finished(&mut self, task_id: &TaskId, success: bool, cx: &mut Context<Self>) {
+        if self.background_tasks.remove(task_id).is_some() {
+            cx.emit(Event::BackgroundTasksChanged);
+        }
+        self.notify_task_listeners(task_id, success, cx);
+    }
+
+    /// Records whether the running background task is building or ready.
+    /// Tasks and debug sessions waiting for the background task get started once it is ready.
+    pub fn background_task_status_changed(
+        &mut self,
+        task_id: &TaskId,
+        label: String,
+        ready: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let task = tasks::BackgroundTask { label, ready };
+        if self.background_tasks.get(task_id) != Some(&task) {
+            self.background_tasks.insert(task_id.clone(), task);
+            cx.emit(Event::BackgroundTasksChanged);
+        }
+        if ready {
+            self.notify_task_listeners(task_id, true, cx);
+        }
+    }
+
+    /// Whether the background task with the id given is running and ready, `None` if it is not running.
+    pub fn background_task_ready(&self, task_id: &TaskId) -> Option<bool> {
+        self.background_tasks.get(task_id).map(|task| task.ready)
+    }
+
+    /// Background tasks that are running currently.
+    pub fn background_tasks(&self) -> impl Iterator<Item = &tasks::BackgroundTask> {
+        self.background_tasks.values()
+    }
+
+    fn notify_task_listeners(&mut self, task_id: &TaskId, success: bool, cx: &mut App) {
+        for listener in self
+            .task_completion_listeners
+            .remove(task_id)
+            .into_iter()
+            .flatten()
+        {
+            listener.send(success).ok();
+        }
+        if success {
+            self.debug_task_ready(task_id, cx);
+        }
+    }
+
     pub fn debug_task_ready(&mut self, task_id: &TaskId, cx: &mut App) {
         if let Some(debug_config) = self.debug_task_queue.remove(task_id) {
             self.project.update(cx, |project, cx| {
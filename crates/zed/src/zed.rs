@@ -14,6 +14,7 @@ use assets::Assets;
 use assistant_context_editor::AssistantPanelDelegate;
 use assistant2::AssistantDiffToolbar;
 use breadcrumbs::Breadcrumbs;
+use call_hierarchy_panel::CallHierarchyPanel;
 use client::{ZED_URL_SCHEME, zed_urls};
 use collections::VecDeque;
 use command_palette_hooks::CommandPaletteFilter;
@@ -230,6 +231,7 @@ pub fn initialize_workspace(
             window,
             cx,
         );
+        let background_tasks = cx.new(|cx| tasks_ui::BackgroundTasksIndicator::new(workspace, cx));
         let active_buffer_language =
             cx.new(|_| language_selector::ActiveBufferLanguage::new(workspace));
         let active_toolchain_language =
@@ -241,6 +243,7 @@ pub fn initialize_workspace(
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
+            status_bar.add_left_item(background_tasks, window, cx);
             status_bar.add_right_item(inline_completion_button, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
@@ -404,6 +407,7 @@ fn initialize_panels(
     cx.spawn_in(window, async move |workspace_handle, cx| {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+        let call_hierarchy_panel = CallHierarchyPanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
             collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -417,6 +421,7 @@ fn initialize_panels(
         let (
             project_panel,
             outline_panel,
+            call_hierarchy_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
@@ -424,6 +429,7 @@ fn initialize_panels(
         ) = futures::try_join!(
             project_panel,
             outline_panel,
+            call_hierarchy_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
@@ -433,6 +439,7 @@ fn initialize_panels(
         workspace_handle.update_in(cx, |workspace, window, cx| {
             workspace.add_panel(project_panel, window, cx);
             workspace.add_panel(outline_panel, window, cx);
+            workspace.add_panel(call_hierarchy_panel, window, cx);
             workspace.add_panel(terminal_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
@@ -4280,6 +4287,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            call_hierarchy_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),